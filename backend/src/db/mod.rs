@@ -1,6 +1,17 @@
+pub mod osm_import;
 pub mod pool;
 pub mod rls_transaction;
+pub mod track_duplicates;
+pub mod track_revisions;
+pub mod track_topology;
 
+pub use osm_import::{
+    load_osm_tracks, lock_replication_sequence, osm_node_positions, osm_ways_using_nodes,
+    remove_osm_nodes, remove_osm_ways, set_replication_sequence, OsmLoad, OsmTrack,
+};
 pub use pool::create_pool;
 pub use rls_transaction::RlsTransaction;
+pub use track_duplicates::{detect_duplicates, DuplicateOptions};
+pub use track_revisions::{RevisionContext, RevisionKind};
+pub use track_topology::{rebuild_topology, DEFAULT_TOPOLOGY_TOLERANCE_M};
 pub type DbPool = sqlx::PgPool;
@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use anyhow::Result;
+use sqlx::{FromRow, PgConnection};
+
+use crate::osm::{way_positions, OsmWay, TrackWay};
+
+/// Rows sent per COPY message
+const COPY_BATCH: usize = 5000;
+
+/// Whether OSM changed the line of an incoming way since it was mirrored
+const OSM_LINE_CHANGED: &str = "NOT EXISTS (SELECT 1 FROM osm_ways w \
+     WHERE w.id = EXCLUDED.osm_way_id AND ST_AsBinary(w.geometry) = ST_AsBinary(EXCLUDED.geometry))";
+
+/// Whether OSM changed the surface of an incoming way since it was mirrored
+const OSM_SURFACE_CHANGED: &str = "NOT EXISTS (SELECT 1 FROM osm_ways w \
+     WHERE w.id = EXCLUDED.osm_way_id AND w.surface IS NOT DISTINCT FROM EXCLUDED.surface)";
+
+#[derive(FromRow)]
+struct MirroredWay {
+    id: i64,
+    refs: Vec<i64>,
+    #[sqlx(json)]
+    tags: HashMap<String, String>,
+}
+
+/// A relevant OSM way with its assembled line, ready to load
+#[derive(Debug, Clone)]
+pub struct OsmTrack {
+    pub way: TrackWay,
+    /// `[lng, lat]`, at least two distinct positions
+    pub positions: Vec<[f64; 2]>,
+}
+
+impl OsmTrack {
+    /// Build the line of a way from known node positions
+    ///
+    /// `None` if fewer than two of its nodes are known.
+    pub fn assemble(way: TrackWay, nodes: &HashMap<i64, (f64, f64)>) -> Option<Self> {
+        let positions = way_positions(&way.refs, nodes)?;
+        Some(Self { way, positions })
+    }
+}
+
+/// What an OSM load changed in `curated_tracks`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsmLoad {
+    pub staged: u64,
+    pub inserted: u64,
+    pub updated: u64,
+}
+
+/// Escape a value for COPY text format
+fn copy_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// `osm_track_staging` row in COPY text format
+fn copy_track(out: &mut String, track: &OsmTrack) {
+    let way = &track.way;
+    let _ = write!(out, "{}\tSRID=4326;LINESTRING(", way.id);
+    for (i, [lng, lat]) in track.positions.iter().enumerate() {
+        let _ = write!(out, "{}{} {}", if i == 0 { "" } else { "," }, lng, lat);
+    }
+
+    let refs: Vec<String> = way.refs.iter().map(i64::to_string).collect();
+    let tags = serde_json::to_string(&way.tags).unwrap_or_else(|_| "{}".to_string());
+    let _ = writeln!(
+        out,
+        ")\t{{{}}}\t{}\t{}\t{}",
+        refs.join(","),
+        copy_text(&tags),
+        way.surface.unwrap_or("\\N"),
+        way.confidence
+    );
+}
+
+/// Bulk-load OSM tracks into `curated_tracks`, keyed by `osm_way_id`
+///
+/// Tracks and the positions of their nodes are copied into temporary
+/// tables and merged against the `osm_ways` mirror of what OSM said last time:
+/// - new ways are inserted, unless tombstoned in `deleted_osm_ways`;
+/// - an existing track takes the new line or surface only if it changed in
+///   OSM, so curator adjustments survive, and confidence and region are
+///   never overwritten;
+/// - tracks a curator has taken over (source no longer 'osm') are left alone.
+///
+/// The mirror and `osm_nodes` are then brought up to date. Runs in the
+/// caller's transaction, which should carry an import `RevisionContext`;
+/// call it at most once per transaction.
+pub async fn load_osm_tracks(
+    conn: &mut PgConnection,
+    tracks: &[OsmTrack],
+    nodes: &HashMap<i64, (f64, f64)>,
+    region: &str,
+) -> Result<OsmLoad> {
+    sqlx::query(
+        "CREATE TEMPORARY TABLE osm_track_staging (
+             osm_way_id BIGINT PRIMARY KEY,
+             geometry GEOMETRY(LineString, 4326) NOT NULL,
+             refs BIGINT[] NOT NULL,
+             tags JSONB NOT NULL,
+             surface TEXT,
+             confidence INTEGER NOT NULL
+         ) ON COMMIT DROP",
+    )
+    .persistent(false)
+    .execute(&mut *conn)
+    .await?;
+
+    let mut copy = conn
+        .copy_in_raw(
+            "COPY osm_track_staging (osm_way_id, geometry, refs, tags, surface, confidence)
+             FROM STDIN",
+        )
+        .await?;
+    for chunk in tracks.chunks(COPY_BATCH) {
+        let mut rows = String::new();
+        for track in chunk {
+            copy_track(&mut rows, track);
+        }
+        copy.send(rows.into_bytes()).await?;
+    }
+    let staged = copy.finish().await?;
+
+    let (inserted, updated): (i64, i64) = sqlx::query_as(&format!(
+        "WITH loaded AS (
+             INSERT INTO curated_tracks (osm_way_id, geometry, source, surface, confidence, region)
+             SELECT s.osm_way_id, s.geometry, 'osm', s.surface, s.confidence, $1
+             FROM osm_track_staging s
+             WHERE NOT EXISTS (SELECT 1 FROM deleted_osm_ways d WHERE d.osm_way_id = s.osm_way_id)
+             ON CONFLICT (osm_way_id) DO UPDATE SET
+                 geometry = CASE WHEN {line_changed} THEN EXCLUDED.geometry
+                                 ELSE curated_tracks.geometry END,
+                 surface = CASE WHEN {surface_changed} THEN EXCLUDED.surface
+                                ELSE curated_tracks.surface END
+             WHERE curated_tracks.source = 'osm'
+                 AND (({line_changed}
+                       AND ST_AsBinary(curated_tracks.geometry)
+                           IS DISTINCT FROM ST_AsBinary(EXCLUDED.geometry))
+                      OR ({surface_changed}
+                          AND curated_tracks.surface IS DISTINCT FROM EXCLUDED.surface))
+             RETURNING xmax = 0 AS inserted
+         )
+         SELECT COUNT(*) FILTER (WHERE inserted), COUNT(*) FILTER (WHERE NOT inserted)
+         FROM loaded",
+        line_changed = OSM_LINE_CHANGED,
+        surface_changed = OSM_SURFACE_CHANGED,
+    ))
+    .bind(region)
+    .persistent(false)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO osm_ways (id, refs, tags, geometry, surface)
+         SELECT osm_way_id, refs, tags, geometry, surface FROM osm_track_staging
+         ON CONFLICT (id) DO UPDATE SET
+             refs = EXCLUDED.refs,
+             tags = EXCLUDED.tags,
+             geometry = EXCLUDED.geometry,
+             surface = EXCLUDED.surface",
+    )
+    .persistent(false)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        "CREATE TEMPORARY TABLE osm_node_staging (
+             id BIGINT PRIMARY KEY,
+             lng DOUBLE PRECISION NOT NULL,
+             lat DOUBLE PRECISION NOT NULL
+         ) ON COMMIT DROP",
+    )
+    .persistent(false)
+    .execute(&mut *conn)
+    .await?;
+
+    let nodes: Vec<(&i64, &(f64, f64))> = nodes.iter().collect();
+    let mut copy = conn
+        .copy_in_raw("COPY osm_node_staging (id, lng, lat) FROM STDIN")
+        .await?;
+    for chunk in nodes.chunks(COPY_BATCH) {
+        let mut rows = String::new();
+        for (id, (lng, lat)) in chunk {
+            let _ = writeln!(rows, "{}\t{}\t{}", id, lng, lat);
+        }
+        copy.send(rows.into_bytes()).await?;
+    }
+    copy.finish().await?;
+
+    sqlx::query(
+        "INSERT INTO osm_nodes (id, lng, lat)
+         SELECT id, lng, lat FROM osm_node_staging
+         ON CONFLICT (id) DO UPDATE SET lng = EXCLUDED.lng, lat = EXCLUDED.lat
+         WHERE (osm_nodes.lng, osm_nodes.lat) IS DISTINCT FROM (EXCLUDED.lng, EXCLUDED.lat)",
+    )
+    .persistent(false)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(OsmLoad {
+        staged,
+        inserted: inserted as u64,
+        updated: updated as u64,
+    })
+}
+
+/// Remove ways deleted from OSM, or no longer relevant, with their tracks
+///
+/// Tracks a curator has taken over are kept. Returns the number of tracks
+/// deleted.
+pub async fn remove_osm_ways(conn: &mut PgConnection, way_ids: &[i64]) -> Result<u64> {
+    let deleted: Vec<i64> = sqlx::query_scalar(
+        "DELETE FROM curated_tracks WHERE osm_way_id = ANY($1) AND source = 'osm'
+         RETURNING osm_way_id",
+    )
+    .bind(way_ids)
+    .persistent(false)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    // Tombstones are for curator deletions; a way restored in OSM may return
+    sqlx::query("DELETE FROM deleted_osm_ways WHERE osm_way_id = ANY($1)")
+        .bind(&deleted)
+        .persistent(false)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query("DELETE FROM osm_ways WHERE id = ANY($1)")
+        .bind(way_ids)
+        .persistent(false)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(deleted.len() as u64)
+}
+
+/// Forget nodes deleted from OSM
+pub async fn remove_osm_nodes(conn: &mut PgConnection, node_ids: &[i64]) -> Result<()> {
+    sqlx::query("DELETE FROM osm_nodes WHERE id = ANY($1)")
+        .bind(node_ids)
+        .persistent(false)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Mirrored ways that use any of `node_ids`
+pub async fn osm_ways_using_nodes(
+    conn: &mut PgConnection,
+    node_ids: &[i64],
+) -> Result<Vec<OsmWay>> {
+    let rows: Vec<MirroredWay> =
+        sqlx::query_as("SELECT id, refs, tags FROM osm_ways WHERE refs && $1::bigint[]")
+            .bind(node_ids)
+            .persistent(false)
+            .fetch_all(&mut *conn)
+            .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| OsmWay {
+            id: row.id,
+            tags: row.tags,
+            refs: row.refs,
+        })
+        .collect())
+}
+
+/// Mirrored positions of `node_ids`, `(lng, lat)`
+pub async fn osm_node_positions(
+    conn: &mut PgConnection,
+    node_ids: &[i64],
+) -> Result<HashMap<i64, (f64, f64)>> {
+    let rows: Vec<(i64, f64, f64)> =
+        sqlx::query_as("SELECT id, lng, lat FROM osm_nodes WHERE id = ANY($1)")
+            .bind(node_ids)
+            .persistent(false)
+            .fetch_all(&mut *conn)
+            .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, lng, lat)| (id, (lng, lat)))
+        .collect())
+}
+
+/// Sequence number of the last change file applied to a region
+///
+/// Holds a lock on the region until the transaction ends, so concurrent
+/// runs cannot apply the same file twice.
+pub async fn lock_replication_sequence(
+    conn: &mut PgConnection,
+    region: &str,
+) -> Result<Option<i64>> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('osm_replication ' || $1))")
+        .bind(region)
+        .persistent(false)
+        .execute(&mut *conn)
+        .await?;
+
+    let sequence =
+        sqlx::query_scalar("SELECT sequence_number FROM osm_replication_state WHERE region = $1")
+            .bind(region)
+            .persistent(false)
+            .fetch_optional(&mut *conn)
+            .await?;
+    Ok(sequence)
+}
+
+/// Record that a region's change file `sequence` has been applied
+pub async fn set_replication_sequence(
+    conn: &mut PgConnection,
+    region: &str,
+    sequence: i64,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO osm_replication_state (region, sequence_number) VALUES ($1, $2)
+         ON CONFLICT (region) DO UPDATE SET
+             sequence_number = EXCLUDED.sequence_number,
+             applied_at = NOW()",
+    )
+    .bind(region)
+    .bind(sequence)
+    .persistent(false)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
@@ -15,6 +15,8 @@ impl<'a> RlsTransaction<'a> {
     /// - `role`: The user's role (e.g., "authenticated")
     /// - `request.jwt.claim.sub`: The user's UUID
     /// - `request.jwt.claim.role`: The user's role
+    /// - `request.jwt.claims`: All verified claims as JSON (read by
+    ///   `is_curator()` for `app_metadata` roles)
     ///
     /// These variables enable RLS policies to use `auth.uid()` for authorization.
     ///
@@ -82,6 +84,15 @@ impl<'a> RlsTransaction<'a> {
                 )
             })?;
 
+        // Set all JWT claims; set_config() takes the JSON as a bound parameter
+        let claims = serde_json::to_string(&auth.full_claims)?;
+        sqlx::query("SELECT set_config('request.jwt.claims', $1, true)")
+            .bind(&claims)
+            .persistent(false)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| anyhow!("Failed to set request.jwt.claims: {}", e))?;
+
         tracing::debug!("RLS context set: user={}, role={}", auth.id, auth.role);
 
         Ok(Self { inner: tx })
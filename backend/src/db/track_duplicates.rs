@@ -0,0 +1,201 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use sqlx::{FromRow, PgConnection, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::geometry::similarity::{
+    choose_keeper, frechet_distance_m, merged_confidence, Keep, MergeSide,
+};
+use crate::geometry::types::LineGeometry;
+use crate::models::{BoundingBox, DuplicateDetection};
+
+/// Thresholds for proposing a merge
+#[derive(Debug, Clone, Copy)]
+pub struct DuplicateOptions {
+    /// Half-width of the corridor around a track that counts as overlap (meters)
+    pub buffer_m: f64,
+    /// Share of a track inside the other's corridor to make it a candidate
+    pub min_overlap: f64,
+    /// Largest Fréchet distance at which two mutual overlaps are the same line
+    pub max_frechet_m: f64,
+    /// Most candidate pairs examined per run
+    pub max_pairs: i64,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        Self {
+            buffer_m: 30.0,
+            min_overlap: 0.8,
+            max_frechet_m: 100.0,
+            max_pairs: 500,
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct CandidatePair {
+    a_id: Uuid,
+    #[sqlx(json)]
+    a_geometry: LineGeometry,
+    a_source: String,
+    a_confidence: i32,
+    a_last_verified: Option<NaiveDate>,
+    a_length_m: f64,
+    overlap_a: f64,
+    b_id: Uuid,
+    #[sqlx(json)]
+    b_geometry: LineGeometry,
+    b_source: String,
+    b_confidence: i32,
+    b_last_verified: Option<NaiveDate>,
+    b_length_m: f64,
+    overlap_b: f64,
+}
+
+/// Find overlapping curated track pairs and record merge proposals
+///
+/// Candidates are pairs where most of one track lies within `buffer_m` of
+/// the other. Pairs that cover each other must also have similar shapes
+/// (Fréchet distance); a track lying along a longer one is always proposed.
+/// Pairs already merged or dismissed are skipped before `max_pairs` is
+/// applied; pending proposals are refreshed. Mutual pairs rejected on shape
+/// are recorded as dismissed, without a curator, so later runs skip them too.
+pub async fn detect_duplicates(
+    conn: &mut PgConnection,
+    bbox: Option<BoundingBox>,
+    options: &DuplicateOptions,
+) -> Result<DuplicateDetection> {
+    // Index prefilter in degrees, generous up to 60° latitude
+    let buffer_deg = options.buffer_m / 111_320.0 * 2.0;
+
+    let mut sql = QueryBuilder::<Postgres>::new(
+        "SELECT * FROM (
+             SELECT
+                 a.id AS a_id, ST_AsGeoJSON(a.geometry)::jsonb AS a_geometry,
+                 a.source AS a_source, a.confidence AS a_confidence,
+                 a.last_verified AS a_last_verified,
+                 ST_Length(a.geometry::geography) AS a_length_m,
+                 b.id AS b_id, ST_AsGeoJSON(b.geometry)::jsonb AS b_geometry,
+                 b.source AS b_source, b.confidence AS b_confidence,
+                 b.last_verified AS b_last_verified,
+                 ST_Length(b.geometry::geography) AS b_length_m,
+                 COALESCE(ST_Length(ST_Intersection(a.geometry, ST_Buffer(b.geometry::geography, ",
+    );
+    sql.push_bind(options.buffer_m)
+        .push(")::geometry)::geography) / NULLIF(ST_Length(a.geometry::geography), 0), 0) AS overlap_a, ")
+        .push("COALESCE(ST_Length(ST_Intersection(b.geometry, ST_Buffer(a.geometry::geography, ")
+        .push_bind(options.buffer_m)
+        .push(")::geometry)::geography) / NULLIF(ST_Length(b.geometry::geography), 0), 0) AS overlap_b ")
+        .push("FROM curated_tracks a JOIN curated_tracks b ON a.id < b.id AND a.geometry && ST_Expand(b.geometry, ")
+        .push_bind(buffer_deg)
+        .push(") AND ST_DWithin(a.geometry::geography, b.geometry::geography, ")
+        .push_bind(options.buffer_m)
+        // Resolved pairs would otherwise use up `max_pairs` before new ones
+        .push(
+            ") WHERE NOT EXISTS (
+                 SELECT 1 FROM track_merge_proposals p
+                 WHERE (p.track_a, p.track_b) = (a.id, b.id) AND p.status <> 'pending'
+             )",
+        );
+    if let Some(bbox) = bbox {
+        sql.push(" AND a.geometry && ST_MakeEnvelope(")
+            .push_bind(bbox.min_lng)
+            .push(", ")
+            .push_bind(bbox.min_lat)
+            .push(", ")
+            .push_bind(bbox.max_lng)
+            .push(", ")
+            .push_bind(bbox.max_lat)
+            .push(", 4326)");
+    }
+    sql.push(") pairs WHERE GREATEST(overlap_a, overlap_b) >= ")
+        .push_bind(options.min_overlap)
+        .push(" ORDER BY a_id, b_id LIMIT ")
+        .push_bind(options.max_pairs);
+
+    let pairs = sql
+        .build_query_as::<CandidatePair>()
+        .persistent(false)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    let mut proposed = 0;
+    let mut rejected = 0;
+    for pair in &pairs {
+        let a_points = pair.a_geometry.points();
+        let b_points = pair.b_geometry.points();
+        let Some(frechet_m) = frechet_distance_m(&a_points, &b_points, options.buffer_m) else {
+            continue;
+        };
+
+        let mutual = pair.overlap_a.min(pair.overlap_b) >= options.min_overlap;
+        let status = if mutual && frechet_m > options.max_frechet_m {
+            "dismissed"
+        } else {
+            "pending"
+        };
+
+        let a = MergeSide {
+            source: &pair.a_source,
+            confidence: pair.a_confidence,
+            last_verified: pair.a_last_verified,
+            length_m: pair.a_length_m,
+            points: a_points.len(),
+            overlap: pair.overlap_a,
+        };
+        let b = MergeSide {
+            source: &pair.b_source,
+            confidence: pair.b_confidence,
+            last_verified: pair.b_last_verified,
+            length_m: pair.b_length_m,
+            points: b_points.len(),
+            overlap: pair.overlap_b,
+        };
+        let keep = match choose_keeper(&a, &b, options.min_overlap) {
+            Keep::A => pair.a_id,
+            Keep::B => pair.b_id,
+        };
+
+        // A pending proposal whose tracks no longer match is dismissed too
+        let result = sqlx::query(
+            "INSERT INTO track_merge_proposals
+                 (track_a, track_b, keep_track_id, overlap_a, overlap_b, frechet_m,
+                  merged_confidence, status, resolved_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8,
+                     CASE WHEN $8 = 'dismissed' THEN NOW() END)
+             ON CONFLICT (track_a, track_b) DO UPDATE SET
+                 keep_track_id = EXCLUDED.keep_track_id,
+                 overlap_a = EXCLUDED.overlap_a,
+                 overlap_b = EXCLUDED.overlap_b,
+                 frechet_m = EXCLUDED.frechet_m,
+                 merged_confidence = EXCLUDED.merged_confidence,
+                 status = EXCLUDED.status,
+                 resolved_at = EXCLUDED.resolved_at,
+                 detected_at = NOW()
+             WHERE track_merge_proposals.status = 'pending'",
+        )
+        .bind(pair.a_id)
+        .bind(pair.b_id)
+        .bind(keep)
+        .bind(pair.overlap_a)
+        .bind(pair.overlap_b)
+        .bind(frechet_m)
+        .bind(merged_confidence(&a, &b))
+        .bind(status)
+        .persistent(false)
+        .execute(&mut *conn)
+        .await?;
+
+        match status {
+            "pending" => proposed += result.rows_affected() as usize,
+            _ => rejected += result.rows_affected() as usize,
+        }
+    }
+
+    Ok(DuplicateDetection {
+        candidates: pairs.len(),
+        proposed,
+        rejected,
+    })
+}
@@ -0,0 +1,92 @@
+use anyhow::Result;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// Why curated tracks are being changed
+///
+/// Matches `curated_track_revisions.kind`; changes made without a context
+/// are recorded as "sql".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisionKind {
+    Import,
+    Edit,
+    Confidence,
+    Merge,
+    Revert,
+    Verification,
+}
+
+impl RevisionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Import => "import",
+            Self::Edit => "edit",
+            Self::Confidence => "confidence",
+            Self::Merge => "merge",
+            Self::Revert => "revert",
+            Self::Verification => "verification",
+        }
+    }
+}
+
+/// Provenance attached to the revisions written by the rest of a transaction
+#[derive(Debug, Clone, Copy)]
+pub struct RevisionContext<'a> {
+    pub kind: RevisionKind,
+    pub reason: Option<&'a str>,
+    /// Name of the job making the change
+    pub job: Option<&'a str>,
+    pub import_run_id: Option<Uuid>,
+}
+
+impl<'a> RevisionContext<'a> {
+    pub fn new(kind: RevisionKind) -> Self {
+        Self {
+            kind,
+            reason: None,
+            job: None,
+            import_run_id: None,
+        }
+    }
+
+    pub fn reason(mut self, reason: Option<&'a str>) -> Self {
+        self.reason = reason;
+        self
+    }
+
+    pub fn job(mut self, job: &'a str) -> Self {
+        self.job = Some(job);
+        self
+    }
+
+    pub fn import_run(mut self, import_run_id: Uuid) -> Self {
+        self.import_run_id = Some(import_run_id);
+        self
+    }
+
+    /// Publish the context for the `record_curated_track_revision` trigger
+    ///
+    /// The settings are transaction-local, so this must run inside the
+    /// transaction that changes the tracks; calling it again replaces them.
+    pub async fn apply(&self, conn: &mut PgConnection) -> Result<()> {
+        sqlx::query(
+            "SELECT set_config('app.revision_kind', $1, true),
+                    set_config('app.revision_reason', $2, true),
+                    set_config('app.revision_job', $3, true),
+                    set_config('app.import_run_id', $4, true)",
+        )
+        .bind(self.kind.as_str())
+        .bind(self.reason.unwrap_or_default())
+        .bind(self.job.unwrap_or_default())
+        .bind(
+            self.import_run_id
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+        )
+        .persistent(false)
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+}
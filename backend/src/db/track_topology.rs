@@ -0,0 +1,109 @@
+use anyhow::Result;
+use sqlx::types::Json;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::geometry::linear_ref::to_linestring;
+use crate::geometry::topology::build_topology;
+use crate::geometry::types::LineGeometry;
+use crate::models::TopologyBuild;
+
+/// Default snapping tolerance for track ends (meters)
+pub const DEFAULT_TOPOLOGY_TOLERANCE_M: f64 = 25.0;
+
+/// Rows per insert statement
+const INSERT_CHUNK: usize = 5000;
+
+/// Rebuild `track_nodes` and `track_edges` from every curated track
+///
+/// Replaces the whole graph inside the caller's transaction, so readers see
+/// either the old topology or the new one.
+pub async fn rebuild_topology(
+    conn: &mut PgConnection,
+    tolerance_m: f64,
+    built_by: Option<Uuid>,
+) -> Result<TopologyBuild> {
+    let tracks: Vec<(Uuid, Json<LineGeometry>)> =
+        sqlx::query_as("SELECT id, ST_AsGeoJSON(geometry)::jsonb FROM curated_tracks ORDER BY id")
+            .persistent(false)
+            .fetch_all(&mut *conn)
+            .await?;
+
+    let lines: Vec<(Uuid, Vec<(f64, f64)>)> = tracks
+        .iter()
+        .map(|(id, geometry)| (*id, geometry.points()))
+        .collect();
+    let topology = build_topology(&lines, tolerance_m);
+
+    sqlx::query("DELETE FROM track_edges")
+        .persistent(false)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("DELETE FROM track_nodes")
+        .persistent(false)
+        .execute(&mut *conn)
+        .await?;
+
+    for (chunk_index, chunk) in topology.nodes.chunks(INSERT_CHUNK).enumerate() {
+        let first = (chunk_index * INSERT_CHUNK) as i64;
+        let ids: Vec<i64> = (first..first + chunk.len() as i64).collect();
+        sqlx::query(
+            "INSERT INTO track_nodes (id, geometry, degree, component)
+             SELECT id, ST_SetSRID(ST_MakePoint(lng, lat), 4326), degree, component
+             FROM UNNEST($1::bigint[], $2::float8[], $3::float8[], $4::int[], $5::int[])
+                 AS n(id, lng, lat, degree, component)",
+        )
+        .bind(&ids)
+        .bind(chunk.iter().map(|n| n.position.0).collect::<Vec<_>>())
+        .bind(chunk.iter().map(|n| n.position.1).collect::<Vec<_>>())
+        .bind(chunk.iter().map(|n| n.degree as i32).collect::<Vec<_>>())
+        .bind(chunk.iter().map(|n| n.component as i32).collect::<Vec<_>>())
+        .persistent(false)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    for (chunk_index, chunk) in topology.edges.chunks(INSERT_CHUNK).enumerate() {
+        let first = (chunk_index * INSERT_CHUNK) as i64;
+        let ids: Vec<i64> = (first..first + chunk.len() as i64).collect();
+        let geometries: Vec<String> = chunk
+            .iter()
+            .map(|e| to_linestring(&e.points).to_string())
+            .collect();
+        sqlx::query(
+            "INSERT INTO track_edges
+                 (id, track_id, source_node, target_node, geometry, length_m, component)
+             SELECT e.id, e.track_id, e.source_node, e.target_node,
+                    ST_SetSRID(ST_GeomFromGeoJSON(e.geometry), 4326), e.length_m, n.component
+             FROM UNNEST($1::bigint[], $2::uuid[], $3::bigint[], $4::bigint[], $5::text[], $6::float8[])
+                 AS e(id, track_id, source_node, target_node, geometry, length_m)
+             JOIN track_nodes n ON n.id = e.source_node",
+        )
+        .bind(&ids)
+        .bind(chunk.iter().map(|e| e.track_id).collect::<Vec<_>>())
+        .bind(chunk.iter().map(|e| e.source as i64).collect::<Vec<_>>())
+        .bind(chunk.iter().map(|e| e.target as i64).collect::<Vec<_>>())
+        .bind(&geometries)
+        .bind(chunk.iter().map(|e| e.length_m).collect::<Vec<_>>())
+        .persistent(false)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    let build = sqlx::query_as::<_, TopologyBuild>(
+        "INSERT INTO track_topology_builds
+             (tolerance_m, node_count, edge_count, component_count, built_by)
+         VALUES ($1, $2, $3, $4, $5)
+         RETURNING *",
+    )
+    .bind(tolerance_m)
+    .bind(topology.nodes.len() as i32)
+    .bind(topology.edges.len() as i32)
+    .bind(topology.components as i32)
+    .bind(built_by)
+    .persistent(false)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(build)
+}
@@ -0,0 +1,74 @@
+use serde_json::{json, Value};
+
+use crate::geometry::RouteAnalysis;
+use crate::models::RoutePoi;
+
+/// Render a route as a GeoJSON FeatureCollection
+///
+/// The first feature is the routed LineString with per-vertex headings in
+/// its properties; each control point follows as a Point feature, then each
+/// nearby POI with its position along the route.
+pub fn route_to_geojson(name: &str, analysis: &RouteAnalysis, pois: &[RoutePoi]) -> Value {
+    let mut features = Vec::with_capacity(analysis.waypoints.len() + pois.len() + 1);
+
+    features.push(json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "LineString",
+            "coordinates": analysis.points.iter().map(|p| vec![p.lng, p.lat]).collect::<Vec<_>>()
+        },
+        "properties": {
+            "name": name,
+            "kind": "route",
+            "length_km": analysis.length_km,
+            "magnetic_model": analysis.magnetic_model,
+            "date": analysis.date,
+            "true_bearings": analysis.points.iter().map(|p| p.true_bearing).collect::<Vec<_>>(),
+            "magnetic_headings": analysis.points.iter().map(|p| p.magnetic_heading).collect::<Vec<_>>()
+        }
+    }));
+
+    for wp in &analysis.waypoints {
+        let mut feature = json!({
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [wp.lng, wp.lat] },
+            "properties": {
+                "kind": "waypoint",
+                "name": format!("WP{}", wp.index + 1),
+                "distance_km": wp.distance_km,
+                "true_bearing": wp.true_bearing,
+                "declination": wp.declination,
+                "magnetic_heading": wp.magnetic_heading
+            }
+        });
+        if let Some(coordinate) = &wp.coordinate {
+            feature["properties"]["coordinate"] = json!(coordinate);
+        }
+        features.push(feature);
+    }
+
+    for poi in pois {
+        features.push(json!({
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [poi.poi.lng, poi.poi.lat] },
+            "properties": {
+                "kind": "poi",
+                "id": poi.poi.id,
+                "category": poi.poi.category,
+                "name": poi.poi.name,
+                "attributes": poi.poi.attributes,
+                "source": poi.poi.source,
+                "confidence": poi.poi.confidence,
+                "last_verified": poi.poi.last_verified,
+                "km": poi.km,
+                "offset_m": poi.offset_m,
+                "side": poi.side
+            }
+        }));
+    }
+
+    json!({
+        "type": "FeatureCollection",
+        "features": features
+    })
+}
@@ -0,0 +1,153 @@
+use std::fmt::Write;
+
+use crate::geometry::linear_ref::Side;
+use crate::geometry::RouteAnalysis;
+use crate::models::RoutePoi;
+
+/// Escape text for inclusion in XML element content or attributes
+pub(crate) fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Render a route as GPX 1.1
+///
+/// Control points become `<wpt>` elements whose comment carries the magnetic
+/// CAP to the next waypoint, followed by nearby POIs typed by category; the
+/// routed geometry becomes a single track.
+pub fn route_to_gpx(name: &str, analysis: &RouteAnalysis, pois: &[RoutePoi]) -> String {
+    let name = xml_escape(name);
+    let mut gpx = String::new();
+
+    gpx.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    gpx.push_str(
+        "<gpx version=\"1.1\" creator=\"Dakar Planner\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n",
+    );
+    let _ = writeln!(gpx, "  <metadata><name>{}</name></metadata>", name);
+
+    for wp in &analysis.waypoints {
+        let _ = writeln!(gpx, "  <wpt lat=\"{:.7}\" lon=\"{:.7}\">", wp.lat, wp.lng);
+        let _ = writeln!(gpx, "    <name>WP{}</name>", wp.index + 1);
+        let _ = writeln!(
+            gpx,
+            "    <cmt>CAP {:03.0}</cmt>",
+            wp.magnetic_heading.round().rem_euclid(360.0)
+        );
+        let position = wp
+            .coordinate
+            .as_ref()
+            .map(|c| format!("{} | ", xml_escape(c)))
+            .unwrap_or_default();
+        let _ = writeln!(
+            gpx,
+            "    <desc>{}km {:.2} | CAP {:.1}° magnetic | {:.1}° true | declination {:+.1}° ({} {})</desc>",
+            position,
+            wp.distance_km,
+            wp.magnetic_heading,
+            wp.true_bearing,
+            wp.declination,
+            xml_escape(&analysis.magnetic_model),
+            analysis.date
+        );
+        gpx.push_str("  </wpt>\n");
+    }
+
+    for poi in pois {
+        let _ = writeln!(
+            gpx,
+            "  <wpt lat=\"{:.7}\" lon=\"{:.7}\">",
+            poi.poi.lat, poi.poi.lng
+        );
+        let _ = writeln!(
+            gpx,
+            "    <name>{}</name>",
+            xml_escape(poi.poi.name.as_deref().unwrap_or(&poi.poi.category))
+        );
+        let side = match poi.side {
+            Side::Left => " left",
+            Side::Right => " right",
+            Side::On => "",
+        };
+        let _ = writeln!(
+            gpx,
+            "    <desc>km {:.2} | {:.1} km{} | confidence {}/5</desc>",
+            poi.km,
+            poi.offset_m / 1000.0,
+            side,
+            poi.poi.confidence
+        );
+        let _ = writeln!(gpx, "    <src>{}</src>", xml_escape(&poi.poi.source));
+        let _ = writeln!(gpx, "    <type>{}</type>", xml_escape(&poi.poi.category));
+        gpx.push_str("  </wpt>\n");
+    }
+
+    let _ = writeln!(gpx, "  <trk>\n    <name>{}</name>\n    <trkseg>", name);
+    for p in &analysis.points {
+        let _ = writeln!(
+            gpx,
+            "      <trkpt lat=\"{:.7}\" lon=\"{:.7}\"><cmt>CAP {:.1}</cmt></trkpt>",
+            p.lat, p.lng, p.magnetic_heading
+        );
+    }
+    gpx.push_str("    </trkseg>\n  </trk>\n</gpx>\n");
+
+    gpx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::analyze_route;
+    use crate::geometry::types::ControlPoint;
+    use crate::models::Poi;
+    use chrono::{NaiveDate, Utc};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_gpx_contains_waypoints_and_cap() {
+        let geometry = serde_json::json!({
+            "type": "MultiLineString",
+            "coordinates": [[[-5.0, 20.0], [-4.9, 20.1]]]
+        });
+        let control_points: Vec<ControlPoint> = serde_json::from_value(serde_json::json!([
+            {"lng": -5.0, "lat": 20.0},
+            {"lng": -4.9, "lat": 20.1}
+        ]))
+        .unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let analysis = analyze_route(&geometry, &control_points, date).unwrap();
+
+        let well = RoutePoi {
+            poi: Poi {
+                id: Uuid::nil(),
+                category: "water_well".to_string(),
+                name: None,
+                lng: -4.95,
+                lat: 20.06,
+                attributes: serde_json::json!({}),
+                source: "rally".to_string(),
+                confidence: 4,
+                last_verified: None,
+                created_by: None,
+                updated_by: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            },
+            km: 7.5,
+            offset_m: 1200.0,
+            side: Side::Left,
+        };
+
+        let gpx = route_to_gpx("Stage <1> & co", &analysis, &[well]);
+
+        assert!(gpx.contains("<name>Stage &lt;1&gt; &amp; co</name>"));
+        assert_eq!(gpx.matches("<wpt ").count(), 3);
+        assert!(gpx.contains("<desc>km 7.50 | 1.2 km left | confidence 4/5</desc>"));
+        assert!(gpx.contains("<type>water_well</type>"));
+        assert_eq!(gpx.matches("<trkpt ").count(), 2);
+        assert!(gpx.contains("<cmt>CAP "));
+    }
+}
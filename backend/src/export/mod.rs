@@ -0,0 +1,41 @@
+//! Route exports for navigation devices and GIS tools
+//!
+//! Every export carries magnetic headings (CAP) alongside true bearings so
+//! roadbook compasses match the plan.
+
+pub mod geojson;
+pub mod gpx;
+
+pub use self::geojson::route_to_geojson;
+pub use gpx::route_to_gpx;
+
+/// Supported export formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Gpx,
+    GeoJson,
+}
+
+impl ExportFormat {
+    pub fn parse(format: &str) -> Option<Self> {
+        match format.to_ascii_lowercase().as_str() {
+            "gpx" => Some(Self::Gpx),
+            "geojson" | "json" => Some(Self::GeoJson),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Gpx => "application/gpx+xml",
+            Self::GeoJson => "application/geo+json",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Gpx => "gpx",
+            Self::GeoJson => "geojson",
+        }
+    }
+}
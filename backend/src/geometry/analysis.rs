@@ -0,0 +1,187 @@
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use geo::{HaversineBearing, HaversineDistance, Point};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::coordinates::{display_coordinate, CoordinateFormat};
+use super::magnetic::{decimal_year, magnetic_heading, MagneticModel};
+use super::types::ControlPoint;
+
+/// Heading information for a single route point or waypoint
+#[derive(Debug, Clone, Serialize)]
+pub struct HeadingPoint {
+    pub index: usize,
+    pub lng: f64,
+    pub lat: f64,
+    /// Distance from the start of the route (km)
+    pub distance_km: f64,
+    /// WGS84 true bearing towards the next point (degrees, 0-360)
+    pub true_bearing: f64,
+    /// Magnetic declination at this point (degrees, positive east)
+    pub declination: f64,
+    /// Magnetic heading (CAP) towards the next point (degrees, 0-360)
+    pub magnetic_heading: f64,
+    /// Position in the requested coordinate format, waypoints only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coordinate: Option<String>,
+}
+
+/// Route analysis output: length plus true and magnetic headings
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteAnalysis {
+    pub length_km: f64,
+    /// Magnetic model used for CAP values (e.g. "WMM-2025")
+    pub magnetic_model: String,
+    /// Date the magnetic headings were computed for
+    pub date: NaiveDate,
+    /// False when `date` falls outside the model's validity window
+    pub magnetic_model_valid: bool,
+    /// Headings along the routed geometry
+    pub points: Vec<HeadingPoint>,
+    /// Headings from each control point to the next one
+    pub waypoints: Vec<HeadingPoint>,
+}
+
+/// Compute true and magnetic headings for every consecutive pair of points
+///
+/// The last point repeats the bearing of its incoming leg, so every point
+/// carries a heading. Distances are cumulative across the whole sequence.
+pub fn headings(points: &[(f64, f64)], date: NaiveDate) -> Vec<HeadingPoint> {
+    let model = MagneticModel::wmm();
+    let year = decimal_year(date);
+
+    let mut result = Vec::with_capacity(points.len());
+    let mut distance_m = 0.0;
+
+    for (i, &(lng, lat)) in points.iter().enumerate() {
+        let here = Point::new(lng, lat);
+
+        if i > 0 {
+            let (prev_lng, prev_lat) = points[i - 1];
+            distance_m += Point::new(prev_lng, prev_lat).haversine_distance(&here);
+        }
+
+        let bearing = match (points.get(i + 1), i.checked_sub(1)) {
+            (Some(&(next_lng, next_lat)), _) => {
+                here.haversine_bearing(Point::new(next_lng, next_lat))
+            }
+            (None, Some(prev)) => {
+                let (prev_lng, prev_lat) = points[prev];
+                Point::new(prev_lng, prev_lat).haversine_bearing(here)
+            }
+            (None, None) => 0.0,
+        };
+        let true_bearing = bearing.rem_euclid(360.0);
+        let declination = model.declination(lat, lng, 0.0, year);
+
+        result.push(HeadingPoint {
+            index: i,
+            lng,
+            lat,
+            distance_km: distance_m / 1000.0,
+            true_bearing,
+            declination,
+            magnetic_heading: magnetic_heading(true_bearing, declination),
+            coordinate: None,
+        });
+    }
+
+    result
+}
+
+impl RouteAnalysis {
+    /// Label every waypoint with its position in `format`
+    pub fn with_coordinate_format(mut self, format: CoordinateFormat) -> Self {
+        for wp in &mut self.waypoints {
+            wp.coordinate = display_coordinate(wp.lng, wp.lat, format);
+        }
+        self
+    }
+}
+
+/// Analyze a route: total length plus true/magnetic headings
+///
+/// # Arguments
+/// * `geometry` - GeoJSON MultiLineString of the routed geometry
+/// * `control_points` - The route's control points (waypoints)
+/// * `date` - Date for which magnetic declination is computed
+pub fn analyze_route(
+    geometry: &Value,
+    control_points: &[ControlPoint],
+    date: NaiveDate,
+) -> Result<RouteAnalysis> {
+    let coords = geometry["coordinates"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Invalid geometry: missing coordinates"))?;
+
+    let route_points: Vec<(f64, f64)> = coords
+        .iter()
+        .filter_map(|line| line.as_array())
+        .flatten()
+        .filter_map(|p| {
+            let arr = p.as_array()?;
+            Some((arr.first()?.as_f64()?, arr.get(1)?.as_f64()?))
+        })
+        .collect();
+
+    let waypoint_positions: Vec<(f64, f64)> =
+        control_points.iter().map(ControlPoint::as_tuple).collect();
+
+    let points = headings(&route_points, date);
+    let length_km = points.last().map(|p| p.distance_km).unwrap_or(0.0);
+    let model = MagneticModel::wmm();
+
+    Ok(RouteAnalysis {
+        length_km,
+        magnetic_model: model.name.clone(),
+        date,
+        magnetic_model_valid: model.is_valid_for(decimal_year(date)),
+        points,
+        waypoints: headings(&waypoint_positions, date),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 1, 15).unwrap()
+    }
+
+    #[test]
+    fn test_headings_due_north() {
+        let points = vec![(-5.0, 20.0), (-5.0, 20.1), (-5.0, 20.2)];
+        let result = headings(&points, date());
+
+        assert_eq!(result.len(), 3);
+        for p in &result {
+            assert!(p.true_bearing < 0.01 || p.true_bearing > 359.99);
+            let expected = magnetic_heading(p.true_bearing, p.declination);
+            assert!((p.magnetic_heading - expected).abs() < 1e-9);
+        }
+        // ~11.1 km per 0.1° of latitude
+        assert!((result[2].distance_km - 22.2).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_analyze_route_with_both_control_point_formats() {
+        let geometry = serde_json::json!({
+            "type": "MultiLineString",
+            "coordinates": [[[-5.0, 20.0], [-4.9, 20.0]]]
+        });
+        let control_points: Vec<ControlPoint> = serde_json::from_value(serde_json::json!([
+            {"lng": -5.0, "lat": 20.0},
+            {"type": "Point", "coordinates": [-4.9, 20.0]}
+        ]))
+        .unwrap();
+
+        let analysis = analyze_route(&geometry, &control_points, date()).unwrap();
+
+        assert_eq!(analysis.points.len(), 2);
+        assert_eq!(analysis.waypoints.len(), 2);
+        assert!((analysis.points[0].true_bearing - 90.0).abs() < 0.1);
+        assert!(analysis.magnetic_model_valid);
+    }
+}
@@ -0,0 +1,1063 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::types::Position;
+
+/// Textual coordinate notations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoordinateFormat {
+    /// Signed decimal degrees, latitude first: `20.123456, -5.654321`
+    #[default]
+    Dd,
+    /// Degrees, minutes, seconds: `20°07'24.4"N 5°39'15.6"W`
+    Dms,
+    /// Degrees and decimal minutes: `20°07.407'N 5°39.259'W`
+    Ddm,
+    /// UTM zone, band, easting, northing: `30Q 430912 2225188`
+    Utm,
+    /// Military Grid Reference System at 1 m: `30Q TB 30912 25188`
+    Mgrs,
+}
+
+/// Why a textual coordinate was rejected
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum CoordinateError {
+    #[error("{0}")]
+    Invalid(String),
+    /// Readable in more than one way; the caller has to be explicit
+    #[error("{0}")]
+    Ambiguous(String),
+}
+
+impl CoordinateError {
+    fn invalid(message: impl Into<String>) -> Self {
+        Self::Invalid(message.into())
+    }
+
+    fn ambiguous(message: impl Into<String>) -> Self {
+        Self::Ambiguous(message.into())
+    }
+}
+
+type Result<T> = std::result::Result<T, CoordinateError>;
+
+/// A parsed coordinate and the notation it was written in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParsedCoordinate {
+    pub position: Position,
+    pub format: CoordinateFormat,
+}
+
+/// Parse a coordinate in any supported notation
+///
+/// MGRS and UTM are recognized by their zone designator. Everything else is
+/// read as latitude/longitude in decimal degrees, DDM or DMS; hemisphere
+/// letters (prefix or suffix) decide which part is which, otherwise the
+/// latitude comes first (ISO 6709 order).
+pub fn parse_coordinate(input: &str) -> Result<ParsedCoordinate> {
+    let text = input.trim().to_uppercase();
+    if text.is_empty() {
+        return Err(CoordinateError::invalid("Coordinate is empty"));
+    }
+
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if looks_like_mgrs(&compact) {
+        return Ok(ParsedCoordinate {
+            position: parse_mgrs(&compact)?,
+            format: CoordinateFormat::Mgrs,
+        });
+    }
+
+    let words: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty())
+        .collect();
+    if let Some(utm) = utm_parts(&words) {
+        return Ok(ParsedCoordinate {
+            position: parse_utm(utm)?,
+            format: CoordinateFormat::Utm,
+        });
+    }
+
+    parse_lat_lng(input.trim())
+}
+
+/// Render a position in the given notation
+///
+/// UTM and MGRS fail outside 80°S to 84°N (polar UPS is not supported).
+pub fn format_coordinate(position: Position, format: CoordinateFormat) -> Result<String> {
+    let Position { lng, lat } = position;
+    Ok(match format {
+        CoordinateFormat::Dd => format!("{:.6}, {:.6}", lat, lng),
+        CoordinateFormat::Dms => format!(
+            "{} {}",
+            format_dms(lat, ['N', 'S']),
+            format_dms(lng, ['E', 'W'])
+        ),
+        CoordinateFormat::Ddm => format!(
+            "{} {}",
+            format_ddm(lat, ['N', 'S']),
+            format_ddm(lng, ['E', 'W'])
+        ),
+        CoordinateFormat::Utm => {
+            let utm = to_utm(position)?;
+            format!(
+                "{}{} {} {}",
+                utm.zone,
+                utm.band,
+                truncate_meters(utm.easting),
+                truncate_meters(utm.northing)
+            )
+        }
+        CoordinateFormat::Mgrs => format_mgrs(position)?,
+    })
+}
+
+/// Render a `(lng, lat)` pair for display; `None` when it cannot be expressed
+/// in `format` (out of range, or polar for UTM/MGRS)
+pub fn display_coordinate(lng: f64, lat: f64, format: CoordinateFormat) -> Option<String> {
+    let position = Position::new(lng, lat).ok()?;
+    format_coordinate(position, format).ok()
+}
+
+// ---------------------------------------------------------------------------
+// Latitude / longitude (DD, DDM, DMS)
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Unit {
+    Degrees,
+    Minutes,
+    Seconds,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Number {
+        value: f64,
+        signed: bool,
+        negative: bool,
+    },
+    Unit(Unit),
+    Hemisphere(char),
+    Separator,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let end_of = |i: usize| chars.get(i).map(|&(b, _)| b).unwrap_or(text.len());
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        let kind = match c.to_ascii_uppercase() {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ',' | ';' => TokenKind::Separator,
+            '°' | 'º' | '˚' => TokenKind::Unit(Unit::Degrees),
+            '"' | '″' | '”' => TokenKind::Unit(Unit::Seconds),
+            '\'' | '′' | '’' => {
+                if matches!(chars.get(i + 1), Some((_, '\'' | '′' | '’'))) {
+                    i += 1;
+                    TokenKind::Unit(Unit::Seconds)
+                } else {
+                    TokenKind::Unit(Unit::Minutes)
+                }
+            }
+            h @ ('N' | 'S' | 'E' | 'W') => TokenKind::Hemisphere(h),
+            '+' | '-' | '.' | '0'..='9' => {
+                let mut j = i + 1;
+                while j < chars.len() && matches!(chars[j].1, '.' | '0'..='9') {
+                    j += 1;
+                }
+                let literal = &text[start..end_of(j)];
+                let value: f64 = literal.parse().map_err(|_| {
+                    CoordinateError::invalid(format!("'{}' is not a number", literal))
+                })?;
+                i = j - 1;
+                TokenKind::Number {
+                    value: value.abs(),
+                    signed: c == '+' || c == '-',
+                    negative: c == '-',
+                }
+            }
+            other => {
+                return Err(CoordinateError::invalid(format!(
+                    "Unexpected character '{}' in '{}'",
+                    other, text
+                )))
+            }
+        };
+        i += 1;
+        tokens.push(Token {
+            kind,
+            start,
+            end: end_of(i),
+        });
+    }
+
+    Ok(tokens)
+}
+
+fn is_number(token: &Token) -> bool {
+    matches!(token.kind, TokenKind::Number { .. })
+}
+
+fn is_hemisphere(token: &Token) -> bool {
+    matches!(token.kind, TokenKind::Hemisphere(_))
+}
+
+/// Split tokens into the two halves of a coordinate pair
+fn split_pair(text: &str, tokens: Vec<Token>) -> Result<Vec<Vec<Token>>> {
+    let separators = tokens
+        .iter()
+        .filter(|t| t.kind == TokenKind::Separator)
+        .count();
+    if separators > 1 {
+        return Err(CoordinateError::invalid(format!(
+            "'{}' has {} separators; use one comma between latitude and longitude",
+            text, separators
+        )));
+    }
+
+    let mut groups: Vec<Vec<Token>> = vec![Vec::new()];
+    let push_group = |groups: &mut Vec<Vec<Token>>| {
+        if !groups.last().is_some_and(|g| g.is_empty()) {
+            groups.push(Vec::new());
+        }
+    };
+
+    if separators == 1 {
+        for token in tokens {
+            if token.kind == TokenKind::Separator {
+                push_group(&mut groups);
+            } else {
+                groups.last_mut().unwrap().push(token);
+            }
+        }
+    } else if tokens.iter().any(is_hemisphere) {
+        // Hemisphere letters either open (N20 30) or close (20 30 N) each half
+        let prefix = tokens.first().is_some_and(is_hemisphere);
+        for token in tokens {
+            let hemisphere = is_hemisphere(&token);
+            if hemisphere && prefix {
+                push_group(&mut groups);
+            }
+            groups.last_mut().unwrap().push(token);
+            if hemisphere && !prefix {
+                push_group(&mut groups);
+            }
+        }
+    } else if tokens.iter().any(|t| matches!(t.kind, TokenKind::Unit(_))) {
+        // A number marked as degrees starts the second half
+        for (i, token) in tokens.iter().enumerate() {
+            let opens_degrees = is_number(token)
+                && matches!(
+                    tokens.get(i + 1).map(|t| &t.kind),
+                    Some(TokenKind::Unit(Unit::Degrees))
+                );
+            if opens_degrees {
+                push_group(&mut groups);
+            }
+            groups.last_mut().unwrap().push(token.clone());
+        }
+    } else {
+        let count = tokens.len();
+        if !count.is_multiple_of(2) || count > 6 {
+            return Err(CoordinateError::ambiguous(format!(
+                "Cannot tell where latitude ends and longitude begins in '{}'; \
+                 separate them with a comma or add hemisphere letters",
+                text
+            )));
+        }
+        let second = tokens[count / 2..].to_vec();
+        groups = vec![tokens[..count / 2].to_vec(), second];
+    }
+
+    groups.retain(|g| !g.is_empty());
+    if groups.len() != 2 {
+        return Err(CoordinateError::invalid(format!(
+            "Expected a latitude and a longitude in '{}', found {} part(s)",
+            text,
+            groups.len()
+        )));
+    }
+    Ok(groups)
+}
+
+/// One half of a latitude/longitude pair
+struct Component<'a> {
+    text: &'a str,
+    /// Unsigned decimal degrees
+    degrees: f64,
+    negative: bool,
+    hemisphere: Option<char>,
+    parts: usize,
+}
+
+fn parse_component<'a>(text: &'a str, tokens: &[Token]) -> Result<Component<'a>> {
+    let source = &text[tokens[0].start..tokens[tokens.len() - 1].end];
+    let mut hemisphere = None;
+    let mut numbers: Vec<(f64, &str)> = Vec::new();
+    let mut negative = false;
+    let mut units: Vec<Unit> = Vec::new();
+
+    for token in tokens {
+        match token.kind {
+            TokenKind::Hemisphere(h) => {
+                if hemisphere.replace(h).is_some() {
+                    return Err(CoordinateError::invalid(format!(
+                        "'{}' has more than one hemisphere letter",
+                        source
+                    )));
+                }
+            }
+            TokenKind::Number {
+                value,
+                signed,
+                negative: minus,
+            } => {
+                if signed && !numbers.is_empty() {
+                    return Err(CoordinateError::invalid(format!(
+                        "Only the degrees in '{}' may carry a sign",
+                        source
+                    )));
+                }
+                negative |= minus;
+                numbers.push((value, &text[token.start..token.end]));
+            }
+            TokenKind::Unit(unit) => {
+                if units.len() + 1 != numbers.len() {
+                    return Err(CoordinateError::invalid(format!(
+                        "Misplaced unit symbol in '{}'",
+                        source
+                    )));
+                }
+                units.push(unit);
+            }
+            TokenKind::Separator => unreachable!("separators are split off"),
+        }
+    }
+
+    if numbers.is_empty() {
+        return Err(CoordinateError::invalid(format!(
+            "'{}' has no degrees",
+            source
+        )));
+    }
+    if numbers.len() > 3 {
+        return Err(CoordinateError::invalid(format!(
+            "'{}' has {} numbers; expected degrees, minutes and seconds at most",
+            source,
+            numbers.len()
+        )));
+    }
+    let expected = [Unit::Degrees, Unit::Minutes, Unit::Seconds];
+    if units.iter().zip(expected).any(|(u, e)| *u != e) {
+        return Err(CoordinateError::invalid(format!(
+            "Units in '{}' must be degrees, then minutes, then seconds",
+            source
+        )));
+    }
+    if let Some((_, literal)) = numbers[..numbers.len() - 1]
+        .iter()
+        .find(|(value, _)| value.fract() != 0.0)
+    {
+        return Err(CoordinateError::invalid(format!(
+            "Only the last value in '{}' may have decimals, but '{}' does",
+            source, literal
+        )));
+    }
+    for (i, name) in [(1, "Minutes"), (2, "Seconds")] {
+        if let Some((value, literal)) = numbers.get(i) {
+            if *value >= 60.0 {
+                return Err(CoordinateError::invalid(format!(
+                    "{} must be below 60, got '{}' in '{}'",
+                    name, literal, source
+                )));
+            }
+        }
+    }
+    if negative && hemisphere.is_some() {
+        return Err(CoordinateError::ambiguous(format!(
+            "'{}' has both a minus sign and a hemisphere letter",
+            source
+        )));
+    }
+
+    let degrees = numbers
+        .iter()
+        .zip([1.0, 60.0, 3600.0])
+        .map(|((value, _), divisor)| value / divisor)
+        .sum();
+
+    Ok(Component {
+        text: source,
+        degrees,
+        negative,
+        hemisphere,
+        parts: numbers.len(),
+    })
+}
+
+fn parse_lat_lng(text: &str) -> Result<ParsedCoordinate> {
+    let groups = split_pair(text, tokenize(text)?)?;
+    let first = parse_component(text, &groups[0])?;
+    let second = parse_component(text, &groups[1])?;
+    let format = match first.parts.max(second.parts) {
+        1 => CoordinateFormat::Dd,
+        2 => CoordinateFormat::Ddm,
+        _ => CoordinateFormat::Dms,
+    };
+
+    let is_lat = |c: &Component| c.hemisphere.map(|h| h == 'N' || h == 'S');
+    let (lat, lng) = match (is_lat(&first), is_lat(&second)) {
+        (Some(true), Some(true)) | (Some(false), Some(false)) => {
+            return Err(CoordinateError::invalid(format!(
+                "'{}' and '{}' are on the same axis; give one N/S and one E/W",
+                first.text, second.text
+            )))
+        }
+        (Some(false), _) | (None, Some(true)) => (second, first),
+        _ => (first, second),
+    };
+
+    let signed = |c: &Component| {
+        let south_or_west = matches!(c.hemisphere, Some('S' | 'W'));
+        if c.negative || south_or_west {
+            -c.degrees
+        } else {
+            c.degrees
+        }
+    };
+    let (lat_deg, lng_deg) = (signed(&lat), signed(&lng));
+
+    if lat_deg.abs() > 90.0 {
+        let hint = if lat.hemisphere.is_none() && lng_deg.abs() <= 90.0 {
+            "; plain coordinates are read latitude first"
+        } else {
+            ""
+        };
+        return Err(CoordinateError::invalid(format!(
+            "Latitude '{}' is outside -90..90{}",
+            lat.text, hint
+        )));
+    }
+    if lng_deg.abs() > 180.0 {
+        return Err(CoordinateError::invalid(format!(
+            "Longitude '{}' is outside -180..180",
+            lng.text
+        )));
+    }
+
+    Ok(ParsedCoordinate {
+        position: Position::new(lng_deg, lat_deg).map_err(CoordinateError::Invalid)?,
+        format,
+    })
+}
+
+fn format_dms(value: f64, hemispheres: [char; 2]) -> String {
+    let tenths = (value.abs() * 36_000.0).round() as u64;
+    format!(
+        "{}°{:02}'{:04.1}\"{}",
+        tenths / 36_000,
+        tenths / 600 % 60,
+        (tenths % 600) as f64 / 10.0,
+        hemispheres[usize::from(value < 0.0)]
+    )
+}
+
+fn format_ddm(value: f64, hemispheres: [char; 2]) -> String {
+    let thousandths = (value.abs() * 60_000.0).round() as u64;
+    format!(
+        "{}°{:06.3}'{}",
+        thousandths / 60_000,
+        (thousandths % 60_000) as f64 / 1000.0,
+        hemispheres[usize::from(value < 0.0)]
+    )
+}
+
+// ---------------------------------------------------------------------------
+// UTM (WGS84, Krüger series)
+// ---------------------------------------------------------------------------
+
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+const UTM_K0: f64 = 0.9996;
+const UTM_FALSE_EASTING: f64 = 500_000.0;
+const UTM_FALSE_NORTHING_SOUTH: f64 = 10_000_000.0;
+
+/// Latitude band letters from 80°S, 8° each (X spans 72°N to 84°N)
+const BANDS: &[u8] = b"CDEFGHJKLMNPQRSTUVWX";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Utm {
+    zone: u8,
+    band: char,
+    easting: f64,
+    northing: f64,
+}
+
+struct Kruger {
+    /// Rectifying radius times k0
+    k0_a: f64,
+    alpha: [f64; 3],
+    beta: [f64; 3],
+    delta: [f64; 3],
+    n: f64,
+}
+
+fn kruger() -> Kruger {
+    let n = WGS84_F / (2.0 - WGS84_F);
+    let (n2, n3) = (n * n, n * n * n);
+    Kruger {
+        k0_a: UTM_K0 * WGS84_A / (1.0 + n) * (1.0 + n2 / 4.0 + n2 * n2 / 64.0),
+        alpha: [
+            n / 2.0 - 2.0 * n2 / 3.0 + 5.0 * n3 / 16.0,
+            13.0 * n2 / 48.0 - 3.0 * n3 / 5.0,
+            61.0 * n3 / 240.0,
+        ],
+        beta: [
+            n / 2.0 - 2.0 * n2 / 3.0 + 37.0 * n3 / 96.0,
+            n2 / 48.0 + n3 / 15.0,
+            17.0 * n3 / 480.0,
+        ],
+        delta: [
+            2.0 * n - 2.0 * n2 / 3.0 - 2.0 * n3,
+            7.0 * n2 / 3.0 - 8.0 * n3 / 5.0,
+            56.0 * n3 / 15.0,
+        ],
+        n,
+    }
+}
+
+fn central_meridian(zone: u8) -> f64 {
+    zone as f64 * 6.0 - 183.0
+}
+
+/// UTM zone, including the Norway and Svalbard exceptions
+fn utm_zone(position: Position) -> u8 {
+    let Position { lng, lat } = position;
+    if (56.0..64.0).contains(&lat) && (3.0..12.0).contains(&lng) {
+        return 32;
+    }
+    if (72.0..=84.0).contains(&lat) && (0.0..42.0).contains(&lng) {
+        return match lng {
+            l if l < 9.0 => 31,
+            l if l < 21.0 => 33,
+            l if l < 33.0 => 35,
+            _ => 37,
+        };
+    }
+    (((lng + 180.0) / 6.0).floor() as u8 % 60) + 1
+}
+
+fn band_letter(lat: f64) -> Result<char> {
+    if !(-80.0..=84.0).contains(&lat) {
+        return Err(CoordinateError::invalid(format!(
+            "Latitude {:.6} is outside UTM/MGRS coverage (80°S to 84°N); polar UPS is not supported",
+            lat
+        )));
+    }
+    let index = (((lat + 80.0) / 8.0).floor() as usize).min(BANDS.len() - 1);
+    Ok(BANDS[index] as char)
+}
+
+/// Project into a given zone (the zone need not contain the position)
+fn project(position: Position, zone: u8) -> (f64, f64) {
+    let k = kruger();
+    let phi = position.lat.to_radians();
+    let lambda = (position.lng - central_meridian(zone)).to_radians();
+
+    let e = 2.0 * k.n.sqrt() / (1.0 + k.n);
+    let t = (phi.sin().atanh() - e * (e * phi.sin()).atanh()).sinh();
+    let xi = t.atan2(lambda.cos());
+    let eta = (lambda.sin() / (1.0 + t * t).sqrt()).atanh();
+
+    let mut easting = eta;
+    let mut northing = xi;
+    for (j, alpha) in k.alpha.iter().enumerate() {
+        let m = 2.0 * (j + 1) as f64;
+        easting += alpha * (m * xi).cos() * (m * eta).sinh();
+        northing += alpha * (m * xi).sin() * (m * eta).cosh();
+    }
+
+    let false_northing = if position.lat < 0.0 {
+        UTM_FALSE_NORTHING_SOUTH
+    } else {
+        0.0
+    };
+    (
+        UTM_FALSE_EASTING + k.k0_a * easting,
+        false_northing + k.k0_a * northing,
+    )
+}
+
+/// Grid references truncate to the containing meter; the millimeter of
+/// slack keeps parsed grid corners from falling into the previous one
+fn truncate_meters(value: f64) -> f64 {
+    (value + 0.001).floor()
+}
+
+fn to_utm(position: Position) -> Result<Utm> {
+    let band = band_letter(position.lat)?;
+    let zone = utm_zone(position);
+    let (easting, northing) = project(position, zone);
+    Ok(Utm {
+        zone,
+        band,
+        easting,
+        northing,
+    })
+}
+
+fn from_utm(zone: u8, north: bool, easting: f64, northing: f64) -> Result<Position> {
+    let k = kruger();
+    let false_northing = if north { 0.0 } else { UTM_FALSE_NORTHING_SOUTH };
+    let xi = (northing - false_northing) / k.k0_a;
+    let eta = (easting - UTM_FALSE_EASTING) / k.k0_a;
+
+    let mut xi_p = xi;
+    let mut eta_p = eta;
+    for (j, beta) in k.beta.iter().enumerate() {
+        let m = 2.0 * (j + 1) as f64;
+        xi_p -= beta * (m * xi).sin() * (m * eta).cosh();
+        eta_p -= beta * (m * xi).cos() * (m * eta).sinh();
+    }
+
+    let chi = (xi_p.sin() / eta_p.cosh()).asin();
+    let mut phi = chi;
+    for (j, delta) in k.delta.iter().enumerate() {
+        phi += delta * (2.0 * (j + 1) as f64 * chi).sin();
+    }
+    let lambda = eta_p.sinh().atan2(xi_p.cos());
+
+    let mut lng = central_meridian(zone) + lambda.to_degrees();
+    if lng > 180.0 {
+        lng -= 360.0;
+    } else if lng < -180.0 {
+        lng += 360.0;
+    }
+    Position::new(lng, phi.to_degrees()).map_err(CoordinateError::Invalid)
+}
+
+/// How the letter after a UTM zone number should be read
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ZoneLetter {
+    Band(char),
+    Hemisphere { north: bool },
+}
+
+struct UtmParts<'a> {
+    zone: &'a str,
+    letter: &'a str,
+    easting: &'a str,
+    northing: &'a str,
+}
+
+/// Recognize `30Q 430912 2225188`, `30 Q 430912E 2225188N`, `30 south ...`
+fn utm_parts<'a>(words: &[&'a str]) -> Option<UtmParts<'a>> {
+    let split_zone = |word: &'a str| {
+        let digits = word
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(word.len());
+        (digits > 0 && digits <= 2).then(|| word.split_at(digits))
+    };
+    let is_letters = |word: &str| !word.is_empty() && word.chars().all(|c| c.is_ascii_alphabetic());
+    let is_distance = |word: &str| {
+        word.trim_end_matches(['E', 'N', 'M'])
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '.')
+            && word.starts_with(|c: char| c.is_ascii_digit())
+    };
+
+    let (zone, letter, rest) = match words {
+        [first, rest @ ..] => match split_zone(first)? {
+            (zone, "") if rest.first().is_some_and(|w| is_letters(w)) => {
+                (zone, rest[0], &rest[1..])
+            }
+            (zone, letter) if is_letters(letter) => (zone, letter, rest),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    match rest {
+        [easting, northing] if is_distance(easting) && is_distance(northing) => Some(UtmParts {
+            zone,
+            letter,
+            easting,
+            northing,
+        }),
+        _ => None,
+    }
+}
+
+fn parse_zone(zone: &str) -> Result<u8> {
+    match zone.parse::<u8>() {
+        Ok(z @ 1..=60) => Ok(z),
+        _ => Err(CoordinateError::invalid(format!(
+            "UTM zone '{}' is not between 1 and 60",
+            zone
+        ))),
+    }
+}
+
+fn parse_zone_letter(letter: &str) -> Result<ZoneLetter> {
+    match letter {
+        "NORTH" => Ok(ZoneLetter::Hemisphere { north: true }),
+        "SOUTH" => Ok(ZoneLetter::Hemisphere { north: false }),
+        "S" => Err(CoordinateError::ambiguous(
+            "UTM zone letter 'S' could be latitude band S (32°N to 40°N) or the southern \
+             hemisphere; write 'north' or 'south' after the zone number instead",
+        )),
+        // Band N (0° to 8°N) and "north" agree on the hemisphere
+        "N" => Ok(ZoneLetter::Hemisphere { north: true }),
+        l if l.len() == 1 && BANDS.contains(&l.as_bytes()[0]) => {
+            Ok(ZoneLetter::Band(l.as_bytes()[0] as char))
+        }
+        "A" | "B" | "Y" | "Z" => Err(CoordinateError::invalid(format!(
+            "'{}' is a polar UPS zone, which is not supported",
+            letter
+        ))),
+        l => Err(CoordinateError::invalid(format!(
+            "'{}' is not a UTM latitude band (C to X, without I and O) or north/south",
+            l
+        ))),
+    }
+}
+
+fn parse_distance(word: &str, suffix: char, name: &str) -> Result<f64> {
+    let digits = word
+        .strip_suffix(suffix)
+        .map(|w| w.strip_suffix('M').unwrap_or(w))
+        .unwrap_or(word);
+    digits
+        .parse()
+        .map_err(|_| CoordinateError::invalid(format!("UTM {} '{}' is not a number", name, word)))
+}
+
+fn band_bounds(band: char) -> (f64, f64) {
+    let index = BANDS.iter().position(|&b| b as char == band).unwrap_or(0);
+    let south = -80.0 + 8.0 * index as f64;
+    let north = if band == 'X' { 84.0 } else { south + 8.0 };
+    (south, north)
+}
+
+fn check_band(position: Position, band: char, what: &str) -> Result<()> {
+    let (south, north) = band_bounds(band);
+    // Half a degree of slack for rounding at band edges
+    if position.lat < south - 0.5 || position.lat > north + 0.5 {
+        return Err(CoordinateError::invalid(format!(
+            "{} lies at latitude {:.4}, outside band {} ({}° to {}°)",
+            what, position.lat, band, south, north
+        )));
+    }
+    Ok(())
+}
+
+fn parse_utm(parts: UtmParts) -> Result<Position> {
+    let zone = parse_zone(parts.zone)?;
+    let letter = parse_zone_letter(parts.letter)?;
+    let easting = parse_distance(parts.easting, 'E', "easting")?;
+    let northing = parse_distance(parts.northing, 'N', "northing")?;
+
+    if !(100_000.0..=900_000.0).contains(&easting) {
+        return Err(CoordinateError::invalid(format!(
+            "UTM easting {} is outside 100000..900000",
+            parts.easting
+        )));
+    }
+    if !(0.0..=10_000_000.0).contains(&northing) {
+        return Err(CoordinateError::invalid(format!(
+            "UTM northing {} is outside 0..10000000",
+            parts.northing
+        )));
+    }
+
+    let north = match letter {
+        ZoneLetter::Band(band) => band >= 'N',
+        ZoneLetter::Hemisphere { north } => north,
+    };
+    let position = from_utm(zone, north, easting, northing)?;
+    if let ZoneLetter::Band(band) = letter {
+        check_band(position, band, &format!("Northing {}", parts.northing))?;
+    }
+    Ok(position)
+}
+
+// ---------------------------------------------------------------------------
+// MGRS
+// ---------------------------------------------------------------------------
+
+/// Row letters of 100 km squares (cycle every 2000 km)
+const MGRS_ROWS: &[u8] = b"ABCDEFGHJKLMNPQRSTUV";
+
+/// Column letters of 100 km squares, by zone set
+fn mgrs_columns(zone: u8) -> &'static [u8] {
+    match zone % 3 {
+        1 => b"ABCDEFGH",
+        2 => b"JKLMNPQR",
+        _ => b"STUVWXYZ",
+    }
+}
+
+/// Even zones start their row lettering at F
+fn mgrs_row_offset(zone: u8) -> usize {
+    if zone.is_multiple_of(2) {
+        5
+    } else {
+        0
+    }
+}
+
+fn looks_like_mgrs(compact: &str) -> bool {
+    let digits = compact
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(compact.len());
+    let rest = &compact[digits..];
+    (1..=2).contains(&digits)
+        && rest.len() >= 3
+        && rest[..3].chars().all(|c| c.is_ascii_alphabetic())
+        && rest[3..].chars().all(|c| c.is_ascii_digit())
+}
+
+fn parse_mgrs(compact: &str) -> Result<Position> {
+    let digits = compact.find(|c: char| !c.is_ascii_digit()).unwrap_or(0);
+    let zone = parse_zone(&compact[..digits])?;
+    let letters = &compact.as_bytes()[digits..digits + 3];
+    let numbers = &compact[digits + 3..];
+
+    let band = match parse_zone_letter(&(letters[0] as char).to_string()) {
+        Ok(ZoneLetter::Band(band)) => band,
+        // In MGRS the letter is always a band, so N and S are unambiguous
+        Ok(ZoneLetter::Hemisphere { .. }) | Err(CoordinateError::Ambiguous(_)) => {
+            letters[0] as char
+        }
+        Err(e) => return Err(e),
+    };
+
+    let (column, row) = (letters[1], letters[2]);
+    let column_index = mgrs_columns(zone)
+        .iter()
+        .position(|&c| c == column)
+        .ok_or_else(|| {
+            CoordinateError::invalid(format!(
+                "MGRS column letter '{}' is not used in zone {}",
+                column as char, zone
+            ))
+        })?;
+    let row_index = MGRS_ROWS.iter().position(|&r| r == row).ok_or_else(|| {
+        CoordinateError::invalid(format!(
+            "MGRS row letter '{}' is not valid (A to V, without I and O)",
+            row as char
+        ))
+    })?;
+
+    if !numbers.len().is_multiple_of(2) || numbers.len() > 10 {
+        return Err(CoordinateError::invalid(format!(
+            "MGRS easting and northing must have the same number of digits (up to 5 each), got '{}'",
+            numbers
+        )));
+    }
+    let precision = numbers.len() / 2;
+    let scale = 10f64.powi(5 - precision as i32);
+    let parse_digits = |s: &str| s.parse::<f64>().unwrap_or(0.0) * scale;
+    let (east_digits, north_digits) = numbers.split_at(precision);
+
+    let easting = (column_index + 1) as f64 * 100_000.0 + parse_digits(east_digits);
+    let row_northing = ((row_index + 20 - mgrs_row_offset(zone)) % 20) as f64 * 100_000.0
+        + parse_digits(north_digits);
+
+    // Lift the 2000 km row cycle to the band's latitude
+    let north = band >= 'N';
+    let (band_south, _) = band_bounds(band);
+    let (_, band_min_northing) = project(
+        Position::new(central_meridian(zone), band_south).map_err(CoordinateError::Invalid)?,
+        zone,
+    );
+    let mut northing = row_northing;
+    while northing < band_min_northing - 100_000.0 {
+        northing += 2_000_000.0;
+    }
+
+    let position = from_utm(zone, north, easting, northing)?;
+    check_band(
+        position,
+        band,
+        &format!("Square {}{}", column as char, row as char),
+    )?;
+    Ok(position)
+}
+
+fn format_mgrs(position: Position) -> Result<String> {
+    let utm = to_utm(position)?;
+    let column_index = (utm.easting / 100_000.0).floor() as usize;
+    let row_index = ((utm.northing / 100_000.0).floor() as usize + mgrs_row_offset(utm.zone)) % 20;
+
+    let column = mgrs_columns(utm.zone)
+        .get(column_index.wrapping_sub(1))
+        .ok_or_else(|| {
+            CoordinateError::invalid(format!(
+                "Easting {:.0} is outside the MGRS grid of zone {}",
+                utm.easting, utm.zone
+            ))
+        })?;
+
+    Ok(format!(
+        "{}{} {}{} {:05} {:05}",
+        utm.zone,
+        utm.band,
+        *column as char,
+        MGRS_ROWS[row_index] as char,
+        truncate_meters(utm.easting) as u64 % 100_000,
+        truncate_meters(utm.northing) as u64 % 100_000
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Position {
+        parse_coordinate(input)
+            .unwrap_or_else(|e| panic!("{}: {}", input, e))
+            .position
+    }
+
+    fn assert_close(position: Position, lng: f64, lat: f64, tolerance_deg: f64) {
+        assert!(
+            (position.lng - lng).abs() < tolerance_deg
+                && (position.lat - lat).abs() < tolerance_deg,
+            "{:?} != ({}, {})",
+            position,
+            lng,
+            lat
+        );
+    }
+
+    #[test]
+    fn test_lat_lng_notations() {
+        let expected = (-5.654333, 20.123444);
+        for input in [
+            "20.123444, -5.654333",
+            "20.123444 -5.654333",
+            "N20.123444 W5.654333",
+            "5.654333W 20.123444N",
+            "20°07'24.4\"N 5°39'15.6\"W",
+            "20 07 24.4 N, 5 39 15.6 W",
+            "N 20° 07.4066' W 005° 39.26'",
+            "20°07′24.4″N 5°39′15.6″W",
+            "20°7'24.4'' -5°39'15.6''",
+        ] {
+            assert_close(parse(input), expected.0, expected.1, 1e-4);
+        }
+
+        assert_eq!(
+            parse_coordinate("20 07.4066 N 5 39.26 W").unwrap().format,
+            CoordinateFormat::Ddm
+        );
+    }
+
+    #[test]
+    fn test_rejects_with_reason() {
+        let cases = [
+            ("", "empty"),
+            ("20 07 24.4 5 39", "where latitude ends"),
+            ("20 75 N 5 30 W", "Minutes must be below 60"),
+            ("20.5 30 N 5 W", "may have decimals"),
+            ("-20 S 5 W", "minus sign and a hemisphere"),
+            ("20 N 5 S", "same axis"),
+            ("120, 5", "latitude first"),
+            ("20 x 5", "Unexpected character"),
+            ("31S 500000 4649776", "could be latitude band S"),
+            ("61N 500000 4649776", "not between 1 and 60"),
+            ("31U DH 48251 11932", "outside band U"),
+            ("31U DQ 4825 11932", "same number of digits"),
+            ("31U JQ 48251 11932", "not used in zone 31"),
+        ];
+        for (input, reason) in cases {
+            let error = parse_coordinate(input).unwrap_err().to_string();
+            assert!(error.contains(reason), "{}: {}", input, error);
+        }
+        assert!(matches!(
+            parse_coordinate("31S 500000 4649776"),
+            Err(CoordinateError::Ambiguous(_))
+        ));
+    }
+
+    #[test]
+    fn test_utm_known_point() {
+        // CN Tower, Toronto
+        let position = parse("17T 630084 4833438");
+        assert_close(position, -79.387139, 43.642567, 1e-5);
+        assert_eq!(
+            format_coordinate(position, CoordinateFormat::Utm).unwrap(),
+            "17T 630084 4833438"
+        );
+        assert_close(
+            parse("17 north 630084mE 4833438mN"),
+            -79.387139,
+            43.642567,
+            1e-5,
+        );
+    }
+
+    #[test]
+    fn test_mgrs_known_point() {
+        let position = parse("17T PJ 30084 33438");
+        assert_close(position, -79.387139, 43.642567, 1e-4);
+        assert_close(parse("17tpj3008433438"), -79.387139, 43.642567, 1e-4);
+        assert_close(parse("17T PJ 3008 3343"), -79.387139, 43.642567, 1e-3);
+        assert_eq!(
+            format_coordinate(position, CoordinateFormat::Mgrs).unwrap(),
+            "17T PJ 30084 33438"
+        );
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let places = [
+            (-5.654333, 20.123444),
+            (45.0667, 24.4667),
+            (-68.3, -54.8),
+            (151.2093, -33.8688),
+            (5.3, 60.4),
+            (-179.9, 0.1),
+        ];
+        for (lng, lat) in places {
+            let position = Position::new(lng, lat).unwrap();
+            for format in [
+                CoordinateFormat::Dd,
+                CoordinateFormat::Dms,
+                CoordinateFormat::Ddm,
+                CoordinateFormat::Utm,
+                CoordinateFormat::Mgrs,
+            ] {
+                let text = format_coordinate(position, format).unwrap();
+                let parsed = parse_coordinate(&text)
+                    .unwrap_or_else(|e| panic!("{:?} {}: {}", format, text, e));
+                assert_eq!(parsed.format, format, "{}", text);
+                assert_close(parsed.position, lng, lat, 3e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_polar_outputs_are_rejected() {
+        let pole = Position::new(0.0, 85.0).unwrap();
+        assert!(format_coordinate(pole, CoordinateFormat::Mgrs).is_err());
+        assert!(format_coordinate(pole, CoordinateFormat::Dms).is_ok());
+    }
+}
@@ -0,0 +1,412 @@
+use chrono::{DateTime, Utc};
+use geo::{Densify, FrechetDistance, HausdorffDistance, LineString};
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::linear_ref::{to_linestring, to_local, LinearRoute};
+use super::passage::locate_trace;
+use super::trace::TracePoint;
+
+/// Share of a stretch's off-plan fixes that must lie on one curated track
+/// for the stretch to count as following it
+const TRACK_MATCH_SHARE: f64 = 0.8;
+
+/// Bin size used to measure how much of the plan was driven (meters)
+const COVERAGE_BIN_M: f64 = 100.0;
+
+/// A curated track considered when explaining deviations
+#[derive(Debug, Clone)]
+pub struct CandidateTrack {
+    pub id: Uuid,
+    pub route: LinearRoute,
+}
+
+/// A stretch where the driven line left the planned corridor
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviationStretch {
+    /// Trace fix indices, including the in-corridor fixes on either side
+    pub start_index: usize,
+    pub end_index: usize,
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Where the stretch leaves and rejoins the plan (km)
+    pub from_km: f64,
+    pub to_km: f64,
+    pub driven_km: f64,
+    pub max_offset_m: f64,
+    /// Hausdorff distance between the driven and planned pieces (meters)
+    pub hausdorff_m: f64,
+    /// Discrete Fréchet distance between the driven and planned pieces (meters)
+    pub frechet_m: f64,
+    /// Driven line as a GeoJSON LineString
+    pub geometry: Value,
+    /// Curated track the stretch consistently follows, if any
+    pub curated_track_id: Option<Uuid>,
+    /// Planned route with this stretch substituted (GeoJSON MultiLineString),
+    /// set when the stretch follows a curated track
+    pub proposed_geometry: Option<Value>,
+}
+
+/// A curated track that was driven while off the plan
+#[derive(Debug, Clone, Serialize)]
+pub struct UnplannedTrack {
+    pub track_id: Uuid,
+    pub driven_km: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviationReport {
+    pub threshold_m: f64,
+    pub driven_km: f64,
+    /// Distance driven within `threshold_m` of the plan (km)
+    pub in_corridor_km: f64,
+    /// Share of the planned route that was driven (0-1)
+    pub plan_coverage: f64,
+    /// Mean of in-corridor share and plan coverage (0-100)
+    pub adherence_score: f64,
+    pub stretches: Vec<DeviationStretch>,
+    pub unplanned_tracks: Vec<UnplannedTrack>,
+}
+
+/// Expanded bounding box of a candidate track, used to skip far-away tracks
+struct TrackIndex<'a> {
+    track: &'a CandidateTrack,
+    min: (f64, f64),
+    max: (f64, f64),
+}
+
+impl<'a> TrackIndex<'a> {
+    fn new(track: &'a CandidateTrack, margin_m: f64) -> Self {
+        let mut min = (f64::INFINITY, f64::INFINITY);
+        let mut max = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for &(lng, lat) in track.route.points() {
+            min = (min.0.min(lng), min.1.min(lat));
+            max = (max.0.max(lng), max.1.max(lat));
+        }
+
+        let margin_lat = margin_m / 111_320.0;
+        let widest = min.1.abs().max(max.1.abs()).min(89.0).to_radians().cos();
+        let margin_lng = margin_lat / widest;
+
+        Self {
+            track,
+            min: (min.0 - margin_lng, min.1 - margin_lat),
+            max: (max.0 + margin_lng, max.1 + margin_lat),
+        }
+    }
+
+    fn may_contain(&self, p: (f64, f64)) -> bool {
+        p.0 >= self.min.0 && p.0 <= self.max.0 && p.1 >= self.min.1 && p.1 <= self.max.1
+    }
+}
+
+/// Closest candidate track within `threshold_m` of each off-plan fix
+fn match_tracks(
+    trace: &[TracePoint],
+    off_plan: &[bool],
+    tracks: &[CandidateTrack],
+    threshold_m: f64,
+) -> Vec<Option<usize>> {
+    let index: Vec<TrackIndex> = tracks
+        .iter()
+        .map(|t| TrackIndex::new(t, threshold_m))
+        .collect();
+
+    trace
+        .iter()
+        .zip(off_plan)
+        .map(|(p, &off)| {
+            if !off {
+                return None;
+            }
+            index
+                .iter()
+                .enumerate()
+                .filter(|(_, idx)| idx.may_contain(p.position()))
+                .map(|(i, idx)| (i, idx.track.route.locate(p.lng, p.lat).offset_m))
+                .filter(|&(_, offset)| offset <= threshold_m)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i)
+        })
+        .collect()
+}
+
+/// Project both pieces into a shared metric frame and compare them
+fn shape_distances(driven: &[(f64, f64)], planned: &[(f64, f64)]) -> (f64, f64) {
+    let origin = driven[0];
+    let local = |points: &[(f64, f64)]| -> LineString<f64> {
+        points.iter().map(|&p| to_local(p, origin)).collect()
+    };
+    let driven = local(driven);
+    let planned = local(planned);
+
+    // Both measures are vertex-based, so densify to keep long straight
+    // segments from hiding their midpoints
+    let length = |line: &LineString<f64>| {
+        line.lines()
+            .map(|l| (l.dx() * l.dx() + l.dy() * l.dy()).sqrt())
+            .sum::<f64>()
+    };
+    let spacing = (length(&driven).max(length(&planned)) / 1000.0).max(25.0);
+    let driven = driven.densify(spacing);
+    let planned = planned.densify(spacing);
+
+    (
+        driven.hausdorff_distance(&planned),
+        driven.frechet_distance(&planned),
+    )
+}
+
+fn path_length_m(trace: &[TracePoint]) -> f64 {
+    trace.windows(2).map(|w| w[1].distance_m(&w[0])).sum()
+}
+
+/// Planned route with the driven piece between `from_km` and `to_km` substituted
+fn substitute(planned: &LinearRoute, from_km: f64, to_km: f64, driven: &[(f64, f64)]) -> Value {
+    let mut coords: Vec<(f64, f64)> = planned.cut(0.0, from_km);
+    coords.extend_from_slice(driven);
+    coords.extend(planned.cut(to_km, planned.length_km()));
+    coords.dedup();
+
+    let line = to_linestring(&coords);
+    serde_json::json!({
+        "type": "MultiLineString",
+        "coordinates": [line["coordinates"]]
+    })
+}
+
+/// Compare a driven trace with the planned route
+///
+/// # Arguments
+/// * `planned` - The planned route geometry
+/// * `trace` - Recorded GPS fixes in time order
+/// * `tracks` - Curated tracks near the trace, used to explain deviations
+/// * `threshold_m` - Offset beyond which the trace counts as off the plan
+pub fn analyze_deviation(
+    planned: &LinearRoute,
+    trace: &[TracePoint],
+    tracks: &[CandidateTrack],
+    threshold_m: f64,
+) -> DeviationReport {
+    let located = locate_trace(planned, trace, threshold_m);
+    let off_plan: Vec<bool> = located.iter().map(|&(_, o)| o > threshold_m).collect();
+    let matches = match_tracks(trace, &off_plan, tracks, threshold_m);
+
+    // Distances, coverage and per-track off-plan driving
+    let bins = ((planned.length_km() * 1000.0 / COVERAGE_BIN_M).ceil() as usize).max(1);
+    let bin_of = |km: f64| ((km * 1000.0 / COVERAGE_BIN_M) as usize).min(bins - 1);
+    let mut covered = vec![false; bins];
+    let mut driven_m = 0.0;
+    let mut in_corridor_m = 0.0;
+    let mut unplanned_m = vec![0.0; tracks.len()];
+
+    for (i, off) in off_plan.iter().enumerate() {
+        if !off {
+            covered[bin_of(located[i].0)] = true;
+        }
+        if i == 0 {
+            continue;
+        }
+
+        let step = trace[i].distance_m(&trace[i - 1]);
+        driven_m += step;
+
+        if !off_plan[i - 1] && !off {
+            in_corridor_m += step;
+            let (a, b) = (located[i - 1].0, located[i].0);
+            // Long jumps mean the fixes snapped to different legs; don't fill between them
+            if (b - a).abs() * 1000.0 <= step + threshold_m {
+                covered[bin_of(a.min(b))..=bin_of(a.max(b))].fill(true);
+            }
+        }
+        if let (Some(a), Some(b)) = (matches[i - 1], matches[i]) {
+            if a == b {
+                unplanned_m[a] += step;
+            }
+        }
+    }
+
+    // Runs of off-plan fixes
+    let mut stretches = Vec::new();
+    let mut i = 0;
+    while i < trace.len() {
+        if !off_plan[i] {
+            i += 1;
+            continue;
+        }
+        let run_start = i;
+        while i < trace.len() && off_plan[i] {
+            i += 1;
+        }
+        let run_end = i - 1;
+
+        let start = run_start.saturating_sub(1);
+        let end = (run_end + 1).min(trace.len() - 1);
+        let piece = &trace[start..=end];
+        let driven: Vec<(f64, f64)> = piece.iter().map(|p| p.position()).collect();
+        let (from_km, to_km) = (located[start].0, located[end].0);
+
+        let mut planned_piece = planned.cut(from_km, to_km);
+        if from_km > to_km {
+            planned_piece.reverse();
+        }
+        let (hausdorff_m, frechet_m) = shape_distances(&driven, &planned_piece);
+
+        let run_len = run_end - run_start + 1;
+        let mut counts = vec![0usize; tracks.len()];
+        for m in matches[run_start..=run_end].iter().flatten() {
+            counts[*m] += 1;
+        }
+        let followed = counts
+            .iter()
+            .enumerate()
+            .max_by_key(|&(_, c)| *c)
+            .filter(|&(_, &c)| c as f64 >= TRACK_MATCH_SHARE * run_len as f64)
+            .map(|(t, _)| t);
+
+        stretches.push(DeviationStretch {
+            start_index: start,
+            end_index: end,
+            started_at: piece[0].time,
+            ended_at: piece[piece.len() - 1].time,
+            from_km,
+            to_km,
+            driven_km: path_length_m(piece) / 1000.0,
+            max_offset_m: located[run_start..=run_end]
+                .iter()
+                .map(|&(_, o)| o)
+                .fold(0.0, f64::max),
+            hausdorff_m,
+            frechet_m,
+            geometry: to_linestring(&driven),
+            curated_track_id: followed.map(|t| tracks[t].id),
+            // Only forward detours make sense as a replacement for the plan
+            proposed_geometry: followed
+                .filter(|_| from_km < to_km)
+                .map(|_| substitute(planned, from_km, to_km, &driven)),
+        });
+    }
+
+    let mut unplanned_tracks: Vec<UnplannedTrack> = unplanned_m
+        .iter()
+        .enumerate()
+        .filter(|&(_, &m)| m >= threshold_m)
+        .map(|(t, &m)| UnplannedTrack {
+            track_id: tracks[t].id,
+            driven_km: m / 1000.0,
+        })
+        .collect();
+    unplanned_tracks.sort_by(|a, b| b.driven_km.total_cmp(&a.driven_km));
+
+    let in_corridor_share = if driven_m > 0.0 {
+        in_corridor_m / driven_m
+    } else {
+        0.0
+    };
+    let plan_coverage = covered.iter().filter(|&&c| c).count() as f64 / bins as f64;
+
+    DeviationReport {
+        threshold_m,
+        driven_km: driven_m / 1000.0,
+        in_corridor_km: in_corridor_m / 1000.0,
+        plan_coverage,
+        adherence_score: (in_corridor_share + plan_coverage) / 2.0 * 100.0,
+        stretches,
+        unplanned_tracks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fix(lng: f64, lat: f64) -> TracePoint {
+        TracePoint {
+            lng,
+            lat,
+            time: None,
+        }
+    }
+
+    /// ~11 km due north along -5.0
+    fn planned() -> LinearRoute {
+        LinearRoute::new(vec![(-5.0, 20.0), (-5.0, 20.1)]).unwrap()
+    }
+
+    /// Northbound with a ~1 km detour east between 20.03 and 20.06
+    fn detour_trace() -> Vec<TracePoint> {
+        let mut points = Vec::new();
+        for i in 0..=100 {
+            let lat = 20.0 + i as f64 * 0.001;
+            let lng = if lat > 20.0305 && lat < 20.0595 {
+                -4.99
+            } else {
+                -5.0
+            };
+            points.push(fix(lng, lat));
+        }
+        points
+    }
+
+    fn detour_track() -> CandidateTrack {
+        CandidateTrack {
+            id: Uuid::nil(),
+            route: LinearRoute::new(vec![
+                (-5.0, 20.03),
+                (-4.99, 20.031),
+                (-4.99, 20.059),
+                (-5.0, 20.06),
+            ])
+            .unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_on_plan_trace_scores_full() {
+        let trace: Vec<TracePoint> = (0..=10)
+            .map(|i| fix(-5.0, 20.0 + i as f64 * 0.01))
+            .collect();
+
+        let report = analyze_deviation(&planned(), &trace, &[], 100.0);
+
+        assert!(report.stretches.is_empty());
+        assert!((report.plan_coverage - 1.0).abs() < 1e-9);
+        assert!((report.adherence_score - 100.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_detour_stretch() {
+        let report = analyze_deviation(&planned(), &detour_trace(), &[], 100.0);
+
+        assert_eq!(report.stretches.len(), 1);
+        let stretch = &report.stretches[0];
+        assert!((stretch.from_km - 3.34).abs() < 0.15, "{}", stretch.from_km);
+        assert!((stretch.to_km - 6.56).abs() < 0.15, "{}", stretch.to_km);
+        assert!((stretch.max_offset_m - 1046.0).abs() < 20.0);
+        assert!(
+            (stretch.hausdorff_m - 1046.0).abs() < 30.0,
+            "{}",
+            stretch.hausdorff_m
+        );
+        assert!(stretch.frechet_m >= stretch.hausdorff_m - 1e-6);
+        assert!(stretch.curated_track_id.is_none());
+        assert!(stretch.proposed_geometry.is_none());
+        assert!(report.adherence_score < 100.0);
+    }
+
+    #[test]
+    fn test_detour_along_curated_track_is_proposed() {
+        let report = analyze_deviation(&planned(), &detour_trace(), &[detour_track()], 100.0);
+
+        let stretch = &report.stretches[0];
+        assert_eq!(stretch.curated_track_id, Some(Uuid::nil()));
+        let proposed = stretch.proposed_geometry.as_ref().unwrap();
+        let proposed = LinearRoute::from_geometry(proposed).unwrap();
+        assert_eq!(proposed.points()[0], (-5.0, 20.0));
+        assert_eq!(*proposed.points().last().unwrap(), (-5.0, 20.1));
+
+        assert_eq!(report.unplanned_tracks.len(), 1);
+        assert!(report.unplanned_tracks[0].driven_km > 2.5);
+    }
+}
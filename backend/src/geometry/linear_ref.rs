@@ -0,0 +1,405 @@
+use anyhow::{anyhow, Result};
+use geo::{HaversineBearing, HaversineDistance, Point};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Mean Earth radius (meters), matching the haversine computations in `geo`
+const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// A kilometer marker along a route
+#[derive(Debug, Clone, Serialize)]
+pub struct KmMarker {
+    pub km: f64,
+    pub lng: f64,
+    pub lat: f64,
+    /// True bearing of the route at the marker (degrees, 0-360)
+    pub bearing: f64,
+    /// Position in the requested coordinate format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coordinate: Option<String>,
+}
+
+/// Position of an arbitrary coordinate relative to a route
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteLocation {
+    /// Distance along the route to the closest point (km)
+    pub km: f64,
+    /// Distance from the coordinate to the route (meters)
+    pub offset_m: f64,
+    /// Which side of the route the coordinate lies on, in direction of travel
+    pub side: Side,
+    /// Closest point on the route `[lng, lat]`
+    pub snapped: [f64; 2],
+    /// `snapped` in the requested coordinate format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapped_coordinate: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Left,
+    Right,
+    On,
+}
+
+/// A route geometry with cumulative distances for linear referencing
+///
+/// MultiLineString parts are joined in order into a single path, so km
+/// positions run continuously from the first vertex to the last.
+#[derive(Debug, Clone)]
+pub struct LinearRoute {
+    points: Vec<(f64, f64)>,
+    /// Cumulative distance (meters) at each vertex
+    cumulative_m: Vec<f64>,
+}
+
+impl LinearRoute {
+    /// Build from a sequence of `(lng, lat)` vertices
+    pub fn new(points: Vec<(f64, f64)>) -> Result<Self> {
+        let mut deduped: Vec<(f64, f64)> = Vec::with_capacity(points.len());
+        for p in points {
+            if deduped.last() != Some(&p) {
+                deduped.push(p);
+            }
+        }
+
+        if deduped.len() < 2 {
+            return Err(anyhow!("Route needs at least two distinct points"));
+        }
+
+        let mut cumulative_m = Vec::with_capacity(deduped.len());
+        let mut total = 0.0;
+        cumulative_m.push(0.0);
+        for pair in deduped.windows(2) {
+            total += Point::from(pair[0]).haversine_distance(&Point::from(pair[1]));
+            cumulative_m.push(total);
+        }
+
+        Ok(Self {
+            points: deduped,
+            cumulative_m,
+        })
+    }
+
+    /// Build from a GeoJSON LineString or MultiLineString
+    pub fn from_geometry(geometry: &Value) -> Result<Self> {
+        let coords = geometry["coordinates"]
+            .as_array()
+            .ok_or_else(|| anyhow!("Invalid geometry: missing coordinates"))?;
+
+        let parse_point = |p: &Value| {
+            let arr = p.as_array()?;
+            Some((arr.first()?.as_f64()?, arr.get(1)?.as_f64()?))
+        };
+
+        let points: Vec<(f64, f64)> = match geometry["type"].as_str() {
+            Some("LineString") => coords.iter().filter_map(parse_point).collect(),
+            _ => coords
+                .iter()
+                .filter_map(|line| line.as_array())
+                .flatten()
+                .filter_map(parse_point)
+                .collect(),
+        };
+
+        Self::new(points)
+    }
+
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    pub fn length_km(&self) -> f64 {
+        self.length_m() / 1000.0
+    }
+
+    fn length_m(&self) -> f64 {
+        *self.cumulative_m.last().unwrap_or(&0.0)
+    }
+
+    /// Index of the segment containing distance `m` (clamped to the route)
+    fn segment_at(&self, m: f64) -> usize {
+        let idx = self.cumulative_m.partition_point(|&c| c <= m);
+        idx.saturating_sub(1).min(self.points.len() - 2)
+    }
+
+    /// Interpolated position at `km` from the start (clamped to the route)
+    pub fn point_at(&self, km: f64) -> (f64, f64) {
+        let m = (km * 1000.0).clamp(0.0, self.length_m());
+        let i = self.segment_at(m);
+        let seg_len = self.cumulative_m[i + 1] - self.cumulative_m[i];
+        let t = if seg_len > 0.0 {
+            (m - self.cumulative_m[i]) / seg_len
+        } else {
+            0.0
+        };
+        lerp(self.points[i], self.points[i + 1], t)
+    }
+
+    /// True bearing of the segment at `km` (degrees, 0-360)
+    pub fn bearing_at(&self, km: f64) -> f64 {
+        let i = self.segment_at((km * 1000.0).clamp(0.0, self.length_m()));
+        Point::from(self.points[i])
+            .haversine_bearing(Point::from(self.points[i + 1]))
+            .rem_euclid(360.0)
+    }
+
+    /// Markers every `interval_km`, starting at km 0 and ending at the finish
+    pub fn km_markers(&self, interval_km: f64) -> Result<Vec<KmMarker>> {
+        if interval_km.is_nan() || interval_km <= 0.0 {
+            return Err(anyhow!("Marker interval must be positive"));
+        }
+
+        let length_km = self.length_km();
+        let count = (length_km / interval_km).floor() as usize;
+
+        let mut kms: Vec<f64> = (0..=count).map(|i| i as f64 * interval_km).collect();
+        if kms.last().is_some_and(|&last| length_km - last > 1e-9) {
+            kms.push(length_km);
+        }
+
+        Ok(kms
+            .into_iter()
+            .map(|km| {
+                let (lng, lat) = self.point_at(km);
+                KmMarker {
+                    km,
+                    lng,
+                    lat,
+                    bearing: self.bearing_at(km),
+                    coordinate: None,
+                }
+            })
+            .collect())
+    }
+
+    /// Locate a coordinate along the route
+    ///
+    /// Projects the coordinate onto every segment in a local equirectangular
+    /// frame and returns the closest projection.
+    pub fn locate(&self, lng: f64, lat: f64) -> RouteLocation {
+        self.locate_from(lng, lat, 0.0, 0.0)
+    }
+
+    /// Locate a coordinate at or after `from_km`
+    ///
+    /// Returns the nearest position of the first pass of the route within
+    /// `tolerance_m` of the coordinate (where the distance stops falling), or the closest position overall when
+    /// no pass comes that close. Positions visited in order (control points,
+    /// GPS fixes) are located from the previous position's km, so on loops
+    /// and out-and-back routes each snaps to the pass it belongs to rather
+    /// than whichever is nearest.
+    pub fn locate_from(&self, lng: f64, lat: f64, from_km: f64, tolerance_m: f64) -> RouteLocation {
+        let from_m = (from_km * 1000.0).clamp(0.0, self.length_m());
+        let first = self.segment_at(from_m);
+        // (dist, segment, t, cross)
+        let mut closest: Option<(f64, usize, f64, f64)> = None;
+        let mut first_pass: Option<(f64, usize, f64, f64)> = None;
+        // Whether `first_pass` lies on the end of its segment
+        let mut at_end = false;
+
+        for i in first..self.points.len() - 1 {
+            let seg_len = self.cumulative_m[i + 1] - self.cumulative_m[i];
+            // Only the part of the first segment after `from_km` is searched
+            let t_min = if i == first && seg_len > 0.0 {
+                ((from_m - self.cumulative_m[i]) / seg_len).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let start = lerp(self.points[i], self.points[i + 1], t_min);
+            let (t, dist, cross) = project_onto_segment(start, self.points[i + 1], (lng, lat));
+            let candidate = (dist, i, t_min + t * (1.0 - t_min), cross);
+
+            if dist <= tolerance_m {
+                match first_pass {
+                    // The pass gets closer past the previous segment's end
+                    Some((d, ..)) if at_end && dist < d => first_pass = Some(candidate),
+                    Some(_) => break,
+                    None => first_pass = Some(candidate),
+                }
+                at_end = t >= 1.0;
+            } else if first_pass.is_some() {
+                break;
+            }
+            if closest.is_none_or(|(d, ..)| dist < d) {
+                closest = Some(candidate);
+            }
+        }
+
+        let (offset_m, i, t, cross) = first_pass
+            .or(closest)
+            .expect("route has at least one segment");
+        let seg_len = self.cumulative_m[i + 1] - self.cumulative_m[i];
+        let snapped = lerp(self.points[i], self.points[i + 1], t);
+
+        let side = if offset_m < 0.01 {
+            Side::On
+        } else if cross > 0.0 {
+            Side::Left
+        } else {
+            Side::Right
+        };
+
+        RouteLocation {
+            km: (self.cumulative_m[i] + t * seg_len) / 1000.0,
+            offset_m,
+            side,
+            snapped: [snapped.0, snapped.1],
+            snapped_coordinate: None,
+        }
+    }
+
+    /// Cut the route between two km positions (order-insensitive, clamped)
+    pub fn cut(&self, from_km: f64, to_km: f64) -> Vec<(f64, f64)> {
+        let (from_km, to_km) = if from_km <= to_km {
+            (from_km, to_km)
+        } else {
+            (to_km, from_km)
+        };
+        let from_m = (from_km * 1000.0).clamp(0.0, self.length_m());
+        let to_m = (to_km * 1000.0).clamp(0.0, self.length_m());
+
+        let mut result = vec![self.point_at(from_m / 1000.0)];
+        for (i, &c) in self.cumulative_m.iter().enumerate() {
+            if c > from_m && c < to_m {
+                result.push(self.points[i]);
+            }
+        }
+        let end = self.point_at(to_m / 1000.0);
+        if result.last() != Some(&end) || result.len() == 1 {
+            result.push(end);
+        }
+
+        result
+    }
+}
+
+fn lerp(a: (f64, f64), b: (f64, f64), t: f64) -> (f64, f64) {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+/// Closest point to `p` on segment `a`→`b`
+///
+/// Returns `(t, distance_m, cross)` where `t` is the position along the
+/// segment (0-1) and `cross` is positive when `p` lies left of the segment.
+pub(crate) fn project_onto_segment(a: (f64, f64), b: (f64, f64), p: (f64, f64)) -> (f64, f64, f64) {
+    // `p` is the origin of the local frame
+    let a = to_local(a, p);
+    let b = to_local(b, p);
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len2 = dx * dx + dy * dy;
+
+    let t = if len2 > 0.0 {
+        ((-a.0 * dx - a.1 * dy) / len2).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (px, py) = (a.0 + t * dx, a.1 + t * dy);
+    let cross = dx * (-a.1) - dy * (-a.0);
+
+    (t, (px * px + py * py).sqrt(), cross)
+}
+
+/// Project `p` to meters in an equirectangular frame centered on `origin`
+pub(crate) fn to_local(p: (f64, f64), origin: (f64, f64)) -> (f64, f64) {
+    let cos_lat = origin.1.to_radians().cos();
+    (
+        (p.0 - origin.0).to_radians() * cos_lat * EARTH_RADIUS_M,
+        (p.1 - origin.1).to_radians() * EARTH_RADIUS_M,
+    )
+}
+
+/// Render `(lng, lat)` pairs as a GeoJSON LineString
+pub fn to_linestring(points: &[(f64, f64)]) -> Value {
+    serde_json::json!({
+        "type": "LineString",
+        "coordinates": points.iter().map(|&(lng, lat)| vec![lng, lat]).collect::<Vec<_>>()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ~22.2 km due north, then ~20.9 km due east at 20°N
+    fn route() -> LinearRoute {
+        LinearRoute::from_geometry(&serde_json::json!({
+            "type": "MultiLineString",
+            "coordinates": [[[-5.0, 20.0], [-5.0, 20.2]], [[-5.0, 20.2], [-4.8, 20.2]]]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_length_and_dedup() {
+        let r = route();
+        assert_eq!(r.points().len(), 3);
+        assert!((r.length_km() - 43.1).abs() < 0.3, "{}", r.length_km());
+    }
+
+    #[test]
+    fn test_km_markers() {
+        let r = route();
+        let markers = r.km_markers(10.0).unwrap();
+
+        assert_eq!(markers.len(), 6); // 0, 10, 20, 30, 40, finish
+        assert_eq!(markers[0].km, 0.0);
+        assert!((markers[5].km - r.length_km()).abs() < 1e-9);
+        assert!(markers[1].bearing < 1.0 || markers[1].bearing > 359.0);
+        assert!((markers[3].bearing - 90.0).abs() < 1.0);
+        assert!(r.km_markers(0.0).is_err());
+    }
+
+    #[test]
+    fn test_locate_left_and_right() {
+        let r = route();
+
+        // West of a northbound leg is left
+        let left = r.locate(-5.01, 20.1);
+        assert_eq!(left.side, Side::Left);
+        assert!((left.km - 11.1).abs() < 0.1);
+        assert!((left.offset_m - 1046.0).abs() < 20.0, "{}", left.offset_m);
+
+        let right = r.locate(-4.99, 20.1);
+        assert_eq!(right.side, Side::Right);
+
+        let on = r.locate(-5.0, 20.0);
+        assert_eq!(on.side, Side::On);
+        assert_eq!(on.km, 0.0);
+    }
+
+    #[test]
+    fn test_locate_from_takes_later_pass() {
+        // North and back south along the same line
+        let r = LinearRoute::new(vec![(-5.0, 20.0), (-5.0, 20.2), (-5.0, 20.0)]).unwrap();
+
+        // Both passes run through the point; the earlier one wins
+        assert!((r.locate(-5.0, 20.1).km - 11.1).abs() < 0.1);
+        assert!((r.locate_from(-5.0, 20.1, 0.0, 50.0).km - 11.1).abs() < 0.1);
+        let back = r.locate_from(-5.0, 20.1, 22.0, 50.0);
+        assert!((back.km - 33.4).abs() < 0.1, "{}", back.km);
+        assert!(back.offset_m < 1.0);
+
+        // Passed on the way out before `from_km`, so found on the way back
+        let behind = r.locate_from(-5.0, 20.05, 11.1, 50.0);
+        assert!((behind.km - 38.9).abs() < 0.1, "{}", behind.km);
+    }
+
+    #[test]
+    fn test_cut() {
+        let r = route();
+        let cut = r.cut(30.0, 10.0);
+
+        // Start, the corner vertex, end
+        assert_eq!(cut.len(), 3);
+        assert_eq!(cut[1], (-5.0, 20.2));
+        let piece = LinearRoute::new(cut).unwrap();
+        assert!((piece.length_km() - 20.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_rejects_degenerate_route() {
+        assert!(LinearRoute::new(vec![(1.0, 1.0), (1.0, 1.0)]).is_err());
+    }
+}
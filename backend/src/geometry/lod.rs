@@ -0,0 +1,227 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use super::simplification::{
+    simplify_geometry, SimplifyAlgorithm, SimplifyOptions, DEFAULT_TOLERANCE_M,
+};
+
+/// Overview tolerances stored for every route version (meters), coarsest first
+///
+/// Roughly two 512 px tile pixels at zooms 6, 8, 10 and 12.
+pub const LOD_TOLERANCES_M: [f64; 4] = [2400.0, 600.0, 150.0, 40.0];
+
+/// Ground size of one pixel at the equator for 512 px tiles (meters)
+const METERS_PER_PIXEL_Z0: f64 = 78_271.517;
+
+/// Pixels of error tolerated when a zoom level picks an overview
+const PIXELS_PER_TOLERANCE: f64 = 2.0;
+
+/// A precomputed overview of a route version
+#[derive(Debug, Clone)]
+pub struct Lod {
+    pub tolerance_m: f64,
+    pub point_count: usize,
+    /// GeoJSON MultiLineString
+    pub geometry: Value,
+}
+
+/// Everything stored for one route version
+#[derive(Debug, Clone)]
+pub struct VersionGeometry {
+    /// Standard display geometry (GeoJSON MultiLineString)
+    pub geometry: Value,
+    /// Unsimplified routed geometry, when it differs from `geometry`
+    pub full_geometry: Option<Value>,
+    pub lods: Vec<Lod>,
+}
+
+impl VersionGeometry {
+    /// Build from full-resolution routed geometry
+    ///
+    /// `keep` positions (control points) survive in the standard geometry;
+    /// overviews only keep line ends.
+    pub fn from_full(full: Value, simplify: &SimplifyOptions, keep: &[(f64, f64)]) -> Result<Self> {
+        let geometry = simplify_geometry(&full, simplify, keep)?;
+        let lods = build_lods(&full, point_count(&geometry))?;
+        let full_geometry = (point_count(&full) > point_count(&geometry)).then_some(full);
+
+        Ok(Self {
+            geometry,
+            full_geometry,
+            lods,
+        })
+    }
+
+    /// Build from a geometry that is stored as-is (no separate full resolution)
+    pub fn from_geometry(geometry: Value) -> Result<Self> {
+        let lods = build_lods(&geometry, point_count(&geometry))?;
+        Ok(Self {
+            geometry,
+            full_geometry: None,
+            lods,
+        })
+    }
+}
+
+/// Number of vertices in a MultiLineString
+pub fn point_count(geometry: &Value) -> usize {
+    geometry["coordinates"]
+        .as_array()
+        .map(|lines| {
+            lines
+                .iter()
+                .filter_map(|line| line.as_array())
+                .map(|line| line.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Overviews that are actually smaller than the standard geometry
+fn build_lods(source: &Value, standard_points: usize) -> Result<Vec<Lod>> {
+    let mut lods = Vec::with_capacity(LOD_TOLERANCES_M.len());
+
+    for tolerance_m in LOD_TOLERANCES_M {
+        let options = SimplifyOptions {
+            tolerance_m,
+            algorithm: SimplifyAlgorithm::TopologyPreserving,
+        };
+        let geometry = simplify_geometry(source, &options, &[])?;
+        let point_count = point_count(&geometry);
+
+        if point_count < standard_points {
+            lods.push(Lod {
+                tolerance_m,
+                point_count,
+                geometry,
+            });
+        }
+    }
+
+    Ok(lods)
+}
+
+/// Display tolerance for a web map zoom level: two pixels at the equator
+pub fn zoom_tolerance_m(zoom: f64) -> Result<f64> {
+    if !zoom.is_finite() || !(0.0..=24.0).contains(&zoom) {
+        return Err(anyhow!("Zoom must be between 0 and 24"));
+    }
+    Ok(PIXELS_PER_TOLERANCE * METERS_PER_PIXEL_Z0 / 2f64.powf(zoom))
+}
+
+/// Which stored geometry a read should return
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeometryResolution {
+    /// The standard display geometry
+    Standard,
+    /// Unsimplified routed geometry (exports, analysis)
+    Full,
+    /// Coarsest overview whose tolerance does not exceed this (meters);
+    /// falls back to the standard geometry
+    Overview(f64),
+}
+
+impl GeometryResolution {
+    /// Resolve `?zoom=` / `?tolerance_m=` request parameters
+    ///
+    /// `tolerance_m` wins when both are given. A zoom maps to two pixels of
+    /// ground size at the equator. Tolerances finer than the standard
+    /// geometry select the full-resolution one.
+    pub fn from_params(zoom: Option<f64>, tolerance_m: Option<f64>) -> Result<Self> {
+        let tolerance_m = match (tolerance_m, zoom) {
+            (Some(t), _) => t,
+            (None, Some(z)) => zoom_tolerance_m(z)?,
+            (None, None) => return Ok(Self::Standard),
+        };
+
+        if !tolerance_m.is_finite() || tolerance_m < 0.0 {
+            return Err(anyhow!("Tolerance must be a non-negative number of meters"));
+        }
+
+        Ok(if tolerance_m < DEFAULT_TOLERANCE_M {
+            Self::Full
+        } else {
+            Self::Overview(tolerance_m)
+        })
+    }
+
+    /// `(overview tolerance, want full)` query parameters
+    pub fn as_params(&self) -> (Option<f64>, bool) {
+        match *self {
+            Self::Standard => (None, false),
+            Self::Full => (None, true),
+            Self::Overview(t) => (Some(t), false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ~55 km wiggly line with a vertex every ~110 m
+    fn dense_route() -> Value {
+        let coords: Vec<Vec<f64>> = (0..=500)
+            .map(|i| {
+                let lat = 20.0 + i as f64 * 0.001;
+                let lng = -5.0 + (i as f64 / 10.0).sin() * 0.01;
+                vec![lng, lat]
+            })
+            .collect();
+        serde_json::json!({ "type": "MultiLineString", "coordinates": [coords] })
+    }
+
+    #[test]
+    fn test_lods_get_coarser() {
+        let version =
+            VersionGeometry::from_full(dense_route(), &SimplifyOptions::default(), &[]).unwrap();
+
+        assert!(!version.lods.is_empty());
+        let counts: Vec<usize> = version.lods.iter().map(|l| l.point_count).collect();
+        assert!(counts.windows(2).all(|w| w[0] <= w[1]), "{:?}", counts);
+        assert!(counts[0] < 60, "{:?}", counts);
+        assert!(*counts.last().unwrap() < point_count(&version.geometry));
+    }
+
+    #[test]
+    fn test_full_geometry_only_kept_when_larger() {
+        let version =
+            VersionGeometry::from_full(dense_route(), &SimplifyOptions::default(), &[]).unwrap();
+        assert_eq!(point_count(version.full_geometry.as_ref().unwrap()), 501);
+
+        let segment = serde_json::json!({
+            "type": "MultiLineString",
+            "coordinates": [[[-5.0, 20.0], [-5.0, 20.1]]]
+        });
+        let version =
+            VersionGeometry::from_full(segment, &SimplifyOptions::default(), &[]).unwrap();
+        assert!(version.full_geometry.is_none());
+        assert!(version.lods.is_empty());
+    }
+
+    #[test]
+    fn test_resolution_from_params() {
+        assert_eq!(
+            GeometryResolution::from_params(None, None).unwrap(),
+            GeometryResolution::Standard
+        );
+        assert_eq!(
+            GeometryResolution::from_params(None, Some(0.0)).unwrap(),
+            GeometryResolution::Full
+        );
+        assert_eq!(
+            GeometryResolution::from_params(Some(16.0), None).unwrap(),
+            GeometryResolution::Full
+        );
+        match GeometryResolution::from_params(Some(6.0), Some(500.0)).unwrap() {
+            GeometryResolution::Overview(t) => assert_eq!(t, 500.0),
+            other => panic!("{:?}", other),
+        }
+        match GeometryResolution::from_params(Some(6.0), None).unwrap() {
+            GeometryResolution::Overview(t) => assert!((t - 2446.0).abs() < 1.0),
+            other => panic!("{:?}", other),
+        }
+        assert!(GeometryResolution::from_params(Some(30.0), None).is_err());
+        assert!(GeometryResolution::from_params(None, Some(-1.0)).is_err());
+    }
+}
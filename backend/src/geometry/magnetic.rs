@@ -0,0 +1,283 @@
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, NaiveDate};
+use std::sync::OnceLock;
+
+/// World Magnetic Model coefficients (NOAA/BGS), bundled so the backend works offline
+const WMM_COF: &str = include_str!("WMM.COF");
+
+/// Geomagnetic reference radius (km)
+const REFERENCE_RADIUS_KM: f64 = 6371.2;
+/// WGS84 semi-major axis (km)
+const WGS84_A_KM: f64 = 6378.137;
+/// WGS84 flattening
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// Spherical harmonic model of the main geomagnetic field
+///
+/// Parsed from a standard `.COF` coefficient file. Coefficients are
+/// Schmidt semi-normalized and valid for five years after `epoch`.
+#[derive(Debug, Clone)]
+pub struct MagneticModel {
+    pub name: String,
+    pub epoch: f64,
+    max_degree: usize,
+    /// Main field coefficients g[n][m], h[n][m] (nT)
+    g: Vec<Vec<f64>>,
+    h: Vec<Vec<f64>>,
+    /// Secular variation gdot[n][m], hdot[n][m] (nT/year)
+    gdot: Vec<Vec<f64>>,
+    hdot: Vec<Vec<f64>>,
+}
+
+impl MagneticModel {
+    /// The bundled World Magnetic Model, parsed once on first use
+    pub fn wmm() -> &'static MagneticModel {
+        static MODEL: OnceLock<MagneticModel> = OnceLock::new();
+        MODEL.get_or_init(|| {
+            MagneticModel::parse(WMM_COF).expect("bundled WMM.COF must be a valid coefficient file")
+        })
+    }
+
+    /// Parse a `.COF` coefficient file
+    ///
+    /// The first line holds the epoch and model name; each following line is
+    /// `n m g h gdot hdot`. A line of 9s terminates the file.
+    pub fn parse(cof: &str) -> Result<Self> {
+        let mut lines = cof.lines().filter(|l| !l.trim().is_empty());
+
+        let header = lines
+            .next()
+            .ok_or_else(|| anyhow!("Empty coefficient file"))?;
+        let mut header_fields = header.split_whitespace();
+        let epoch: f64 = header_fields
+            .next()
+            .ok_or_else(|| anyhow!("Missing model epoch"))?
+            .parse()?;
+        let name = header_fields.next().unwrap_or("WMM").to_string();
+
+        let mut rows = Vec::new();
+        for line in lines {
+            if line.trim_start().starts_with("9999") {
+                break;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 6 {
+                return Err(anyhow!("Malformed coefficient line: {}", line));
+            }
+            let n: usize = fields[0].parse()?;
+            let m: usize = fields[1].parse()?;
+            if m > n {
+                return Err(anyhow!("Invalid coefficient order m={} > n={}", m, n));
+            }
+            let values = fields[2..6]
+                .iter()
+                .map(|f| f.parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()?;
+            rows.push((n, m, values));
+        }
+
+        let max_degree = rows
+            .iter()
+            .map(|(n, _, _)| *n)
+            .max()
+            .ok_or_else(|| anyhow!("Coefficient file has no coefficients"))?;
+
+        let table = || vec![vec![0.0; max_degree + 1]; max_degree + 1];
+        let (mut g, mut h, mut gdot, mut hdot) = (table(), table(), table(), table());
+        for (n, m, values) in rows {
+            g[n][m] = values[0];
+            h[n][m] = values[1];
+            gdot[n][m] = values[2];
+            hdot[n][m] = values[3];
+        }
+
+        Ok(Self {
+            name,
+            epoch,
+            max_degree,
+            g,
+            h,
+            gdot,
+            hdot,
+        })
+    }
+
+    /// Whether `decimal_year` falls inside the model's five-year validity window
+    pub fn is_valid_for(&self, decimal_year: f64) -> bool {
+        decimal_year >= self.epoch && decimal_year < self.epoch + 5.0
+    }
+
+    /// Magnetic declination in degrees (positive east of true north)
+    ///
+    /// # Arguments
+    /// * `lat` / `lng` - WGS84 geodetic position in degrees
+    /// * `height_km` - Height above the ellipsoid in kilometers
+    /// * `decimal_year` - Date as a decimal year (see [`decimal_year`])
+    pub fn declination(&self, lat: f64, lng: f64, height_km: f64, decimal_year: f64) -> f64 {
+        let (north, east, _down) = self.field(lat, lng, height_km, decimal_year);
+        east.atan2(north).to_degrees()
+    }
+
+    /// Geodetic field components (north, east, down) in nT
+    fn field(&self, lat: f64, lng: f64, height_km: f64, decimal_year: f64) -> (f64, f64, f64) {
+        let dt = decimal_year - self.epoch;
+
+        // Geodetic → geocentric spherical coordinates
+        let phi = lat.to_radians();
+        let lambda = lng.to_radians();
+        let e2 = WGS84_F * (2.0 - WGS84_F);
+        let rc = WGS84_A_KM / (1.0 - e2 * phi.sin().powi(2)).sqrt();
+        let p = (rc + height_km) * phi.cos();
+        let z = (rc * (1.0 - e2) + height_km) * phi.sin();
+        let r = (p * p + z * z).sqrt();
+        let phi_c = (z / r).asin();
+
+        // Colatitude; keep away from the poles where the east component is singular
+        let cos_theta = phi_c.sin();
+        let sin_theta = phi_c.cos().max(1e-10);
+
+        let n_max = self.max_degree;
+        let (p_nm, dp_nm) = schmidt_legendre(n_max, cos_theta, sin_theta);
+
+        let mut x_sph = 0.0;
+        let mut y_sph = 0.0;
+        let mut z_sph = 0.0;
+        let ratio = REFERENCE_RADIUS_KM / r;
+
+        for n in 1..=n_max {
+            let radial = ratio.powi(n as i32 + 2);
+            for m in 0..=n {
+                let g = self.g[n][m] + dt * self.gdot[n][m];
+                let h = self.h[n][m] + dt * self.hdot[n][m];
+                let (sin_ml, cos_ml) = (m as f64 * lambda).sin_cos();
+
+                let cos_term = g * cos_ml + h * sin_ml;
+                x_sph += radial * cos_term * dp_nm[n][m];
+                y_sph += radial * m as f64 * (g * sin_ml - h * cos_ml) * p_nm[n][m];
+                z_sph -= radial * (n as f64 + 1.0) * cos_term * p_nm[n][m];
+            }
+        }
+        y_sph /= sin_theta;
+
+        // Rotate from geocentric back to the geodetic frame
+        let (sin_d, cos_d) = (phi_c - phi).sin_cos();
+        let north = x_sph * cos_d - z_sph * sin_d;
+        let down = x_sph * sin_d + z_sph * cos_d;
+
+        (north, y_sph, down)
+    }
+}
+
+/// Schmidt semi-normalized associated Legendre functions P[n][m](cos θ)
+/// and their derivatives with respect to colatitude θ
+fn schmidt_legendre(
+    n_max: usize,
+    cos_theta: f64,
+    sin_theta: f64,
+) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let mut p = vec![vec![0.0; n_max + 1]; n_max + 1];
+    let mut dp = vec![vec![0.0; n_max + 1]; n_max + 1];
+    p[0][0] = 1.0;
+
+    // Gauss-normalized recursion
+    for n in 1..=n_max {
+        for m in 0..=n {
+            if n == m {
+                p[n][m] = sin_theta * p[n - 1][m - 1];
+                dp[n][m] = sin_theta * dp[n - 1][m - 1] + cos_theta * p[n - 1][m - 1];
+            } else if n == 1 {
+                p[n][m] = cos_theta * p[n - 1][m];
+                dp[n][m] = cos_theta * dp[n - 1][m] - sin_theta * p[n - 1][m];
+            } else {
+                let k = if m + 2 > n {
+                    0.0
+                } else {
+                    (((n - 1) * (n - 1)) as f64 - (m * m) as f64)
+                        / (((2 * n - 1) * (2 * n - 3)) as f64)
+                };
+                let (p2, dp2) = if m + 2 > n {
+                    (0.0, 0.0)
+                } else {
+                    (p[n - 2][m], dp[n - 2][m])
+                };
+                p[n][m] = cos_theta * p[n - 1][m] - k * p2;
+                dp[n][m] = cos_theta * dp[n - 1][m] - sin_theta * p[n - 1][m] - k * dp2;
+            }
+        }
+    }
+
+    // Convert Gauss normalization to Schmidt semi-normalization
+    let mut schmidt = 1.0;
+    for n in 1..=n_max {
+        schmidt *= (2 * n - 1) as f64 / n as f64;
+        let mut factor = schmidt;
+        for m in 0..=n {
+            if m > 0 {
+                let delta = if m == 1 { 2.0 } else { 1.0 };
+                factor *= (((n - m + 1) as f64 * delta) / (n + m) as f64).sqrt();
+            }
+            p[n][m] *= factor;
+            dp[n][m] *= factor;
+        }
+    }
+
+    (p, dp)
+}
+
+/// Convert a calendar date to a decimal year (e.g. 2025-07-02 → ~2025.5)
+pub fn decimal_year(date: NaiveDate) -> f64 {
+    let days_in_year = if date.leap_year() { 366.0 } else { 365.0 };
+    date.year() as f64 + (date.ordinal0() as f64) / days_in_year
+}
+
+/// Convert a true bearing to a magnetic heading, normalized to [0, 360)
+///
+/// Declination is positive east, so an easterly variation is subtracted.
+pub fn magnetic_heading(true_bearing: f64, declination: f64) -> f64 {
+    (true_bearing - declination).rem_euclid(360.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn declination_on(lat: f64, lng: f64) -> f64 {
+        let year = decimal_year(NaiveDate::from_ymd_opt(2025, 7, 1).unwrap());
+        MagneticModel::wmm().declination(lat, lng, 0.0, year)
+    }
+
+    #[test]
+    fn test_bundled_model_parses() {
+        let model = MagneticModel::wmm();
+        assert_eq!(model.epoch, 2025.0);
+        assert_eq!(model.max_degree, 12);
+        assert!(model.is_valid_for(2026.5));
+        assert!(!model.is_valid_for(2031.0));
+    }
+
+    #[test]
+    fn test_known_declinations() {
+        // Well-known compass variations (rounded): New York ≈ 13°W, Seattle ≈ 15°E
+        let new_york = declination_on(40.71, -74.01);
+        assert!((-14.5..-11.5).contains(&new_york), "New York: {}", new_york);
+
+        let seattle = declination_on(47.61, -122.33);
+        assert!((14.0..16.5).contains(&seattle), "Seattle: {}", seattle);
+
+        let paris = declination_on(48.86, 2.35);
+        assert!((0.0..3.0).contains(&paris), "Paris: {}", paris);
+    }
+
+    #[test]
+    fn test_magnetic_heading_wraps() {
+        assert_eq!(magnetic_heading(10.0, 15.0), 355.0);
+        assert_eq!(magnetic_heading(355.0, -10.0), 5.0);
+        assert_eq!(magnetic_heading(90.0, 0.0), 90.0);
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_lines() {
+        let cof = "2025.0 TEST 01/01/2025\n  1  0  -29351.8  0.0\n";
+        assert!(MagneticModel::parse(cof).is_err());
+    }
+}
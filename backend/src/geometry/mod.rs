@@ -1,5 +1,22 @@
+pub mod analysis;
+pub mod coordinates;
+pub mod deviation;
+pub mod linear_ref;
+pub mod lod;
+pub mod magnetic;
+pub mod operations;
+pub mod passage;
+pub mod quality;
 pub mod routing;
+pub mod similarity;
 pub mod simplification;
+pub mod topology;
+pub mod trace;
+pub mod types;
+pub mod validate;
 
+pub use analysis::{analyze_route, RouteAnalysis};
+pub use linear_ref::LinearRoute;
+pub use magnetic::MagneticModel;
 pub use routing::{route_geometry, RoutingConfig};
 pub use simplification::simplify_geometry;
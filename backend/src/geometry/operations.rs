@@ -0,0 +1,265 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::linear_ref::LinearRoute;
+use super::types::ControlPoint;
+
+/// Control points and geometry of a route after an editing operation
+#[derive(Debug, Clone)]
+pub struct EditedRoute {
+    pub control_points: Vec<ControlPoint>,
+    /// GeoJSON MultiLineString
+    pub geometry: Value,
+}
+
+/// Which part of a route to remove when trimming at a control point
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrimSide {
+    /// Drop everything before the control point (it becomes the start)
+    Before,
+    /// Drop everything after the control point (it becomes the finish)
+    After,
+}
+
+fn lines(geometry: &Value) -> Result<Vec<Vec<Value>>> {
+    geometry["coordinates"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Invalid geometry: missing coordinates"))?
+        .iter()
+        .map(|line| {
+            line.as_array()
+                .cloned()
+                .ok_or_else(|| anyhow!("Invalid line coordinates"))
+        })
+        .collect()
+}
+
+fn multilinestring(lines: Vec<Vec<Value>>) -> Value {
+    serde_json::json!({
+        "type": "MultiLineString",
+        "coordinates": lines
+    })
+}
+
+fn single_line(points: &[(f64, f64)]) -> Value {
+    let coords: Vec<Value> = points
+        .iter()
+        .map(|&(lng, lat)| serde_json::json!([lng, lat]))
+        .collect();
+    multilinestring(vec![coords])
+}
+
+/// Control points this close to the route geometry are taken to lie on it (meters)
+const CONTROL_POINT_TOLERANCE_M: f64 = 50.0;
+
+/// Km position of the last of `control_points` on the route geometry
+///
+/// Control points are located in order, each at or after the one before,
+/// so a loop or out-and-back route is cut at the right pass.
+fn control_point_km(route: &LinearRoute, control_points: &[ControlPoint]) -> f64 {
+    control_points.iter().fold(0.0, |km, control_point| {
+        let (lng, lat) = control_point.as_tuple();
+        route
+            .locate_from(lng, lat, km, CONTROL_POINT_TOLERANCE_M)
+            .km
+    })
+}
+
+/// Ensure `index` is an interior control point, so both sides keep at least two points
+fn check_interior(control_points: &[ControlPoint], index: usize) -> Result<()> {
+    if index == 0 || index + 1 >= control_points.len() {
+        return Err(anyhow!(
+            "Control point {} is not an interior point of a {}-point route",
+            index,
+            control_points.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Reverse the direction of travel
+pub fn reverse(control_points: &[ControlPoint], geometry: &Value) -> Result<EditedRoute> {
+    let mut reversed_lines = lines(geometry)?;
+    reversed_lines.reverse();
+    for line in &mut reversed_lines {
+        line.reverse();
+    }
+
+    Ok(EditedRoute {
+        control_points: control_points.iter().rev().cloned().collect(),
+        geometry: multilinestring(reversed_lines),
+    })
+}
+
+/// Split a route at an interior control point into two routes
+///
+/// The split control point ends the first route and starts the second.
+pub fn split_at(
+    control_points: &[ControlPoint],
+    geometry: &Value,
+    index: usize,
+) -> Result<(EditedRoute, EditedRoute)> {
+    check_interior(control_points, index)?;
+
+    let route = LinearRoute::from_geometry(geometry)?;
+    let km = control_point_km(&route, &control_points[..=index]);
+
+    let first = EditedRoute {
+        control_points: control_points[..=index].to_vec(),
+        geometry: single_line(&route.cut(0.0, km)),
+    };
+    let second = EditedRoute {
+        control_points: control_points[index..].to_vec(),
+        geometry: single_line(&route.cut(km, route.length_km())),
+    };
+
+    Ok((first, second))
+}
+
+/// Trim a route before or after an interior control point
+pub fn trim(
+    control_points: &[ControlPoint],
+    geometry: &Value,
+    index: usize,
+    side: TrimSide,
+) -> Result<EditedRoute> {
+    let (first, second) = split_at(control_points, geometry, index)?;
+    Ok(match side {
+        TrimSide::Before => second,
+        TrimSide::After => first,
+    })
+}
+
+/// Join two routes end-to-start
+///
+/// The second route's first control point is dropped when it coincides with
+/// the first route's last one.
+pub fn join(
+    first_control_points: &[ControlPoint],
+    first_geometry: &Value,
+    second_control_points: &[ControlPoint],
+    second_geometry: &Value,
+) -> Result<EditedRoute> {
+    let mut control_points = first_control_points.to_vec();
+    let mut rest = second_control_points;
+
+    let last = first_control_points.last().map(|p| p.position);
+    let next = second_control_points.first().map(|p| p.position);
+    if last.is_some() && last == next {
+        rest = &second_control_points[1..];
+    }
+    control_points.extend(rest.iter().cloned());
+
+    let mut joined = lines(first_geometry)?;
+    joined.extend(lines(second_geometry)?);
+    joined.retain(|line| !line.is_empty());
+
+    Ok(EditedRoute {
+        control_points,
+        geometry: multilinestring(joined),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::types::Position;
+    use serde_json::json;
+
+    fn control_points() -> Vec<ControlPoint> {
+        [(-5.0, 20.0), (-5.0, 20.2), (-4.8, 20.2)]
+            .into_iter()
+            .map(|(lng, lat)| ControlPoint::new(Position::new(lng, lat).unwrap()))
+            .collect()
+    }
+
+    fn geometry() -> Value {
+        json!({
+            "type": "MultiLineString",
+            "coordinates": [[[-5.0, 20.0], [-5.0, 20.1], [-5.0, 20.2], [-4.9, 20.2], [-4.8, 20.2]]]
+        })
+    }
+
+    #[test]
+    fn test_reverse() {
+        let reversed = reverse(&control_points(), &geometry()).unwrap();
+
+        assert_eq!(reversed.control_points[0].as_tuple(), (-4.8, 20.2));
+        assert_eq!(reversed.geometry["coordinates"][0][0], json!([-4.8, 20.2]));
+        assert_eq!(reversed.geometry["coordinates"][0][4], json!([-5.0, 20.0]));
+    }
+
+    #[test]
+    fn test_split_at_interior_point() {
+        let (first, second) = split_at(&control_points(), &geometry(), 1).unwrap();
+
+        assert_eq!(first.control_points.len(), 2);
+        assert_eq!(second.control_points.len(), 2);
+        assert_eq!(first.control_points[1], second.control_points[0]);
+
+        let first_line = first.geometry["coordinates"][0].as_array().unwrap();
+        assert_eq!(first_line.last().unwrap(), &json!([-5.0, 20.2]));
+        assert_eq!(second.geometry["coordinates"][0][0], json!([-5.0, 20.2]));
+    }
+
+    #[test]
+    fn test_split_loop_at_second_pass() {
+        // Out to the east and back: the second control point is passed twice
+        let control_points: Vec<ControlPoint> = [
+            (-5.0, 20.0),
+            (-4.9, 20.0),
+            (-4.8, 20.0),
+            (-4.9, 20.0),
+            (-5.0, 20.0),
+        ]
+        .into_iter()
+        .map(|(lng, lat)| ControlPoint::new(Position::new(lng, lat).unwrap()))
+        .collect();
+        let geometry = json!({
+            "type": "MultiLineString",
+            "coordinates": [[[-5.0, 20.0], [-4.9, 20.0], [-4.8, 20.0], [-4.9, 20.0], [-5.0, 20.0]]]
+        });
+
+        let (first, second) = split_at(&control_points, &geometry, 3).unwrap();
+
+        let first_line = first.geometry["coordinates"][0].as_array().unwrap();
+        assert_eq!(first_line.len(), 4);
+        assert_eq!(first_line[2], json!([-4.8, 20.0]));
+        assert_eq!(
+            second.geometry["coordinates"][0],
+            json!([[-4.9, 20.0], [-5.0, 20.0]])
+        );
+    }
+
+    #[test]
+    fn test_split_rejects_endpoints() {
+        assert!(split_at(&control_points(), &geometry(), 0).is_err());
+        assert!(split_at(&control_points(), &geometry(), 2).is_err());
+    }
+
+    #[test]
+    fn test_trim() {
+        let trimmed = trim(&control_points(), &geometry(), 1, TrimSide::Before).unwrap();
+        assert_eq!(trimmed.control_points[0].as_tuple(), (-5.0, 20.2));
+
+        let trimmed = trim(&control_points(), &geometry(), 1, TrimSide::After).unwrap();
+        assert_eq!(trimmed.control_points.len(), 2);
+    }
+
+    #[test]
+    fn test_join_drops_shared_point() {
+        let (first, second) = split_at(&control_points(), &geometry(), 1).unwrap();
+        let joined = join(
+            &first.control_points,
+            &first.geometry,
+            &second.control_points,
+            &second.geometry,
+        )
+        .unwrap();
+
+        assert_eq!(joined.control_points, control_points());
+        assert_eq!(joined.geometry["coordinates"].as_array().unwrap().len(), 2);
+    }
+}
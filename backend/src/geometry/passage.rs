@@ -0,0 +1,528 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::linear_ref::{project_onto_segment, to_local, LinearRoute};
+use super::trace::TracePoint;
+use super::types::ControlPoint;
+
+/// Default speed limit inside a DZ/FZ speed zone (km/h)
+pub const DEFAULT_SPEED_LIMIT_KMH: f64 = 50.0;
+
+/// Rally waypoint types, as used in roadbooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum WaypointType {
+    /// Stage start (Départ Secteur Sélectif)
+    Dss,
+    /// Stage finish (Arrivée Secteur Sélectif)
+    Ass,
+    /// Visible waypoint
+    Wpv,
+    /// Masked waypoint
+    Wpm,
+    /// Eclipse waypoint
+    Wpe,
+    /// Safety waypoint
+    Wps,
+    /// Control waypoint
+    Wpc,
+    /// Speed zone start (Début de Zone)
+    Dz,
+    /// Speed zone end (Fin de Zone)
+    Fz,
+}
+
+impl WaypointType {
+    /// Validation radius used when a waypoint does not specify one (meters)
+    pub fn default_radius_m(&self) -> f64 {
+        match self {
+            Self::Dss | Self::Ass | Self::Wpv => 300.0,
+            Self::Wpm | Self::Wpe => 200.0,
+            Self::Wps | Self::Wpc | Self::Dz | Self::Fz => 90.0,
+        }
+    }
+}
+
+/// A control point carrying a rally waypoint type
+#[derive(Debug, Clone, Serialize)]
+pub struct TypedWaypoint {
+    /// Index in the route's control points
+    pub index: usize,
+    pub waypoint_type: WaypointType,
+    pub radius_m: f64,
+    pub speed_limit_kmh: Option<f64>,
+    pub lng: f64,
+    pub lat: f64,
+}
+
+/// Extract typed waypoints from a route's control points
+///
+/// Untyped points are skipped; a missing or non-positive radius falls back
+/// to the waypoint type's default.
+pub fn typed_waypoints(control_points: &[ControlPoint]) -> Vec<TypedWaypoint> {
+    control_points
+        .iter()
+        .enumerate()
+        .filter_map(|(index, point)| {
+            let waypoint_type = point.waypoint.waypoint_type?;
+
+            Some(TypedWaypoint {
+                index,
+                waypoint_type,
+                radius_m: point
+                    .waypoint
+                    .radius_m
+                    .filter(|r| *r > 0.0)
+                    .unwrap_or_else(|| waypoint_type.default_radius_m()),
+                speed_limit_kmh: point.waypoint.speed_limit_kmh,
+                lng: point.position.lng,
+                lat: point.position.lat,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WaypointPassage {
+    pub index: usize,
+    pub waypoint_type: WaypointType,
+    pub radius_m: f64,
+    pub lng: f64,
+    pub lat: f64,
+    pub validated: bool,
+    /// First time the trace entered the validation radius
+    pub validated_at: Option<DateTime<Utc>>,
+    /// Closest approach of the trace to the waypoint (meters)
+    pub closest_distance_m: f64,
+    pub closest_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeedInfringement {
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Position on the planned route where the infringement started (km)
+    pub km: f64,
+    pub max_speed_kmh: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeedZoneReport {
+    /// Control point index of the DZ waypoint
+    pub start_index: usize,
+    /// Control point index of the FZ waypoint
+    pub end_index: usize,
+    pub speed_limit_kmh: f64,
+    pub from_km: f64,
+    pub to_km: f64,
+    /// `None` when the trace has no timestamps inside the zone
+    pub max_speed_kmh: Option<f64>,
+    pub avg_speed_kmh: Option<f64>,
+    pub infringements: Vec<SpeedInfringement>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviationSummary {
+    /// Offset beyond which the trace counts as off the planned route (meters)
+    pub corridor_m: f64,
+    pub max_offset_m: f64,
+    pub mean_offset_m: f64,
+    pub driven_km: f64,
+    /// Distance driven outside the corridor (km)
+    pub off_route_km: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PassageReport {
+    pub validated_count: usize,
+    pub missed_count: usize,
+    pub waypoints: Vec<WaypointPassage>,
+    pub speed_zones: Vec<SpeedZoneReport>,
+    pub deviation: DeviationSummary,
+}
+
+fn interpolate_time(a: &TracePoint, b: &TracePoint, t: f64) -> Option<DateTime<Utc>> {
+    let (start, end) = (a.time?, b.time?);
+    let millis = ((end - start).num_milliseconds() as f64 * t).round() as i64;
+    Some(start + Duration::milliseconds(millis))
+}
+
+/// Fraction of segment `a`-`b` at which it enters the circle of `radius_m`
+/// around `center`, given that its closest approach at `closest_t` is inside
+fn entry_fraction(
+    a: (f64, f64),
+    b: (f64, f64),
+    center: (f64, f64),
+    radius_m: f64,
+    closest_t: f64,
+) -> f64 {
+    let a = to_local(a, center);
+    let b = to_local(b, center);
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len2 = dx * dx + dy * dy;
+    let c = a.0 * a.0 + a.1 * a.1 - radius_m * radius_m;
+    if c <= 0.0 || len2 == 0.0 {
+        return 0.0;
+    }
+
+    // Smaller root of |a + t·(b - a)|² = r²
+    let half_b = a.0 * dx + a.1 * dy;
+    let disc = (half_b * half_b - len2 * c).max(0.0);
+    ((-half_b - disc.sqrt()) / len2).clamp(0.0, closest_t)
+}
+
+fn check_waypoint(waypoint: &TypedWaypoint, trace: &[TracePoint]) -> WaypointPassage {
+    let target = (waypoint.lng, waypoint.lat);
+    let mut closest = (f64::INFINITY, None);
+    let mut validated_at = None;
+    let mut validated = false;
+
+    for pair in trace.windows(2) {
+        let (a, b) = (pair[0].position(), pair[1].position());
+        let (t, dist, _) = project_onto_segment(a, b, target);
+        let at = interpolate_time(&pair[0], &pair[1], t);
+
+        if dist < closest.0 {
+            closest = (dist, at);
+        }
+        if !validated && dist <= waypoint.radius_m {
+            // Validated when the trace enters the radius, not at its closest
+            let entry = entry_fraction(a, b, target, waypoint.radius_m, t);
+            validated = true;
+            validated_at = interpolate_time(&pair[0], &pair[1], entry);
+        }
+    }
+
+    WaypointPassage {
+        index: waypoint.index,
+        waypoint_type: waypoint.waypoint_type,
+        radius_m: waypoint.radius_m,
+        lng: waypoint.lng,
+        lat: waypoint.lat,
+        validated,
+        validated_at,
+        closest_distance_m: closest.0,
+        closest_at: closest.1,
+    }
+}
+
+/// Position of each trace fix on the planned route: `(km, offset_m)`
+///
+/// Fixes are located in order, each on the first pass within `corridor_m`
+/// after the last fix that was on the route, so on out-and-back stages they
+/// snap to the pass being driven. Fixes off the route don't move the search
+/// on, which keeps a detour from skipping ahead.
+pub(crate) fn locate_trace(
+    planned: &LinearRoute,
+    trace: &[TracePoint],
+    corridor_m: f64,
+) -> Vec<(f64, f64)> {
+    let mut from_km = 0.0;
+    trace
+        .iter()
+        .map(|p| {
+            let location = planned.locate_from(p.lng, p.lat, from_km, corridor_m);
+            if location.offset_m <= corridor_m {
+                from_km = location.km;
+            }
+            (location.km, location.offset_m)
+        })
+        .collect()
+}
+
+fn check_speed_zone(
+    (dz, from_km): (&TypedWaypoint, f64),
+    (fz, to_km): (&TypedWaypoint, f64),
+    trace: &[TracePoint],
+    located: &[(f64, f64)],
+    corridor_m: f64,
+) -> SpeedZoneReport {
+    let limit = dz
+        .speed_limit_kmh
+        .or(fz.speed_limit_kmh)
+        .unwrap_or(DEFAULT_SPEED_LIMIT_KMH);
+    let in_zone = |i: usize| {
+        let (km, offset) = located[i];
+        km >= from_km && km <= to_km && offset <= corridor_m
+    };
+
+    let mut max_speed: Option<f64> = None;
+    let mut distance_m = 0.0;
+    let mut seconds = 0.0;
+    let mut infringements: Vec<SpeedInfringement> = Vec::new();
+    let mut open_infringement = false;
+
+    for i in 1..trace.len() {
+        let speed = if in_zone(i - 1) && in_zone(i) {
+            trace[i].speed_kmh_from(&trace[i - 1])
+        } else {
+            None
+        };
+
+        let Some(speed) = speed else {
+            open_infringement = false;
+            continue;
+        };
+
+        max_speed = Some(max_speed.map_or(speed, |m| m.max(speed)));
+        distance_m += trace[i].distance_m(&trace[i - 1]);
+        if let (Some(a), Some(b)) = (trace[i - 1].time, trace[i].time) {
+            seconds += (b - a).num_milliseconds() as f64 / 1000.0;
+        }
+
+        if speed > limit {
+            match infringements.last_mut() {
+                Some(current) if open_infringement => {
+                    current.ended_at = trace[i].time;
+                    current.max_speed_kmh = current.max_speed_kmh.max(speed);
+                }
+                _ => infringements.push(SpeedInfringement {
+                    started_at: trace[i - 1].time,
+                    ended_at: trace[i].time,
+                    km: located[i - 1].0,
+                    max_speed_kmh: speed,
+                }),
+            }
+            open_infringement = true;
+        } else {
+            open_infringement = false;
+        }
+    }
+
+    SpeedZoneReport {
+        start_index: dz.index,
+        end_index: fz.index,
+        speed_limit_kmh: limit,
+        from_km,
+        to_km,
+        max_speed_kmh: max_speed,
+        avg_speed_kmh: (seconds > 0.0).then(|| distance_m / seconds * 3.6),
+        infringements,
+    }
+}
+
+fn summarize_deviation(
+    trace: &[TracePoint],
+    located: &[(f64, f64)],
+    corridor_m: f64,
+) -> DeviationSummary {
+    let offsets: Vec<f64> = located.iter().map(|(_, offset)| *offset).collect();
+    let mut driven_m = 0.0;
+    let mut off_route_m = 0.0;
+
+    for i in 1..trace.len() {
+        let step = trace[i].distance_m(&trace[i - 1]);
+        driven_m += step;
+        if offsets[i - 1] > corridor_m && offsets[i] > corridor_m {
+            off_route_m += step;
+        }
+    }
+
+    DeviationSummary {
+        corridor_m,
+        max_offset_m: offsets.iter().cloned().fold(0.0, f64::max),
+        mean_offset_m: offsets.iter().sum::<f64>() / offsets.len().max(1) as f64,
+        driven_km: driven_m / 1000.0,
+        off_route_km: off_route_m / 1000.0,
+    }
+}
+
+/// Score a recorded trace against a route's typed waypoints and speed zones
+///
+/// # Arguments
+/// * `control_points` - The route's control points (typed ones are checked)
+/// * `planned` - The planned route geometry
+/// * `trace` - Recorded GPS fixes in time order
+/// * `corridor_m` - Offset from the plan beyond which the trace is off-route
+pub fn validate_passage(
+    control_points: &[ControlPoint],
+    planned: &LinearRoute,
+    trace: &[TracePoint],
+    corridor_m: f64,
+) -> PassageReport {
+    let waypoints = typed_waypoints(control_points);
+    let located = locate_trace(planned, trace, corridor_m);
+
+    // Waypoints in route order, each located after the one before
+    let mut km = 0.0;
+    let waypoint_kms: Vec<f64> = waypoints
+        .iter()
+        .map(|wp| {
+            km = planned.locate_from(wp.lng, wp.lat, km, wp.radius_m).km;
+            km
+        })
+        .collect();
+
+    let passages: Vec<WaypointPassage> = waypoints
+        .iter()
+        .map(|wp| check_waypoint(wp, trace))
+        .collect();
+
+    // Each DZ opens a zone closed by the next FZ
+    let mut speed_zones = Vec::new();
+    for (i, dz) in waypoints.iter().enumerate() {
+        if dz.waypoint_type != WaypointType::Dz {
+            continue;
+        }
+        if let Some(j) = waypoints[i + 1..]
+            .iter()
+            .position(|w| w.waypoint_type == WaypointType::Fz)
+        {
+            let j = i + 1 + j;
+            speed_zones.push(check_speed_zone(
+                (dz, waypoint_kms[i]),
+                (&waypoints[j], waypoint_kms[j]),
+                trace,
+                &located,
+                corridor_m,
+            ));
+        }
+    }
+
+    let validated_count = passages.iter().filter(|p| p.validated).count();
+
+    PassageReport {
+        validated_count,
+        missed_count: passages.len() - validated_count,
+        waypoints: passages,
+        speed_zones,
+        deviation: summarize_deviation(trace, &located, corridor_m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::{json, Value};
+
+    /// Northbound trace at ~0.001°/10 s (≈ 40 km/h), 0.002°/10 s (≈ 80 km/h) after 20.05
+    fn trace() -> Vec<TracePoint> {
+        let start = Utc.with_ymd_and_hms(2026, 1, 10, 8, 0, 0).unwrap();
+        let mut points = Vec::new();
+        let mut lat = 20.0;
+        let mut t = 0;
+        while lat <= 20.1 + 1e-9 {
+            points.push(TracePoint {
+                lng: -5.0,
+                lat,
+                time: Some(start + Duration::seconds(t)),
+            });
+            lat += if lat < 20.05 { 0.001 } else { 0.002 };
+            t += 10;
+        }
+        points
+    }
+
+    fn control_points(value: Value) -> Vec<ControlPoint> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn planned() -> LinearRoute {
+        LinearRoute::new(vec![(-5.0, 20.0), (-5.0, 20.1)]).unwrap()
+    }
+
+    #[test]
+    fn test_typed_waypoints_defaults() {
+        let control_points = control_points(json!([
+            {"lng": -5.0, "lat": 20.0},
+            {"lng": -5.0, "lat": 20.05, "waypoint_type": "wpm"},
+            {"type": "Point", "coordinates": [-5.0, 20.1], "properties": {"waypoint_type": "WPV", "radius_m": 50}},
+        ]));
+
+        let waypoints = typed_waypoints(&control_points);
+
+        assert_eq!(waypoints.len(), 2);
+        assert_eq!(waypoints[0].waypoint_type, WaypointType::Wpm);
+        assert_eq!(waypoints[0].radius_m, 200.0);
+        assert_eq!(waypoints[1].radius_m, 50.0);
+    }
+
+    #[test]
+    fn test_validates_and_misses_waypoints() {
+        let control_points = control_points(json!([
+            {"lng": -5.0, "lat": 20.02, "waypoint_type": "WPM"},
+            // ~1 km east of the trace
+            {"lng": -4.99, "lat": 20.04, "waypoint_type": "WPM"},
+        ]));
+
+        let report = validate_passage(&control_points, &planned(), &trace(), 200.0);
+
+        assert_eq!(report.validated_count, 1);
+        assert_eq!(report.missed_count, 1);
+        assert!(report.waypoints[0].validated_at.is_some());
+        assert!(report.waypoints[0].closest_distance_m < 1.0);
+        assert!((report.waypoints[1].closest_distance_m - 1046.0).abs() < 20.0);
+    }
+
+    #[test]
+    fn test_validated_on_entering_the_radius() {
+        // One fix every 10 minutes: the segment enters the 200 m radius
+        // ~11 s before it passes the waypoint
+        let start = Utc.with_ymd_and_hms(2026, 1, 10, 8, 0, 0).unwrap();
+        let trace = [
+            TracePoint {
+                lng: -5.0,
+                lat: 20.0,
+                time: Some(start),
+            },
+            TracePoint {
+                lng: -5.0,
+                lat: 20.1,
+                time: Some(start + Duration::seconds(600)),
+            },
+        ];
+        let control_points = control_points(json!([
+            {"lng": -5.0, "lat": 20.05, "waypoint_type": "WPM"},
+        ]));
+
+        let report = validate_passage(&control_points, &planned(), &trace, 200.0);
+        let passage = &report.waypoints[0];
+
+        assert!(passage.validated);
+        assert_eq!(passage.closest_at, Some(start + Duration::seconds(300)));
+        let entered = (passage.validated_at.unwrap() - start).num_milliseconds() as f64 / 1000.0;
+        assert!((entered - 289.2).abs() < 0.5, "entered after {} s", entered);
+    }
+
+    #[test]
+    fn test_speed_zone_infringement() {
+        let control_points = control_points(json!([
+            {"lng": -5.0, "lat": 20.01, "waypoint_type": "DZ", "speed_limit_kmh": 50},
+            {"lng": -5.0, "lat": 20.09, "waypoint_type": "FZ"},
+        ]));
+
+        let report = validate_passage(&control_points, &planned(), &trace(), 200.0);
+        let zone = &report.speed_zones[0];
+
+        assert_eq!(zone.speed_limit_kmh, 50.0);
+        let max = zone.max_speed_kmh.unwrap();
+        assert!((max - 80.0).abs() < 1.0, "{}", max);
+        assert_eq!(zone.infringements.len(), 1);
+        assert!(zone.infringements[0].km > 4.9);
+        assert!(zone.avg_speed_kmh.unwrap() > 40.0);
+    }
+
+    #[test]
+    fn test_deviation_summary_on_plan() {
+        let report = validate_passage(&[], &planned(), &trace(), 200.0);
+
+        assert!(report.deviation.max_offset_m < 1.0);
+        assert_eq!(report.deviation.off_route_km, 0.0);
+        assert!((report.deviation.driven_km - 11.1).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_locate_trace_out_and_back() {
+        let planned = LinearRoute::new(vec![(-5.0, 20.0), (-5.0, 20.1), (-5.0, 20.0)]).unwrap();
+        let mut trace = trace();
+        let back: Vec<TracePoint> = trace.iter().rev().skip(1).cloned().collect();
+        trace.extend(back);
+
+        let located = locate_trace(&planned, &trace, 200.0);
+
+        assert!(located.windows(2).all(|w| w[1].0 >= w[0].0));
+        assert!((located.last().unwrap().0 - planned.length_km()).abs() < 0.01);
+        assert!(located.iter().all(|(_, offset)| *offset < 1.0));
+    }
+}
@@ -0,0 +1,191 @@
+use geo::{HaversineDistance, Point};
+use serde::Serialize;
+
+/// Lines shorter than this are unlikely to be worth curating (meters)
+pub const MIN_LENGTH_M: f64 = 200.0;
+
+/// Consecutive points farther apart than this leave the line's shape a guess (meters)
+pub const MAX_GAP_M: f64 = 2_000.0;
+
+/// Share of a line lying along existing tracks above which it is a duplicate
+pub const DUPLICATE_RATIO: f64 = 0.8;
+
+/// An out-and-back shorter than this share of its legs is a GPS spike
+const SPIKE_RATIO: f64 = 0.1;
+
+/// Legs shorter than this are ignored by spike detection (meters)
+const MIN_SPIKE_LEG_M: f64 = 50.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityIssueKind {
+    TooShort,
+    /// Points too far apart to follow the terrain
+    SparsePoints,
+    /// A point jumps away and straight back, typical of a bad GPS fix
+    Spike,
+    /// Mostly follows tracks that are already curated
+    Duplicate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QualityIssue {
+    pub kind: QualityIssueKind,
+    /// Index of the point the issue starts at, when it has one
+    pub point: Option<usize>,
+    pub message: String,
+}
+
+/// Automatic checks on a submitted line, to help curators triage
+#[derive(Debug, Clone, Serialize)]
+pub struct QualityReport {
+    pub length_m: f64,
+    pub points: usize,
+    /// Longest distance between consecutive points
+    pub max_gap_m: f64,
+    /// Share of the line within reach of existing tracks (0-1)
+    pub overlap_ratio: f64,
+    pub issues: Vec<QualityIssue>,
+}
+
+impl QualityReport {
+    pub fn passed(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+fn distance_m(a: (f64, f64), b: (f64, f64)) -> f64 {
+    Point::new(a.0, a.1).haversine_distance(&Point::new(b.0, b.1))
+}
+
+/// Assess a `(lng, lat)` line whose `overlap_m` meters run along existing tracks
+pub fn assess_line(points: &[(f64, f64)], overlap_m: f64) -> QualityReport {
+    let legs: Vec<f64> = points.windows(2).map(|w| distance_m(w[0], w[1])).collect();
+    let length_m: f64 = legs.iter().sum();
+    let max_gap_m = legs.iter().copied().fold(0.0, f64::max);
+    let overlap_ratio = if length_m > 0.0 {
+        (overlap_m / length_m).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    let mut issues = Vec::new();
+
+    if length_m < MIN_LENGTH_M {
+        issues.push(QualityIssue {
+            kind: QualityIssueKind::TooShort,
+            point: None,
+            message: format!("Line is only {:.0} m long", length_m),
+        });
+    }
+
+    for (i, &leg) in legs.iter().enumerate() {
+        if leg > MAX_GAP_M {
+            issues.push(QualityIssue {
+                kind: QualityIssueKind::SparsePoints,
+                point: Some(i),
+                message: format!("{:.1} km without a point after point {}", leg / 1000.0, i),
+            });
+        }
+    }
+
+    for (i, w) in points.windows(3).enumerate() {
+        let (out, back) = (legs[i], legs[i + 1]);
+        if out.min(back) >= MIN_SPIKE_LEG_M && distance_m(w[0], w[2]) < SPIKE_RATIO * (out + back) {
+            issues.push(QualityIssue {
+                kind: QualityIssueKind::Spike,
+                point: Some(i + 1),
+                message: format!("Point {} jumps {:.0} m away and back", i + 1, out.max(back)),
+            });
+        }
+    }
+
+    if overlap_ratio >= DUPLICATE_RATIO {
+        issues.push(QualityIssue {
+            kind: QualityIssueKind::Duplicate,
+            point: None,
+            message: format!(
+                "{:.0}% of the line follows existing tracks",
+                overlap_ratio * 100.0
+            ),
+        });
+    }
+
+    QualityReport {
+        length_m,
+        points: points.len(),
+        max_gap_m,
+        overlap_ratio,
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(report: &QualityReport) -> Vec<QualityIssueKind> {
+        report.issues.iter().map(|i| i.kind).collect()
+    }
+
+    #[test]
+    fn test_clean_line_passes() {
+        // ~1.1 km north in 110 m steps
+        let points: Vec<(f64, f64)> = (0..=10).map(|i| (-5.0, 20.0 + i as f64 * 0.001)).collect();
+
+        let report = assess_line(&points, 0.0);
+
+        assert!(report.passed(), "{:?}", report.issues);
+        assert_eq!(report.points, 11);
+        assert!(
+            (report.length_m - 1113.0).abs() < 5.0,
+            "{}",
+            report.length_m
+        );
+        assert!((report.max_gap_m - 111.3).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_short_and_sparse_lines() {
+        let short = assess_line(&[(-5.0, 20.0), (-5.0, 20.001)], 0.0);
+        assert_eq!(kinds(&short), vec![QualityIssueKind::TooShort]);
+
+        // Two 5.5 km legs
+        let sparse = assess_line(&[(-5.0, 20.0), (-5.0, 20.05), (-5.0, 20.1)], 0.0);
+        assert_eq!(
+            kinds(&sparse),
+            vec![
+                QualityIssueKind::SparsePoints,
+                QualityIssueKind::SparsePoints
+            ]
+        );
+        assert_eq!(sparse.issues[1].point, Some(1));
+    }
+
+    #[test]
+    fn test_spike_detected() {
+        let points = [
+            (-5.0, 20.0),
+            (-5.0, 20.001),
+            (-4.99, 20.002), // ~1 km east and straight back
+            (-5.0, 20.002),
+            (-5.0, 20.003),
+        ];
+
+        let report = assess_line(&points, 0.0);
+
+        assert_eq!(kinds(&report), vec![QualityIssueKind::Spike]);
+        assert_eq!(report.issues[0].point, Some(2));
+    }
+
+    #[test]
+    fn test_duplicate_overlap() {
+        let points: Vec<(f64, f64)> = (0..=10).map(|i| (-5.0, 20.0 + i as f64 * 0.001)).collect();
+
+        let report = assess_line(&points, 1000.0);
+        assert_eq!(kinds(&report), vec![QualityIssueKind::Duplicate]);
+        assert!(report.overlap_ratio > 0.89 && report.overlap_ratio < 0.91);
+
+        assert!(assess_line(&points, 500.0).passed());
+    }
+}
@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::PgPool;
 
+use super::validate::parse_position;
+
 /// Configuration for routing behavior
 #[derive(Debug, Clone)]
 pub struct RoutingConfig {
@@ -10,6 +12,10 @@ pub struct RoutingConfig {
     pub curated_track_threshold_meters: f64,
     /// Mapbox API access token
     pub mapbox_token: String,
+    /// Skip curated tracks with an active severe hazard nearby
+    pub avoid_severe_hazards: bool,
+    /// How close a severe hazard must be to a track to rule it out (meters)
+    pub hazard_clearance_meters: f64,
 }
 
 impl Default for RoutingConfig {
@@ -17,6 +23,8 @@ impl Default for RoutingConfig {
         Self {
             curated_track_threshold_meters: 100.0,
             mapbox_token: std::env::var("MAPBOX_ACCESS_TOKEN").unwrap_or_default(),
+            avoid_severe_hazards: false,
+            hazard_clearance_meters: 100.0,
         }
     }
 }
@@ -44,7 +52,8 @@ struct MapboxGeometry {
 ///
 /// # Algorithm
 /// 1. For each consecutive pair of points (A → B):
-///    a. Check if both points are near curated tracks (< threshold)
+///    a. Check if both points are near curated tracks (< threshold), leaving
+///    out tracks near severe hazards when `config.avoid_severe_hazards`
 ///    b. If YES: Route using curated tracks (PostGIS nearest-neighbor)
 ///    c. If NO: Route using Mapbox Directions API (road network)
 /// 2. Combine all segments into final MultiLineString
@@ -75,11 +84,8 @@ pub async fn route_geometry(
             .as_array()
             .ok_or_else(|| anyhow!("Invalid line coordinates"))?
             .iter()
-            .filter_map(|p| {
-                let arr = p.as_array()?;
-                Some((arr[0].as_f64()?, arr[1].as_f64()?))
-            })
-            .collect();
+            .map(|p| parse_position(p).ok_or_else(|| anyhow!("Invalid position {}", p)))
+            .collect::<Result<_>>()?;
 
         if points.is_empty() {
             continue;
@@ -96,17 +102,8 @@ pub async fn route_geometry(
                 let next = points[i + 1];
 
                 // Check if both points are near curated tracks
-                let start_near_track =
-                    is_near_curated_track(pool, lng, lat, config.curated_track_threshold_meters)
-                        .await?;
-
-                let end_near_track = is_near_curated_track(
-                    pool,
-                    next.0,
-                    next.1,
-                    config.curated_track_threshold_meters,
-                )
-                .await?;
+                let start_near_track = is_near_curated_track(pool, lng, lat, config).await?;
+                let end_near_track = is_near_curated_track(pool, next.0, next.1, config).await?;
 
                 if start_near_track && end_near_track {
                     // OFF-ROAD: Both points near tracks → Use curated track routing
@@ -118,13 +115,8 @@ pub async fn route_geometry(
                         next.1
                     );
 
-                    let (track_points, confidence) = route_via_curated_tracks(
-                        pool,
-                        (lng, lat),
-                        next,
-                        config.curated_track_threshold_meters,
-                    )
-                    .await?;
+                    let (track_points, confidence) =
+                        route_via_curated_tracks(pool, (lng, lat), next, config).await?;
 
                     // Add intermediate points (skip first as it's already added)
                     routed_line.extend(track_points.into_iter().skip(1));
@@ -171,28 +163,36 @@ pub async fn route_geometry(
     ))
 }
 
-/// Check if a point is within threshold distance of any curated track
+/// Check if a point is within threshold distance of any usable curated track
 async fn is_near_curated_track(
     pool: &PgPool,
     lng: f64,
     lat: f64,
-    threshold_meters: f64,
+    config: &RoutingConfig,
 ) -> Result<bool> {
+    // $4 set: leave out tracks with an active severe hazard within $5 meters
     let result = sqlx::query_scalar!(
         r#"
         SELECT EXISTS(
             SELECT 1
-            FROM curated_tracks
+            FROM curated_tracks t
             WHERE ST_DWithin(
-                geometry::geography,
+                t.geometry::geography,
                 ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography,
                 $3
             )
+            AND NOT ($4 AND EXISTS (
+                SELECT 1 FROM active_hazard_reports h
+                WHERE h.severity = 'severe'
+                  AND ST_DWithin(h.geometry::geography, t.geometry::geography, $5)
+            ))
         ) as "exists!"
         "#,
         lng,
         lat,
-        threshold_meters
+        config.curated_track_threshold_meters,
+        config.avoid_severe_hazards,
+        config.hazard_clearance_meters
     )
     .fetch_one(pool)
     .await?;
@@ -206,50 +206,61 @@ async fn route_via_curated_tracks(
     pool: &PgPool,
     start: (f64, f64),
     end: (f64, f64),
-    max_distance_meters: f64,
+    config: &RoutingConfig,
 ) -> Result<(Vec<Vec<f64>>, f64)> {
     // Simplified approach: Find nearest track and snap both points to it
     // Future enhancement: Implement proper A* routing across track network
 
+    // $6 set: leave out tracks with an active severe hazard within $7 meters
     let result = sqlx::query!(
         r#"
         WITH start_snap AS (
             SELECT
-                id,
+                t.id,
                 ST_ClosestPoint(
-                    geometry,
+                    t.geometry,
                     ST_SetSRID(ST_MakePoint($1, $2), 4326)
                 ) as snap_point,
-                confidence
-            FROM curated_tracks
+                t.confidence
+            FROM curated_tracks t
             WHERE ST_DWithin(
-                geometry::geography,
+                t.geometry::geography,
                 ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography,
                 $5
             )
-            ORDER BY geometry <-> ST_SetSRID(ST_MakePoint($1, $2), 4326)
+            AND NOT ($6 AND EXISTS (
+                SELECT 1 FROM active_hazard_reports h
+                WHERE h.severity = 'severe'
+                  AND ST_DWithin(h.geometry::geography, t.geometry::geography, $7)
+            ))
+            ORDER BY t.geometry <-> ST_SetSRID(ST_MakePoint($1, $2), 4326)
             LIMIT 1
         ),
         end_snap AS (
             SELECT
                 ST_ClosestPoint(
-                    geometry,
+                    t.geometry,
                     ST_SetSRID(ST_MakePoint($3, $4), 4326)
                 ) as snap_point
-            FROM curated_tracks
+            FROM curated_tracks t
             WHERE ST_DWithin(
-                geometry::geography,
+                t.geometry::geography,
                 ST_SetSRID(ST_MakePoint($3, $4), 4326)::geography,
                 $5
             )
-            ORDER BY geometry <-> ST_SetSRID(ST_MakePoint($3, $4), 4326)
+            AND NOT ($6 AND EXISTS (
+                SELECT 1 FROM active_hazard_reports h
+                WHERE h.severity = 'severe'
+                  AND ST_DWithin(h.geometry::geography, t.geometry::geography, $7)
+            ))
+            ORDER BY t.geometry <-> ST_SetSRID(ST_MakePoint($3, $4), 4326)
             LIMIT 1
         )
         SELECT
-            ST_X(start_snap.snap_point) as start_lng,
-            ST_Y(start_snap.snap_point) as start_lat,
-            ST_X(end_snap.snap_point) as end_lng,
-            ST_Y(end_snap.snap_point) as end_lat,
+            ST_X(start_snap.snap_point) as "start_lng!",
+            ST_Y(start_snap.snap_point) as "start_lat!",
+            ST_X(end_snap.snap_point) as "end_lng!",
+            ST_Y(end_snap.snap_point) as "end_lat!",
             start_snap.confidence
         FROM start_snap, end_snap
         "#,
@@ -257,7 +268,9 @@ async fn route_via_curated_tracks(
         start.1,
         end.0,
         end.1,
-        max_distance_meters
+        config.curated_track_threshold_meters,
+        config.avoid_severe_hazards,
+        config.hazard_clearance_meters
     )
     .fetch_optional(pool)
     .await?;
@@ -266,8 +279,8 @@ async fn route_via_curated_tracks(
         // Return simple straight line between snapped points
         // Future: Extract actual track geometry between snap points
         let points = vec![
-            vec![track.start_lng.unwrap(), track.start_lat.unwrap()],
-            vec![track.end_lng.unwrap(), track.end_lat.unwrap()],
+            vec![track.start_lng, track.start_lat],
+            vec![track.end_lng, track.end_lat],
         ];
 
         let confidence = track.confidence as f64 / 5.0; // Normalize to 0.0-1.0
@@ -345,5 +358,6 @@ mod tests {
     fn test_routing_config_default() {
         let config = RoutingConfig::default();
         assert_eq!(config.curated_track_threshold_meters, 100.0);
+        assert!(!config.avoid_severe_hazards);
     }
 }
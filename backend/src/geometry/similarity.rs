@@ -0,0 +1,235 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use super::linear_ref::to_local;
+
+/// Discrete Fréchet distance between two `(lng, lat)` lines (meters)
+///
+/// The "dog leash" distance: the shortest leash that lets two walkers
+/// traverse both lines start to end without backtracking. Lines digitized in
+/// opposite directions are compared both ways and the closer match wins.
+/// Distances are measured in a local frame around the first line's start.
+///
+/// Both lines are first densified to vertices at most `max_spacing_m` apart,
+/// so the same line digitized with more or fewer vertices scores about zero
+/// instead of the length of its longest segment.
+pub fn frechet_distance_m(a: &[(f64, f64)], b: &[(f64, f64)], max_spacing_m: f64) -> Option<f64> {
+    let origin = *a.first()?;
+    b.first()?;
+
+    let local = |line: &[(f64, f64)]| {
+        let line: Vec<(f64, f64)> = line.iter().map(|&p| to_local(p, origin)).collect();
+        densify(&line, max_spacing_m)
+    };
+    let a = local(a);
+    let mut b = local(b);
+
+    let forward = discrete_frechet(&a, &b);
+    b.reverse();
+    let backward = discrete_frechet(&a, &b);
+
+    Some(forward.min(backward))
+}
+
+/// Insert evenly spaced vertices so no segment is longer than `max_spacing_m`
+fn densify(line: &[(f64, f64)], max_spacing_m: f64) -> Vec<(f64, f64)> {
+    if max_spacing_m.is_nan() || max_spacing_m <= 0.0 {
+        return line.to_vec();
+    }
+
+    let mut dense = Vec::with_capacity(line.len());
+    for pair in line.windows(2) {
+        let (p, q) = (pair[0], pair[1]);
+        let steps = ((q.0 - p.0).hypot(q.1 - p.1) / max_spacing_m)
+            .ceil()
+            .max(1.0) as usize;
+        dense.extend((0..steps).map(|i| {
+            let t = i as f64 / steps as f64;
+            (p.0 + t * (q.0 - p.0), p.1 + t * (q.1 - p.1))
+        }));
+    }
+    dense.extend(line.last());
+    dense
+}
+
+/// Row-by-row dynamic program over the coupling matrix, O(|b|) memory
+fn discrete_frechet(a: &[(f64, f64)], b: &[(f64, f64)]) -> f64 {
+    let dist = |p: (f64, f64), q: (f64, f64)| (p.0 - q.0).hypot(p.1 - q.1);
+
+    let mut previous: Vec<f64> = Vec::with_capacity(b.len());
+    for (j, &q) in b.iter().enumerate() {
+        let d = dist(a[0], q);
+        previous.push(if j == 0 { d } else { d.max(previous[j - 1]) });
+    }
+
+    let mut current = vec![0.0; b.len()];
+    for &p in &a[1..] {
+        for (j, &q) in b.iter().enumerate() {
+            let d = dist(p, q);
+            let reachable = if j == 0 {
+                previous[0]
+            } else {
+                previous[j].min(previous[j - 1]).min(current[j - 1])
+            };
+            current[j] = d.max(reachable);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len() - 1]
+}
+
+/// What a merge decision needs to know about one track
+#[derive(Debug, Clone)]
+pub struct MergeSide<'a> {
+    pub source: &'a str,
+    pub confidence: i32,
+    pub last_verified: Option<NaiveDate>,
+    pub length_m: f64,
+    pub points: usize,
+    /// Share of this track within reach of the other (0-1)
+    pub overlap: f64,
+}
+
+impl MergeSide<'_> {
+    /// Hand-curated lines beat rally archives beat bulk OSM imports
+    fn source_rank(&self) -> u8 {
+        match self.source {
+            "curated" => 2,
+            "rally" => 1,
+            _ => 0,
+        }
+    }
+
+    fn points_per_km(&self) -> f64 {
+        if self.length_m > 0.0 {
+            self.points as f64 / (self.length_m / 1000.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Keep {
+    A,
+    B,
+}
+
+/// Which geometry to keep when merging two overlapping tracks
+///
+/// When one track lies along the other but not the reverse, the longer one
+/// covers both and is kept. Otherwise the better-attested geometry wins:
+/// higher confidence, then more recently verified, then the more trusted
+/// source, then the more detailed line.
+pub fn choose_keeper(a: &MergeSide, b: &MergeSide, duplicate_ratio: f64) -> Keep {
+    let a_inside = a.overlap >= duplicate_ratio;
+    let b_inside = b.overlap >= duplicate_ratio;
+    if a_inside != b_inside {
+        return if a_inside { Keep::B } else { Keep::A };
+    }
+
+    let key = |s: &MergeSide| (s.confidence, s.last_verified, s.source_rank());
+    match key(a).cmp(&key(b)) {
+        std::cmp::Ordering::Greater => Keep::A,
+        std::cmp::Ordering::Less => Keep::B,
+        std::cmp::Ordering::Equal if b.points_per_km() > a.points_per_km() => Keep::B,
+        std::cmp::Ordering::Equal => Keep::A,
+    }
+}
+
+/// Confidence of a merged track
+///
+/// Agreement between independent sources corroborates the line, so it is
+/// raised one step; a duplicate from the same source only keeps the best.
+pub fn merged_confidence(a: &MergeSide, b: &MergeSide) -> i32 {
+    let best = a.confidence.max(b.confidence);
+    if a.source != b.source {
+        (best + 1).min(5)
+    } else {
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn side(source: &str, confidence: i32) -> MergeSide<'_> {
+        MergeSide {
+            source,
+            confidence,
+            last_verified: None,
+            length_m: 1000.0,
+            points: 10,
+            overlap: 0.95,
+        }
+    }
+
+    #[test]
+    fn test_frechet_parallel_lines() {
+        let a: Vec<(f64, f64)> = (0..=10).map(|i| (-5.0, 20.0 + i as f64 * 0.001)).collect();
+        // Same line ~10 m east (0.0001° of longitude at 20°N ≈ 10.5 m)
+        let b: Vec<(f64, f64)> = a.iter().map(|&(lng, lat)| (lng + 0.0001, lat)).collect();
+
+        let d = frechet_distance_m(&a, &b, 30.0).unwrap();
+        assert!((d - 10.5).abs() < 0.5, "{}", d);
+
+        // Direction of digitizing does not matter
+        let reversed: Vec<(f64, f64)> = b.iter().rev().copied().collect();
+        assert!((frechet_distance_m(&a, &reversed, 30.0).unwrap() - d).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_frechet_detects_different_shapes() {
+        let a = [(-5.0, 20.0), (-5.0, 20.01)];
+        // Same ends, but a 1 km detour east in the middle
+        let b = [(-5.0, 20.0), (-4.99, 20.005), (-5.0, 20.01)];
+
+        let d = frechet_distance_m(&a, &b, 30.0).unwrap();
+        assert!(d > 1000.0, "{}", d);
+        assert!(frechet_distance_m(&a, &[], 30.0).is_none());
+    }
+
+    #[test]
+    fn test_frechet_ignores_vertex_density() {
+        // The same ~1.1 km straight line with 2 and with 11 vertices
+        let sparse = [(-5.0, 20.0), (-5.0, 20.01)];
+        let dense: Vec<(f64, f64)> = (0..=10).map(|i| (-5.0, 20.0 + i as f64 * 0.001)).collect();
+
+        // Without densifying this was ~556 m, half the sparse segment
+        let d = frechet_distance_m(&sparse, &dense, 30.0).unwrap();
+        assert!(d < 30.0, "{}", d);
+        assert!(frechet_distance_m(&dense, &sparse, 30.0).unwrap() < 30.0);
+    }
+
+    #[test]
+    fn test_keeper_prefers_container_then_attestation() {
+        let mut short = side("osm", 5);
+        short.length_m = 300.0;
+        let mut long = side("osm", 2);
+        long.overlap = 0.2;
+        assert_eq!(choose_keeper(&short, &long, 0.8), Keep::B);
+
+        let mut recent = side("osm", 3);
+        recent.last_verified = NaiveDate::from_ymd_opt(2026, 1, 5);
+        assert_eq!(choose_keeper(&side("curated", 3), &recent, 0.8), Keep::B);
+        assert_eq!(
+            choose_keeper(&side("osm", 3), &side("rally", 3), 0.8),
+            Keep::B
+        );
+
+        let mut detailed = side("osm", 3);
+        detailed.points = 40;
+        assert_eq!(choose_keeper(&side("osm", 3), &detailed, 0.8), Keep::B);
+        assert_eq!(choose_keeper(&side("osm", 4), &detailed, 0.8), Keep::A);
+    }
+
+    #[test]
+    fn test_merged_confidence() {
+        assert_eq!(merged_confidence(&side("osm", 2), &side("rally", 3)), 4);
+        assert_eq!(merged_confidence(&side("osm", 5), &side("rally", 3)), 5);
+        assert_eq!(merged_confidence(&side("osm", 2), &side("osm", 3)), 3);
+    }
+}
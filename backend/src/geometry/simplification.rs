@@ -1,27 +1,91 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, BinaryHeap};
+
 use anyhow::{anyhow, Result};
-use geo::algorithm::simplify::Simplify;
-use geo::LineString;
+use geo::{Intersects, Line};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-/// Simplifies a MultiLineString geometry using Douglas-Peucker algorithm
+use super::linear_ref::{project_onto_segment, to_local};
+
+/// Default tolerance (meters), close to the former 0.0001° at the equator
+pub const DEFAULT_TOLERANCE_M: f64 = 11.0;
+
+/// Line simplification algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimplifyAlgorithm {
+    /// Douglas-Peucker: drops vertices within `tolerance_m` of the simplified line
+    DouglasPeucker,
+    /// Visvalingam-Whyatt: drops vertices whose effective area is below `tolerance_m²`
+    VisvalingamWhyatt,
+    /// Douglas-Peucker that never introduces new self-intersections
+    #[default]
+    TopologyPreserving,
+}
+
+/// Simplification settings, as accepted by route create/update payloads
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SimplifyOptions {
+    #[serde(default = "default_tolerance_m")]
+    pub tolerance_m: f64,
+    #[serde(default)]
+    pub algorithm: SimplifyAlgorithm,
+}
+
+fn default_tolerance_m() -> f64 {
+    DEFAULT_TOLERANCE_M
+}
+
+impl Default for SimplifyOptions {
+    fn default() -> Self {
+        Self {
+            tolerance_m: DEFAULT_TOLERANCE_M,
+            algorithm: SimplifyAlgorithm::default(),
+        }
+    }
+}
+
+impl SimplifyOptions {
+    pub fn validate(&self) -> Result<()> {
+        if !self.tolerance_m.is_finite() || self.tolerance_m < 0.0 {
+            return Err(anyhow!(
+                "Simplification tolerance must be a non-negative number of meters"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Simplifies a MultiLineString geometry with a tolerance in meters
+///
+/// Distances are measured in a local equirectangular frame around each
+/// vertex, so the tolerance means the same on the ground at any latitude.
+/// The vertex closest to each `keep` position (control points, typed
+/// waypoints) is always retained, as are the ends of every line.
 ///
 /// Target: 100km route should have ~200-500 points max (not 10,000+)
-/// Epsilon: 0.0001 degrees ≈ 11 meters tolerance
 ///
 /// # Arguments
 /// * `geometry` - GeoJSON MultiLineString as JSON Value
-/// * `epsilon` - Simplification tolerance in degrees (default: 0.0001 ≈ 11m)
+/// * `options` - Tolerance (meters) and algorithm
+/// * `keep` - `(lng, lat)` positions whose nearest vertex must survive
 ///
 /// # Returns
 /// Simplified GeoJSON MultiLineString
-pub fn simplify_geometry(geometry: &Value, epsilon: f64) -> Result<Value> {
+pub fn simplify_geometry(
+    geometry: &Value,
+    options: &SimplifyOptions,
+    keep: &[(f64, f64)],
+) -> Result<Value> {
+    options.validate()?;
+
     // Parse GeoJSON MultiLineString
     let coords = geometry["coordinates"]
         .as_array()
         .ok_or_else(|| anyhow!("Invalid geometry: missing coordinates"))?;
 
-    let mut simplified_coords = Vec::new();
-
+    let mut lines = Vec::new();
     for line_coords in coords {
         let points: Vec<(f64, f64)> = line_coords
             .as_array()
@@ -29,26 +93,28 @@ pub fn simplify_geometry(geometry: &Value, epsilon: f64) -> Result<Value> {
             .iter()
             .filter_map(|p| {
                 let arr = p.as_array()?;
-                Some((arr[0].as_f64()?, arr[1].as_f64()?))
+                Some((arr.first()?.as_f64()?, arr.get(1)?.as_f64()?))
             })
             .collect();
 
         // Skip empty or single-point lines
-        if points.len() < 2 {
-            continue;
+        if points.len() >= 2 {
+            lines.push(points);
         }
+    }
 
-        let linestring = LineString::from(points);
-        let simplified = linestring.simplify(&epsilon);
-
-        let simplified_points: Vec<Vec<f64>> = simplified
-            .0
-            .iter()
-            .map(|coord| vec![coord.x, coord.y])
-            .collect();
+    let anchors = anchor_vertices(&lines, keep);
 
-        simplified_coords.push(simplified_points);
-    }
+    let simplified_coords: Vec<Vec<Vec<f64>>> = lines
+        .iter()
+        .zip(anchors)
+        .map(|(points, anchors)| {
+            simplify_line(points, anchors, options)
+                .into_iter()
+                .map(|(lng, lat)| vec![lng, lat])
+                .collect()
+        })
+        .collect();
 
     Ok(serde_json::json!({
         "type": "MultiLineString",
@@ -56,10 +122,261 @@ pub fn simplify_geometry(geometry: &Value, epsilon: f64) -> Result<Value> {
     }))
 }
 
+/// Simplify one line, never removing vertices flagged in `anchors`
+pub fn simplify_line(
+    points: &[(f64, f64)],
+    mut anchors: Vec<bool>,
+    options: &SimplifyOptions,
+) -> Vec<(f64, f64)> {
+    if points.len() < 3 || options.tolerance_m == 0.0 {
+        return points.to_vec();
+    }
+
+    anchors[0] = true;
+    anchors[points.len() - 1] = true;
+
+    let kept = match options.algorithm {
+        SimplifyAlgorithm::DouglasPeucker => {
+            douglas_peucker(points, &mut anchors, options.tolerance_m);
+            anchors
+        }
+        SimplifyAlgorithm::VisvalingamWhyatt => {
+            visvalingam_whyatt(points, &anchors, options.tolerance_m * options.tolerance_m)
+        }
+        SimplifyAlgorithm::TopologyPreserving => {
+            topology_preserving(points, &mut anchors, options.tolerance_m);
+            anchors
+        }
+    };
+
+    points
+        .iter()
+        .zip(kept)
+        .filter_map(|(p, keep)| keep.then_some(*p))
+        .collect()
+}
+
+/// Flag, per line, the vertex nearest to each keep position
+fn anchor_vertices(lines: &[Vec<(f64, f64)>], keep: &[(f64, f64)]) -> Vec<Vec<bool>> {
+    let mut anchors: Vec<Vec<bool>> = lines.iter().map(|l| vec![false; l.len()]).collect();
+
+    for &target in keep {
+        let nearest = lines
+            .iter()
+            .enumerate()
+            .flat_map(|(l, line)| line.iter().enumerate().map(move |(v, &p)| (l, v, p)))
+            .map(|(l, v, p)| {
+                let (x, y) = to_local(p, target);
+                (l, v, x * x + y * y)
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+
+        if let Some((l, v, _)) = nearest {
+            anchors[l][v] = true;
+        }
+    }
+
+    anchors
+}
+
+/// Vertex between `start` and `end` farthest from the chord, with its distance (meters)
+fn farthest_from_chord(points: &[(f64, f64)], start: usize, end: usize) -> Option<(usize, f64)> {
+    (start + 1..end)
+        .map(|k| {
+            let (_, dist, _) = project_onto_segment(points[start], points[end], points[k]);
+            (k, dist)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Douglas-Peucker between every pair of consecutive kept vertices
+fn douglas_peucker(points: &[(f64, f64)], kept: &mut [bool], tolerance_m: f64) {
+    let mut stack: Vec<(usize, usize)> = kept_indices(kept)
+        .windows(2)
+        .map(|w| (w[0], w[1]))
+        .collect();
+
+    while let Some((start, end)) = stack.pop() {
+        if let Some((k, dist)) = farthest_from_chord(points, start, end) {
+            if dist > tolerance_m {
+                kept[k] = true;
+                stack.push((start, k));
+                stack.push((k, end));
+            }
+        }
+    }
+}
+
+fn kept_indices(kept: &[bool]) -> Vec<usize> {
+    kept.iter()
+        .enumerate()
+        .filter_map(|(i, &k)| k.then_some(i))
+        .collect()
+}
+
+/// Douglas-Peucker, then restore vertices until no simplified segment crosses
+/// another one whose original stretch it did not cross
+fn topology_preserving(points: &[(f64, f64)], kept: &mut [bool], tolerance_m: f64) {
+    douglas_peucker(points, kept, tolerance_m);
+
+    loop {
+        let indices = kept_indices(kept);
+        let spans: Vec<(usize, usize)> = indices.windows(2).map(|w| (w[0], w[1])).collect();
+        let mut restore = BTreeSet::new();
+
+        for a in 0..spans.len() {
+            for b in a + 2..spans.len() {
+                let (sa, sb) = (spans[a], spans[b]);
+                if !segment(points, sa.0, sa.1).intersects(&segment(points, sb.0, sb.1))
+                    || spans_intersect(points, sa, sb)
+                {
+                    continue;
+                }
+
+                // Restore the worst vertex of the span with more to give back
+                let span = if sa.1 - sa.0 >= sb.1 - sb.0 { sa } else { sb };
+                if let Some((k, _)) = farthest_from_chord(points, span.0, span.1) {
+                    restore.insert(k);
+                }
+            }
+        }
+
+        if restore.is_empty() {
+            break;
+        }
+        for k in restore {
+            kept[k] = true;
+        }
+        // Restored vertices split spans; make the halves honor the tolerance too
+        douglas_peucker(points, kept, tolerance_m);
+    }
+}
+
+fn segment(points: &[(f64, f64)], a: usize, b: usize) -> Line<f64> {
+    Line::new(points[a], points[b])
+}
+
+/// Whether the original vertices of two spans cross each other
+fn spans_intersect(points: &[(f64, f64)], a: (usize, usize), b: (usize, usize)) -> bool {
+    (a.0..a.1).any(|i| {
+        let first = segment(points, i, i + 1);
+        (b.0..b.1).any(|j| first.intersects(&segment(points, j, j + 1)))
+    })
+}
+
+/// Heap entry ordered by smallest area first
+#[derive(PartialEq)]
+struct AreaEntry {
+    area: f64,
+    index: usize,
+}
+
+impl Eq for AreaEntry {}
+
+impl Ord for AreaEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.area.total_cmp(&self.area)
+    }
+}
+
+impl PartialOrd for AreaEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Triangle area (m²) in a local frame centered on `b`
+fn triangle_area_m2(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    let a = to_local(a, b);
+    let c = to_local(c, b);
+    (a.0 * c.1 - a.1 * c.0).abs() / 2.0
+}
+
+/// Visvalingam-Whyatt; anchored vertices are never removed
+fn visvalingam_whyatt(points: &[(f64, f64)], anchors: &[bool], min_area_m2: f64) -> Vec<bool> {
+    let n = points.len();
+    let mut kept = vec![true; n];
+    let mut prev: Vec<usize> = (0..n).map(|i| i.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (0..n).map(|i| (i + 1).min(n - 1)).collect();
+    let mut area = vec![f64::INFINITY; n];
+    let mut heap = BinaryHeap::new();
+
+    for i in 1..n - 1 {
+        if !anchors[i] {
+            area[i] = triangle_area_m2(points[i - 1], points[i], points[i + 1]);
+            heap.push(AreaEntry {
+                area: area[i],
+                index: i,
+            });
+        }
+    }
+
+    while let Some(AreaEntry {
+        area: smallest,
+        index,
+    }) = heap.pop()
+    {
+        if smallest >= min_area_m2 {
+            break;
+        }
+        // Stale entry: the vertex's area changed after this was queued
+        if !kept[index] || smallest != area[index] {
+            continue;
+        }
+
+        kept[index] = false;
+        let (p, q) = (prev[index], next[index]);
+        next[p] = q;
+        prev[q] = p;
+
+        for neighbor in [p, q] {
+            if anchors[neighbor] || neighbor == 0 || neighbor == n - 1 {
+                continue;
+            }
+            // Never let a neighbor's area drop below the one just removed
+            area[neighbor] = triangle_area_m2(
+                points[prev[neighbor]],
+                points[neighbor],
+                points[next[neighbor]],
+            )
+            .max(smallest);
+            heap.push(AreaEntry {
+                area: area[neighbor],
+                index: neighbor,
+            });
+        }
+    }
+
+    kept
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Degrees per meter along a meridian
+    const DEG_PER_M: f64 = 1.0 / 111_195.0;
+
+    /// Local meters around (0, 0) to `(lng, lat)`
+    fn from_meters(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+        points
+            .iter()
+            .map(|&(x, y)| (x * DEG_PER_M, y * DEG_PER_M))
+            .collect()
+    }
+
+    fn multilinestring(points: &[(f64, f64)]) -> Value {
+        let coords: Vec<Vec<f64>> = points.iter().map(|&(x, y)| vec![x, y]).collect();
+        serde_json::json!({ "type": "MultiLineString", "coordinates": [coords] })
+    }
+
+    fn options(algorithm: SimplifyAlgorithm, tolerance_m: f64) -> SimplifyOptions {
+        SimplifyOptions {
+            tolerance_m,
+            algorithm,
+        }
+    }
+
     #[test]
     fn test_simplification() {
         let geometry = serde_json::json!({
@@ -75,7 +392,7 @@ mod tests {
             ]
         });
 
-        let simplified = simplify_geometry(&geometry, 0.0001).unwrap();
+        let simplified = simplify_geometry(&geometry, &SimplifyOptions::default(), &[]).unwrap();
         let coords = simplified["coordinates"][0].as_array().unwrap();
 
         // Should reduce from 5 points to 3 points
@@ -90,7 +407,115 @@ mod tests {
             "coordinates": []
         });
 
-        let simplified = simplify_geometry(&geometry, 0.0001).unwrap();
+        let simplified = simplify_geometry(&geometry, &SimplifyOptions::default(), &[]).unwrap();
         assert_eq!(simplified["coordinates"].as_array().unwrap().len(), 0);
     }
+
+    #[test]
+    fn test_tolerance_is_metric_at_high_latitude() {
+        // Northbound line at 60°N; a degree of longitude is ~55.6 km there
+        let east = |m: f64| m * DEG_PER_M / 60f64.to_radians().cos();
+        let line = vec![
+            (10.0, 60.0),
+            (10.0 + east(8.0), 60.001),
+            (10.0, 60.002),
+            (10.0 + east(15.0), 60.003),
+            (10.0, 60.004),
+        ];
+
+        for algorithm in [
+            SimplifyAlgorithm::DouglasPeucker,
+            SimplifyAlgorithm::TopologyPreserving,
+        ] {
+            let simplified =
+                simplify_line(&line, vec![false; line.len()], &options(algorithm, 11.0));
+            // The 8 m bump goes, the 15 m one stays
+            assert!(!simplified.contains(&line[1]), "{:?}", algorithm);
+            assert!(simplified.contains(&line[3]), "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_keeps_control_points() {
+        let line = from_meters(&[
+            (0.0, 0.0),
+            (50.0, 1.0),
+            (100.0, 0.0),
+            (150.0, 1.0),
+            (200.0, 0.0),
+        ]);
+        let geometry = multilinestring(&line);
+
+        for algorithm in [
+            SimplifyAlgorithm::DouglasPeucker,
+            SimplifyAlgorithm::VisvalingamWhyatt,
+            SimplifyAlgorithm::TopologyPreserving,
+        ] {
+            let simplified =
+                simplify_geometry(&geometry, &options(algorithm, 11.0), &[line[3]]).unwrap();
+            let coords = simplified["coordinates"][0].as_array().unwrap();
+
+            assert_eq!(coords.len(), 3, "{:?}", algorithm);
+            assert_eq!(coords[1], serde_json::json!([line[3].0, line[3].1]));
+        }
+    }
+
+    #[test]
+    fn test_visvalingam_drops_small_triangles() {
+        let line = from_meters(&[
+            (0.0, 0.0),
+            (10.0, 2.0),
+            (20.0, 0.0),
+            (30.0, 40.0),
+            (40.0, 0.0),
+        ]);
+
+        let simplified = simplify_line(
+            &line,
+            vec![false; line.len()],
+            &options(SimplifyAlgorithm::VisvalingamWhyatt, 11.0),
+        );
+
+        assert_eq!(simplified, vec![line[0], line[2], line[3], line[4]]);
+    }
+
+    #[test]
+    fn test_topology_preserving_avoids_self_intersection() {
+        // A shallow 4 m dip, with a later hairpin poking into the pocket above it
+        let line = from_meters(&[
+            (0.0, 0.0),
+            (50.0, -4.0),
+            (100.0, 0.0),
+            (100.0, 40.0),
+            (60.0, 20.0),
+            (50.0, -2.0),
+            (40.0, 20.0),
+            (0.0, 40.0),
+        ]);
+
+        let plain = simplify_line(
+            &line,
+            vec![false; line.len()],
+            &options(SimplifyAlgorithm::DouglasPeucker, 5.0),
+        );
+        assert!(!plain.contains(&line[1]));
+
+        let preserved = simplify_line(
+            &line,
+            vec![false; line.len()],
+            &options(SimplifyAlgorithm::TopologyPreserving, 5.0),
+        );
+        assert!(preserved.contains(&line[1]));
+    }
+
+    #[test]
+    fn test_rejects_negative_tolerance() {
+        let geometry = multilinestring(&[(0.0, 0.0), (1.0, 1.0)]);
+        assert!(simplify_geometry(
+            &geometry,
+            &options(SimplifyAlgorithm::DouglasPeucker, -1.0),
+            &[]
+        )
+        .is_err());
+    }
 }
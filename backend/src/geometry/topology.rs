@@ -0,0 +1,453 @@
+use std::collections::{HashMap, HashSet};
+
+use geo::{HaversineDistance, Point};
+use uuid::Uuid;
+
+use super::linear_ref::{project_onto_segment, to_local};
+
+/// Largest accepted snapping tolerance (meters)
+///
+/// The segment index assumes a tolerance well below its cell size.
+pub const MAX_TOLERANCE_M: f64 = 500.0;
+
+/// Segment index cell size (degrees, about 2 km)
+const SEGMENT_CELL_DEG: f64 = 0.02;
+
+const METERS_PER_DEGREE: f64 = 111_320.0;
+
+/// A junction or dead end of the track network
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopologyNode {
+    /// `(lng, lat)`
+    pub position: (f64, f64),
+    /// Number of edge ends at this node
+    pub degree: usize,
+    /// Connected component, numbered from 0 by decreasing total length
+    pub component: usize,
+}
+
+/// Part of a curated track between two nodes
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopologyEdge {
+    pub track_id: Uuid,
+    pub source: usize,
+    pub target: usize,
+    pub points: Vec<(f64, f64)>,
+    pub length_m: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Topology {
+    pub nodes: Vec<TopologyNode>,
+    pub edges: Vec<TopologyEdge>,
+    pub components: usize,
+}
+
+/// A place to cut a track: `t` along segment `segment`
+#[derive(Debug, Clone, Copy)]
+struct Cut {
+    segment: usize,
+    t: f64,
+    position: (f64, f64),
+}
+
+impl Cut {
+    fn key(&self) -> f64 {
+        self.segment as f64 + self.t
+    }
+}
+
+struct UnionFind(Vec<usize>);
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        Self((0..n).collect())
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.0[i] != i {
+            self.0[i] = self.0[self.0[i]];
+            i = self.0[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.0[a.max(b)] = a.min(b);
+        }
+    }
+}
+
+fn cell(p: (f64, f64), size: f64) -> (i64, i64) {
+    ((p.0 / size).floor() as i64, (p.1 / size).floor() as i64)
+}
+
+fn distance_m(a: (f64, f64), b: (f64, f64)) -> f64 {
+    Point::new(a.0, a.1).haversine_distance(&Point::new(b.0, b.1))
+}
+
+fn lerp(a: (f64, f64), b: (f64, f64), t: f64) -> (f64, f64) {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+fn edge(track_id: Uuid, source: usize, target: usize, points: Vec<(f64, f64)>) -> TopologyEdge {
+    let length_m = points.windows(2).map(|w| distance_m(w[0], w[1])).sum();
+    TopologyEdge {
+        track_id,
+        source,
+        target,
+        points,
+        length_m,
+    }
+}
+
+/// Where segments `a` and `b` cross, as `(t along a, t along b)`
+fn segment_intersection(
+    a: ((f64, f64), (f64, f64)),
+    b: ((f64, f64), (f64, f64)),
+) -> Option<(f64, f64)> {
+    let origin = a.0;
+    let (p, p2) = (to_local(a.0, origin), to_local(a.1, origin));
+    let (q, q2) = (to_local(b.0, origin), to_local(b.1, origin));
+    let r = (p2.0 - p.0, p2.1 - p.1);
+    let s = (q2.0 - q.0, q2.1 - q.1);
+    let cross = |u: (f64, f64), v: (f64, f64)| u.0 * v.1 - u.1 * v.0;
+
+    let denom = cross(r, s);
+    // Parallel segments: overlapping ends are joined by snapping instead
+    if denom.abs() < 1e-9 {
+        return None;
+    }
+    let qp = (q.0 - p.0, q.1 - p.1);
+    let t = cross(qp, s) / denom;
+    let u = cross(qp, r) / denom;
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then_some((t, u))
+}
+
+/// Build the track network graph
+///
+/// Tracks are cut where they cross each other and where one ends within
+/// `tolerance_m` of another; cut points and track ends closer than the
+/// tolerance become one node. Each piece of track between two nodes is an
+/// edge; a piece that leaves and returns to the same node, like a closed
+/// loop, gets a node at its farthest vertex and becomes two edges.
+/// Self-crossings of a single track are not noded.
+pub fn build_topology(tracks: &[(Uuid, Vec<(f64, f64)>)], tolerance_m: f64) -> Topology {
+    let tolerance_m = tolerance_m.clamp(0.0, MAX_TOLERANCE_M);
+
+    // Every track starts with its two ends as cuts
+    let mut cuts: Vec<Vec<Cut>> = tracks
+        .iter()
+        .map(|(_, points)| match points.as_slice() {
+            [first, .., last] => vec![
+                Cut {
+                    segment: 0,
+                    t: 0.0,
+                    position: *first,
+                },
+                Cut {
+                    segment: points.len() - 2,
+                    t: 1.0,
+                    position: *last,
+                },
+            ],
+            _ => Vec::new(),
+        })
+        .collect();
+
+    // Index segments by grid cell
+    let mut grid: HashMap<(i64, i64), Vec<(usize, usize)>> = HashMap::new();
+    for (track, (_, points)) in tracks.iter().enumerate() {
+        for (segment, w) in points.windows(2).enumerate() {
+            let (min_x, min_y) = cell((w[0].0.min(w[1].0), w[0].1.min(w[1].1)), SEGMENT_CELL_DEG);
+            let (max_x, max_y) = cell((w[0].0.max(w[1].0), w[0].1.max(w[1].1)), SEGMENT_CELL_DEG);
+            for x in min_x..=max_x {
+                for y in min_y..=max_y {
+                    grid.entry((x, y)).or_default().push((track, segment));
+                }
+            }
+        }
+    }
+    let segment = |(track, segment): (usize, usize)| {
+        let points = &tracks[track].1;
+        (points[segment], points[segment + 1])
+    };
+
+    // Crossings between different tracks
+    let mut seen = HashSet::new();
+    for members in grid.values() {
+        for (i, &a) in members.iter().enumerate() {
+            for &b in &members[i + 1..] {
+                if a.0 == b.0 || !seen.insert((a.min(b), a.max(b))) {
+                    continue;
+                }
+                if let Some((ta, tb)) = segment_intersection(segment(a), segment(b)) {
+                    let (a0, a1) = segment(a);
+                    let position = lerp(a0, a1, ta);
+                    cuts[a.0].push(Cut {
+                        segment: a.1,
+                        t: ta,
+                        position,
+                    });
+                    cuts[b.0].push(Cut {
+                        segment: b.1,
+                        t: tb,
+                        position,
+                    });
+                }
+            }
+        }
+    }
+
+    // Track ends near another track's line: cut that track at the closest point
+    for (track, (_, points)) in tracks.iter().enumerate() {
+        let ends = match points.as_slice() {
+            [first, .., last] => [*first, *last],
+            _ => continue,
+        };
+        for end in ends {
+            let (cx, cy) = cell(end, SEGMENT_CELL_DEG);
+            let mut best: Option<((usize, usize), f64, f64)> = None;
+            for x in cx - 1..=cx + 1 {
+                for y in cy - 1..=cy + 1 {
+                    for &other in grid.get(&(x, y)).map(Vec::as_slice).unwrap_or_default() {
+                        if other.0 == track {
+                            continue;
+                        }
+                        let (a, b) = segment(other);
+                        let (t, d, _) = project_onto_segment(a, b, end);
+                        if d <= tolerance_m && best.is_none_or(|(_, _, best_d)| d < best_d) {
+                            best = Some((other, t, d));
+                        }
+                    }
+                }
+            }
+            if let Some((other, t, _)) = best {
+                let (a, b) = segment(other);
+                cuts[other.0].push(Cut {
+                    segment: other.1,
+                    t,
+                    position: lerp(a, b, t),
+                });
+            }
+        }
+    }
+
+    // Merge cut positions within the tolerance into nodes
+    let positions: Vec<(f64, f64)> = cuts.iter().flatten().map(|c| c.position).collect();
+    let point_cell = (tolerance_m / METERS_PER_DEGREE).max(1e-7);
+    let mut point_grid: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    for (i, &p) in positions.iter().enumerate() {
+        point_grid.entry(cell(p, point_cell)).or_default().push(i);
+    }
+    let mut clusters = UnionFind::new(positions.len());
+    for (i, &p) in positions.iter().enumerate() {
+        let (cx, cy) = cell(p, point_cell);
+        // A degree of longitude shrinks with latitude
+        let reach = (1.0 / p.1.to_radians().cos().max(0.01)).ceil() as i64;
+        for x in cx - reach..=cx + reach {
+            for y in cy - 1..=cy + 1 {
+                for &j in point_grid
+                    .get(&(x, y))
+                    .map(Vec::as_slice)
+                    .unwrap_or_default()
+                {
+                    if j > i && distance_m(p, positions[j]) <= tolerance_m {
+                        clusters.union(i, j);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut node_of_root: HashMap<usize, usize> = HashMap::new();
+    let mut nodes = Vec::new();
+    let node_ids: Vec<usize> = (0..positions.len())
+        .map(|i| {
+            let root = clusters.find(i);
+            *node_of_root.entry(root).or_insert_with(|| {
+                nodes.push(TopologyNode {
+                    position: positions[root],
+                    degree: 0,
+                    component: 0,
+                });
+                nodes.len() - 1
+            })
+        })
+        .collect();
+
+    // Cut each track into edges between consecutive nodes
+    let mut edges = Vec::new();
+    let mut next_id = 0;
+    for (track, (id, points)) in tracks.iter().enumerate() {
+        let mut track_cuts: Vec<(Cut, usize)> = cuts[track]
+            .iter()
+            .map(|&c| {
+                let node = node_ids[next_id];
+                next_id += 1;
+                (c, node)
+            })
+            .collect();
+        track_cuts.sort_by(|a, b| a.0.key().total_cmp(&b.0.key()));
+
+        for pair in track_cuts.windows(2) {
+            let ((from, source), (to, target)) = (pair[0], pair[1]);
+            let mut piece = vec![from.position];
+            piece.extend_from_slice(&points[from.segment + 1..=to.segment]);
+            piece.push(to.position);
+            piece.dedup();
+
+            if source != target {
+                edges.push(edge(*id, source, target, piece));
+                continue;
+            }
+            // A piece back to the node it left, such as a closed loop track,
+            // is split at its farthest vertex so the loop stays in the network
+            let node = nodes[source].position;
+            let split = (1..piece.len().saturating_sub(1))
+                .max_by(|&a, &b| distance_m(node, piece[a]).total_cmp(&distance_m(node, piece[b])))
+                .filter(|&i| distance_m(node, piece[i]) > tolerance_m);
+            let Some(split) = split else {
+                continue;
+            };
+            let middle = nodes.len();
+            nodes.push(TopologyNode {
+                position: piece[split],
+                degree: 0,
+                component: 0,
+            });
+            edges.push(edge(*id, source, middle, piece[..=split].to_vec()));
+            edges.push(edge(*id, middle, target, piece[split..].to_vec()));
+        }
+    }
+
+    // Degrees and connected components
+    let mut connected = UnionFind::new(nodes.len());
+    for edge in &edges {
+        nodes[edge.source].degree += 1;
+        nodes[edge.target].degree += 1;
+        connected.union(edge.source, edge.target);
+    }
+    let mut length_of_root: HashMap<usize, f64> = HashMap::new();
+    for edge in &edges {
+        *length_of_root
+            .entry(connected.find(edge.source))
+            .or_default() += edge.length_m;
+    }
+    for i in 0..nodes.len() {
+        length_of_root.entry(connected.find(i)).or_default();
+    }
+    let mut roots: Vec<(usize, f64)> = length_of_root.into_iter().collect();
+    roots.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    let component_of_root: HashMap<usize, usize> = roots
+        .iter()
+        .enumerate()
+        .map(|(c, &(root, _))| (root, c))
+        .collect();
+    for i in 0..nodes.len() {
+        nodes[i].component = component_of_root[&connected.find(i)];
+    }
+
+    Topology {
+        nodes,
+        edges,
+        components: roots.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(n: u128, points: &[(f64, f64)]) -> (Uuid, Vec<(f64, f64)>) {
+        (Uuid::from_u128(n), points.to_vec())
+    }
+
+    #[test]
+    fn test_crossing_tracks_share_a_node() {
+        let tracks = [
+            track(1, &[(-5.01, 20.0), (-4.99, 20.0)]),
+            track(2, &[(-5.0, 19.99), (-5.0, 20.01)]),
+        ];
+
+        let topology = build_topology(&tracks, 10.0);
+
+        // Four ends plus the crossing, each track cut in two
+        assert_eq!(topology.nodes.len(), 5);
+        assert_eq!(topology.edges.len(), 4);
+        assert_eq!(topology.components, 1);
+        let junction = topology.nodes.iter().find(|n| n.degree == 4).unwrap();
+        assert!((junction.position.0 + 5.0).abs() < 1e-9);
+        assert!((junction.position.1 - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_near_miss_end_joins_within_tolerance() {
+        // Second track stops ~20 m short of the first
+        let tracks = [
+            track(1, &[(-5.01, 20.0), (-4.99, 20.0)]),
+            track(2, &[(-5.0, 20.01), (-5.0, 20.0002)]),
+        ];
+
+        let joined = build_topology(&tracks, 30.0);
+        assert_eq!(joined.components, 1);
+        assert_eq!(joined.edges.len(), 3);
+        assert!(joined.nodes.iter().any(|n| n.degree == 3));
+
+        let apart = build_topology(&tracks, 10.0);
+        assert_eq!(apart.components, 2);
+        assert_eq!(apart.edges.len(), 2);
+        // The longer track is the main component
+        let main = &apart
+            .edges
+            .iter()
+            .find(|e| e.track_id == Uuid::from_u128(1))
+            .unwrap();
+        assert_eq!(apart.nodes[main.source].component, 0);
+    }
+
+    #[test]
+    fn test_shared_endpoints_chain() {
+        let tracks = [
+            track(1, &[(-5.0, 20.0), (-5.0, 20.01)]),
+            track(2, &[(-5.0, 20.01), (-5.0, 20.02), (-4.99, 20.02)]),
+        ];
+
+        let topology = build_topology(&tracks, 5.0);
+
+        assert_eq!(topology.nodes.len(), 3);
+        assert_eq!(topology.edges.len(), 2);
+        assert_eq!(topology.edges[1].points.len(), 3);
+        let degrees: Vec<usize> = topology.nodes.iter().map(|n| n.degree).collect();
+        assert_eq!(degrees.iter().filter(|&&d| d == 1).count(), 2);
+        assert!((topology.edges[0].length_m - 1112.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_closed_loop_is_split_in_two() {
+        // A ring of about 4.3 km starting and ending at the same point
+        let tracks = [track(
+            1,
+            &[
+                (-5.0, 20.0),
+                (-5.0, 20.01),
+                (-4.99, 20.01),
+                (-4.99, 20.0),
+                (-5.0, 20.0),
+            ],
+        )];
+
+        let topology = build_topology(&tracks, 10.0);
+
+        assert_eq!(topology.nodes.len(), 2);
+        assert_eq!(topology.edges.len(), 2);
+        assert_eq!(topology.components, 1);
+        assert!(topology.nodes.iter().all(|n| n.degree == 2));
+        // Split at the corner opposite the start
+        assert!(topology.nodes.iter().any(|n| n.position == (-4.99, 20.01)));
+        let length_m: f64 = topology.edges.iter().map(|e| e.length_m).sum();
+        assert!((length_m - 4314.0).abs() < 5.0, "{}", length_m);
+    }
+}
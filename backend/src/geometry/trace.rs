@@ -0,0 +1,241 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use geo::{HaversineDistance, Point};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// A single recorded GPS fix
+#[derive(Debug, Clone, PartialEq)]
+pub struct TracePoint {
+    pub lng: f64,
+    pub lat: f64,
+    pub time: Option<DateTime<Utc>>,
+}
+
+impl TracePoint {
+    pub fn position(&self) -> (f64, f64) {
+        (self.lng, self.lat)
+    }
+
+    pub fn distance_m(&self, other: &TracePoint) -> f64 {
+        Point::new(self.lng, self.lat).haversine_distance(&Point::new(other.lng, other.lat))
+    }
+
+    /// Speed from `previous` to this fix (km/h), when both carry timestamps
+    pub fn speed_kmh_from(&self, previous: &TracePoint) -> Option<f64> {
+        let seconds = (self.time? - previous.time?).num_milliseconds() as f64 / 1000.0;
+        if seconds <= 0.0 {
+            return None;
+        }
+        Some(self.distance_m(previous) / seconds * 3.6)
+    }
+}
+
+fn coordinate_attr(element: &BytesStart, name: &str) -> Result<f64> {
+    let attr = element
+        .try_get_attribute(name)?
+        .ok_or_else(|| anyhow!("GPX point missing '{}' attribute", name))?;
+    let value = attr.unescape_value()?;
+    let parsed: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("Invalid {} value '{}'", name, value))?;
+    if !parsed.is_finite() {
+        return Err(anyhow!("Invalid {} value '{}'", name, value));
+    }
+    Ok(parsed)
+}
+
+pub(crate) fn trace_point(element: &BytesStart) -> Result<TracePoint> {
+    Ok(TracePoint {
+        lng: coordinate_attr(element, "lon")?,
+        lat: coordinate_attr(element, "lat")?,
+        time: None,
+    })
+}
+
+/// Add a point to the last segment, opening one if there is none
+fn push_to_segment(segments: &mut Vec<Vec<TracePoint>>, point: TracePoint) {
+    match segments.last_mut() {
+        Some(segment) => segment.push(point),
+        None => segments.push(vec![point]),
+    }
+}
+
+/// Read the segments of a GPX document: every `<trkseg>` in document order,
+/// or every `<rte>` when the file has no track
+fn read_segments(gpx: &str) -> Result<Vec<Vec<TracePoint>>> {
+    let mut reader = Reader::from_str(gpx);
+    reader.config_mut().trim_text(true);
+
+    let mut track_segments = Vec::new();
+    let mut route_segments = Vec::new();
+    // (is_track_point, point) currently being read
+    let mut current: Option<(bool, TracePoint)> = None;
+    let mut in_time = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if e.local_name().as_ref() == b"trkseg" => {
+                track_segments.push(Vec::new());
+            }
+            Event::Start(e) if e.local_name().as_ref() == b"rte" => {
+                route_segments.push(Vec::new());
+            }
+            Event::Start(e) if matches!(e.local_name().as_ref(), b"trkpt" | b"rtept") => {
+                let point = trace_point(&e)?;
+                current = Some((e.local_name().as_ref() == b"trkpt", point));
+            }
+            // Self-closing points have no children (and no closing tag)
+            Event::Empty(e) if matches!(e.local_name().as_ref(), b"trkpt" | b"rtept") => {
+                let point = trace_point(&e)?;
+                if e.local_name().as_ref() == b"trkpt" {
+                    push_to_segment(&mut track_segments, point);
+                } else {
+                    push_to_segment(&mut route_segments, point);
+                }
+            }
+            Event::Start(e) if e.local_name().as_ref() == b"time" && current.is_some() => {
+                in_time = true;
+            }
+            Event::Text(t) if in_time => {
+                let text = t.unescape()?;
+                if let Some((_, point)) = current.as_mut() {
+                    point.time = DateTime::parse_from_rfc3339(text.trim())
+                        .ok()
+                        .map(|dt| dt.with_timezone(&Utc));
+                }
+            }
+            Event::End(e) if e.local_name().as_ref() == b"time" => {
+                in_time = false;
+            }
+            Event::End(e) if matches!(e.local_name().as_ref(), b"trkpt" | b"rtept") => {
+                if let Some((is_track, point)) = current.take() {
+                    if is_track {
+                        push_to_segment(&mut track_segments, point);
+                    } else {
+                        push_to_segment(&mut route_segments, point);
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    let has_track_points = track_segments.iter().any(|segment| !segment.is_empty());
+    Ok(if has_track_points {
+        track_segments
+    } else {
+        route_segments
+    })
+}
+
+/// Parse the track points of a GPX document
+///
+/// Reads every `<trkpt>` in document order (all tracks and segments are
+/// concatenated). When the file has no track, `<rtept>` route points are used.
+pub fn parse_gpx(gpx: &str) -> Result<Vec<TracePoint>> {
+    let points: Vec<TracePoint> = read_segments(gpx)?.into_iter().flatten().collect();
+
+    if points.len() < 2 {
+        return Err(anyhow!("GPX contains fewer than two track points"));
+    }
+
+    Ok(points)
+}
+
+/// Parse a GPX document into separate lines, one per track segment
+///
+/// A gap between segments is where the recording stopped, so the segments
+/// are not joined. When the file has no track, each route is a line.
+/// Segments with fewer than two points are dropped.
+pub fn parse_gpx_segments(gpx: &str) -> Result<Vec<Vec<TracePoint>>> {
+    let segments: Vec<Vec<TracePoint>> = read_segments(gpx)?
+        .into_iter()
+        .filter(|segment| segment.len() >= 2)
+        .collect();
+
+    if segments.is_empty() {
+        return Err(anyhow!("GPX contains no segment of two or more points"));
+    }
+
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gpx_track_with_times() {
+        let gpx = r#"<?xml version="1.0"?>
+            <gpx version="1.1" xmlns="http://www.topografix.com/GPX/1/1">
+              <trk><trkseg>
+                <trkpt lat="20.0" lon="-5.0"><ele>300</ele><time>2026-01-10T08:00:00Z</time></trkpt>
+                <trkpt lat="20.01" lon="-5.0"><time>2026-01-10T08:01:00Z</time></trkpt>
+                <trkpt lat="20.02" lon="-5.0"/>
+              </trkseg></trk>
+            </gpx>"#;
+
+        let points = parse_gpx(gpx).unwrap();
+
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[0].position(), (-5.0, 20.0));
+        assert!(points[0].time.is_some());
+        assert!(points[2].time.is_none());
+
+        // ~1.11 km in one minute ≈ 66.7 km/h
+        let speed = points[1].speed_kmh_from(&points[0]).unwrap();
+        assert!((speed - 66.7).abs() < 0.5, "{}", speed);
+    }
+
+    #[test]
+    fn test_parse_gpx_falls_back_to_route_points() {
+        let gpx = r#"<gpx><rte>
+                <rtept lat="20.0" lon="-5.0"/>
+                <rtept lat="20.1" lon="-5.1"></rtept>
+            </rte></gpx>"#;
+
+        assert_eq!(parse_gpx(gpx).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_gpx_rejects_bad_coordinates() {
+        let gpx = r#"<gpx><trk><trkseg>
+                <trkpt lat="abc" lon="-5.0"/>
+                <trkpt lat="20.1" lon="-5.1"/>
+            </trkseg></trk></gpx>"#;
+
+        assert!(parse_gpx(gpx).is_err());
+    }
+
+    #[test]
+    fn test_parse_gpx_segments_keeps_segments_apart() {
+        let gpx = r#"<gpx>
+              <trk>
+                <trkseg>
+                  <trkpt lat="20.0" lon="-5.0"/>
+                  <trkpt lat="20.1" lon="-5.1"/>
+                </trkseg>
+                <trkseg><trkpt lat="20.5" lon="-5.5"/></trkseg>
+              </trk>
+              <trk><trkseg>
+                <trkpt lat="21.0" lon="-6.0"/>
+                <trkpt lat="21.1" lon="-6.1"/>
+                <trkpt lat="21.2" lon="-6.2"/>
+              </trkseg></trk>
+            </gpx>"#;
+
+        let segments = parse_gpx_segments(gpx).unwrap();
+
+        // The single-point segment is dropped
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].len(), 2);
+        assert_eq!(segments[1][0].position(), (-6.0, 21.0));
+        assert_eq!(parse_gpx(gpx).unwrap().len(), 6);
+
+        let gpx = r#"<gpx><trk><trkseg><trkpt lat="20.0" lon="-5.0"/></trkseg></trk></gpx>"#;
+        assert!(parse_gpx_segments(gpx).is_err());
+    }
+}
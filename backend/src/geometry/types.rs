@@ -0,0 +1,374 @@
+use geo_types::{Coord, LineString, MultiLineString};
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::coordinates::parse_coordinate;
+use super::passage::WaypointType;
+
+/// A WGS84 position, serialized as a GeoJSON `[lng, lat]` array
+///
+/// Deserialization rejects non-finite and out-of-range coordinates; extra
+/// elements (altitude) are dropped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub lng: f64,
+    pub lat: f64,
+}
+
+impl Position {
+    pub fn new(lng: f64, lat: f64) -> Result<Self, String> {
+        if !lng.is_finite() || !lat.is_finite() {
+            return Err(format!("non-finite position [{}, {}]", lng, lat));
+        }
+        if !(-180.0..=180.0).contains(&lng) || !(-90.0..=90.0).contains(&lat) {
+            return Err(format!(
+                "position [{}, {}] is outside WGS84 bounds",
+                lng, lat
+            ));
+        }
+        Ok(Self { lng, lat })
+    }
+
+    pub fn as_tuple(&self) -> (f64, f64) {
+        (self.lng, self.lat)
+    }
+}
+
+impl From<Position> for Coord<f64> {
+    fn from(position: Position) -> Self {
+        Coord {
+            x: position.lng,
+            y: position.lat,
+        }
+    }
+}
+
+impl Serialize for Position {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        [self.lng, self.lat].serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Position {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let coords = Vec::<f64>::deserialize(deserializer)?;
+        if coords.len() < 2 {
+            return Err(de::Error::invalid_length(coords.len(), &"[lng, lat]"));
+        }
+        Position::new(coords[0], coords[1]).map_err(de::Error::custom)
+    }
+}
+
+/// A route control point, optionally typed as a rally waypoint
+///
+/// Stored and returned as a GeoJSON Point carrying the waypoint fields as
+/// foreign members. Legacy `{lng, lat}` objects and waypoint fields nested
+/// in `properties` are still accepted on input, as is a textual coordinate
+/// (DMS, DDM, UTM, MGRS...) either bare or in a `position` field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlPoint {
+    pub position: Position,
+    pub waypoint: WaypointFields,
+}
+
+/// Rally waypoint fields of a control point
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WaypointFields {
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "waypoint_type_any_case"
+    )]
+    pub waypoint_type: Option<WaypointType>,
+    /// Validation radius (meters); the type's default when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub radius_m: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed_limit_kmh: Option<f64>,
+}
+
+impl WaypointFields {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn waypoint_type_any_case<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<WaypointType>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|t| {
+            serde_json::from_value(Value::String(t.to_uppercase()))
+                .map_err(|_| de::Error::custom(format!("unknown waypoint type '{}'", t)))
+        })
+        .transpose()
+}
+
+impl ControlPoint {
+    pub fn new(position: Position) -> Self {
+        Self {
+            position,
+            waypoint: WaypointFields::default(),
+        }
+    }
+
+    pub fn as_tuple(&self) -> (f64, f64) {
+        self.position.as_tuple()
+    }
+}
+
+#[derive(Serialize)]
+struct ControlPointOut<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    coordinates: Position,
+    #[serde(flatten)]
+    waypoint: &'a WaypointFields,
+}
+
+#[derive(Deserialize)]
+struct ControlPointIn {
+    coordinates: Option<Position>,
+    position: Option<String>,
+    lng: Option<f64>,
+    lat: Option<f64>,
+    properties: Option<WaypointFields>,
+    #[serde(flatten)]
+    waypoint: WaypointFields,
+}
+
+impl Serialize for ControlPoint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ControlPointOut {
+            kind: "Point",
+            coordinates: self.position,
+            waypoint: &self.waypoint,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ControlPoint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = match Value::deserialize(deserializer)? {
+            Value::String(text) => {
+                return parse_coordinate(&text)
+                    .map(|parsed| Self::new(parsed.position))
+                    .map_err(de::Error::custom)
+            }
+            value => ControlPointIn::deserialize(value).map_err(de::Error::custom)?,
+        };
+
+        let text_position = raw
+            .position
+            .map(|text| parse_coordinate(&text).map(|parsed| parsed.position))
+            .transpose()
+            .map_err(de::Error::custom)?;
+        let position = match (raw.coordinates, text_position, raw.lng, raw.lat) {
+            (Some(position), _, _, _) | (None, Some(position), _, _) => position,
+            (None, None, Some(lng), Some(lat)) => {
+                Position::new(lng, lat).map_err(de::Error::custom)?
+            }
+            _ => {
+                return Err(de::Error::custom(
+                    "control point needs Point coordinates, lng/lat or a position",
+                ))
+            }
+        };
+
+        let waypoint = match raw.properties {
+            Some(properties) if raw.waypoint.is_empty() => properties,
+            _ => raw.waypoint,
+        };
+
+        Ok(Self { position, waypoint })
+    }
+}
+
+/// A GeoJSON LineString with valid positions and at least two of them
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineGeometry(pub LineString<f64>);
+
+/// A GeoJSON MultiLineString with valid positions and non-empty lines
+///
+/// A bare LineString is accepted on input and stored as a single line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiLineGeometry(pub MultiLineString<f64>);
+
+fn line_from_positions(positions: &[Vec<f64>]) -> Result<LineString<f64>, String> {
+    if positions.len() < 2 {
+        return Err("a line needs at least two positions".to_string());
+    }
+
+    positions
+        .iter()
+        .map(|p| match p.as_slice() {
+            [lng, lat, ..] => Position::new(*lng, *lat).map(Coord::from),
+            _ => Err("position is not [lng, lat]".to_string()),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(LineString::new)
+}
+
+fn lines_of(geometry: geojson::Geometry) -> Result<Vec<LineString<f64>>, String> {
+    match geometry.value {
+        geojson::Value::LineString(line) => Ok(vec![line_from_positions(&line)?]),
+        geojson::Value::MultiLineString(lines) if !lines.is_empty() => {
+            lines.iter().map(|line| line_from_positions(line)).collect()
+        }
+        geojson::Value::MultiLineString(_) => Err("MultiLineString has no lines".to_string()),
+        other => Err(format!(
+            "expected LineString or MultiLineString, got {}",
+            other.type_name()
+        )),
+    }
+}
+
+fn line_coordinates(line: &LineString<f64>) -> impl Iterator<Item = (f64, f64)> + '_ {
+    line.coords().map(|c| (c.x, c.y))
+}
+
+impl LineGeometry {
+    pub fn points(&self) -> Vec<(f64, f64)> {
+        line_coordinates(&self.0).collect()
+    }
+}
+
+impl MultiLineGeometry {
+    pub fn lines(&self) -> Vec<Vec<(f64, f64)>> {
+        self.0
+             .0
+            .iter()
+            .map(|line| line_coordinates(line).collect())
+            .collect()
+    }
+
+    /// All positions, lines concatenated
+    pub fn points(&self) -> Vec<(f64, f64)> {
+        self.lines().into_iter().flatten().collect()
+    }
+}
+
+impl Serialize for LineGeometry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        geojson::Geometry::new(geojson::Value::from(&self.0)).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LineGeometry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut lines =
+            lines_of(geojson::Geometry::deserialize(deserializer)?).map_err(de::Error::custom)?;
+        match lines.len() {
+            1 => Ok(Self(lines.remove(0))),
+            n => Err(de::Error::custom(format!(
+                "expected a single line, got {}",
+                n
+            ))),
+        }
+    }
+}
+
+impl Serialize for MultiLineGeometry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        geojson::Geometry::new(geojson::Value::from(&self.0)).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MultiLineGeometry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        lines_of(geojson::Geometry::deserialize(deserializer)?)
+            .map(|lines| Self(MultiLineString::new(lines)))
+            .map_err(de::Error::custom)
+    }
+}
+
+impl TryFrom<Value> for MultiLineGeometry {
+    type Error = serde_json::Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        serde_json::from_value(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_position_rejects_bad_coordinates() {
+        assert!(serde_json::from_value::<Position>(json!([-5.0, 20.0, 300.0])).is_ok());
+        assert!(serde_json::from_value::<Position>(json!([-5.0])).is_err());
+        assert!(serde_json::from_value::<Position>(json!([-5.0, 95.0])).is_err());
+        assert!(serde_json::from_value::<Position>(json!({"lng": -5.0, "lat": 20.0})).is_err());
+    }
+
+    #[test]
+    fn test_control_point_shapes_normalize_to_point() {
+        let expected =
+            json!({"type": "Point", "coordinates": [-5.0, 20.0], "waypoint_type": "WPM"});
+
+        for input in [
+            json!({"lng": -5.0, "lat": 20.0, "waypoint_type": "wpm"}),
+            json!({"type": "Point", "coordinates": [-5.0, 20.0], "waypoint_type": "WPM"}),
+            json!({"type": "Point", "coordinates": [-5.0, 20.0], "properties": {"waypoint_type": "WPM"}}),
+            json!({"position": "N20 W5", "waypoint_type": "WPM"}),
+        ] {
+            let point: ControlPoint = serde_json::from_value(input).unwrap();
+            assert_eq!(serde_json::to_value(&point).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_control_point_rejects_malformed() {
+        for input in [
+            json!({"lng": -5.0}),
+            json!({"coordinates": [1.0]}),
+            json!({"lng": 10.0, "lat": 120.0}),
+            json!({"lng": 10.0, "lat": 20.0, "waypoint_type": "XYZ"}),
+            json!("20 07 24 5 39"),
+            json!({"position": "20 75 N 5 W"}),
+        ] {
+            assert!(serde_json::from_value::<ControlPoint>(input).is_err());
+        }
+    }
+
+    #[test]
+    fn test_control_point_from_text() {
+        let point: ControlPoint = serde_json::from_value(json!("20°07.5'N 5°30'W")).unwrap();
+        assert_eq!(point.as_tuple(), (-5.5, 20.125));
+    }
+
+    #[test]
+    fn test_multi_line_accepts_line_string() {
+        let geometry: MultiLineGeometry = serde_json::from_value(
+            json!({"type": "LineString", "coordinates": [[-5.0, 20.0], [-5.1, 20.1]]}),
+        )
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&geometry).unwrap(),
+            json!({"type": "MultiLineString", "coordinates": [[[-5.0, 20.0], [-5.1, 20.1]]]})
+        );
+    }
+
+    #[test]
+    fn test_lines_reject_malformed() {
+        for input in [
+            json!({"type": "Point", "coordinates": [-5.0, 20.0]}),
+            json!({"type": "MultiLineString", "coordinates": []}),
+            json!({"type": "MultiLineString", "coordinates": [[[-5.0, 20.0]]]}),
+            json!({"type": "LineString", "coordinates": [[-5.0, 20.0], [200.0, 20.0]]}),
+        ] {
+            assert!(serde_json::from_value::<MultiLineGeometry>(input).is_err());
+        }
+
+        let two_lines = json!({"type": "MultiLineString", "coordinates": [
+            [[-5.0, 20.0], [-5.1, 20.1]], [[-5.2, 20.2], [-5.3, 20.3]]
+        ]});
+        assert!(serde_json::from_value::<LineGeometry>(two_lines).is_err());
+    }
+}
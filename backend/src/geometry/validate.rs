@@ -0,0 +1,594 @@
+use std::fmt;
+
+use geo::{HaversineDistance, Point};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Consecutive points farther apart than this are rejected (km)
+///
+/// Generous enough for long liaisons drawn with few clicks, but catches
+/// stray `[0, 0]` points and unit mix-ups.
+pub const DEFAULT_MAX_JUMP_KM: f64 = 500.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// Not one of the geometry types accepted here
+    UnsupportedType,
+    MissingCoordinates,
+    /// Position is not an array of at least two numbers
+    MalformedPosition,
+    NonFiniteCoordinate,
+    OutOfRange,
+    /// Latitude out of range but valid once swapped with longitude
+    SwappedLatLng,
+    DuplicatePoint,
+    /// Line without two distinct points
+    ZeroLengthLine,
+    AbsurdJump,
+    /// Nothing left to route
+    EmptyGeometry,
+    /// Several lines where a single LineString is required
+    MultipleLines,
+    /// Polygon ring with fewer than four positions or different ends
+    OpenRing,
+}
+
+impl IssueKind {
+    pub fn repairable(self) -> bool {
+        matches!(
+            self,
+            Self::SwappedLatLng | Self::DuplicatePoint | Self::ZeroLengthLine
+        )
+    }
+}
+
+/// A single problem found in a geometry
+#[derive(Debug, Clone, Serialize)]
+pub struct GeometryIssue {
+    pub kind: IssueKind,
+    pub repairable: bool,
+    /// Line index within the MultiLineString (0 for a LineString)
+    pub line: Option<usize>,
+    /// Position index within the line (or control point index)
+    pub point: Option<usize>,
+    pub message: String,
+}
+
+impl GeometryIssue {
+    fn new(kind: IssueKind, line: Option<usize>, point: Option<usize>, message: String) -> Self {
+        Self {
+            kind,
+            repairable: kind.repairable(),
+            line,
+            point,
+            message,
+        }
+    }
+}
+
+/// Every problem found in a rejected geometry
+#[derive(Debug, Clone, Serialize)]
+pub struct GeometryValidationError {
+    pub issues: Vec<GeometryIssue>,
+}
+
+impl fmt::Display for GeometryValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<&str> = self.issues.iter().map(|i| i.message.as_str()).collect();
+        write!(f, "Invalid geometry: {}", messages.join("; "))
+    }
+}
+
+impl std::error::Error for GeometryValidationError {}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ValidationOptions {
+    /// Fix repairable issues instead of rejecting them
+    pub repair: bool,
+    pub max_jump_km: f64,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            repair: false,
+            max_jump_km: DEFAULT_MAX_JUMP_KM,
+        }
+    }
+}
+
+/// A geometry that passed validation
+#[derive(Debug, Clone)]
+pub struct ValidatedGeometry {
+    /// Normalized GeoJSON MultiLineString
+    pub geometry: Value,
+    /// Issues that were repaired (empty unless repair was requested)
+    pub repaired: Vec<GeometryIssue>,
+}
+
+/// Parse a `[lng, lat, ...]` position without panicking on short arrays
+pub fn parse_position(position: &Value) -> Option<(f64, f64)> {
+    let arr = position.as_array()?;
+    Some((arr.first()?.as_f64()?, arr.get(1)?.as_f64()?))
+}
+
+fn in_range((lng, lat): (f64, f64)) -> bool {
+    (-180.0..=180.0).contains(&lng) && (-90.0..=90.0).contains(&lat)
+}
+
+/// Check one position; returns the usable position (swapped if needed)
+fn check_position(
+    position: &Value,
+    line: Option<usize>,
+    point: usize,
+    issues: &mut Vec<GeometryIssue>,
+) -> Option<(f64, f64)> {
+    let Some((lng, lat)) = parse_position(position) else {
+        issues.push(GeometryIssue::new(
+            IssueKind::MalformedPosition,
+            line,
+            Some(point),
+            format!("Position {} is not an array of two numbers", point),
+        ));
+        return None;
+    };
+
+    if !lng.is_finite() || !lat.is_finite() {
+        issues.push(GeometryIssue::new(
+            IssueKind::NonFiniteCoordinate,
+            line,
+            Some(point),
+            format!("Position {} has a non-finite coordinate", point),
+        ));
+        return None;
+    }
+
+    if in_range((lng, lat)) {
+        return Some((lng, lat));
+    }
+
+    if in_range((lat, lng)) {
+        issues.push(GeometryIssue::new(
+            IssueKind::SwappedLatLng,
+            line,
+            Some(point),
+            format!(
+                "Position {} [{}, {}] looks like [lat, lng]; GeoJSON expects [lng, lat]",
+                point, lng, lat
+            ),
+        ));
+        return Some((lat, lng));
+    }
+
+    issues.push(GeometryIssue::new(
+        IssueKind::OutOfRange,
+        line,
+        Some(point),
+        format!(
+            "Position {} [{}, {}] is outside WGS84 bounds",
+            point, lng, lat
+        ),
+    ));
+    None
+}
+
+/// Validate a GeoJSON LineString or MultiLineString
+///
+/// Collects every issue rather than stopping at the first. Repairable issues
+/// (swapped lat/lng, duplicate consecutive points, zero-length lines) are
+/// fixed when `options.repair` is set; anything else rejects the geometry.
+pub fn validate_line_geometry(
+    geometry: &Value,
+    options: &ValidationOptions,
+) -> Result<ValidatedGeometry, GeometryValidationError> {
+    let reject = |kind, message: &str| GeometryValidationError {
+        issues: vec![GeometryIssue::new(kind, None, None, message.to_string())],
+    };
+
+    let coordinates = geometry
+        .get("coordinates")
+        .and_then(|c| c.as_array())
+        .ok_or_else(|| {
+            reject(
+                IssueKind::MissingCoordinates,
+                "Geometry has no coordinates array",
+            )
+        })?;
+
+    let raw_lines: Vec<&Vec<Value>> = match geometry.get("type").and_then(|t| t.as_str()) {
+        Some("LineString") => vec![coordinates],
+        Some("MultiLineString") => coordinates
+            .iter()
+            .map(|line| line.as_array())
+            .collect::<Option<_>>()
+            .ok_or_else(|| {
+                reject(
+                    IssueKind::MissingCoordinates,
+                    "MultiLineString lines must be arrays of positions",
+                )
+            })?,
+        other => {
+            return Err(reject(
+                IssueKind::UnsupportedType,
+                &format!(
+                    "Expected LineString or MultiLineString, got {}",
+                    other.unwrap_or("no type")
+                ),
+            ))
+        }
+    };
+
+    let mut issues = Vec::new();
+    let mut lines: Vec<Vec<(f64, f64)>> = Vec::with_capacity(raw_lines.len());
+
+    for (l, raw_line) in raw_lines.iter().enumerate() {
+        let mut line: Vec<(f64, f64)> = Vec::with_capacity(raw_line.len());
+
+        for (p, position) in raw_line.iter().enumerate() {
+            let Some(point) = check_position(position, Some(l), p, &mut issues) else {
+                continue;
+            };
+
+            if let Some(&previous) = line.last() {
+                if previous == point {
+                    issues.push(GeometryIssue::new(
+                        IssueKind::DuplicatePoint,
+                        Some(l),
+                        Some(p),
+                        format!("Position {} repeats the previous one", p),
+                    ));
+                    continue;
+                }
+
+                let jump_km =
+                    Point::from(previous).haversine_distance(&Point::from(point)) / 1000.0;
+                if jump_km > options.max_jump_km {
+                    issues.push(GeometryIssue::new(
+                        IssueKind::AbsurdJump,
+                        Some(l),
+                        Some(p),
+                        format!(
+                            "Position {} is {:.0} km from the previous one (limit {:.0} km)",
+                            p, jump_km, options.max_jump_km
+                        ),
+                    ));
+                }
+            }
+            line.push(point);
+        }
+
+        if line.len() < 2 {
+            issues.push(GeometryIssue::new(
+                IssueKind::ZeroLengthLine,
+                Some(l),
+                None,
+                format!("Line {} has fewer than two distinct positions", l),
+            ));
+            continue;
+        }
+        lines.push(line);
+    }
+
+    if lines.is_empty() {
+        issues.push(GeometryIssue::new(
+            IssueKind::EmptyGeometry,
+            None,
+            None,
+            "Geometry has no line with two distinct positions".to_string(),
+        ));
+    }
+
+    let rejected = issues
+        .iter()
+        .any(|issue| !issue.repairable || !options.repair);
+    if rejected {
+        return Err(GeometryValidationError { issues });
+    }
+
+    let coordinates: Vec<Vec<[f64; 2]>> = lines
+        .into_iter()
+        .map(|line| line.into_iter().map(|(lng, lat)| [lng, lat]).collect())
+        .collect();
+
+    Ok(ValidatedGeometry {
+        geometry: serde_json::json!({
+            "type": "MultiLineString",
+            "coordinates": coordinates
+        }),
+        repaired: issues,
+    })
+}
+
+/// Validate a geometry that must be a single line (curated tracks)
+///
+/// Same checks and repairs as `validate_line_geometry`; a MultiLineString is
+/// accepted only if it holds exactly one line. Returns a GeoJSON LineString.
+pub fn validate_single_line(
+    geometry: &Value,
+    options: &ValidationOptions,
+) -> Result<ValidatedGeometry, GeometryValidationError> {
+    let validated = validate_line_geometry(geometry, options)?;
+    let lines = validated.geometry["coordinates"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+
+    match <[Value; 1]>::try_from(lines) {
+        Ok([line]) => Ok(ValidatedGeometry {
+            geometry: serde_json::json!({ "type": "LineString", "coordinates": line }),
+            repaired: validated.repaired,
+        }),
+        Err(lines) => Err(GeometryValidationError {
+            issues: vec![GeometryIssue::new(
+                IssueKind::MultipleLines,
+                None,
+                None,
+                format!("Expected a single line, got {}", lines.len()),
+            )],
+        }),
+    }
+}
+
+/// Validate a GeoJSON Polygon or MultiPolygon (region outlines)
+///
+/// Every ring must be closed and hold at least four positions. Nothing is
+/// repaired; self-intersections are left to `ST_MakeValid`. Returns a GeoJSON
+/// MultiPolygon.
+pub fn validate_area_geometry(geometry: &Value) -> Result<Value, GeometryValidationError> {
+    let reject = |kind, message: String| GeometryValidationError {
+        issues: vec![GeometryIssue::new(kind, None, None, message)],
+    };
+
+    let coordinates = geometry
+        .get("coordinates")
+        .filter(|c| c.is_array())
+        .ok_or_else(|| {
+            reject(
+                IssueKind::MissingCoordinates,
+                "Geometry has no coordinates array".to_string(),
+            )
+        })?;
+
+    let raw_polygons: Vec<&Value> = match geometry.get("type").and_then(|t| t.as_str()) {
+        Some("Polygon") => vec![coordinates],
+        Some("MultiPolygon") => coordinates.as_array().into_iter().flatten().collect(),
+        other => {
+            return Err(reject(
+                IssueKind::UnsupportedType,
+                format!(
+                    "Expected Polygon or MultiPolygon, got {}",
+                    other.unwrap_or("no type")
+                ),
+            ))
+        }
+    };
+
+    let mut issues = Vec::new();
+    let mut polygons: Vec<Vec<Vec<[f64; 2]>>> = Vec::with_capacity(raw_polygons.len());
+
+    // Rings are numbered across all polygons in `GeometryIssue::line`
+    let mut ring_index = 0;
+    for raw_polygon in raw_polygons {
+        let Some(raw_rings) = raw_polygon.as_array().filter(|r| !r.is_empty()) else {
+            issues.push(GeometryIssue::new(
+                IssueKind::MissingCoordinates,
+                None,
+                None,
+                "Polygons must be non-empty arrays of rings".to_string(),
+            ));
+            continue;
+        };
+
+        let mut rings = Vec::with_capacity(raw_rings.len());
+        for raw_ring in raw_rings {
+            let r = ring_index;
+            ring_index += 1;
+
+            let positions = raw_ring.as_array().map(Vec::as_slice).unwrap_or_default();
+            let ring: Vec<[f64; 2]> = positions
+                .iter()
+                .enumerate()
+                .filter_map(|(p, position)| check_position(position, Some(r), p, &mut issues))
+                .map(|(lng, lat)| [lng, lat])
+                .collect();
+
+            if ring.len() == positions.len() && (ring.len() < 4 || ring.first() != ring.last()) {
+                issues.push(GeometryIssue::new(
+                    IssueKind::OpenRing,
+                    Some(r),
+                    None,
+                    format!("Ring {} must be closed and have at least four positions", r),
+                ));
+            }
+            rings.push(ring);
+        }
+        polygons.push(rings);
+    }
+
+    if !issues.is_empty() {
+        return Err(GeometryValidationError { issues });
+    }
+    if polygons.is_empty() {
+        return Err(reject(
+            IssueKind::EmptyGeometry,
+            "Geometry has no polygon".to_string(),
+        ));
+    }
+
+    Ok(serde_json::json!({
+        "type": "MultiPolygon",
+        "coordinates": polygons
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn kinds(error: &GeometryValidationError) -> Vec<IssueKind> {
+        error.issues.iter().map(|i| i.kind).collect()
+    }
+
+    #[test]
+    fn test_valid_linestring_is_normalized() {
+        let validated = validate_line_geometry(
+            &json!({"type": "LineString", "coordinates": [[-5.0, 20.0], [-5.1, 20.1, 300.0]]}),
+            &ValidationOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            validated.geometry,
+            json!({"type": "MultiLineString", "coordinates": [[[-5.0, 20.0], [-5.1, 20.1]]]})
+        );
+        assert!(validated.repaired.is_empty());
+    }
+
+    #[test]
+    fn test_reports_every_problem() {
+        let error = validate_line_geometry(
+            &json!({"type": "MultiLineString", "coordinates": [[[-5.0], [-5.0, 20.0], [200.0, 95.0], [-5.1, 20.1]]]}),
+            &ValidationOptions::default(),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            kinds(&error),
+            vec![IssueKind::MalformedPosition, IssueKind::OutOfRange]
+        );
+        assert_eq!(error.issues[1].point, Some(2));
+    }
+
+    #[test]
+    fn test_repairable_issues_rejected_without_repair() {
+        let geometry = json!({"type": "MultiLineString", "coordinates": [
+            [[-5.0, 20.0], [-5.0, 20.0], [-5.1, 20.1]],
+            [[-5.2, 20.2]]
+        ]});
+
+        let error = validate_line_geometry(&geometry, &ValidationOptions::default()).unwrap_err();
+        assert_eq!(
+            kinds(&error),
+            vec![IssueKind::DuplicatePoint, IssueKind::ZeroLengthLine]
+        );
+        assert!(error.issues.iter().all(|i| i.repairable));
+
+        let repaired = validate_line_geometry(
+            &geometry,
+            &ValidationOptions {
+                repair: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            repaired.geometry["coordinates"],
+            json!([[[-5.0, 20.0], [-5.1, 20.1]]])
+        );
+        assert_eq!(repaired.repaired.len(), 2);
+    }
+
+    #[test]
+    fn test_swapped_lat_lng_is_repaired() {
+        // Nome, Alaska, with the second position given as [lat, lng]
+        let geometry =
+            json!({"type": "LineString", "coordinates": [[-165.4, 64.5], [64.51, -165.41]]});
+
+        let repaired = validate_line_geometry(
+            &geometry,
+            &ValidationOptions {
+                repair: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(repaired.repaired[0].kind, IssueKind::SwappedLatLng);
+        assert_eq!(
+            repaired.geometry["coordinates"][0][1],
+            json!([-165.41, 64.51])
+        );
+    }
+
+    #[test]
+    fn test_absurd_jump_is_not_repairable() {
+        let geometry = json!({"type": "LineString", "coordinates": [[-5.0, 20.0], [0.0, 0.0]]});
+
+        let error = validate_line_geometry(
+            &geometry,
+            &ValidationOptions {
+                repair: true,
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+
+        assert_eq!(kinds(&error), vec![IssueKind::AbsurdJump]);
+    }
+
+    #[test]
+    fn test_rejects_unsupported_type() {
+        let error = validate_line_geometry(
+            &json!({"type": "Point", "coordinates": [-5.0, 20.0]}),
+            &ValidationOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(kinds(&error), vec![IssueKind::UnsupportedType]);
+    }
+
+    #[test]
+    fn test_single_line() {
+        let validated = validate_single_line(
+            &json!({"type": "MultiLineString", "coordinates": [[[-5.0, 20.0], [-5.1, 20.1]]]}),
+            &ValidationOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            validated.geometry,
+            json!({"type": "LineString", "coordinates": [[-5.0, 20.0], [-5.1, 20.1]]})
+        );
+
+        let error = validate_single_line(
+            &json!({"type": "MultiLineString", "coordinates": [
+                [[-5.0, 20.0], [-5.1, 20.1]], [[-5.2, 20.2], [-5.3, 20.3]]
+            ]}),
+            &ValidationOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(kinds(&error), vec![IssueKind::MultipleLines]);
+    }
+
+    #[test]
+    fn test_area_is_normalized_to_multipolygon() {
+        let square = json!([[[-5.0, 20.0], [-4.0, 20.0], [-4.0, 21.0], [-5.0, 20.0]]]);
+        let validated =
+            validate_area_geometry(&json!({"type": "Polygon", "coordinates": square})).unwrap();
+
+        assert_eq!(
+            validated,
+            json!({"type": "MultiPolygon", "coordinates": [square]})
+        );
+    }
+
+    #[test]
+    fn test_area_rejects_open_rings_and_lines() {
+        let error = validate_area_geometry(&json!({"type": "MultiPolygon", "coordinates": [
+            [[[-5.0, 20.0], [-4.0, 20.0], [-4.0, 21.0], [-5.0, 21.0]]],
+            [[[-5.0, 20.0], [200.0, 95.0], [-4.0, 21.0], [-5.0, 20.0]]]
+        ]}))
+        .unwrap_err();
+        assert_eq!(
+            kinds(&error),
+            vec![IssueKind::OpenRing, IssueKind::OutOfRange]
+        );
+        assert_eq!(error.issues[1].line, Some(1));
+
+        let error = validate_area_geometry(
+            &json!({"type": "LineString", "coordinates": [[-5.0, 20.0], [-4.0, 20.0]]}),
+        )
+        .unwrap_err();
+        assert_eq!(kinds(&error), vec![IssueKind::UnsupportedType]);
+    }
+}
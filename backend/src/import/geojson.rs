@@ -0,0 +1,153 @@
+//! GeoJSON track files
+//!
+//! Parsed loosely as JSON rather than through the `geojson` types, so one
+//! malformed feature does not reject the whole collection.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Map, Value};
+
+use super::{FeatureError, TrackFeature, TrackFile};
+
+/// Line geometry of a GeoJSON geometry object
+///
+/// GeometryCollections of lines are merged into one MultiLineString.
+fn line_geometry(geometry: &Value) -> Result<Value, String> {
+    let geometry_type = geometry
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or("Feature has no geometry")?;
+
+    match geometry_type {
+        "LineString" | "MultiLineString" => Ok(geometry.clone()),
+        "GeometryCollection" => {
+            let mut lines = Vec::new();
+            for part in geometry
+                .get("geometries")
+                .and_then(Value::as_array)
+                .ok_or("GeometryCollection has no geometries")?
+            {
+                let part = line_geometry(part)?;
+                match part["type"].as_str() {
+                    Some("LineString") => lines.push(part["coordinates"].clone()),
+                    _ => lines.extend(part["coordinates"].as_array().cloned().unwrap_or_default()),
+                }
+            }
+            Ok(json!({ "type": "MultiLineString", "coordinates": lines }))
+        }
+        other => Err(format!("Unsupported geometry type {}", other)),
+    }
+}
+
+fn feature(index: usize, value: &Value) -> Result<TrackFeature, FeatureError> {
+    let properties = match value.get("properties") {
+        Some(Value::Object(properties)) => properties.clone(),
+        _ => Map::new(),
+    };
+    let name = properties
+        .get("name")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    let geometry = match value.get("type").and_then(Value::as_str) {
+        Some("Feature") => value.get("geometry").unwrap_or(&Value::Null),
+        _ => value,
+    };
+    let geometry = line_geometry(geometry).map_err(|message| FeatureError {
+        index,
+        name: name.clone(),
+        message,
+    })?;
+
+    Ok(TrackFeature {
+        index,
+        name,
+        geometry,
+        properties,
+    })
+}
+
+/// Parse a FeatureCollection, a single Feature or a bare line geometry
+pub fn parse_geojson(data: &str) -> Result<TrackFile> {
+    let document: Value = serde_json::from_str(data).context("Invalid GeoJSON")?;
+
+    let values = match document.get("type").and_then(Value::as_str) {
+        Some("FeatureCollection") => document
+            .get("features")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("FeatureCollection has no features"))?
+            .iter()
+            .collect(),
+        Some(_) => vec![&document],
+        None => return Err(anyhow!("GeoJSON object has no type")),
+    };
+
+    let mut file = TrackFile::default();
+    for (index, value) in values.into_iter().enumerate() {
+        match feature(index, value) {
+            Ok(feature) => file.features.push(feature),
+            Err(error) => file.errors.push(error),
+        }
+    }
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_feature_collection() {
+        let file = parse_geojson(
+            r#"{
+                "type": "FeatureCollection",
+                "features": [
+                    {
+                        "type": "Feature",
+                        "properties": { "name": "Piste de Chinguetti", "surface": "sand" },
+                        "geometry": { "type": "LineString", "coordinates": [[-12.3, 20.4], [-12.4, 20.5]] }
+                    },
+                    {
+                        "type": "Feature",
+                        "properties": { "name": "Puits" },
+                        "geometry": { "type": "Point", "coordinates": [-12.3, 20.4] }
+                    },
+                    {
+                        "type": "Feature",
+                        "properties": null,
+                        "geometry": {
+                            "type": "GeometryCollection",
+                            "geometries": [
+                                { "type": "LineString", "coordinates": [[-5.0, 20.0], [-5.1, 20.1]] },
+                                { "type": "MultiLineString", "coordinates": [[[-6.0, 21.0], [-6.1, 21.1]]] }
+                            ]
+                        }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(file.features.len(), 2);
+        assert_eq!(
+            file.features[0].name.as_deref(),
+            Some("Piste de Chinguetti")
+        );
+        assert_eq!(file.features[0].properties["surface"], "sand");
+        assert_eq!(file.features[1].index, 2);
+        assert_eq!(file.features[1].geometry["type"], "MultiLineString");
+        assert_eq!(
+            file.features[1].geometry["coordinates"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+
+        assert_eq!(file.errors.len(), 1);
+        assert_eq!(file.errors[0].index, 1);
+        assert_eq!(file.errors[0].name.as_deref(), Some("Puits"));
+        assert!(file.errors[0].message.contains("Point"));
+
+        assert!(parse_geojson("[1, 2]").is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//! GPX track files
+//!
+//! Every `<trk>` and `<rte>` is a feature. Track segments become the parts
+//! of a MultiLineString; a single segment is a LineString. The track's
+//! `name`, `desc`, `cmt`, `type`, `src` and `number` are its properties.
+
+use anyhow::Result;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::{FeatureBuilder, TrackFile};
+use crate::geometry::trace::trace_point;
+
+/// Child elements of a track or route read as properties
+const PROPERTY_ELEMENTS: [&str; 6] = ["name", "desc", "cmt", "type", "src", "number"];
+
+fn push_point(feature: &mut FeatureBuilder, element: &BytesStart) {
+    match trace_point(element) {
+        Ok(point) => feature.push([point.lng, point.lat]),
+        Err(error) => feature.fail(error.to_string()),
+    }
+}
+
+/// Parse the tracks and routes of a GPX document
+///
+/// Malformed XML fails the whole file; a track with an unreadable point is
+/// reported as a feature error.
+pub fn parse_gpx_tracks(gpx: &str) -> Result<TrackFile> {
+    let mut reader = Reader::from_str(gpx);
+    reader.config_mut().trim_text(true);
+
+    let mut file = TrackFile::default();
+    let mut pending: Option<FeatureBuilder> = None;
+    let mut count = 0;
+    let mut in_point = false;
+    // Property element whose text is being read
+    let mut property: Option<String> = None;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if matches!(e.local_name().as_ref(), b"trk" | b"rte") => {
+                let mut feature = FeatureBuilder::new(count);
+                if e.local_name().as_ref() == b"rte" {
+                    feature.start_part();
+                }
+                count += 1;
+                pending = Some(feature);
+            }
+            Event::Empty(e) if matches!(e.local_name().as_ref(), b"trk" | b"rte") => {
+                if let Err(error) = FeatureBuilder::new(count).finish() {
+                    file.errors.push(error);
+                }
+                count += 1;
+            }
+            Event::Start(e) if e.local_name().as_ref() == b"trkseg" => {
+                if let Some(feature) = pending.as_mut() {
+                    feature.start_part();
+                }
+            }
+            Event::Start(e) if matches!(e.local_name().as_ref(), b"trkpt" | b"rtept") => {
+                in_point = true;
+                if let Some(feature) = pending.as_mut() {
+                    push_point(feature, &e);
+                }
+            }
+            Event::Empty(e) if matches!(e.local_name().as_ref(), b"trkpt" | b"rtept") => {
+                if let Some(feature) = pending.as_mut() {
+                    push_point(feature, &e);
+                }
+            }
+            Event::End(e) if matches!(e.local_name().as_ref(), b"trkpt" | b"rtept") => {
+                in_point = false;
+            }
+            Event::Start(e) if pending.is_some() && !in_point => {
+                let local_name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                property = PROPERTY_ELEMENTS
+                    .contains(&local_name.as_str())
+                    .then_some(local_name);
+            }
+            Event::Text(t) => {
+                if let (Some(key), Some(feature)) = (property.as_ref(), pending.as_mut()) {
+                    feature.property(key, t.unescape()?.into_owned());
+                }
+            }
+            Event::CData(t) => {
+                if let (Some(key), Some(feature)) = (property.as_ref(), pending.as_mut()) {
+                    feature.property(key, String::from_utf8_lossy(&t.into_inner()).into_owned());
+                }
+            }
+            Event::End(e) if matches!(e.local_name().as_ref(), b"trk" | b"rte") => {
+                if let Some(feature) = pending.take() {
+                    match feature.finish() {
+                        Ok(feature) => file.features.push(feature),
+                        Err(error) => file.errors.push(error),
+                    }
+                }
+            }
+            Event::End(_) => property = None,
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_gpx_tracks_and_routes() {
+        let file = parse_gpx_tracks(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test" xmlns="http://www.topografix.com/GPX/1/1">
+  <metadata><name>Etape 4</name></metadata>
+  <trk>
+    <name>Liaison Atar</name>
+    <type>gravel</type>
+    <trkseg>
+      <trkpt lat="20.50" lon="-13.05"><name>WPT 1</name></trkpt>
+      <trkpt lat="20.51" lon="-13.06"/>
+    </trkseg>
+    <trkseg>
+      <trkpt lat="20.60" lon="-13.10"/>
+      <trkpt lat="20.61" lon="-13.11"/>
+    </trkseg>
+  </trk>
+  <trk>
+    <name>Broken</name>
+    <trkseg><trkpt lat="20.5"/></trkseg>
+  </trk>
+  <rte>
+    <name>Piste &amp; dunes</name>
+    <desc><![CDATA[Soft sand]]></desc>
+    <rtept lat="21.0" lon="-12.0"/>
+    <rtept lat="21.1" lon="-12.1"/>
+  </rte>
+</gpx>"#,
+        )
+        .unwrap();
+
+        assert_eq!(file.features.len(), 2);
+        let track = &file.features[0];
+        assert_eq!(track.name.as_deref(), Some("Liaison Atar"));
+        assert_eq!(track.properties["type"], "gravel");
+        assert_eq!(track.geometry["type"], "MultiLineString");
+        assert_eq!(track.geometry["coordinates"][0][0], json!([-13.05, 20.5]));
+
+        let route = &file.features[1];
+        assert_eq!(route.index, 2);
+        assert_eq!(route.name.as_deref(), Some("Piste & dunes"));
+        assert_eq!(route.properties["desc"], "Soft sand");
+        assert_eq!(route.geometry["type"], "LineString");
+
+        assert_eq!(file.errors.len(), 1);
+        assert_eq!(file.errors[0].index, 1);
+        assert_eq!(file.errors[0].name.as_deref(), Some("Broken"));
+        assert!(file.errors[0].message.contains("lon"));
+    }
+}
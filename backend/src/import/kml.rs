@@ -0,0 +1,239 @@
+//! KML and KMZ track files
+//!
+//! Every `<Placemark>` is a feature. Its `LineString`s, including those in a
+//! `MultiGeometry`, and `gx:Track`s become the parts of its line; other
+//! geometries are ignored. The name, description and `ExtendedData` values
+//! are its properties. A KMZ is a zip archive holding `doc.kml`, or failing
+//! that the first `.kml` entry.
+
+use std::io::{Cursor, Read};
+
+use anyhow::{anyhow, Context, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::{FeatureBuilder, TrackFile};
+
+/// Parse a `lng,lat[,alt]` KML coordinate, or a space-separated `gx:coord`
+fn position(tuple: &str, separator: char) -> Result<[f64; 2], String> {
+    let mut values = tuple.split(separator).map(|v| v.trim().parse::<f64>());
+    match (values.next(), values.next()) {
+        (Some(Ok(lng)), Some(Ok(lat))) if lng.is_finite() && lat.is_finite() => Ok([lng, lat]),
+        _ => Err(format!("Invalid coordinate '{}'", tuple)),
+    }
+}
+
+fn push_coordinates(feature: &mut FeatureBuilder, text: &str) {
+    feature.start_part();
+    for tuple in text.split_whitespace() {
+        match position(tuple, ',') {
+            Ok(position) => feature.push(position),
+            Err(message) => return feature.fail(message),
+        }
+    }
+}
+
+fn name_attr(element: &BytesStart) -> Result<Option<String>> {
+    Ok(match element.try_get_attribute("name")? {
+        Some(attr) => Some(attr.unescape_value()?.trim().to_string()),
+        None => None,
+    })
+}
+
+/// Parse the placemarks of a KML document
+///
+/// Malformed XML fails the whole file; a placemark without a readable line
+/// is reported as a feature error.
+pub fn parse_kml(kml: &str) -> Result<TrackFile> {
+    let mut reader = Reader::from_str(kml);
+    reader.config_mut().trim_text(true);
+
+    let mut file = TrackFile::default();
+    let mut pending: Option<FeatureBuilder> = None;
+    let mut count = 0;
+    // Local names of the open elements
+    let mut path: Vec<Vec<u8>> = Vec::new();
+    // Key of the ExtendedData value being read
+    let mut data_key: Option<String> = None;
+
+    loop {
+        let text = match reader.read_event()? {
+            Event::Start(e) => {
+                match e.local_name().as_ref() {
+                    b"Placemark" => {
+                        pending = Some(FeatureBuilder::new(count));
+                        count += 1;
+                    }
+                    b"Track" => {
+                        if let Some(feature) = pending.as_mut() {
+                            feature.start_part();
+                        }
+                    }
+                    b"Data" | b"SimpleData" => data_key = name_attr(&e)?,
+                    _ => {}
+                }
+                path.push(e.local_name().as_ref().to_vec());
+                continue;
+            }
+            Event::Empty(e) => {
+                if e.local_name().as_ref() == b"Placemark" {
+                    if let Err(error) = FeatureBuilder::new(count).finish() {
+                        file.errors.push(error);
+                    }
+                    count += 1;
+                }
+                continue;
+            }
+            Event::End(e) => {
+                path.pop();
+                if e.local_name().as_ref() == b"Placemark" {
+                    if let Some(feature) = pending.take() {
+                        match feature.finish() {
+                            Ok(feature) => file.features.push(feature),
+                            Err(error) => file.errors.push(error),
+                        }
+                    }
+                }
+                continue;
+            }
+            Event::Text(t) => t.unescape()?.into_owned(),
+            Event::CData(t) => String::from_utf8_lossy(&t.into_inner()).into_owned(),
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        let Some(feature) = pending.as_mut() else {
+            continue;
+        };
+        let element = path.last().map(Vec::as_slice).unwrap_or_default();
+        let parent = path
+            .len()
+            .checked_sub(2)
+            .map(|i| path[i].as_slice())
+            .unwrap_or_default();
+        match (parent, element) {
+            (b"Placemark", b"name") => feature.property("name", text),
+            (b"Placemark", b"description") => feature.property("description", text),
+            (b"LineString", b"coordinates") => push_coordinates(feature, &text),
+            (b"Track", b"coord") => match position(&text, ' ') {
+                Ok(position) => feature.push(position),
+                Err(message) => feature.fail(message),
+            },
+            (b"Data", b"value") | (_, b"SimpleData") => {
+                if let Some(key) = data_key.as_deref() {
+                    feature.property(key, text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(file)
+}
+
+/// Parse the KML document inside a KMZ archive
+pub fn parse_kmz(data: &[u8]) -> Result<TrackFile> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).context("Invalid KMZ archive")?;
+
+    let entry = archive
+        .file_names()
+        .find(|name| name.eq_ignore_ascii_case("doc.kml"))
+        .or_else(|| {
+            archive
+                .file_names()
+                .find(|name| name.to_ascii_lowercase().ends_with(".kml"))
+        })
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("KMZ archive contains no KML document"))?;
+
+    let mut kml = String::new();
+    archive
+        .by_name(&entry)?
+        .read_to_string(&mut kml)
+        .with_context(|| format!("Failed to read {} from KMZ archive", entry))?;
+    parse_kml(&kml)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use serde_json::json;
+
+    use super::*;
+
+    const KML: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<kml xmlns="http://www.opengis.net/kml/2.2" xmlns:gx="http://www.google.com/kml/ext/2.2">
+  <Document>
+    <name>Mauritanie</name>
+    <Placemark>
+      <name>Passe d'Amogjar</name>
+      <description><![CDATA[<b>Rocky</b> climb]]></description>
+      <ExtendedData>
+        <Data name="surface"><value>rock</value></Data>
+        <SchemaData schemaUrl="#tracks"><SimpleData name="confidence">4</SimpleData></SchemaData>
+      </ExtendedData>
+      <MultiGeometry>
+        <LineString><coordinates>-13.1,20.4,310 -13.2,20.5,320</coordinates></LineString>
+        <LineString><coordinates>
+          -13.3,20.6 -13.4,20.7
+        </coordinates></LineString>
+        <Point><coordinates>-13.0,20.0</coordinates></Point>
+      </MultiGeometry>
+    </Placemark>
+    <Placemark>
+      <name>Camp</name>
+      <Point><coordinates>-13.0,20.0</coordinates></Point>
+    </Placemark>
+    <Placemark>
+      <gx:Track>
+        <when>2026-01-10T08:00:00Z</when>
+        <gx:coord>-12.0 21.0 250</gx:coord>
+        <gx:coord>-12.1 21.1 255</gx:coord>
+      </gx:Track>
+    </Placemark>
+  </Document>
+</kml>"##;
+
+    #[test]
+    fn test_parse_kml_placemarks() {
+        let file = parse_kml(KML).unwrap();
+
+        assert_eq!(file.features.len(), 2);
+        let pass = &file.features[0];
+        assert_eq!(pass.name.as_deref(), Some("Passe d'Amogjar"));
+        assert_eq!(pass.properties["description"], "<b>Rocky</b> climb");
+        assert_eq!(pass.properties["surface"], "rock");
+        assert_eq!(pass.properties["confidence"], "4");
+        assert_eq!(pass.geometry["type"], "MultiLineString");
+        assert_eq!(
+            pass.geometry["coordinates"][1],
+            json!([[-13.3, 20.6], [-13.4, 20.7]])
+        );
+
+        let track = &file.features[1];
+        assert_eq!(track.index, 2);
+        assert_eq!(track.name, None);
+        assert_eq!(
+            track.geometry,
+            json!({ "type": "LineString", "coordinates": [[-12.0, 21.0], [-12.1, 21.1]] })
+        );
+
+        assert_eq!(file.errors.len(), 1);
+        assert_eq!(file.errors[0].index, 1);
+        assert_eq!(file.errors[0].name.as_deref(), Some("Camp"));
+    }
+
+    #[test]
+    fn test_parse_kmz() {
+        let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        archive
+            .start_file("files/tracks.kml", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        archive.write_all(KML.as_bytes()).unwrap();
+        let data = archive.finish().unwrap().into_inner();
+
+        assert_eq!(parse_kmz(&data).unwrap().features.len(), 2);
+        assert!(parse_kmz(b"not a zip").is_err());
+    }
+}
@@ -0,0 +1,304 @@
+//! Track files from rally archives and community databases
+//!
+//! GeoJSON FeatureCollections, GPX tracks and routes, and KML/KMZ
+//! placemarks are read into features: a line geometry and the properties
+//! describing it. A feature that cannot be read is reported on its own;
+//! the rest of the file is still imported.
+
+pub mod geojson;
+pub mod gpx;
+pub mod kml;
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::geometry::validate::{
+    validate_line_geometry, GeometryValidationError, ValidationOptions,
+};
+
+pub use self::geojson::parse_geojson;
+pub use gpx::parse_gpx_tracks;
+pub use kml::{parse_kml, parse_kmz};
+
+/// Supported track file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackFileFormat {
+    GeoJson,
+    Gpx,
+    Kml,
+    Kmz,
+}
+
+impl TrackFileFormat {
+    /// Format from a file's extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "geojson" | "json" => Some(Self::GeoJson),
+            "gpx" => Some(Self::Gpx),
+            "kml" => Some(Self::Kml),
+            "kmz" => Some(Self::Kmz),
+            _ => None,
+        }
+    }
+}
+
+/// A feature read from a track file
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackFeature {
+    /// Position in the file, counting from 0
+    pub index: usize,
+    pub name: Option<String>,
+    /// GeoJSON LineString or MultiLineString, as found in the file
+    pub geometry: Value,
+    pub properties: Map<String, Value>,
+}
+
+/// A feature that could not be read or imported
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeatureError {
+    pub index: usize,
+    pub name: Option<String>,
+    pub message: String,
+}
+
+/// The features of a track file, and those that could not be read
+#[derive(Debug, Default)]
+pub struct TrackFile {
+    pub features: Vec<TrackFeature>,
+    pub errors: Vec<FeatureError>,
+}
+
+/// A feature being read from a GPX or KML file
+struct FeatureBuilder {
+    index: usize,
+    properties: Map<String, Value>,
+    parts: Vec<Vec<[f64; 2]>>,
+    /// First unreadable position
+    error: Option<String>,
+}
+
+impl FeatureBuilder {
+    fn new(index: usize) -> Self {
+        Self {
+            index,
+            properties: Map::new(),
+            parts: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn property(&mut self, key: &str, value: String) {
+        self.properties
+            .insert(key.to_string(), Value::String(value.trim().to_string()));
+    }
+
+    fn start_part(&mut self) {
+        self.parts.push(Vec::new());
+    }
+
+    fn push(&mut self, position: [f64; 2]) {
+        match self.parts.last_mut() {
+            Some(part) => part.push(position),
+            None => self.parts.push(vec![position]),
+        }
+    }
+
+    fn fail(&mut self, message: String) {
+        self.error.get_or_insert(message);
+    }
+
+    fn finish(self) -> Result<TrackFeature, FeatureError> {
+        let name = self
+            .properties
+            .get("name")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let error = |message: String| FeatureError {
+            index: self.index,
+            name: name.clone(),
+            message,
+        };
+
+        if let Some(message) = self.error.clone() {
+            return Err(error(message));
+        }
+        let mut parts: Vec<Vec<[f64; 2]>> =
+            self.parts.into_iter().filter(|p| !p.is_empty()).collect();
+        let geometry = match parts.len() {
+            0 => return Err(error("Feature has no line geometry".to_string())),
+            1 => json!({ "type": "LineString", "coordinates": parts.remove(0) }),
+            _ => json!({ "type": "MultiLineString", "coordinates": parts }),
+        };
+
+        Ok(TrackFeature {
+            index: self.index,
+            name,
+            geometry,
+            properties: self.properties,
+        })
+    }
+}
+
+/// Read a track file, choosing the parser by extension
+///
+/// Fails only when the file as a whole cannot be read.
+pub fn read_track_file(path: &Path) -> Result<TrackFile> {
+    let format = TrackFileFormat::from_path(path)
+        .ok_or_else(|| anyhow!("Unsupported track file type: {}", path.display()))?;
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    match format {
+        TrackFileFormat::GeoJson => parse_geojson(std::str::from_utf8(&bytes)?),
+        TrackFileFormat::Gpx => parse_gpx_tracks(std::str::from_utf8(&bytes)?),
+        TrackFileFormat::Kml => parse_kml(std::str::from_utf8(&bytes)?),
+        TrackFileFormat::Kmz => parse_kmz(&bytes),
+    }
+}
+
+/// Split a feature's geometry into LineStrings that fit `curated_tracks`
+///
+/// Each part of a multi-part line becomes its own LineString. Swapped
+/// coordinates, duplicate points and zero-length parts are repaired.
+pub fn track_lines(geometry: &Value) -> Result<Vec<Value>, GeometryValidationError> {
+    let validated = validate_line_geometry(
+        geometry,
+        &ValidationOptions {
+            repair: true,
+            ..Default::default()
+        },
+    )?;
+
+    Ok(validated.geometry["coordinates"]
+        .as_array()
+        .map(|lines| {
+            lines
+                .iter()
+                .map(|line| json!({ "type": "LineString", "coordinates": line }))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Confidence (1-5) of a track by how it was captured
+///
+/// "rally_official", "community_verified", "gps_trace" (a recorded trace)
+/// and "satellite_derived" (drawn over imagery); anything else is 1.
+pub fn calculate_confidence(source_type: &str) -> i32 {
+    match source_type {
+        "rally_official" => 5,
+        "community_verified" => 4,
+        "gps_trace" => 3,
+        "satellite_derived" => 2,
+        _ => 1,
+    }
+}
+
+/// Which feature properties carry track attributes
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FieldMapping {
+    /// Property holding the surface
+    pub surface: String,
+    /// Surface values renamed onto the planner's own, keyed in lower case
+    /// (`{"piste": "gravel"}`); other values are kept
+    pub surface_values: HashMap<String, String>,
+    /// Property naming the source type for `calculate_confidence`
+    /// ("rally_official", "community_verified", "gps_trace", ...)
+    pub source_type: String,
+    /// Source type of features without that property
+    pub default_source_type: String,
+    /// Property with an explicit confidence (1-5), preferred over the
+    /// source type
+    pub confidence: Option<String>,
+}
+
+impl Default for FieldMapping {
+    fn default() -> Self {
+        Self {
+            surface: "surface".to_string(),
+            surface_values: HashMap::new(),
+            source_type: "source_type".to_string(),
+            default_source_type: "gps_trace".to_string(),
+            confidence: None,
+        }
+    }
+}
+
+impl FieldMapping {
+    pub fn surface(&self, properties: &Map<String, Value>) -> Option<String> {
+        let value = properties
+            .get(&self.surface)?
+            .as_str()?
+            .trim()
+            .to_lowercase();
+        if value.is_empty() {
+            return None;
+        }
+        Some(self.surface_values.get(&value).cloned().unwrap_or(value))
+    }
+
+    pub fn source_type<'a>(&'a self, properties: &'a Map<String, Value>) -> &'a str {
+        properties
+            .get(&self.source_type)
+            .and_then(Value::as_str)
+            .unwrap_or(&self.default_source_type)
+    }
+
+    /// Explicit confidence, if the mapped property holds 1-5
+    pub fn confidence(&self, properties: &Map<String, Value>) -> Option<i32> {
+        let value = properties.get(self.confidence.as_ref()?)?;
+        let confidence = value
+            .as_i64()
+            .or_else(|| value.as_str()?.trim().parse().ok())?;
+        (1..=5).contains(&confidence).then_some(confidence as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_lines_split_parts() {
+        let lines = track_lines(&json!({
+            "type": "MultiLineString",
+            "coordinates": [
+                [[-5.0, 20.0], [-5.0, 20.0], [-5.1, 20.1]],
+                [[-6.0, 21.0], [-6.1, 21.1]]
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["type"], "LineString");
+        assert_eq!(lines[0]["coordinates"], json!([[-5.0, 20.0], [-5.1, 20.1]]));
+        assert!(track_lines(&json!({ "type": "Point", "coordinates": [-5.0, 20.0] })).is_err());
+    }
+
+    #[test]
+    fn test_field_mapping() {
+        let mapping = FieldMapping {
+            surface: "terrain".to_string(),
+            surface_values: [("piste".to_string(), "gravel".to_string())].into(),
+            confidence: Some("stars".to_string()),
+            ..Default::default()
+        };
+        let properties =
+            json!({ "terrain": " Piste ", "stars": "4", "source_type": "rally_official" });
+        let properties = properties.as_object().unwrap();
+
+        assert_eq!(mapping.surface(properties).as_deref(), Some("gravel"));
+        assert_eq!(mapping.confidence(properties), Some(4));
+        assert_eq!(mapping.source_type(properties), "rally_official");
+
+        let empty = Map::new();
+        assert_eq!(mapping.surface(&empty), None);
+        assert_eq!(mapping.confidence(&empty), None);
+        assert_eq!(mapping.source_type(&empty), "gps_trace");
+    }
+}
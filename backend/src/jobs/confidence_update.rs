@@ -1,6 +1,6 @@
 use anyhow::Result;
 use chrono::{Utc, Duration};
-use crate::db::DbPool;
+use crate::db::{DbPool, RevisionContext, RevisionKind};
 
 /// Confidence Update Job
 ///
@@ -26,8 +26,17 @@ impl ConfidenceUpdateJob {
 
     async fn downgrade_old_tracks(&self, pool: &DbPool) -> Result<()> {
         let cutoff_date = Utc::now().naive_utc().date() - Duration::days(365);
+        let reason = format!("Not verified since {}", cutoff_date);
 
-        sqlx::query!(
+        // Each downgraded track gets a revision naming this job and the reason
+        let mut tx = pool.begin().await?;
+        RevisionContext::new(RevisionKind::Confidence)
+            .job("confidence_update")
+            .reason(Some(&reason))
+            .apply(&mut tx)
+            .await?;
+
+        let result = sqlx::query!(
             r#"
             UPDATE curated_tracks
             SET confidence = GREATEST(confidence - 1, 1)
@@ -35,9 +44,12 @@ impl ConfidenceUpdateJob {
             "#,
             cutoff_date
         )
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
+        tx.commit().await?;
+        tracing::info!("Downgraded {} tracks: {}", result.rows_affected(), reason);
+
         Ok(())
     }
 
@@ -1,5 +1,16 @@
-use anyhow::Result;
-use crate::db::DbPool;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use sqlx::PgConnection;
+use uuid::Uuid;
+use crate::db::{DbPool, RevisionContext, RevisionKind};
+use crate::import::{
+    calculate_confidence, read_track_file, track_lines, FeatureError, FieldMapping,
+};
+use crate::models::TRACK_SOURCES;
+
+const JOB_NAME: &str = "curated_tracks_import";
 
 /// Curated Tracks Import Job
 ///
@@ -7,59 +18,158 @@ use crate::db::DbPool;
 /// - Rally archives
 /// - Community databases
 /// - Verified overland routes
+///
+/// `source_file` is a GeoJSON, GPX or KML/KMZ file. Every part of a
+/// feature's line becomes its own track, with surface and confidence taken
+/// from the properties named in `mapping`. Features that cannot be imported
+/// are recorded in the run's `feature_errors`; the rest of the file still
+/// goes in.
 pub struct CuratedTracksImportJob {
     pub source_name: String,
     pub source_file: String,
+    /// `curated_tracks.source` of the imported tracks ("rally" or "curated")
+    pub source: String,
+    pub mapping: FieldMapping,
+}
+
+/// Tracks an import wrote, and the features it skipped
+#[derive(Debug, Default)]
+struct ImportSummary {
+    imported: i32,
+    errors: Vec<FeatureError>,
 }
 
 impl CuratedTracksImportJob {
     pub async fn run(&self, pool: &DbPool) -> Result<()> {
+        if !TRACK_SOURCES.contains(&self.source.as_str()) {
+            return Err(anyhow!("Invalid track source '{}'", self.source));
+        }
+
         tracing::info!("Starting curated tracks import from: {}", self.source_name);
 
-        // TODO: Implement actual file parsing (GeoJSON, KML, etc.)
-        // For PoC, this is a placeholder
+        // Every track revision written by this run links back to it
+        let run_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO track_import_runs (job, source_name, source_file)
+            VALUES ($1, $2, $3)
+            RETURNING id
+            "#,
+            JOB_NAME,
+            &self.source_name,
+            &self.source_file
+        )
+        .fetch_one(pool)
+        .await?;
 
-        // Example:
-        // let geojson = read_geojson(&self.source_file)?;
-        // for feature in geojson.features {
-        //     insert_curated_track(pool, feature).await?;
-        // }
+        let result = self.import(pool, run_id).await;
 
-        tracing::info!("Curated tracks import completed");
+        let (imported, feature_errors) = match &result {
+            Ok(summary) => (Some(summary.imported), serde_json::to_value(&summary.errors)?),
+            Err(_) => (None, json!([])),
+        };
+        sqlx::query!(
+            r#"
+            UPDATE track_import_runs
+            SET status = $2, error = $3, tracks_imported = $4, feature_errors = $5,
+                finished_at = NOW()
+            WHERE id = $1
+            "#,
+            run_id,
+            if result.is_ok() { "succeeded" } else { "failed" },
+            result.as_ref().err().map(|e| e.to_string()),
+            imported,
+            feature_errors
+        )
+        .execute(pool)
+        .await?;
+
+        let summary = result?;
+        if !summary.errors.is_empty() {
+            tracing::warn!(
+                "Skipped {} features of {} (run {})",
+                summary.errors.len(),
+                self.source_file,
+                run_id
+            );
+        }
+        tracing::info!(
+            "Curated tracks import completed (run {}): {} tracks",
+            run_id,
+            summary.imported
+        );
         Ok(())
     }
 
+    async fn import(&self, pool: &DbPool, run_id: Uuid) -> Result<ImportSummary> {
+        let file = tokio::task::spawn_blocking({
+            let path = self.source_file.clone();
+            move || read_track_file(Path::new(&path))
+        })
+        .await??;
+
+        let mut summary = ImportSummary {
+            imported: 0,
+            errors: file.errors,
+        };
+
+        let mut tx = pool.begin().await?;
+        RevisionContext::new(RevisionKind::Import)
+            .job(JOB_NAME)
+            .import_run(run_id)
+            .apply(&mut tx)
+            .await?;
+
+        for feature in &file.features {
+            let lines = match track_lines(&feature.geometry) {
+                Ok(lines) => lines,
+                Err(error) => {
+                    summary.errors.push(FeatureError {
+                        index: feature.index,
+                        name: feature.name.clone(),
+                        message: error.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let surface = self.mapping.surface(&feature.properties);
+            let confidence = self
+                .mapping
+                .confidence(&feature.properties)
+                .unwrap_or_else(|| calculate_confidence(self.mapping.source_type(&feature.properties)));
+
+            for line in &lines {
+                self.insert_track(&mut tx, line, confidence, surface.as_deref())
+                    .await?;
+            }
+            summary.imported += lines.len() as i32;
+        }
+
+        tx.commit().await?;
+        summary.errors.sort_by_key(|error| error.index);
+        Ok(summary)
+    }
+
     async fn insert_track(
         &self,
-        pool: &DbPool,
-        geometry: serde_json::Value,
+        conn: &mut PgConnection,
+        geometry: &Value,
         confidence: i32,
-        surface: Option<String>,
+        surface: Option<&str>,
     ) -> Result<()> {
         sqlx::query!(
             r#"
             INSERT INTO curated_tracks (geometry, source, surface, confidence, last_verified)
-            VALUES ($1, $2, $3, $4, CURRENT_DATE)
+            VALUES (ST_SetSRID(ST_GeomFromGeoJSON($1), 4326), $2, $3, $4, CURRENT_DATE)
             "#,
-            geometry,
-            &self.source_name,
+            geometry.to_string(),
+            &self.source,
             surface,
             confidence
         )
-        .execute(pool)
+        .execute(conn)
         .await?;
 
         Ok(())
     }
 }
-
-/// Determines confidence level based on source
-pub fn calculate_confidence(source_type: &str, metadata: &serde_json::Value) -> i32 {
-    match source_type {
-        "rally_official" => 5,
-        "community_verified" => 4,
-        "gps_trace" => 3,
-        "satellite_derived" => 2,
-        _ => 1,
-    }
-}
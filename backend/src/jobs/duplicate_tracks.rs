@@ -0,0 +1,36 @@
+use anyhow::Result;
+use crate::db::{detect_duplicates, DbPool, DuplicateOptions};
+
+/// Duplicate Tracks Job
+///
+/// Finds overlapping curated tracks across the whole network and records
+/// merge proposals for curators to review. Run after imports, which are the
+/// main source of near-duplicate lines.
+pub struct DuplicateTracksJob {
+    pub options: DuplicateOptions,
+}
+
+impl DuplicateTracksJob {
+    pub async fn run(&self, pool: &DbPool) -> Result<()> {
+        tracing::info!("Starting duplicate tracks job");
+
+        let mut tx = pool.begin().await?;
+        let detection = detect_duplicates(&mut tx, None, &self.options).await?;
+        tx.commit().await?;
+
+        if detection.candidates as i64 >= self.options.max_pairs {
+            tracing::warn!(
+                "Duplicate tracks job hit the {} pair limit; run again after resolving proposals",
+                self.options.max_pairs
+            );
+        }
+
+        tracing::info!(
+            "Duplicate tracks job completed: {} candidates, {} proposals, {} rejected",
+            detection.candidates,
+            detection.proposed,
+            detection.rejected
+        );
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 pub mod osm_import;
 pub mod curated_tracks_import;
 pub mod confidence_update;
+pub mod duplicate_tracks;
+pub mod track_topology;
 
 // Job runner utilities
 use anyhow::Result;
@@ -1,6 +1,21 @@
-use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use osmpbf::BlobReader;
+use sqlx::PgConnection;
 use uuid::Uuid;
-use crate::db::DbPool;
+use crate::db::{
+    load_osm_tracks, lock_replication_sequence, osm_node_positions, osm_ways_using_nodes,
+    remove_osm_nodes, remove_osm_ways, set_replication_sequence, DbPool, OsmLoad, OsmTrack,
+    RevisionContext, RevisionKind,
+};
+use crate::osm::{
+    change_files, changed_ways, read_node_positions, read_osc, read_track_ways, OsmChange,
+    TrackWay,
+};
+
+const JOB_NAME: &str = "osm_import";
 
 /// OSM Import Job - Imports relevant OSM tracks
 ///
@@ -8,77 +23,276 @@ use crate::db::DbPool;
 /// - highway=track, path, unclassified
 /// - surface=sand, gravel, dirt
 /// - tracktype=grade2-5
+///
+/// `run` loads a whole extract: it is read twice, once for the relevant
+/// ways and once for the positions of just their nodes, so a whole country
+/// never sits in memory. `apply_changes` then keeps the region current from
+/// OSM change files. Tracks are keyed by `osm_way_id` and only fields that
+/// changed in OSM are written, so curator adjustments survive both.
 pub struct OsmImportJob {
     pub region: String,
     pub osm_pbf_path: String,
 }
 
+/// The relevant contents of a PBF extract
+struct Extract {
+    tracks: Vec<OsmTrack>,
+    /// Positions of the tracks' nodes
+    nodes: HashMap<i64, (f64, f64)>,
+    /// Replication sequence number the extract was cut at
+    sequence: Option<i64>,
+}
+
+/// What one change file did to the region's tracks
+#[derive(Debug, Default)]
+struct ChangeSummary {
+    load: OsmLoad,
+    removed: u64,
+    skipped: usize,
+}
+
 impl OsmImportJob {
     pub async fn run(&self, pool: &DbPool) -> Result<()> {
         tracing::info!("Starting OSM import for region: {}", self.region);
 
-        // TODO: Implement actual OSM parsing using osmpbf or osmium
-        // For PoC, this is a placeholder structure
-
-        // Example: Parse PBF file and filter relevant ways
-        // let ways = parse_osm_pbf(&self.osm_pbf_path)?;
-        // for way in ways {
-        //     if is_relevant_way(&way) {
-        //         insert_track(pool, way).await?;
-        //     }
-        // }
+        let run_id = start_run(pool, &self.region, &self.osm_pbf_path).await?;
+        let result = self.import(pool, run_id).await;
+        finish_run(pool, run_id, &result).await?;
 
-        tracing::info!("OSM import completed for region: {}", self.region);
+        let load = result?;
+        tracing::info!(
+            "OSM import completed for region {} (run {}): {} ways, {} inserted, {} updated",
+            self.region,
+            run_id,
+            load.staged,
+            load.inserted,
+            load.updated
+        );
         Ok(())
     }
 
-    async fn insert_track(
-        &self,
-        pool: &DbPool,
-        geometry: serde_json::Value,
-        surface: Option<String>,
-    ) -> Result<()> {
-        sqlx::query!(
-            r#"
-            INSERT INTO curated_tracks (geometry, source, surface, confidence, region)
-            VALUES ($1, 'osm', $2, 3, $3)
-            ON CONFLICT DO NOTHING
-            "#,
-            geometry,
-            surface,
-            &self.region
-        )
-        .execute(pool)
-        .await?;
+    async fn import(&self, pool: &DbPool, run_id: Uuid) -> Result<OsmLoad> {
+        let extract = tokio::task::spawn_blocking({
+            let path = self.osm_pbf_path.clone();
+            move || read_tracks(&path)
+        })
+        .await??;
+
+        let mut tx = pool.begin().await?;
+        RevisionContext::new(RevisionKind::Import)
+            .job(JOB_NAME)
+            .import_run(run_id)
+            .apply(&mut tx)
+            .await?;
 
+        let load = load_osm_tracks(&mut tx, &extract.tracks, &extract.nodes, &self.region).await?;
+
+        // Change files continue from the sequence the extract was cut at
+        lock_replication_sequence(&mut tx, &self.region).await?;
+        match extract.sequence {
+            Some(sequence) => set_replication_sequence(&mut tx, &self.region, sequence).await?,
+            None => tracing::warn!(
+                "{} has no replication sequence; change files cannot be applied after it",
+                self.osm_pbf_path
+            ),
+        }
+
+        tx.commit().await?;
+        Ok(load)
+    }
+
+    /// Apply the change files in `changes_dir` newer than the last one applied
+    ///
+    /// Each file is applied in its own transaction together with the
+    /// region's replication sequence number, so a file is never applied
+    /// twice and a failure stops at the last good file. Missing files in the
+    /// sequence stop the run: the region needs a fresh extract instead.
+    pub async fn apply_changes(&self, pool: &DbPool, changes_dir: &str) -> Result<()> {
+        tracing::info!(
+            "Applying OSM change files for region {} from {}",
+            self.region,
+            changes_dir
+        );
+
+        let run_id = start_run(pool, &self.region, changes_dir).await?;
+        let result = self.apply_files(pool, run_id, changes_dir).await;
+        finish_run(pool, run_id, &result).await?;
+
+        let applied = result?;
+        tracing::info!(
+            "Applied {} OSM change files for region {} (run {})",
+            applied,
+            self.region,
+            run_id
+        );
         Ok(())
     }
-}
 
-// Helper functions for OSM filtering
-fn is_relevant_way(tags: &std::collections::HashMap<String, String>) -> bool {
-    // Check highway type
-    if let Some(highway) = tags.get("highway") {
-        if !["track", "path", "unclassified"].contains(&highway.as_str()) {
-            return false;
+    async fn apply_files(&self, pool: &DbPool, run_id: Uuid, changes_dir: &str) -> Result<usize> {
+        let files = change_files(Path::new(changes_dir))?;
+        let mut applied = 0;
+
+        for (sequence, path) in files {
+            let mut tx = pool.begin().await?;
+            let last = lock_replication_sequence(&mut tx, &self.region)
+                .await?
+                .ok_or_else(|| {
+                    anyhow!(
+                        "No replication sequence for region {}; import an extract first",
+                        self.region
+                    )
+                })?;
+            if sequence <= last {
+                continue;
+            }
+            if sequence != last + 1 {
+                return Err(anyhow!(
+                    "Change files {} to {} are missing for region {}",
+                    last + 1,
+                    sequence - 1,
+                    self.region
+                ));
+            }
+
+            let change = tokio::task::spawn_blocking(move || read_osc(&path)).await??;
+
+            let reason = format!("OSM change {}", sequence);
+            RevisionContext::new(RevisionKind::Import)
+                .job(JOB_NAME)
+                .import_run(run_id)
+                .reason(Some(&reason))
+                .apply(&mut tx)
+                .await?;
+
+            let summary = self.apply_change(&mut tx, &change).await?;
+            set_replication_sequence(&mut tx, &self.region, sequence).await?;
+            tx.commit().await?;
+
+            tracing::info!(
+                "Applied OSM change {} to {}: {} inserted, {} updated, {} removed, {} skipped",
+                sequence,
+                self.region,
+                summary.load.inserted,
+                summary.load.updated,
+                summary.removed,
+                summary.skipped
+            );
+            applied += 1;
         }
-    } else {
-        return false;
+
+        Ok(applied)
     }
 
-    // Prefer ways with surface tags
-    if let Some(surface) = tags.get("surface") {
-        if ["sand", "gravel", "dirt", "unpaved", "ground"].contains(&surface.as_str()) {
-            return true;
+    async fn apply_change(
+        &self,
+        conn: &mut PgConnection,
+        change: &OsmChange,
+    ) -> Result<ChangeSummary> {
+        // Imported ways whose line moves with a changed node
+        let changed_nodes: Vec<i64> = change.nodes.keys().copied().collect();
+        let moved = osm_ways_using_nodes(conn, &changed_nodes).await?;
+        let (ways, removed) = changed_ways(change, moved);
+
+        // Stored positions, overlaid with the diff's own
+        let wanted: HashSet<i64> = ways.iter().flat_map(|w| w.refs.iter().copied()).collect();
+        let mut nodes =
+            osm_node_positions(conn, &wanted.iter().copied().collect::<Vec<_>>()).await?;
+        for (id, position) in &change.nodes {
+            match position {
+                Some(position) if wanted.contains(id) => {
+                    nodes.insert(*id, *position);
+                }
+                Some(_) => {}
+                None => {
+                    nodes.remove(id);
+                }
+            }
         }
-    }
 
-    // Or tracktype
-    if let Some(tracktype) = tags.get("tracktype") {
-        if ["grade2", "grade3", "grade4", "grade5"].contains(&tracktype.as_str()) {
-            return true;
+        // Ways that just became relevant may use nodes never imported
+        let (tracks, skipped) = assemble_tracks(ways, &nodes);
+        if skipped > 0 {
+            tracing::warn!(
+                "Skipped {} changed OSM ways with fewer than two known nodes",
+                skipped
+            );
         }
+
+        let load = load_osm_tracks(conn, &tracks, &nodes, &self.region).await?;
+        let removed = remove_osm_ways(conn, &removed).await?;
+        let deleted_nodes: Vec<i64> = change
+            .nodes
+            .iter()
+            .filter(|(_, position)| position.is_none())
+            .map(|(id, _)| *id)
+            .collect();
+        remove_osm_nodes(conn, &deleted_nodes).await?;
+
+        Ok(ChangeSummary {
+            load,
+            removed,
+            skipped,
+        })
     }
+}
 
-    false
+/// Record an import run; every track revision it writes links back to it
+async fn start_run(pool: &DbPool, region: &str, source_file: &str) -> Result<Uuid> {
+    let run_id = sqlx::query_scalar(
+        "INSERT INTO track_import_runs (job, source_name, source_file)
+         VALUES ($1, $2, $3)
+         RETURNING id",
+    )
+    .bind(JOB_NAME)
+    .bind(region)
+    .bind(source_file)
+    .fetch_one(pool)
+    .await?;
+    Ok(run_id)
+}
+
+async fn finish_run<T>(pool: &DbPool, run_id: Uuid, result: &Result<T>) -> Result<()> {
+    sqlx::query(
+        "UPDATE track_import_runs
+         SET status = $2, error = $3, finished_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(run_id)
+    .bind(if result.is_ok() { "succeeded" } else { "failed" })
+    .bind(result.as_ref().err().map(|e| e.to_string()))
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Lines for the ways whose nodes are known, and how many were not
+fn assemble_tracks(
+    ways: Vec<TrackWay>,
+    nodes: &HashMap<i64, (f64, f64)>,
+) -> (Vec<OsmTrack>, usize) {
+    let total = ways.len();
+    let tracks: Vec<OsmTrack> = ways
+        .into_iter()
+        .filter_map(|way| OsmTrack::assemble(way, nodes))
+        .collect();
+    let skipped = total - tracks.len();
+    (tracks, skipped)
+}
+
+/// Relevant ways of a PBF extract with their lines assembled
+fn read_tracks(path: &str) -> Result<Extract> {
+    let (ways, sequence) = read_track_ways(BlobReader::from_path(path)?)?;
+
+    let wanted: HashSet<i64> = ways.iter().flat_map(|w| w.refs.iter().copied()).collect();
+    let nodes = read_node_positions(BlobReader::from_path(path)?, &wanted)?;
+
+    let (tracks, skipped) = assemble_tracks(ways, &nodes);
+    if skipped > 0 {
+        tracing::warn!("Skipped {} OSM ways with fewer than two known nodes", skipped);
+    }
+    Ok(Extract {
+        tracks,
+        nodes,
+        sequence,
+    })
 }
@@ -0,0 +1,29 @@
+use anyhow::Result;
+use crate::db::{rebuild_topology, DbPool};
+
+/// Track Topology Job
+///
+/// Rebuilds the `track_nodes`/`track_edges` network graph from curated
+/// tracks, so connectivity diagnostics reflect the latest imports and edits.
+pub struct TrackTopologyJob {
+    /// Track ends closer than this to another track are joined (meters)
+    pub tolerance_m: f64,
+}
+
+impl TrackTopologyJob {
+    pub async fn run(&self, pool: &DbPool) -> Result<()> {
+        tracing::info!("Starting track topology job");
+
+        let mut tx = pool.begin().await?;
+        let build = rebuild_topology(&mut tx, self.tolerance_m, None).await?;
+        tx.commit().await?;
+
+        tracing::info!(
+            "Track topology job completed: {} nodes, {} edges, {} components",
+            build.node_count,
+            build.edge_count,
+            build.component_count
+        );
+        Ok(())
+    }
+}
@@ -2,10 +2,13 @@
 
 pub mod config;
 pub mod db;
+pub mod export;
 pub mod geometry;
+pub mod import;
 pub mod jwks;
 pub mod middleware;
 pub mod models;
+pub mod osm;
 pub mod routes;
 
 // Re-export AppState for convenience
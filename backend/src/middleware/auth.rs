@@ -15,6 +15,28 @@ pub struct Claims {
     pub exp: i64,     // Expiration time (Unix timestamp)
     pub role: String, // Supabase role (usually "authenticated")
     pub email: Option<String>,
+    #[serde(default)]
+    pub app_metadata: AppMetadata,
+}
+
+/// Application role granting write access to curated tracks
+pub const CURATOR_ROLE: &str = "curator";
+
+/// Supabase `app_metadata` claims; only admins (service role) can set these
+///
+/// Application roles are read from either `role` or `roles`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+}
+
+impl AppMetadata {
+    pub fn has_role(&self, role: &str) -> bool {
+        self.role.as_deref() == Some(role) || self.roles.iter().any(|r| r == role)
+    }
 }
 
 /// Authenticated user extracted from JWT token
@@ -27,6 +49,35 @@ pub struct AuthUser {
     pub full_claims: Claims,
 }
 
+impl AuthUser {
+    pub fn is_curator(&self) -> bool {
+        self.full_claims.app_metadata.has_role(CURATOR_ROLE)
+    }
+}
+
+/// Authenticated user holding the curator role
+///
+/// Rejects other authenticated users with 403. RLS checks the same claim, so
+/// this only gives an early, clear error.
+#[derive(Clone, Debug)]
+pub struct Curator(pub AuthUser);
+
+impl FromRequestParts<AppState> for Curator {
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+        if !user.is_curator() {
+            tracing::warn!("User {} is not a curator", user.id);
+            return Err((StatusCode::FORBIDDEN, "Curator role required".into()));
+        }
+        Ok(Self(user))
+    }
+}
+
 impl FromRequestParts<AppState> for AuthUser {
     type Rejection = (StatusCode, String);
 
@@ -117,6 +168,7 @@ mod tests {
             exp: 1735137600, // Some future timestamp
             role: "authenticated".to_string(),
             email: Some("test@example.com".to_string()),
+            app_metadata: AppMetadata::default(),
         };
 
         // Serialize to JSON
@@ -232,6 +284,31 @@ mod tests {
         }
     }
 
+    /// Test that curator roles are read from either app_metadata shape
+    #[test]
+    fn test_curator_from_app_metadata() {
+        let with_metadata = |app_metadata: serde_json::Value| {
+            let claims: Claims = serde_json::from_value(serde_json::json!({
+                "sub": Uuid::new_v4().to_string(),
+                "aud": "authenticated",
+                "exp": 1735137600,
+                "role": "authenticated",
+                "app_metadata": app_metadata,
+            }))
+            .unwrap();
+            AuthUser {
+                id: claims.sub.clone(),
+                role: claims.role.clone(),
+                full_claims: claims,
+            }
+        };
+
+        assert!(with_metadata(serde_json::json!({"role": "curator"})).is_curator());
+        assert!(with_metadata(serde_json::json!({"roles": ["editor", "curator"]})).is_curator());
+        assert!(!with_metadata(serde_json::json!({"provider": "email"})).is_curator());
+        assert!(!with_metadata(serde_json::json!({"roles": ["editor"]})).is_curator());
+    }
+
     /// Test AuthUser struct can be cloned and debugged
     #[test]
     fn test_auth_user_clone_debug() {
@@ -241,6 +318,7 @@ mod tests {
             exp: 1735137600,
             role: "authenticated".to_string(),
             email: Some("test@example.com".to_string()),
+            app_metadata: AppMetadata::default(),
         };
 
         let auth_user = AuthUser {
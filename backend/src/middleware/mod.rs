@@ -1,3 +1,3 @@
 pub mod auth;
 
-pub use auth::AuthUser;
+pub use auth::{AuthUser, Curator};
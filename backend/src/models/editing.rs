@@ -1,8 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::geometry::types::Position;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct EditingSession {
     pub id: Uuid,
@@ -45,8 +48,8 @@ pub struct PointChange {
     pub user_email: String,
     pub feature_index: i32,
     pub point_index: i32,
-    pub original_position: serde_json::Value,
-    pub new_position: serde_json::Value,
+    pub original_position: Json<Position>,
+    pub new_position: Json<Position>,
     pub status: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -58,8 +61,8 @@ pub struct PointChange {
 pub struct CreatePointChange {
     pub feature_index: i32,
     pub point_index: i32,
-    pub original_position: serde_json::Value,
-    pub new_position: serde_json::Value,
+    pub original_position: Position,
+    pub new_position: Position,
 }
 
 #[derive(Debug, Deserialize)]
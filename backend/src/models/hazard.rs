@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::track::comma_list;
+use crate::models::BoundingBox;
+
+/// Values allowed in `hazard_reports.kind`
+pub const HAZARD_KINDS: [&str; 6] = [
+    "washout",
+    "soft_sand",
+    "fesh_fesh",
+    "border_closed",
+    "security",
+    "other",
+];
+
+/// Accepted `severity` values, from least to most serious
+pub const HAZARD_SEVERITIES: [&str; 3] = ["low", "moderate", "severe"];
+
+/// A time-limited hazard reported from the field
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct HazardReport {
+    pub id: Uuid,
+    pub reported_by: Uuid,
+    /// One of `HAZARD_KINDS`
+    pub kind: String,
+    /// "low" | "moderate" | "severe"
+    pub severity: String,
+    pub description: Option<String>,
+    /// GeoJSON Point or LineString
+    pub geometry: Value,
+    pub expires_at: DateTime<Utc>,
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Report a hazard (requires authentication)
+#[derive(Debug, Deserialize)]
+pub struct CreateHazard {
+    pub kind: String,
+    pub severity: String,
+    pub description: Option<String>,
+    /// GeoJSON Point, or LineString for a stretch of piste
+    pub geometry: Value,
+    /// At most 90 days ahead
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Active hazards (public endpoint)
+#[derive(Debug, Deserialize)]
+pub struct HazardQuery {
+    /// Viewport as `minLng,minLat,maxLng,maxLat`
+    pub bbox: Option<BoundingBox>,
+    #[serde(default, deserialize_with = "comma_list")]
+    pub kind: Vec<String>,
+    /// Only hazards at least this severe
+    pub min_severity: Option<String>,
+}
+
+/// Active hazards near a route version
+#[derive(Debug, Deserialize)]
+pub struct RouteHazardQuery {
+    /// Corridor half-width around the route (meters, default 1000)
+    pub corridor_m: Option<f64>,
+    pub version_id: Option<Uuid>,
+}
+
+/// An active hazard near a route
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteHazard {
+    #[serde(flatten)]
+    pub hazard: HazardReport,
+    /// Distance along the route to the point closest to the hazard (km)
+    pub km: f64,
+    /// Distance between the hazard and the route (meters)
+    pub distance_m: f64,
+}
@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::{BoundingBox, CuratedTrack};
+
+/// Two overlapping curated tracks proposed for merging
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TrackMergeProposal {
+    pub id: Uuid,
+    pub track_a: Uuid,
+    pub track_b: Uuid,
+    /// Track whose geometry survives the merge
+    pub keep_track_id: Uuid,
+    /// Share of `track_a` within the overlap buffer of `track_b` (0-1)
+    pub overlap_a: f64,
+    pub overlap_b: f64,
+    /// Discrete Fréchet distance between the two lines (meters)
+    pub frechet_m: f64,
+    pub merged_confidence: i32,
+    /// "pending" | "merged" | "dismissed"
+    pub status: String,
+    pub detected_at: DateTime<Utc>,
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// A pending proposal with both tracks, for side-by-side review
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeProposalDetail {
+    #[serde(flatten)]
+    pub proposal: TrackMergeProposal,
+    pub a: CuratedTrack,
+    pub b: CuratedTrack,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DetectDuplicatesQuery {
+    /// Only look at tracks intersecting `min_lng,min_lat,max_lng,max_lat`
+    pub bbox: Option<BoundingBox>,
+}
+
+/// Result of a detection run
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateDetection {
+    /// Overlapping pairs examined
+    pub candidates: usize,
+    /// Pairs similar enough to propose, new or refreshed
+    pub proposed: usize,
+    /// Mutual overlaps too different in shape, recorded as dismissed
+    pub rejected: usize,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ApplyMerge {
+    /// Keep this track's geometry instead of the proposed one
+    pub keep_track_id: Option<Uuid>,
+    pub reason: Option<String>,
+}
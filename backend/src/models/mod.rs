@@ -1,9 +1,23 @@
 pub mod editing;
+pub mod hazard;
+pub mod merge;
+pub mod poi;
 pub mod proposal;
+pub mod region;
 pub mod route;
+pub mod submission;
+pub mod topology;
 pub mod track;
+pub mod verification;
 
 pub use editing::*;
+pub use hazard::*;
+pub use merge::*;
+pub use poi::*;
 pub use proposal::*;
+pub use region::*;
 pub use route::*;
+pub use submission::*;
+pub use topology::*;
 pub use track::*;
+pub use verification::*;
@@ -0,0 +1,107 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::geometry::linear_ref::Side;
+use crate::models::track::comma_list;
+use crate::models::BoundingBox;
+
+/// Values allowed in `pois.category`
+pub const POI_CATEGORIES: [&str; 8] = [
+    "water_well",
+    "fuel",
+    "bivouac",
+    "village",
+    "checkpoint",
+    "hazard",
+    "mechanic",
+    "medical",
+];
+
+/// A point of interest
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Poi {
+    pub id: Uuid,
+    /// One of `POI_CATEGORIES`
+    pub category: String,
+    pub name: Option<String>,
+    pub lng: f64,
+    pub lat: f64,
+    /// Category-specific details, e.g. `{"depth_m": 40, "potable": true}`
+    pub attributes: Value,
+    /// "osm" | "rally" | "curated"
+    pub source: String,
+    pub confidence: i32,
+    pub last_verified: Option<NaiveDate>,
+    pub created_by: Option<Uuid>,
+    pub updated_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Create a POI (curators only)
+#[derive(Debug, Deserialize)]
+pub struct CreatePoi {
+    pub category: String,
+    pub name: Option<String>,
+    pub lng: f64,
+    pub lat: f64,
+    pub attributes: Option<Value>,
+    pub source: String,
+    pub confidence: i32,
+    pub last_verified: Option<NaiveDate>,
+}
+
+/// Change a POI (curators only); absent fields are kept
+///
+/// `attributes` replaces the whole object.
+#[derive(Debug, Deserialize)]
+pub struct UpdatePoi {
+    pub category: Option<String>,
+    pub name: Option<String>,
+    pub lng: Option<f64>,
+    pub lat: Option<f64>,
+    pub attributes: Option<Value>,
+    pub source: Option<String>,
+    pub confidence: Option<i32>,
+    pub last_verified: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PoiQuery {
+    /// Viewport as `minLng,minLat,maxLng,maxLat`
+    pub bbox: Option<BoundingBox>,
+    /// Any of these categories, comma-separated (`water_well,fuel`)
+    #[serde(default, deserialize_with = "comma_list")]
+    pub category: Vec<String>,
+    pub min_confidence: Option<i32>,
+    /// Page size (default and cap: 1000)
+    pub limit: Option<i64>,
+}
+
+/// POIs near a route version, in driving order
+#[derive(Debug, Deserialize)]
+pub struct RoutePoiQuery {
+    /// Corridor half-width around the route (km, default 5)
+    pub within_km: Option<f64>,
+    #[serde(default, deserialize_with = "comma_list")]
+    pub category: Vec<String>,
+    /// Only POIs past this distance along the route ("next well after km 230")
+    pub after_km: Option<f64>,
+    pub version_id: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+/// A POI located along a route
+#[derive(Debug, Clone, Serialize)]
+pub struct RoutePoi {
+    #[serde(flatten)]
+    pub poi: Poi,
+    /// Distance along the route to the closest point (km)
+    pub km: f64,
+    /// Distance from the route (meters)
+    pub offset_m: f64,
+    pub side: Side,
+}
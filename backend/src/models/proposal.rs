@@ -1,14 +1,16 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::geometry::types::MultiLineGeometry;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct RouteProposal {
     pub id: Uuid,
     pub route_id: Uuid,
-    #[sqlx(json)]
-    pub geometry: serde_json::Value,
+    pub geometry: Json<MultiLineGeometry>,
     pub comment: String,
     pub status: String,
     pub created_by: Option<Uuid>,
@@ -16,10 +18,10 @@ pub struct RouteProposal {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CreateProposal {
     pub route_id: Uuid,
-    pub geometry: serde_json::Value,
+    pub geometry: MultiLineGeometry,
     pub comment: String,
 }
 
@@ -0,0 +1,98 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Accepted `level` values, outermost first
+pub const REGION_LEVELS: [&str; 2] = ["country", "area"];
+
+/// A named region polygon, without its outline
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Region {
+    pub id: Uuid,
+    /// Stable identifier used in URLs and the `region` track filter
+    pub code: String,
+    pub name: String,
+    /// "country" | "area"
+    pub level: String,
+    /// Country an area lies in
+    pub parent_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Curated tracks intersecting a region
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct RegionStats {
+    pub track_count: i64,
+    /// Track length inside the region (km)
+    pub length_km: f64,
+    pub avg_confidence: Option<f64>,
+}
+
+/// A region with its track statistics
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct RegionSummary {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub region: Region,
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub stats: RegionStats,
+}
+
+/// A region with its outline, statistics and subregions
+#[derive(Debug, Clone, Serialize)]
+pub struct RegionDetail {
+    #[serde(flatten)]
+    pub summary: RegionSummary,
+    /// GeoJSON MultiPolygon
+    pub geometry: Value,
+    pub children: Vec<RegionSummary>,
+}
+
+/// Short reference to a region a track lies in
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct RegionRef {
+    pub id: Uuid,
+    pub code: String,
+    pub name: String,
+    pub level: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegionQuery {
+    pub level: Option<String>,
+    /// Only the areas of this country (code)
+    pub parent: Option<String>,
+}
+
+/// GeoJSON FeatureCollection of region outlines
+#[derive(Debug, Deserialize)]
+pub struct RegionFeatureCollection {
+    pub features: Vec<RegionFeature>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegionFeature {
+    /// GeoJSON Polygon or MultiPolygon
+    pub geometry: Value,
+    pub properties: RegionProperties,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegionProperties {
+    pub code: String,
+    pub name: String,
+    /// Defaults to "area" when `parent` is set, else "country"
+    pub level: Option<String>,
+    /// Code of the enclosing country; in the same file or already imported
+    pub parent: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegionImportResult {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+}
@@ -1,14 +1,21 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::geometry::coordinates::{parse_coordinate, CoordinateError, CoordinateFormat};
+use crate::geometry::operations::TrimSide;
+use crate::geometry::simplification::SimplifyOptions;
+use crate::geometry::types::{ControlPoint, Position};
+use crate::models::RouteHazard;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Route {
     pub id: Uuid,
     pub name: String,
     pub owner_id: Uuid,
-    pub control_points: serde_json::Value,
+    pub control_points: Json<Vec<ControlPoint>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -17,19 +24,46 @@ pub struct Route {
 pub struct CreateRoute {
     pub name: String,
     pub geometry: serde_json::Value,
-    pub control_points: serde_json::Value,
+    pub control_points: Vec<ControlPoint>,
+    #[serde(default)]
+    pub simplify: SimplifyOptions,
+    /// Repair swapped lat/lng, duplicate points and zero-length lines
+    /// instead of rejecting the geometry
+    #[serde(default)]
+    pub repair: bool,
+    /// Keep off curated tracks near active severe hazards
+    #[serde(default)]
+    pub avoid_hazards: bool,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateRoute {
     pub geometry: serde_json::Value,
+    #[serde(default)]
+    pub simplify: SimplifyOptions,
+    /// Repair swapped lat/lng, duplicate points and zero-length lines
+    /// instead of rejecting the geometry
+    #[serde(default)]
+    pub repair: bool,
+    /// Keep off curated tracks near active severe hazards
+    #[serde(default)]
+    pub avoid_hazards: bool,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateRouteControlPoints {
-    pub control_points: serde_json::Value,
+    pub control_points: Vec<ControlPoint>,
     pub feature_index: i32,
     pub point_index: i32,
+    #[serde(default)]
+    pub simplify: SimplifyOptions,
+    /// Repair swapped lat/lng, duplicate points and zero-length lines
+    /// instead of rejecting the geometry
+    #[serde(default)]
+    pub repair: bool,
+    /// Keep off curated tracks near active severe hazards
+    #[serde(default)]
+    pub avoid_hazards: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -40,4 +74,117 @@ pub struct RouteWithGeometry {
     pub length_km: Option<f64>,
     pub estimated_time_min: Option<i32>,
     pub created_by: Option<Uuid>,
+    /// Active hazards near the route, in driving order; absent when not checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hazards: Option<Vec<RouteHazard>>,
+}
+
+/// Geometry resolution for route reads: `tolerance_m` wins over `zoom`
+#[derive(Debug, Default, Deserialize)]
+pub struct GeometryQuery {
+    pub zoom: Option<f64>,
+    pub tolerance_m: Option<f64>,
+}
+
+/// Date used for magnetic heading (CAP) computation; defaults to today
+#[derive(Debug, Deserialize)]
+pub struct HeadingQuery {
+    pub date: Option<NaiveDate>,
+    /// Also label waypoints in this format (dd, dms, ddm, utm, mgrs)
+    pub coordinate_format: Option<CoordinateFormat>,
+}
+
+/// Options of a route export
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    /// Date used for magnetic headings (CAP); defaults to today
+    pub date: Option<NaiveDate>,
+    /// Also label waypoints in this format (dd, dms, ddm, utm, mgrs)
+    pub coordinate_format: Option<CoordinateFormat>,
+    /// Include POIs within this distance of the route (km, default 5; 0 for none)
+    pub poi_within_km: Option<f64>,
+}
+
+/// Query for km markers along a route version
+#[derive(Debug, Deserialize)]
+pub struct KmMarkersQuery {
+    /// Marker spacing in km (default: 1)
+    pub interval_km: Option<f64>,
+    pub version_id: Option<Uuid>,
+    /// Also label markers in this format (dd, dms, ddm, utm, mgrs)
+    pub coordinate_format: Option<CoordinateFormat>,
+}
+
+/// Query for locating a coordinate along a route version
+///
+/// The coordinate is given either as `lng`/`lat` or as text in `coordinate`
+/// (decimal, DMS, DDM, UTM or MGRS).
+#[derive(Debug, Deserialize)]
+pub struct LocateQuery {
+    pub lng: Option<f64>,
+    pub lat: Option<f64>,
+    pub coordinate: Option<String>,
+    pub version_id: Option<Uuid>,
+    /// Also return the snapped point in this format
+    pub coordinate_format: Option<CoordinateFormat>,
+}
+
+impl LocateQuery {
+    pub fn position(&self) -> Result<Position, CoordinateError> {
+        match (&self.coordinate, self.lng, self.lat) {
+            (Some(text), None, None) => parse_coordinate(text).map(|parsed| parsed.position),
+            (None, Some(lng), Some(lat)) => {
+                Position::new(lng, lat).map_err(CoordinateError::Invalid)
+            }
+            (Some(_), _, _) => Err(CoordinateError::Ambiguous(
+                "Give either coordinate or lng/lat, not both".to_string(),
+            )),
+            _ => Err(CoordinateError::Invalid(
+                "Give a coordinate, or both lng and lat".to_string(),
+            )),
+        }
+    }
+}
+
+/// Query for cutting a route version between two km positions
+#[derive(Debug, Deserialize)]
+pub struct CutQuery {
+    pub from_km: f64,
+    pub to_km: f64,
+    pub version_id: Option<Uuid>,
+}
+
+/// Query for validating a recorded GPX trace against a route version
+#[derive(Debug, Deserialize)]
+pub struct ValidateTraceQuery {
+    pub version_id: Option<Uuid>,
+    /// Off-route corridor half-width (meters)
+    pub corridor_m: Option<f64>,
+}
+
+/// Query for comparing a recorded GPX trace with a route version
+#[derive(Debug, Deserialize)]
+pub struct DeviationQuery {
+    pub version_id: Option<Uuid>,
+    /// Offset from the plan beyond which the trace counts as deviating (meters)
+    pub threshold_m: Option<f64>,
+}
+
+/// Split a route at an interior control point into two new routes
+#[derive(Debug, Deserialize)]
+pub struct SplitRoute {
+    pub point_index: usize,
+}
+
+/// Trim a route before or after an interior control point
+#[derive(Debug, Deserialize)]
+pub struct TrimRoute {
+    pub point_index: usize,
+    pub side: TrimSide,
+}
+
+/// Join another route onto the end of this one
+#[derive(Debug, Deserialize)]
+pub struct JoinRoute {
+    pub other_route_id: Uuid,
 }
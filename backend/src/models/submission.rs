@@ -0,0 +1,92 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::geometry::quality::QualityReport;
+use crate::geometry::types::LineGeometry;
+use crate::import::calculate_confidence;
+
+/// A user-submitted track awaiting (or past) curator review
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TrackSubmission {
+    pub id: Uuid,
+    pub submitted_by: Uuid,
+    #[sqlx(json)]
+    pub geometry: LineGeometry,
+    /// "drawn" | "gpx"
+    pub origin: String,
+    pub surface: Option<String>,
+    pub region: Option<String>,
+    pub comment: Option<String>,
+    /// Date the GPX trace was recorded
+    pub recorded_on: Option<NaiveDate>,
+    /// "pending" | "approved" | "rejected"
+    pub status: String,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub review_comment: Option<String>,
+    /// Curated track created on approval
+    pub track_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TrackSubmission {
+    /// Confidence given on approval unless the curator chooses one
+    ///
+    /// A recorded GPS trace is worth more than a line drawn over imagery.
+    pub fn default_confidence(&self) -> i32 {
+        calculate_confidence(match self.origin.as_str() {
+            "gpx" => "gps_trace",
+            _ => "satellite_derived",
+        })
+    }
+}
+
+/// A drawn line submission
+#[derive(Debug, Deserialize)]
+pub struct CreateSubmission {
+    pub geometry: Value,
+    pub surface: Option<String>,
+    pub region: Option<String>,
+    pub comment: Option<String>,
+    /// Repair swapped lat/lng and duplicate points instead of rejecting
+    #[serde(default)]
+    pub repair: bool,
+}
+
+/// Attributes of a GPX submission; the body is the GPX document
+#[derive(Debug, Deserialize)]
+pub struct GpxSubmissionQuery {
+    pub surface: Option<String>,
+    pub region: Option<String>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewSubmission {
+    pub status: String, // "approved" | "rejected"
+    pub comment: Option<String>,
+    /// Overrides for the approved track
+    pub confidence: Option<i32>,
+    pub surface: Option<String>,
+    pub region: Option<String>,
+}
+
+/// Existing curated tracks a submission runs along
+#[derive(Debug, Clone, Serialize)]
+pub struct SubmissionOverlap {
+    /// Length of the submission within reach of existing tracks (meters)
+    pub length_m: f64,
+    pub track_ids: Vec<Uuid>,
+}
+
+/// A pending submission with what a curator needs to judge it
+#[derive(Debug, Clone, Serialize)]
+pub struct SubmissionQueueEntry {
+    #[serde(flatten)]
+    pub submission: TrackSubmission,
+    pub overlap: SubmissionOverlap,
+    pub quality: QualityReport,
+}
@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// One rebuild of the track network topology
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TopologyBuild {
+    pub id: Uuid,
+    pub tolerance_m: f64,
+    pub node_count: i32,
+    pub edge_count: i32,
+    pub component_count: i32,
+    pub built_by: Option<Uuid>,
+    pub built_at: DateTime<Utc>,
+}
+
+/// The current topology and whether tracks changed since it was built
+#[derive(Debug, Clone, Serialize)]
+pub struct TopologyStatus {
+    pub build: Option<TopologyBuild>,
+    pub stale: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RebuildTopology {
+    /// Track ends closer than this to another track are joined (meters)
+    pub tolerance_m: Option<f64>,
+}
+
+/// A connected piece of the track network
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TopologyComponent {
+    /// 0 is the component with the most track length
+    pub component: i32,
+    pub edge_count: i64,
+    pub track_count: i64,
+    pub length_m: f64,
+    /// `[min_lng, min_lat, max_lng, max_lat]`
+    pub bbox: Vec<f64>,
+}
+
+/// A dead end lying close to another track: a likely missing link
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DanglingEnd {
+    pub node_id: i64,
+    pub lng: f64,
+    pub lat: f64,
+    /// Track the dead end belongs to
+    pub track_id: Uuid,
+    /// Closest track not connected at this node
+    pub nearest_track_id: Uuid,
+    pub distance_m: f64,
+    /// Joining here would connect two components
+    pub bridges_components: bool,
+}
+
+/// Shortest link from a component to the rest of the network
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ComponentGap {
+    pub component: i32,
+    pub length_m: f64,
+    /// Component on the other side of the gap
+    pub nearest_component: i32,
+    pub gap_m: f64,
+    /// `[lng, lat]` ends of the shortest link
+    pub from: Vec<f64>,
+    pub to: Vec<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiagnosticsQuery {
+    /// Largest distance reported (meters)
+    pub max_distance_m: Option<f64>,
+    pub limit: Option<i64>,
+}
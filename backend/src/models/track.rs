@@ -1,23 +1,424 @@
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::de::{self, Deserializer};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::geometry::types::LineGeometry;
+use crate::models::{HazardReport, RegionRef, TrackVerification};
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct CuratedTrack {
     pub id: Uuid,
     #[sqlx(json)]
+    pub geometry: LineGeometry,
+    pub source: String,
+    pub surface: Option<String>,
+    pub confidence: i32,
+    pub last_verified: Option<NaiveDate>,
+    pub region: Option<String>,
+    /// Curator who created the track (absent for imported tracks)
+    #[sqlx(default)]
+    pub created_by: Option<Uuid>,
+    /// Curator who last changed the track
+    #[sqlx(default)]
+    pub updated_by: Option<Uuid>,
+    /// Every source the line has been seen in, including merged duplicates
+    #[sqlx(default)]
+    pub provenance: Vec<String>,
+    /// OpenStreetMap way the track was imported from
+    #[sqlx(default)]
+    pub osm_way_id: Option<i64>,
+}
+
+/// Values allowed in `curated_tracks.source`
+pub const TRACK_SOURCES: [&str; 3] = ["osm", "rally", "curated"];
+
+/// Create a curated track (curators only)
+#[derive(Debug, Deserialize)]
+pub struct CreateTrack {
+    /// GeoJSON LineString (or a single-line MultiLineString)
     pub geometry: serde_json::Value,
     pub source: String,
     pub surface: Option<String>,
     pub confidence: i32,
     pub last_verified: Option<NaiveDate>,
     pub region: Option<String>,
+    /// Repair swapped lat/lng and duplicate points instead of rejecting
+    #[serde(default)]
+    pub repair: bool,
+    /// Recorded in the track's history
+    pub reason: Option<String>,
+}
+
+/// Change a curated track (curators only); absent fields are kept, while
+/// `surface` and `region` sent as `null` are cleared
+#[derive(Debug, Deserialize)]
+pub struct UpdateTrack {
+    pub geometry: Option<serde_json::Value>,
+    pub source: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub surface: Option<Option<String>>,
+    pub confidence: Option<i32>,
+    pub last_verified: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub region: Option<Option<String>>,
+    #[serde(default)]
+    pub repair: bool,
+    /// Recorded in the track's history
+    pub reason: Option<String>,
+}
+
+/// One track change within a bulk request
+#[derive(Debug, Deserialize)]
+pub struct BulkTrackUpdate {
+    pub id: Uuid,
+    #[serde(flatten)]
+    pub changes: UpdateTrack,
+}
+
+/// Creates, updates and deletes applied in one transaction
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct BulkTrackChanges {
+    pub create: Vec<CreateTrack>,
+    pub update: Vec<BulkTrackUpdate>,
+    pub delete: Vec<Uuid>,
+    /// Recorded in the history of every track without its own reason
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkTrackResult {
+    pub created: Vec<Uuid>,
+    pub updated: Vec<Uuid>,
+    pub deleted: Vec<Uuid>,
+}
+
+/// A curated track with its most recent field reports
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackDetail {
+    #[serde(flatten)]
+    pub track: CuratedTrack,
+    /// Regions the track passes through, countries first
+    pub regions: Vec<RegionRef>,
+    /// Active hazards on or beside the track, most severe first
+    pub hazards: Vec<HazardReport>,
+    /// Newest drive first
+    pub verifications: Vec<TrackVerification>,
+}
+
+/// A curated track as it was before one change
+///
+/// Attributes are absent for the revision that created the track.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TrackRevision {
+    pub id: Uuid,
+    pub track_id: Uuid,
+    /// "insert" | "update" | "delete"
+    pub operation: String,
+    /// "import" | "edit" | "confidence" | "merge" | "revert" | "verification" | "sql"
+    pub kind: String,
+    #[sqlx(json(nullable))]
+    pub geometry: Option<LineGeometry>,
+    pub source: Option<String>,
+    pub surface: Option<String>,
+    pub confidence: Option<i32>,
+    pub last_verified: Option<NaiveDate>,
+    pub region: Option<String>,
+    pub provenance: Option<Vec<String>>,
+    /// User who made the change
+    pub actor_id: Option<Uuid>,
+    /// Job that made the change
+    pub job: Option<String>,
+    pub reason: Option<String>,
+    pub import_run_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Reason recorded for a track delete or revert
+#[derive(Debug, Default, Deserialize)]
+pub struct ChangeReason {
+    pub reason: Option<String>,
+}
+
+/// A page of tracks
+///
+/// `truncated` is set when more tracks matched; pass `next_cursor` back as
+/// `cursor` to fetch the next page.
+#[derive(Debug, Serialize)]
+pub struct TrackList {
+    pub tracks: Vec<CuratedTrack>,
+    pub truncated: bool,
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct TrackQuery {
     pub source: Option<String>,
     pub min_confidence: Option<i32>,
+    pub max_confidence: Option<i32>,
+    /// Region code; tracks passing through that region
     pub region: Option<String>,
+    /// Any of these surfaces, comma-separated (`sand,gravel`)
+    #[serde(default, deserialize_with = "comma_list")]
+    pub surface: Vec<String>,
+    /// Only tracks verified on or after this date
+    pub verified_since: Option<NaiveDate>,
+    /// Viewport as `minLng,minLat,maxLng,maxLat`
+    pub bbox: Option<BoundingBox>,
+    /// Center of a proximity search as `lng,lat`; requires `radius_m`
+    pub near: Option<NearPoint>,
+    pub radius_m: Option<f64>,
+    /// Simplify returned geometry for display at this zoom level
+    pub zoom: Option<f64>,
+    /// Page size (default and cap: 1000)
+    pub limit: Option<i64>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<TrackCursor>,
+}
+
+/// Keyset position after the last track of a page
+///
+/// Pages are ordered by id, or by distance then id for proximity searches;
+/// the cursor carries the sort key of the last track returned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrackCursor {
+    Id(Uuid),
+    Distance(f64, Uuid),
+}
+
+impl TrackCursor {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid cursor '{}'", text);
+        match text.split_once('~') {
+            Some((distance, id)) => {
+                let distance: f64 = distance.parse().map_err(|_| invalid())?;
+                if !distance.is_finite() {
+                    return Err(invalid());
+                }
+                Ok(Self::Distance(distance, id.parse().map_err(|_| invalid())?))
+            }
+            None => text.parse().map(Self::Id).map_err(|_| invalid()),
+        }
+    }
+}
+
+impl std::fmt::Display for TrackCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Id(id) => write!(f, "{}", id),
+            Self::Distance(distance, id) => write!(f, "{}~{}", distance, id),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TrackCursor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::parse(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+/// Tell a field sent as `null` (`Some(None)`) from an absent one (`None`)
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+pub(crate) fn comma_list<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    Ok(String::deserialize(deserializer)?
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// WGS84 bounding box, parsed from `minLng,minLat,maxLng,maxLat`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min_lng: f64,
+    pub min_lat: f64,
+    pub max_lng: f64,
+    pub max_lat: f64,
+}
+
+/// Center of a proximity search, parsed from `lng,lat`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NearPoint {
+    pub lng: f64,
+    pub lat: f64,
+}
+
+fn comma_separated(text: &str, expected: usize) -> Result<Vec<f64>, String> {
+    let values = text
+        .split(',')
+        .map(|v| v.trim().parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| format!("'{}' is not a list of numbers", text))?;
+    if values.len() != expected || values.iter().any(|v| !v.is_finite()) {
+        return Err(format!("expected {} comma-separated numbers", expected));
+    }
+    Ok(values)
+}
+
+impl BoundingBox {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let v = comma_separated(text, 4)?;
+        let bbox = Self {
+            min_lng: v[0],
+            min_lat: v[1],
+            max_lng: v[2],
+            max_lat: v[3],
+        };
+        let lng_ok = |x: f64| (-180.0..=180.0).contains(&x);
+        let lat_ok = |y: f64| (-90.0..=90.0).contains(&y);
+        if !(lng_ok(bbox.min_lng)
+            && lng_ok(bbox.max_lng)
+            && lat_ok(bbox.min_lat)
+            && lat_ok(bbox.max_lat))
+        {
+            return Err("bbox is outside WGS84 bounds".to_string());
+        }
+        if bbox.min_lng > bbox.max_lng || bbox.min_lat > bbox.max_lat {
+            return Err("bbox minimum exceeds maximum".to_string());
+        }
+        Ok(bbox)
+    }
+}
+
+impl NearPoint {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let v = comma_separated(text, 2)?;
+        if !(-180.0..=180.0).contains(&v[0]) || !(-90.0..=90.0).contains(&v[1]) {
+            return Err("near point is outside WGS84 bounds".to_string());
+        }
+        Ok(Self {
+            lng: v[0],
+            lat: v[1],
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for BoundingBox {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::parse(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for NearPoint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::parse(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_cursor_round_trip() {
+        let id = Uuid::new_v4();
+        for cursor in [
+            TrackCursor::Id(id),
+            TrackCursor::Distance(0.0, id),
+            TrackCursor::Distance(1234.5678, id),
+        ] {
+            assert_eq!(TrackCursor::parse(&cursor.to_string()), Ok(cursor));
+        }
+    }
+
+    #[test]
+    fn test_track_cursor_rejects_malformed_input() {
+        let id = Uuid::new_v4();
+        for text in [
+            String::new(),
+            "not-a-uuid".to_string(),
+            format!("abc~{}", id),
+            format!("NaN~{}", id),
+            format!("inf~{}", id),
+            "12.5~not-a-uuid".to_string(),
+            format!("12.5~{}~{}", id, id),
+        ] {
+            assert!(TrackCursor::parse(&text).is_err(), "accepted '{}'", text);
+        }
+    }
+
+    #[test]
+    fn test_bounding_box_parse() {
+        assert_eq!(
+            BoundingBox::parse("-5.5, 20, -4,21.25"),
+            Ok(BoundingBox {
+                min_lng: -5.5,
+                min_lat: 20.0,
+                max_lng: -4.0,
+                max_lat: 21.25,
+            })
+        );
+
+        for text in [
+            "",
+            "-5,20,-4",
+            "-5,20,-4,21,0",
+            "-5,20,x,21",
+            "-5,20,-4,inf",
+            "-181,20,-4,21",
+            "-5,-91,-4,21",
+            "-4,20,-5,21",
+            "-5,21,-4,20",
+        ] {
+            assert!(BoundingBox::parse(text).is_err(), "accepted '{}'", text);
+        }
+    }
+
+    #[test]
+    fn test_near_point_parse() {
+        assert_eq!(
+            NearPoint::parse("2.5,35.2"),
+            Ok(NearPoint {
+                lng: 2.5,
+                lat: 35.2
+            })
+        );
+
+        for text in ["", "2.5", "2.5,35.2,1", "2.5,north", "180.5,0", "0,-90.5"] {
+            assert!(NearPoint::parse(text).is_err(), "accepted '{}'", text);
+        }
+    }
+
+    #[test]
+    fn test_comma_list_trims_and_drops_empty_values() {
+        let query: TrackQuery = serde_json::from_str(r#"{"surface": " sand, gravel,,"}"#).unwrap();
+        assert_eq!(query.surface, vec!["sand", "gravel"]);
+
+        let query: TrackQuery = serde_json::from_str(r#"{"surface": ""}"#).unwrap();
+        assert!(query.surface.is_empty());
+
+        let query: TrackQuery = serde_json::from_str("{}").unwrap();
+        assert!(query.surface.is_empty());
+    }
+
+    #[test]
+    fn test_update_track_tells_null_from_absent() {
+        let changes: UpdateTrack =
+            serde_json::from_str(r#"{"surface": null, "confidence": 3}"#).unwrap();
+        assert_eq!(changes.surface, Some(None));
+        assert_eq!(changes.region, None);
+
+        let changes: UpdateTrack = serde_json::from_str(r#"{"region": "Adrar"}"#).unwrap();
+        assert_eq!(changes.region, Some(Some("Adrar".to_string())));
+        assert_eq!(changes.surface, None);
+
+        // Bulk updates flatten the changes next to the track id
+        let bulk: BulkTrackUpdate =
+            serde_json::from_str(&format!(r#"{{"id": "{}", "surface": null}}"#, Uuid::nil()))
+                .unwrap();
+        assert_eq!(bulk.changes.surface, Some(None));
+        assert_eq!(bulk.changes.region, None);
+    }
 }
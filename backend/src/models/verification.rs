@@ -0,0 +1,35 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::geometry::types::LineGeometry;
+
+/// Accepted `passability` values, from best to worst
+pub const PASSABILITY: [&str; 3] = ["good", "difficult", "impassable"];
+
+/// A user's report of driving a curated track
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TrackVerification {
+    pub id: Uuid,
+    pub track_id: Uuid,
+    pub user_id: Uuid,
+    pub driven_on: NaiveDate,
+    /// Surface observed on the ground
+    pub surface: Option<String>,
+    /// "good" | "difficult" | "impassable"
+    pub passability: String,
+    #[sqlx(json(nullable))]
+    pub trace: Option<LineGeometry>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateVerification {
+    pub driven_on: NaiveDate,
+    pub surface: Option<String>,
+    pub passability: String,
+    /// GeoJSON LineString of the stretch driven
+    pub trace: Option<Value>,
+}
@@ -0,0 +1,449 @@
+//! OpenStreetMap extracts
+//!
+//! Streams ways and nodes out of `.osm.pbf` files and decides which ways are
+//! desert tracks worth importing as curated tracks.
+
+pub mod osc;
+
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+
+use osmpbf::{BlobDecode, BlobReader, Element, PrimitiveBlock};
+use thiserror::Error;
+
+pub use osc::{change_files, changed_ways, read_osc, OsmChange};
+
+/// Highway types that can be desert tracks
+const TRACK_HIGHWAYS: [&str; 3] = ["track", "path", "unclassified"];
+
+/// Tags that decide whether a way is imported and how
+pub const TRACK_TAGS: [&str; 3] = ["highway", "surface", "tracktype"];
+
+/// Track grades that are not paved or near-paved
+const UNPAVED_TRACKTYPES: [&str; 4] = ["grade2", "grade3", "grade4", "grade5"];
+
+/// Confidence of an OSM way with a recognised `surface` tag
+///
+/// Below rally (5) and curated (4) tracks: OSM tags are rarely checked on
+/// the ground in the Sahara.
+pub const OSM_SURFACE_CONFIDENCE: i32 = 3;
+
+/// Confidence of an OSM way known only by its `tracktype`
+pub const OSM_TRACKTYPE_CONFIDENCE: i32 = 2;
+
+/// `required_features` the importer understands
+///
+/// History files are rejected: they hold every version of each entity, and
+/// the importer would take old versions for current ones.
+const SUPPORTED_FEATURES: [&str; 2] = ["OsmSchema-V0.6", "DenseNodes"];
+
+/// Why a PBF file could not be read
+#[derive(Debug, Error)]
+pub enum PbfError {
+    #[error("failed to read PBF file: {0}")]
+    Read(#[from] osmpbf::Error),
+    #[error("PBF file requires unsupported feature {0}")]
+    UnsupportedFeature(String),
+}
+
+/// A way with its tags and node references, in order
+#[derive(Debug, Clone, PartialEq)]
+pub struct OsmWay {
+    pub id: i64,
+    pub tags: HashMap<String, String>,
+    pub refs: Vec<i64>,
+}
+
+/// A relevant way reduced to what the importer keeps
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackWay {
+    pub id: i64,
+    pub refs: Vec<i64>,
+    /// Only the `TRACK_TAGS`
+    pub tags: HashMap<String, String>,
+    pub surface: Option<&'static str>,
+    pub confidence: i32,
+}
+
+impl TrackWay {
+    /// `None` if the way is not a track worth importing
+    pub fn from_way(way: OsmWay) -> Option<Self> {
+        if !is_relevant_way(&way.tags) {
+            return None;
+        }
+        Some(Self {
+            id: way.id,
+            surface: track_surface(&way.tags),
+            confidence: way_confidence(&way.tags),
+            tags: way
+                .tags
+                .into_iter()
+                .filter(|(k, _)| TRACK_TAGS.contains(&k.as_str()))
+                .collect(),
+            refs: way.refs,
+        })
+    }
+}
+
+/// Decode the data blocks of a PBF file one at a time, in file order
+///
+/// Memory use is bounded by one block however large the extract. Returns
+/// the replication sequence number from the file header.
+fn for_each_block<R: Read + Send>(
+    reader: BlobReader<R>,
+    mut f: impl FnMut(&PrimitiveBlock),
+) -> Result<Option<i64>, PbfError> {
+    let mut sequence = None;
+    for blob in reader {
+        match blob?.decode()? {
+            BlobDecode::OsmHeader(header) => {
+                if let Some(feature) = header
+                    .required_features()
+                    .iter()
+                    .find(|f| !SUPPORTED_FEATURES.contains(&f.as_str()))
+                {
+                    return Err(PbfError::UnsupportedFeature(feature.clone()));
+                }
+                sequence = header.osmosis_replication_sequence_number();
+            }
+            BlobDecode::OsmData(block) => f(&block),
+            BlobDecode::Unknown(_) => {}
+        }
+    }
+    Ok(sequence)
+}
+
+/// First pass over an extract: the relevant ways, and the replication
+/// sequence number the extract was cut at
+pub fn read_track_ways<R: Read + Send>(
+    reader: BlobReader<R>,
+) -> Result<(Vec<TrackWay>, Option<i64>), PbfError> {
+    let mut ways = Vec::new();
+    let sequence = for_each_block(reader, |block| {
+        for group in block.groups() {
+            ways.extend(group.ways().filter_map(|way| {
+                TrackWay::from_way(OsmWay {
+                    id: way.id(),
+                    tags: way
+                        .tags()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect(),
+                    refs: way.refs().collect(),
+                })
+            }));
+        }
+    })?;
+    Ok((ways, sequence))
+}
+
+/// Second pass: positions of the nodes in `wanted`, ignoring all others
+///
+/// Together with `read_track_ways` this keeps memory proportional to the
+/// tracks being imported rather than to the whole extract.
+pub fn read_node_positions<R: Read + Send>(
+    reader: BlobReader<R>,
+    wanted: &HashSet<i64>,
+) -> Result<HashMap<i64, (f64, f64)>, PbfError> {
+    let mut positions = HashMap::with_capacity(wanted.len());
+    for_each_block(reader, |block| {
+        block.for_each_element(|element| {
+            let (id, position) = match element {
+                Element::Node(node) => (node.id(), (node.lon(), node.lat())),
+                Element::DenseNode(node) => (node.id(), (node.lon(), node.lat())),
+                _ => return,
+            };
+            if wanted.contains(&id) {
+                positions.insert(id, position);
+            }
+        });
+    })?;
+    Ok(positions)
+}
+
+/// `curated_tracks.surface` for an OSM `surface` value
+///
+/// OSM's many unpaved values collapse onto the planner's vocabulary; paved
+/// and unknown values give `None`.
+pub fn track_surface(tags: &HashMap<String, String>) -> Option<&'static str> {
+    match tags.get("surface")?.as_str() {
+        "sand" => Some("sand"),
+        "gravel" | "fine_gravel" | "pebblestone" | "compacted" => Some("gravel"),
+        "dirt" | "earth" | "ground" | "mud" | "clay" => Some("dirt"),
+        "rock" | "stone" => Some("rock"),
+        "salt" => Some("salt"),
+        "unpaved" => Some("unpaved"),
+        _ => None,
+    }
+}
+
+/// Whether a way is a track worth importing
+///
+/// Filters for:
+/// - highway=track, path, unclassified
+/// - an unpaved surface (sand, gravel, dirt, ...)
+/// - or tracktype=grade2-5
+pub fn is_relevant_way(tags: &HashMap<String, String>) -> bool {
+    let Some(highway) = tags.get("highway") else {
+        return false;
+    };
+    if !TRACK_HIGHWAYS.contains(&highway.as_str()) {
+        return false;
+    }
+
+    track_surface(tags).is_some()
+        || tags
+            .get("tracktype")
+            .is_some_and(|t| UNPAVED_TRACKTYPES.contains(&t.as_str()))
+}
+
+/// Confidence for a relevant way: a surface tag says more than a grade
+pub fn way_confidence(tags: &HashMap<String, String>) -> i32 {
+    if track_surface(tags).is_some() {
+        OSM_SURFACE_CONFIDENCE
+    } else {
+        OSM_TRACKTYPE_CONFIDENCE
+    }
+}
+
+/// Positions of a way's nodes, `[lng, lat]`
+///
+/// Nodes missing from the extract (ways clipped at its border) and repeated
+/// positions are skipped; `None` if fewer than two positions remain.
+pub fn way_positions(refs: &[i64], nodes: &HashMap<i64, (f64, f64)>) -> Option<Vec<[f64; 2]>> {
+    let mut positions: Vec<[f64; 2]> = Vec::with_capacity(refs.len());
+    for (lng, lat) in refs.iter().filter_map(|id| nodes.get(id)) {
+        if positions.last() != Some(&[*lng, *lat]) {
+            positions.push([*lng, *lat]);
+        }
+    }
+
+    (positions.len() >= 2).then_some(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn put_varint(out: &mut Vec<u8>, mut v: u64) {
+        while v >= 0x80 {
+            out.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        out.push(v as u8);
+    }
+
+    fn varint_field(out: &mut Vec<u8>, number: u32, v: u64) {
+        put_varint(out, u64::from(number) << 3);
+        put_varint(out, v);
+    }
+
+    fn bytes_field(out: &mut Vec<u8>, number: u32, data: &[u8]) {
+        put_varint(out, (u64::from(number) << 3) | 2);
+        put_varint(out, data.len() as u64);
+        out.extend_from_slice(data);
+    }
+
+    fn sint(v: i64) -> u64 {
+        ((v << 1) ^ (v >> 63)) as u64
+    }
+
+    fn packed(values: impl IntoIterator<Item = u64>) -> Vec<u8> {
+        let mut out = Vec::new();
+        for v in values {
+            put_varint(&mut out, v);
+        }
+        out
+    }
+
+    fn deltas(values: &[i64]) -> Vec<u64> {
+        let mut previous = 0;
+        values
+            .iter()
+            .map(|v| {
+                let delta = sint(v - previous);
+                previous = *v;
+                delta
+            })
+            .collect()
+    }
+
+    /// Length-prefixed blob header and blob, zlib-compressed unless `raw`
+    fn blob(kind: &str, payload: &[u8], raw: bool) -> Vec<u8> {
+        let mut blob = Vec::new();
+        if raw {
+            bytes_field(&mut blob, 1, payload);
+        } else {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(payload).unwrap();
+            varint_field(&mut blob, 2, payload.len() as u64);
+            bytes_field(&mut blob, 3, &encoder.finish().unwrap());
+        }
+
+        let mut header = Vec::new();
+        bytes_field(&mut header, 1, kind.as_bytes());
+        varint_field(&mut header, 3, blob.len() as u64);
+
+        let mut out = (header.len() as u32).to_be_bytes().to_vec();
+        out.extend(header);
+        out.extend(blob);
+        out
+    }
+
+    fn header_block(features: &[&str]) -> Vec<u8> {
+        let mut block = Vec::new();
+        for feature in features {
+            bytes_field(&mut block, 4, feature.as_bytes());
+        }
+        varint_field(&mut block, 33, 4123);
+        block
+    }
+
+    /// Three dense nodes and a sandy track through them
+    fn data_block() -> Vec<u8> {
+        let mut strings = Vec::new();
+        for s in ["", "highway", "track", "surface", "sand"] {
+            bytes_field(&mut strings, 1, s.as_bytes());
+        }
+
+        // Coordinates in units of granularity (100 nanodegrees)
+        let mut dense = Vec::new();
+        bytes_field(&mut dense, 1, &packed(deltas(&[10, 11, 12])));
+        bytes_field(
+            &mut dense,
+            8,
+            &packed(deltas(&[200_000_000, 200_500_000, 201_000_000])),
+        );
+        bytes_field(
+            &mut dense,
+            9,
+            &packed(deltas(&[-50_000_000, -50_250_000, -51_000_000])),
+        );
+
+        let mut way = Vec::new();
+        varint_field(&mut way, 1, 7);
+        bytes_field(&mut way, 2, &packed([1, 3]));
+        bytes_field(&mut way, 3, &packed([2, 4]));
+        bytes_field(&mut way, 8, &packed(deltas(&[10, 11, 12])));
+
+        let mut nodes_group = Vec::new();
+        bytes_field(&mut nodes_group, 2, &dense);
+        let mut ways_group = Vec::new();
+        bytes_field(&mut ways_group, 3, &way);
+
+        let mut block = Vec::new();
+        bytes_field(&mut block, 1, &strings);
+        bytes_field(&mut block, 2, &nodes_group);
+        bytes_field(&mut block, 2, &ways_group);
+        block
+    }
+
+    /// A small extract, its blobs zlib-compressed unless `raw`
+    fn file(raw: bool) -> Vec<u8> {
+        let mut file = blob(
+            "OSMHeader",
+            &header_block(&["OsmSchema-V0.6", "DenseNodes"]),
+            raw,
+        );
+        file.extend(blob("OSMData", &data_block(), raw));
+        file
+    }
+
+    fn tags(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_relevant_ways() {
+        assert!(is_relevant_way(&tags(&[
+            ("highway", "track"),
+            ("surface", "sand")
+        ])));
+        assert!(is_relevant_way(&tags(&[
+            ("highway", "unclassified"),
+            ("tracktype", "grade3")
+        ])));
+        // Paved, graded-smooth, or not a track at all
+        assert!(!is_relevant_way(&tags(&[
+            ("highway", "track"),
+            ("surface", "asphalt")
+        ])));
+        assert!(!is_relevant_way(&tags(&[
+            ("highway", "track"),
+            ("tracktype", "grade1")
+        ])));
+        assert!(!is_relevant_way(&tags(&[
+            ("highway", "primary"),
+            ("surface", "sand")
+        ])));
+        assert!(!is_relevant_way(&tags(&[("surface", "sand")])));
+    }
+
+    #[test]
+    fn test_surface_and_confidence() {
+        let graded = tags(&[("highway", "track"), ("surface", "compacted")]);
+        assert_eq!(track_surface(&graded), Some("gravel"));
+        assert_eq!(way_confidence(&graded), OSM_SURFACE_CONFIDENCE);
+
+        let grade_only = tags(&[("highway", "track"), ("tracktype", "grade4")]);
+        assert_eq!(track_surface(&grade_only), None);
+        assert_eq!(way_confidence(&grade_only), OSM_TRACKTYPE_CONFIDENCE);
+    }
+
+    #[test]
+    fn test_way_positions_skip_missing_and_repeated_nodes() {
+        let nodes: HashMap<i64, (f64, f64)> =
+            [(1, (-5.0, 20.0)), (2, (-5.0, 20.0)), (3, (-5.1, 20.1))]
+                .into_iter()
+                .collect();
+
+        assert_eq!(
+            way_positions(&[1, 2, 99, 3], &nodes),
+            Some(vec![[-5.0, 20.0], [-5.1, 20.1]])
+        );
+        assert_eq!(way_positions(&[1, 2, 99], &nodes), None);
+    }
+
+    #[test]
+    fn test_two_passes_read_ways_then_their_nodes() {
+        for raw in [false, true] {
+            let data = file(raw);
+            let (ways, sequence) = read_track_ways(BlobReader::new(data.as_slice())).unwrap();
+            assert_eq!(sequence, Some(4123));
+            assert_eq!(ways.len(), 1);
+            assert_eq!(ways[0].id, 7);
+            assert_eq!(ways[0].refs, vec![10, 11, 12]);
+            assert_eq!(ways[0].surface, Some("sand"));
+
+            let wanted = HashSet::from([10, 11]);
+            let nodes = read_node_positions(BlobReader::new(data.as_slice()), &wanted).unwrap();
+            assert_eq!(nodes.len(), 2);
+            assert_eq!(nodes[&11], (-5.025, 20.05));
+        }
+    }
+
+    #[test]
+    fn test_rejects_unsupported_features_and_truncation() {
+        for feature in ["Sort.Type_then_ID", "HistoricalInformation"] {
+            let mut unsupported = blob(
+                "OSMHeader",
+                &header_block(&["OsmSchema-V0.6", "DenseNodes", feature]),
+                false,
+            );
+            unsupported.extend(blob("OSMData", &data_block(), false));
+            assert!(matches!(
+                read_track_ways(BlobReader::new(unsupported.as_slice())),
+                Err(PbfError::UnsupportedFeature(f)) if f == feature
+            ));
+        }
+
+        let full = file(false);
+        let truncated = &full[..full.len() - 10];
+        assert!(read_track_ways(BlobReader::new(truncated)).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+//! OSM change files (`.osc`, `.osc.gz`)
+//!
+//! Replication diffs list created, modified and deleted elements in
+//! osmChange XML. Only nodes and ways are read; the net effect of a diff is
+//! the last state it gives each element. Directories of diffs follow the
+//! replication layout (`000/004/123.osc.gz` is sequence 4123) or are flat
+//! (`4123.osc.gz`).
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Result};
+use flate2::read::GzDecoder;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::osm::{OsmWay, TrackWay};
+
+/// Net effect of a change file
+#[derive(Debug, Default, PartialEq)]
+pub struct OsmChange {
+    /// New node positions `(lng, lat)`; `None` for deleted nodes
+    pub nodes: HashMap<i64, Option<(f64, f64)>>,
+    /// New way states; `None` for deleted ways
+    pub ways: HashMap<i64, Option<OsmWay>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+    Create,
+    Modify,
+    Delete,
+}
+
+fn string_attr(element: &BytesStart, name: &str) -> Result<String> {
+    let attr = element.try_get_attribute(name)?.ok_or_else(|| {
+        anyhow!(
+            "<{}> missing '{}' attribute",
+            String::from_utf8_lossy(element.local_name().as_ref()),
+            name
+        )
+    })?;
+    Ok(attr.unescape_value()?.into_owned())
+}
+
+fn attr<T: FromStr>(element: &BytesStart, name: &str) -> Result<T> {
+    let value = string_attr(element, name)?;
+    value
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("Invalid {} value '{}'", name, value))
+}
+
+fn node_change(action: Action, element: &BytesStart) -> Result<(i64, Option<(f64, f64)>)> {
+    let id = attr(element, "id")?;
+    if action == Action::Delete {
+        return Ok((id, None));
+    }
+
+    let lng: f64 = attr(element, "lon")?;
+    let lat: f64 = attr(element, "lat")?;
+    if !(-180.0..=180.0).contains(&lng) || !(-90.0..=90.0).contains(&lat) {
+        return Err(anyhow!("Node {} has an invalid position", id));
+    }
+    Ok((id, Some((lng, lat))))
+}
+
+/// Parse an osmChange document
+pub fn parse_osc<R: BufRead>(input: R) -> Result<OsmChange> {
+    let mut reader = Reader::from_reader(input);
+    reader.config_mut().trim_text(true);
+
+    let mut change = OsmChange::default();
+    let mut action = None;
+    // Way being read, with the action it appeared under
+    let mut way: Option<(Action, OsmWay)> = None;
+    let mut buf = Vec::new();
+
+    loop {
+        buf.clear();
+        let (element, empty) = match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => (e, false),
+            Event::Empty(e) => (e, true),
+            Event::End(e) => {
+                match e.local_name().as_ref() {
+                    b"create" | b"modify" | b"delete" => action = None,
+                    b"way" => {
+                        if let Some((action, way)) = way.take() {
+                            change
+                                .ways
+                                .insert(way.id, (action != Action::Delete).then_some(way));
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        match element.local_name().as_ref() {
+            b"create" => action = Some(Action::Create),
+            b"modify" => action = Some(Action::Modify),
+            b"delete" => action = Some(Action::Delete),
+            b"node" => {
+                let action = action.ok_or_else(|| anyhow!("<node> outside an action"))?;
+                let (id, position) = node_change(action, &element)?;
+                change.nodes.insert(id, position);
+            }
+            b"way" => {
+                let action = action.ok_or_else(|| anyhow!("<way> outside an action"))?;
+                let read = OsmWay {
+                    id: attr(&element, "id")?,
+                    tags: HashMap::new(),
+                    refs: Vec::new(),
+                };
+                if empty {
+                    change
+                        .ways
+                        .insert(read.id, (action != Action::Delete).then_some(read));
+                } else {
+                    way = Some((action, read));
+                }
+            }
+            // Node and relation children are not needed
+            b"nd" => {
+                if let Some((_, way)) = way.as_mut() {
+                    way.refs.push(attr(&element, "ref")?);
+                }
+            }
+            b"tag" => {
+                if let Some((_, way)) = way.as_mut() {
+                    way.tags
+                        .insert(string_attr(&element, "k")?, string_attr(&element, "v")?);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(change)
+}
+
+/// Read a change file, gunzipping `.gz` files
+pub fn read_osc(path: &Path) -> Result<OsmChange> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let input: Box<dyn Read> = if path.extension().is_some_and(|e| e == "gz") {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    parse_osc(BufReader::new(input)).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Replication sequence number of a change file, from its path below the
+/// change directory
+fn sequence_number(relative: &Path) -> Option<i64> {
+    let name = relative.file_name()?.to_str()?;
+    let stem = name
+        .strip_suffix(".osc.gz")
+        .or_else(|| name.strip_suffix(".osc"))?;
+
+    let mut digits = String::new();
+    let directories = relative
+        .parent()
+        .into_iter()
+        .flat_map(Path::components)
+        .map(|c| c.as_os_str().to_str());
+    for part in directories.chain([Some(stem)]) {
+        let part = part?;
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits.push_str(part);
+    }
+    digits.parse().ok()
+}
+
+/// Change files below `dir` with their sequence numbers, oldest first
+///
+/// Files whose path is not a sequence number are skipped with a warning.
+pub fn change_files(dir: &Path) -> Result<Vec<(i64, PathBuf)>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let entries = fs::read_dir(&current)
+            .with_context(|| format!("Failed to list {}", current.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            if !(name.ends_with(".osc") || name.ends_with(".osc.gz")) {
+                continue;
+            }
+            match path.strip_prefix(dir).ok().and_then(sequence_number) {
+                Some(sequence) => files.push((sequence, path)),
+                None => tracing::warn!(
+                    "Skipping change file without a sequence number: {}",
+                    path.display()
+                ),
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Imported ways a change affects, in their new state
+///
+/// `moved` are the stored ways that use a node the change creates, moves or
+/// deletes. Returns the relevant ways to (re)load and the ids of ways that
+/// were deleted or are no longer relevant.
+pub fn changed_ways(change: &OsmChange, moved: Vec<OsmWay>) -> (Vec<TrackWay>, Vec<i64>) {
+    let mut load = Vec::new();
+    let mut removed = Vec::new();
+    for (id, way) in &change.ways {
+        match way.clone().and_then(TrackWay::from_way) {
+            Some(way) => load.push(way),
+            None => removed.push(*id),
+        }
+    }
+
+    // Ways the diff leaves alone keep their stored refs and tags
+    load.extend(
+        moved
+            .into_iter()
+            .filter(|way| !change.ways.contains_key(&way.id))
+            .filter_map(TrackWay::from_way),
+    );
+
+    load.sort_by_key(|way| way.id);
+    removed.sort_unstable();
+    (load, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osmChange version="0.6" generator="osmium">
+  <create>
+    <node id="10" version="1" lat="20.0" lon="-5.0"/>
+    <node id="11" version="1" lat="20.1" lon="-5.1">
+      <tag k="natural" v="spring"/>
+    </node>
+  </create>
+  <modify>
+    <way id="7" version="3">
+      <nd ref="10"/>
+      <nd ref="11"/>
+      <tag k="highway" v="track"/>
+      <tag k="surface" v="sand"/>
+      <tag k="name" v="Piste &amp; puits"/>
+    </way>
+    <way id="8" version="2">
+      <nd ref="12"/>
+      <nd ref="13"/>
+      <tag k="highway" v="residential"/>
+    </way>
+    <relation id="1" version="2">
+      <member type="way" ref="7" role=""/>
+      <tag k="type" v="route"/>
+    </relation>
+  </modify>
+  <delete>
+    <way id="9" version="4"/>
+    <node id="12" version="2"/>
+  </delete>
+</osmChange>"#;
+
+    #[test]
+    fn test_parse_osc() {
+        let change = parse_osc(DIFF.as_bytes()).unwrap();
+
+        assert_eq!(change.nodes.get(&10), Some(&Some((-5.0, 20.0))));
+        assert_eq!(change.nodes.get(&12), Some(&None));
+
+        let way = change.ways[&7].as_ref().unwrap();
+        assert_eq!(way.refs, vec![10, 11]);
+        assert_eq!(way.tags["name"], "Piste & puits");
+        // Relation tags are not taken for the last way
+        assert!(!way.tags.contains_key("type"));
+        assert_eq!(change.ways.get(&9), Some(&None));
+    }
+
+    #[test]
+    fn test_changed_ways() {
+        let change = parse_osc(DIFF.as_bytes()).unwrap();
+        let moved = vec![
+            OsmWay {
+                id: 5,
+                tags: [("highway", "track"), ("tracktype", "grade3")]
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                refs: vec![12, 14],
+            },
+            // Replaced by the diff's own state
+            OsmWay {
+                id: 7,
+                tags: HashMap::new(),
+                refs: vec![1, 2],
+            },
+        ];
+
+        let (load, removed) = changed_ways(&change, moved);
+        assert_eq!(load.iter().map(|w| w.id).collect::<Vec<_>>(), vec![5, 7]);
+        assert_eq!(load[1].refs, vec![10, 11]);
+        assert!(!load[1].tags.contains_key("name"));
+        // No longer a track, and deleted
+        assert_eq!(removed, vec![8, 9]);
+    }
+
+    #[test]
+    fn test_sequence_numbers() {
+        assert_eq!(sequence_number(Path::new("000/004/123.osc.gz")), Some(4123));
+        assert_eq!(sequence_number(Path::new("4123.osc")), Some(4123));
+        assert_eq!(sequence_number(Path::new("000/004/123.state.txt")), None);
+        assert_eq!(sequence_number(Path::new("latest/123.osc.gz")), None);
+    }
+}
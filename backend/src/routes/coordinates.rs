@@ -0,0 +1,45 @@
+use axum::{extract::Query, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::coordinates::{format_coordinate, parse_coordinate, CoordinateFormat};
+use crate::geometry::types::Position;
+use crate::routes::error::ApiError;
+
+#[derive(Debug, Deserialize)]
+pub struct ConvertQuery {
+    /// Coordinate text in any supported notation
+    pub q: String,
+}
+
+/// A coordinate in every notation; UTM and MGRS are absent near the poles
+#[derive(Debug, Serialize)]
+pub struct ConvertedCoordinate {
+    pub position: Position,
+    /// Notation the input was recognized as
+    pub input_format: CoordinateFormat,
+    pub dd: String,
+    pub dms: String,
+    pub ddm: String,
+    pub utm: Option<String>,
+    pub mgrs: Option<String>,
+}
+
+/// Parse a coordinate and render it in every notation (public endpoint)
+///
+/// Invalid or ambiguous input is rejected with 422 and the reason.
+pub async fn convert_coordinate(
+    Query(query): Query<ConvertQuery>,
+) -> Result<Json<ConvertedCoordinate>, ApiError> {
+    let parsed = parse_coordinate(&query.q)?;
+    let position = parsed.position;
+
+    Ok(Json(ConvertedCoordinate {
+        position,
+        input_format: parsed.format,
+        dd: format_coordinate(position, CoordinateFormat::Dd)?,
+        dms: format_coordinate(position, CoordinateFormat::Dms)?,
+        ddm: format_coordinate(position, CoordinateFormat::Ddm)?,
+        utm: format_coordinate(position, CoordinateFormat::Utm).ok(),
+        mgrs: format_coordinate(position, CoordinateFormat::Mgrs).ok(),
+    }))
+}
@@ -8,11 +8,15 @@ use serde_json::Value;
 use uuid::Uuid;
 
 use crate::db::RlsTransaction;
+use crate::geometry::lod::VersionGeometry;
+use crate::geometry::types::{ControlPoint, Position};
 use crate::middleware::AuthUser;
 use crate::models::{
     CreateEditingSession, CreatePointChange, EditingSession, EditingSessionInfo,
     EditingSessionResponse, PointChange, UpdatePointChangeStatus,
 };
+use crate::routes::error::is_permission_denied;
+use crate::routes::route_handlers::insert_route_version;
 use crate::AppState;
 
 // ============================================
@@ -249,15 +253,19 @@ pub async fn create_point_change(
             original_position, new_position
         )
         VALUES ($1, $2, $3, $4, $5, $6, $7)
-        RETURNING *
+        RETURNING
+            id, route_id, user_id, user_email, feature_index, point_index,
+            original_position as "original_position: sqlx::types::Json<Position>",
+            new_position as "new_position: sqlx::types::Json<Position>",
+            status, created_at, updated_at, resolved_at, resolved_by
         "#,
         route_id,
         user_id,
         session.user_email,
         payload.feature_index,
         payload.point_index,
-        payload.original_position,
-        payload.new_position
+        sqlx::types::Json(payload.original_position) as _,
+        sqlx::types::Json(payload.new_position) as _
     )
     .fetch_one(&mut **tx)
     .await
@@ -296,7 +304,11 @@ pub async fn list_point_changes(
     let changes = sqlx::query_as!(
         PointChange,
         r#"
-        SELECT *
+        SELECT
+            id, route_id, user_id, user_email, feature_index, point_index,
+            original_position as "original_position: sqlx::types::Json<Position>",
+            new_position as "new_position: sqlx::types::Json<Position>",
+            status, created_at, updated_at, resolved_at, resolved_by
         FROM route_point_changes
         WHERE route_id = $1 AND status = $2
         ORDER BY created_at DESC
@@ -345,7 +357,15 @@ pub async fn update_point_change_status(
     // RLS policy "Users read point changes" allows seeing own changes or changes for owned routes
     let change = sqlx::query_as!(
         PointChange,
-        "SELECT * FROM route_point_changes WHERE id = $1",
+        r#"
+        SELECT
+            id, route_id, user_id, user_email, feature_index, point_index,
+            original_position as "original_position: sqlx::types::Json<Position>",
+            new_position as "new_position: sqlx::types::Json<Position>",
+            status, created_at, updated_at, resolved_at, resolved_by
+        FROM route_point_changes
+        WHERE id = $1
+        "#,
         change_id
     )
     .fetch_one(&mut **tx)
@@ -369,7 +389,9 @@ pub async fn update_point_change_status(
     if payload.status == "accepted" {
         // Fetch current geometry
         let current_version = sqlx::query!(
-            "SELECT geometry FROM route_versions WHERE route_id = $1 ORDER BY created_at DESC LIMIT 1",
+            r#"SELECT rv.geometry, r.control_points as "control_points: sqlx::types::Json<Vec<ControlPoint>>"
+            FROM route_versions rv JOIN routes r ON r.id = rv.route_id
+            WHERE rv.route_id = $1 ORDER BY rv.created_at DESC LIMIT 1"#,
             change.route_id
         )
         .fetch_one(&mut **tx)
@@ -391,31 +413,32 @@ pub async fn update_point_change_status(
                 StatusCode::BAD_REQUEST
             })?;
 
-        *coords = change.new_position.clone();
+        *coords = serde_json::json!(change.new_position);
+
+        // Create new route version; the edit applies to the stored display
+        // geometry, so it replaces the full-resolution line
+        let version = VersionGeometry::from_geometry(geometry).map_err(|e| {
+            tracing::error!("Failed to build route version geometry: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
 
-        // Create new route version
         // RLS policy "Owner create route versions" ensures only owner can create versions
-        sqlx::query!(
-            "INSERT INTO route_versions (route_id, geometry, created_by) VALUES ($1, $2, $3)",
+        insert_route_version(
+            &mut tx,
             change.route_id,
-            geometry,
-            user_id
+            &current_version.control_points,
+            &version,
+            user_id,
         )
-        .execute(&mut **tx)
-        .await
+            .await
         .map_err(|e| {
-            // Check if error is due to RLS policy violation
-            if let sqlx::Error::Database(ref db_err) = e {
-                if db_err.message().contains("permission denied")
-                    || db_err.message().contains("violates row-level security policy")
-                {
-                    tracing::warn!(
-                        "User {} attempted to accept point change for route {} (permission denied by RLS)",
-                        user_id,
-                        change.route_id
-                    );
-                    return StatusCode::FORBIDDEN;
-                }
+            if is_permission_denied(&e) {
+                tracing::warn!(
+                    "User {} attempted to accept point change for route {} (permission denied by RLS)",
+                    user_id,
+                    change.route_id
+                );
+                return StatusCode::FORBIDDEN;
             }
 
             tracing::error!("Failed to create route version: {}", e);
@@ -437,44 +460,32 @@ pub async fn update_point_change_status(
 
         // Update control_points to match the new geometry
         // Fetch current control points
-        let route = sqlx::query!(
-            "SELECT control_points FROM routes WHERE id = $1",
+        let sqlx::types::Json(mut control_points) = sqlx::query_scalar!(
+            r#"SELECT control_points as "control_points: sqlx::types::Json<Vec<ControlPoint>>" FROM routes WHERE id = $1"#,
             change.route_id
         )
-        .fetch_one(&mut **tx)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to fetch route for control points update: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
-
-        // Update control points with new position
-        let mut control_points_value = route.control_points;
-        if let Some(control_points) = control_points_value.as_array_mut() {
-            if let Some(point) = control_points.get_mut(change.point_index as usize) {
-                // Update the point with new position
-                // Convert [lng, lat] array to {lng, lat} object format
-                if let Some(coords) = change.new_position.as_array() {
-                    if coords.len() == 2 {
-                        *point = serde_json::json!({
-                            "lng": coords[0],
-                            "lat": coords[1]
-                        });
-                    }
-                }
-            }
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to fetch route for control points update: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
 
-            // Save updated control_points back to database
-            sqlx::query("UPDATE routes SET control_points = $1 WHERE id = $2")
-                .bind(&control_points_value)
-                .bind(change.route_id)
-                .execute(&mut **tx)
-                .await
-                .map_err(|e| {
-                    tracing::error!("Failed to update control points: {}", e);
-                    StatusCode::INTERNAL_SERVER_ERROR
-                })?;
+        // Move the control point, keeping its waypoint fields
+        if let Some(point) = control_points.get_mut(change.point_index as usize) {
+            point.position = *change.new_position;
         }
+
+        // Save updated control_points back to database
+        sqlx::query("UPDATE routes SET control_points = $1 WHERE id = $2")
+            .bind(sqlx::types::Json(&control_points))
+            .bind(change.route_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to update control points: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
     }
 
     // Update change status
@@ -485,7 +496,11 @@ pub async fn update_point_change_status(
         UPDATE route_point_changes
         SET status = $1, resolved_at = NOW(), resolved_by = $2
         WHERE id = $3
-        RETURNING *
+        RETURNING
+            id, route_id, user_id, user_email, feature_index, point_index,
+            original_position as "original_position: sqlx::types::Json<Position>",
+            new_position as "new_position: sqlx::types::Json<Position>",
+            status, created_at, updated_at, resolved_at, resolved_by
         "#,
         payload.status,
         user_id,
@@ -0,0 +1,87 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::geometry::coordinates::CoordinateError;
+use crate::geometry::validate::GeometryValidationError;
+
+/// Whether a database error is Postgres refusing the write: a missing
+/// privilege or a row-level security policy rejecting the row
+///
+/// Handlers map it to 403 instead of a server error.
+pub fn is_permission_denied(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Database(db_err) => {
+            db_err.message().contains("permission denied")
+                || db_err
+                    .message()
+                    .contains("violates row-level security policy")
+        }
+        _ => false,
+    }
+}
+
+/// Handler error that can carry a structured body
+///
+/// Most failures are a bare status code; `?` on a `StatusCode` error converts
+/// automatically. Rejected geometry is returned as 422 with every issue found,
+/// rejected coordinate text as 422 with the reason.
+#[derive(Debug)]
+pub enum ApiError {
+    Status(StatusCode),
+    InvalidGeometry(GeometryValidationError),
+    InvalidCoordinate(CoordinateError),
+}
+
+impl From<StatusCode> for ApiError {
+    fn from(status: StatusCode) -> Self {
+        Self::Status(status)
+    }
+}
+
+impl From<GeometryValidationError> for ApiError {
+    fn from(error: GeometryValidationError) -> Self {
+        Self::InvalidGeometry(error)
+    }
+}
+
+impl From<CoordinateError> for ApiError {
+    fn from(error: CoordinateError) -> Self {
+        Self::InvalidCoordinate(error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Status(status) => status.into_response(),
+            Self::InvalidGeometry(error) => {
+                tracing::warn!("{}", error);
+                (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(serde_json::json!({
+                        "error": "invalid_geometry",
+                        "issues": error.issues,
+                    })),
+                )
+                    .into_response()
+            }
+            Self::InvalidCoordinate(error) => {
+                let code = match error {
+                    CoordinateError::Invalid(_) => "invalid_coordinate",
+                    CoordinateError::Ambiguous(_) => "ambiguous_coordinate",
+                };
+                (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(serde_json::json!({
+                        "error": code,
+                        "message": error.to_string(),
+                    })),
+                )
+                    .into_response()
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::export::{route_to_geojson, route_to_gpx, ExportFormat};
+use crate::geometry::analyze_route;
+use crate::geometry::lod::GeometryResolution;
+use crate::models::ExportQuery;
+use crate::routes::pois::{pois_along_route, DEFAULT_ROUTE_POI_KM, MAX_ROUTE_POI_KM};
+use crate::routes::route_handlers::fetch_route;
+use crate::AppState;
+
+/// Export a route's latest geometry (public endpoint)
+///
+/// Supported formats: `gpx`, `geojson`. Waypoints carry magnetic headings
+/// (CAP) computed for `?date=YYYY-MM-DD` (defaults to today), and their
+/// position in `?coordinate_format=` when given. POIs within
+/// `?poi_within_km=` of the route (default 5, 0 for none) are included as
+/// extra waypoints in driving order.
+pub async fn export_route(
+    State(state): State<AppState>,
+    Path((id, format)): Path<(Uuid, String)>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, StatusCode> {
+    let format = ExportFormat::parse(&format).ok_or(StatusCode::NOT_FOUND)?;
+    let poi_within_km = query.poi_within_km.unwrap_or(DEFAULT_ROUTE_POI_KM);
+    if !(0.0..=MAX_ROUTE_POI_KM).contains(&poi_within_km) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let route = fetch_route(&state.pool, id, GeometryResolution::Full).await?;
+    let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+
+    let mut analysis =
+        analyze_route(&route.geometry, &route.route.control_points, date).map_err(|e| {
+            tracing::error!("Route analysis failed for export of {}: {}", id, e);
+            StatusCode::UNPROCESSABLE_ENTITY
+        })?;
+    if let Some(coordinate_format) = query.coordinate_format {
+        analysis = analysis.with_coordinate_format(coordinate_format);
+    }
+
+    let pois = if poi_within_km > 0.0 {
+        pois_along_route(&state.pool, &route.geometry, poi_within_km, &[]).await?
+    } else {
+        Vec::new()
+    };
+
+    let body = match format {
+        ExportFormat::Gpx => route_to_gpx(&route.route.name, &analysis, &pois),
+        ExportFormat::GeoJson => route_to_geojson(&route.route.name, &analysis, &pois).to_string(),
+    };
+
+    let disposition = format!(
+        "attachment; filename=\"route-{}.{}\"",
+        id,
+        format.extension()
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
@@ -0,0 +1,302 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde_json::Value;
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::geometry::linear_ref::LinearRoute;
+use crate::geometry::types::Position;
+use crate::middleware::AuthUser;
+use crate::models::{
+    CreateHazard, HazardQuery, HazardReport, RouteHazard, RouteHazardQuery, HAZARD_KINDS,
+    HAZARD_SEVERITIES,
+};
+use crate::routes::error::ApiError;
+use crate::routes::route_handlers::fetch_route_version_geometry;
+use crate::routes::tracks::{begin, commit, track_geometry, user_id, write_error};
+use crate::AppState;
+
+/// Columns of a `HazardReport`
+pub(crate) const HAZARD_COLUMNS: &str = "id, reported_by, kind, severity, description, \
+     ST_AsGeoJSON(geometry)::jsonb AS geometry, expires_at, resolved_by, resolved_at, created_at";
+
+/// Columns of a `HazardReport` read through the alias `h`
+pub(crate) const HAZARD_COLUMNS_H: &str =
+    "h.id, h.reported_by, h.kind, h.severity, h.description, \
+     ST_AsGeoJSON(h.geometry)::jsonb AS geometry, h.expires_at, h.resolved_by, h.resolved_at, \
+     h.created_at";
+
+/// Default corridor around a route in which hazards are flagged (meters)
+pub const DEFAULT_HAZARD_CORRIDOR_M: f64 = 1_000.0;
+
+/// Widest accepted hazard corridor (meters)
+const MAX_HAZARD_CORRIDOR_M: f64 = 50_000.0;
+
+/// How far ahead a report may expire
+const MAX_HAZARD_DAYS: i64 = 90;
+
+/// Most hazards per listing
+const MAX_HAZARDS: i64 = 1000;
+
+#[derive(FromRow)]
+struct CorridorRow {
+    #[sqlx(flatten)]
+    hazard: HazardReport,
+    distance_m: f64,
+    /// Point of the route closest to the hazard
+    lng: f64,
+    lat: f64,
+}
+
+fn fetch_error(what: &str, e: sqlx::Error) -> StatusCode {
+    tracing::error!("Failed to fetch {}: {}", what, e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+/// Severities at least as serious as `min`
+fn severities_from(min: &str) -> Option<Vec<String>> {
+    let start = HAZARD_SEVERITIES.iter().position(|s| *s == min)?;
+    Some(
+        HAZARD_SEVERITIES[start..]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+    )
+}
+
+/// Validate a hazard location, returning GeoJSON text for `ST_GeomFromGeoJSON`
+fn hazard_geometry(geometry: &Value) -> Result<String, ApiError> {
+    match geometry.get("type").and_then(|t| t.as_str()) {
+        Some("Point") => {
+            let position: Position = geometry
+                .get("coordinates")
+                .cloned()
+                .and_then(|c| serde_json::from_value(c).ok())
+                .ok_or(StatusCode::UNPROCESSABLE_ENTITY)?;
+            Ok(serde_json::json!({ "type": "Point", "coordinates": position }).to_string())
+        }
+        Some("LineString") => Ok(track_geometry(geometry, false)?),
+        _ => Err(StatusCode::UNPROCESSABLE_ENTITY.into()),
+    }
+}
+
+/// Active hazards within `corridor_m` of a route geometry, in driving order
+pub(crate) async fn hazards_along_route(
+    pool: &PgPool,
+    geometry: &Value,
+    corridor_m: f64,
+) -> Result<Vec<RouteHazard>, StatusCode> {
+    let route = LinearRoute::from_geometry(geometry).map_err(|e| {
+        tracing::warn!("Route has no usable geometry for hazards: {}", e);
+        StatusCode::UNPROCESSABLE_ENTITY
+    })?;
+
+    // Index prefilter in degrees, generous up to 60° latitude
+    let corridor_deg = corridor_m / 111_320.0 * 2.0;
+
+    let rows = sqlx::query_as::<_, CorridorRow>(&format!(
+        "WITH route AS (SELECT ST_SetSRID(ST_GeomFromGeoJSON($1), 4326) AS geometry)
+         SELECT {},
+                ST_Distance(h.geometry::geography, route.geometry::geography) AS distance_m,
+                ST_X(ST_ClosestPoint(route.geometry, h.geometry)) AS lng,
+                ST_Y(ST_ClosestPoint(route.geometry, h.geometry)) AS lat
+         FROM active_hazard_reports h, route
+         WHERE h.geometry && ST_Expand(route.geometry, $2)
+           AND ST_DWithin(h.geometry::geography, route.geometry::geography, $3)",
+        HAZARD_COLUMNS_H
+    ))
+    .bind(geometry.to_string())
+    .bind(corridor_deg)
+    .bind(corridor_m)
+    .persistent(false)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| fetch_error("hazards along route", e))?;
+
+    let mut hazards: Vec<RouteHazard> = rows
+        .into_iter()
+        .map(|row| RouteHazard {
+            km: route.locate(row.lng, row.lat).km,
+            distance_m: row.distance_m,
+            hazard: row.hazard,
+        })
+        .collect();
+    hazards.sort_by(|a, b| a.km.total_cmp(&b.km));
+
+    Ok(hazards)
+}
+
+/// Active hazards (public endpoint)
+pub async fn list_hazards(
+    State(state): State<AppState>,
+    Query(query): Query<HazardQuery>,
+) -> Result<Json<Vec<HazardReport>>, StatusCode> {
+    if query
+        .kind
+        .iter()
+        .any(|k| !HAZARD_KINDS.contains(&k.as_str()))
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let severities = query
+        .min_severity
+        .as_deref()
+        .map(|min| severities_from(min).ok_or(StatusCode::BAD_REQUEST))
+        .transpose()?;
+
+    let mut sql = QueryBuilder::<Postgres>::new(format!(
+        "SELECT {} FROM active_hazard_reports WHERE true",
+        HAZARD_COLUMNS
+    ));
+    if !query.kind.is_empty() {
+        sql.push(" AND kind = ANY(")
+            .push_bind(query.kind.clone())
+            .push(")");
+    }
+    if let Some(severities) = severities {
+        sql.push(" AND severity = ANY(")
+            .push_bind(severities)
+            .push(")");
+    }
+    if let Some(bbox) = query.bbox {
+        sql.push(" AND geometry && ST_MakeEnvelope(")
+            .push_bind(bbox.min_lng)
+            .push(", ")
+            .push_bind(bbox.min_lat)
+            .push(", ")
+            .push_bind(bbox.max_lng)
+            .push(", ")
+            .push_bind(bbox.max_lat)
+            .push(", 4326)");
+    }
+    sql.push(" ORDER BY created_at DESC LIMIT ")
+        .push_bind(MAX_HAZARDS);
+
+    let hazards = sql
+        .build_query_as::<HazardReport>()
+        .persistent(false)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| fetch_error("hazards", e))?;
+
+    Ok(Json(hazards))
+}
+
+/// One hazard report, active or not (public endpoint)
+pub async fn get_hazard(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<HazardReport>, StatusCode> {
+    let hazard = sqlx::query_as::<_, HazardReport>(&format!(
+        "SELECT {} FROM hazard_reports WHERE id = $1",
+        HAZARD_COLUMNS
+    ))
+    .bind(id)
+    .persistent(false)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| fetch_error("hazard", e))?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(hazard))
+}
+
+/// Report a hazard (requires authentication)
+pub async fn create_hazard(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Json(payload): Json<CreateHazard>,
+) -> Result<Json<HazardReport>, ApiError> {
+    let now = Utc::now();
+    if !HAZARD_KINDS.contains(&payload.kind.as_str())
+        || !HAZARD_SEVERITIES.contains(&payload.severity.as_str())
+        || payload.expires_at <= now
+        || payload.expires_at > now + Duration::days(MAX_HAZARD_DAYS)
+    {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY.into());
+    }
+    let geometry = hazard_geometry(&payload.geometry)?;
+
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    // RLS policy "Users report hazards" checks reported_by = auth.uid()
+    let hazard = sqlx::query_as::<_, HazardReport>(&format!(
+        "INSERT INTO hazard_reports (reported_by, kind, severity, description, geometry, expires_at)
+         VALUES ($1, $2, $3, $4, ST_SetSRID(ST_GeomFromGeoJSON($5), 4326), $6)
+         RETURNING {}",
+        HAZARD_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(&payload.kind)
+    .bind(&payload.severity)
+    .bind(&payload.description)
+    .bind(geometry)
+    .bind(payload.expires_at)
+    .persistent(false)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| write_error("report hazard", e))?;
+
+    commit(tx).await?;
+    tracing::info!(
+        "User {} reported {} {} hazard {}",
+        user_id,
+        hazard.severity,
+        hazard.kind,
+        hazard.id
+    );
+
+    Ok(Json(hazard))
+}
+
+/// Mark a hazard as no longer present (reporter or curators)
+pub async fn resolve_hazard(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<HazardReport>, StatusCode> {
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    // RLS limits the update to the reporter and curators; others see no row
+    let hazard = sqlx::query_as::<_, HazardReport>(&format!(
+        "UPDATE hazard_reports SET resolved_by = $2, resolved_at = NOW()
+         WHERE id = $1 AND resolved_at IS NULL
+         RETURNING {}",
+        HAZARD_COLUMNS
+    ))
+    .bind(id)
+    .bind(user_id)
+    .persistent(false)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| write_error("resolve hazard", e))?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    commit(tx).await?;
+    tracing::info!("User {} resolved hazard {}", user_id, id);
+
+    Ok(Json(hazard))
+}
+
+/// Active hazards near a route version in driving order (public endpoint)
+pub async fn list_route_hazards(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<RouteHazardQuery>,
+) -> Result<Json<Vec<RouteHazard>>, StatusCode> {
+    let corridor_m = query.corridor_m.unwrap_or(DEFAULT_HAZARD_CORRIDOR_M);
+    if !(0.0..=MAX_HAZARD_CORRIDOR_M).contains(&corridor_m) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let geometry = fetch_route_version_geometry(&state.pool, id, query.version_id).await?;
+    let hazards = hazards_along_route(&state.pool, &geometry, corridor_m).await?;
+
+    Ok(Json(hazards))
+}
@@ -0,0 +1,125 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::geometry::coordinates::display_coordinate;
+use crate::geometry::linear_ref::{to_linestring, KmMarker, LinearRoute, RouteLocation};
+use crate::models::{CutQuery, KmMarkersQuery, LocateQuery};
+use crate::routes::error::ApiError;
+use crate::routes::route_handlers::fetch_route_version_geometry;
+use crate::AppState;
+
+/// Upper bound on markers per request, to keep tiny intervals on long routes sane
+const MAX_MARKERS: f64 = 10_000.0;
+
+#[derive(Debug, Serialize)]
+pub struct KmMarkersResponse {
+    pub length_km: f64,
+    pub interval_km: f64,
+    pub markers: Vec<KmMarker>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CutResponse {
+    pub from_km: f64,
+    pub to_km: f64,
+    pub length_km: f64,
+    pub geometry: Value,
+}
+
+async fn load_linear_route(
+    state: &AppState,
+    route_id: Uuid,
+    version_id: Option<Uuid>,
+) -> Result<LinearRoute, StatusCode> {
+    let geometry = fetch_route_version_geometry(&state.pool, route_id, version_id).await?;
+
+    LinearRoute::from_geometry(&geometry).map_err(|e| {
+        tracing::warn!("Route {} has no usable geometry: {}", route_id, e);
+        StatusCode::UNPROCESSABLE_ENTITY
+    })
+}
+
+/// Km markers along a route version (public endpoint)
+///
+/// Returns a marker every `interval_km` from the start, plus the finish.
+pub async fn get_km_markers(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<KmMarkersQuery>,
+) -> Result<Json<KmMarkersResponse>, StatusCode> {
+    let interval_km = query.interval_km.unwrap_or(1.0);
+    let route = load_linear_route(&state, id, query.version_id).await?;
+
+    if !interval_km.is_finite()
+        || interval_km <= 0.0
+        || route.length_km() / interval_km > MAX_MARKERS
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut markers = route
+        .km_markers(interval_km)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if let Some(format) = query.coordinate_format {
+        for marker in &mut markers {
+            marker.coordinate = display_coordinate(marker.lng, marker.lat, format);
+        }
+    }
+
+    Ok(Json(KmMarkersResponse {
+        length_km: route.length_km(),
+        interval_km,
+        markers,
+    }))
+}
+
+/// Locate a coordinate along a route version (public endpoint)
+///
+/// Returns km-from-start, lateral offset and side of the closest route point.
+/// The coordinate may be given as text in any supported notation.
+pub async fn locate_on_route(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<LocateQuery>,
+) -> Result<Json<RouteLocation>, ApiError> {
+    let position = query.position()?;
+    let route = load_linear_route(&state, id, query.version_id).await?;
+
+    let mut location = route.locate(position.lng, position.lat);
+    if let Some(format) = query.coordinate_format {
+        let [lng, lat] = location.snapped;
+        location.snapped_coordinate = display_coordinate(lng, lat, format);
+    }
+
+    Ok(Json(location))
+}
+
+/// Cut a route version between two km positions (public endpoint)
+pub async fn cut_route(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<CutQuery>,
+) -> Result<Json<CutResponse>, StatusCode> {
+    if !query.from_km.is_finite() || !query.to_km.is_finite() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let route = load_linear_route(&state, id, query.version_id).await?;
+    let points = route.cut(query.from_km, query.to_km);
+    let length_km = LinearRoute::new(points.clone())
+        .map(|piece| piece.length_km())
+        .unwrap_or(0.0);
+
+    Ok(Json(CutResponse {
+        from_km: query.from_km.min(query.to_km).max(0.0),
+        to_km: query.from_km.max(query.to_km).min(route.length_km()),
+        length_km,
+        geometry: to_linestring(&points),
+    }))
+}
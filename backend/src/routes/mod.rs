@@ -1,19 +1,111 @@
+pub mod coordinates;
 pub mod editing;
+pub mod error;
+pub mod exports;
+pub mod hazards;
+pub mod linear_ref;
+pub mod pois;
 pub mod proposals;
+pub mod regions;
 pub mod route_handlers;
+pub mod route_operations;
+pub mod submissions;
+pub mod tiles;
+pub mod topology;
+pub mod trace_validation;
+pub mod track_merges;
 pub mod tracks;
 
 use crate::AppState;
 use axum::{
+    extract::DefaultBodyLimit,
     routing::{get, patch, post, put},
     Router,
 };
 
 pub fn api_routes() -> Router<AppState> {
     Router::new()
+        // Coordinates
+        .route("/coordinates", get(coordinates::convert_coordinate))
         // Tracks
-        .route("/tracks", get(tracks::list_tracks))
-        .route("/tracks/{id}", get(tracks::get_track))
+        .route(
+            "/tracks",
+            get(tracks::list_tracks).post(tracks::create_track),
+        )
+        .route("/tracks/bulk", post(tracks::bulk_tracks))
+        .route(
+            "/tracks/merge-proposals",
+            get(track_merges::list_merge_proposals),
+        )
+        .route(
+            "/tracks/merge-proposals/detect",
+            post(track_merges::detect_track_duplicates),
+        )
+        .route(
+            "/tracks/merge-proposals/{id}/merge",
+            post(track_merges::merge_tracks),
+        )
+        .route(
+            "/tracks/merge-proposals/{id}/dismiss",
+            post(track_merges::dismiss_merge_proposal),
+        )
+        .route(
+            "/tracks/{id}",
+            get(tracks::get_track)
+                .patch(tracks::update_track)
+                .delete(tracks::delete_track),
+        )
+        .route(
+            "/tracks/{id}/verifications",
+            post(tracks::create_verification),
+        )
+        .route("/tracks/{id}/revisions", get(tracks::list_track_revisions))
+        .route(
+            "/tracks/{id}/revisions/{revision_id}/revert",
+            post(tracks::revert_track),
+        )
+        .route("/tiles/tracks/{z}/{x}/{y}", get(tiles::get_track_tile))
+        // Hazard reports
+        .route(
+            "/hazards",
+            get(hazards::list_hazards).post(hazards::create_hazard),
+        )
+        .route("/hazards/{id}", get(hazards::get_hazard))
+        .route("/hazards/{id}/resolve", post(hazards::resolve_hazard))
+        // Points of interest
+        .route("/pois", get(pois::list_pois).post(pois::create_poi))
+        .route(
+            "/pois/{id}",
+            get(pois::get_poi)
+                .patch(pois::update_poi)
+                .delete(pois::delete_poi),
+        )
+        // Regions
+        .route("/regions", get(regions::list_regions))
+        .route(
+            "/regions/import",
+            post(regions::import_regions)
+                .layer(DefaultBodyLimit::max(regions::MAX_REGION_IMPORT_BYTES)),
+        )
+        .route("/regions/{code}", get(regions::get_region))
+        // Track network topology
+        .route("/topology", get(topology::get_topology_status))
+        .route("/topology/rebuild", post(topology::rebuild_track_topology))
+        .route("/topology/components", get(topology::list_components))
+        .route("/topology/dangling-ends", get(topology::list_dangling_ends))
+        .route("/topology/gaps", get(topology::list_component_gaps))
+        // Track submissions
+        .route(
+            "/submissions",
+            get(submissions::list_my_submissions).post(submissions::create_submission),
+        )
+        .route(
+            "/submissions/gpx",
+            post(submissions::create_gpx_submission)
+                .layer(DefaultBodyLimit::max(trace_validation::MAX_TRACE_BYTES)),
+        )
+        .route("/submissions/queue", get(submissions::submission_queue))
+        .route("/submissions/{id}", patch(submissions::review_submission))
         // Routes
         .route(
             "/routes",
@@ -23,6 +115,33 @@ pub fn api_routes() -> Router<AppState> {
             "/routes/{id}",
             get(route_handlers::get_route).put(route_handlers::update_route),
         )
+        .route(
+            "/routes/{id}/analysis",
+            get(route_handlers::get_route_analysis),
+        )
+        .route("/routes/{id}/export/{format}", get(exports::export_route))
+        .route("/routes/{id}/km-markers", get(linear_ref::get_km_markers))
+        .route("/routes/{id}/locate", get(linear_ref::locate_on_route))
+        .route("/routes/{id}/pois", get(pois::list_route_pois))
+        .route("/routes/{id}/hazards", get(hazards::list_route_hazards))
+        .route("/routes/{id}/cut", get(linear_ref::cut_route))
+        .route(
+            "/routes/{id}/reverse",
+            post(route_operations::reverse_route),
+        )
+        .route("/routes/{id}/split", post(route_operations::split_route))
+        .route("/routes/{id}/trim", post(route_operations::trim_route))
+        .route("/routes/{id}/join", post(route_operations::join_routes))
+        .route(
+            "/routes/{id}/validate-trace",
+            post(trace_validation::validate_trace)
+                .layer(DefaultBodyLimit::max(trace_validation::MAX_TRACE_BYTES)),
+        )
+        .route(
+            "/routes/{id}/deviations",
+            post(trace_validation::analyze_trace_deviation)
+                .layer(DefaultBodyLimit::max(trace_validation::MAX_TRACE_BYTES)),
+        )
         .route(
             "/routes/{id}/control-points",
             put(route_handlers::update_route_control_points),
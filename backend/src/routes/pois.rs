@@ -0,0 +1,368 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde_json::Value;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::geometry::linear_ref::LinearRoute;
+use crate::geometry::types::Position;
+use crate::middleware::Curator;
+use crate::models::{CreatePoi, Poi, PoiQuery, RoutePoi, RoutePoiQuery, UpdatePoi, POI_CATEGORIES};
+use crate::routes::route_handlers::fetch_route_version_geometry;
+use crate::routes::tracks::{begin, check_attributes, commit, user_id, write_error};
+use crate::AppState;
+
+/// Default and maximum number of POIs per request
+const MAX_POIS: i64 = 1000;
+
+/// Default corridor around a route for POI searches and exports (km)
+pub const DEFAULT_ROUTE_POI_KM: f64 = 5.0;
+
+/// Widest accepted corridor around a route (km)
+pub const MAX_ROUTE_POI_KM: f64 = 100.0;
+
+/// Columns of a `Poi`
+const POI_COLUMNS: &str = "id, category, name, ST_X(geometry) AS lng, ST_Y(geometry) AS lat, \
+     attributes, source, confidence, last_verified, created_by, updated_by, created_at, updated_at";
+
+/// Columns of a `Poi` read through the alias `p`
+const POI_COLUMNS_P: &str =
+    "p.id, p.category, p.name, ST_X(p.geometry) AS lng, ST_Y(p.geometry) AS lat, \
+     p.attributes, p.source, p.confidence, p.last_verified, p.created_by, p.updated_by, \
+     p.created_at, p.updated_at";
+
+fn limit(limit: Option<i64>) -> Result<i64, StatusCode> {
+    match limit {
+        Some(n) if !(1..=MAX_POIS).contains(&n) => Err(StatusCode::BAD_REQUEST),
+        n => Ok(n.unwrap_or(MAX_POIS)),
+    }
+}
+
+fn check_categories(categories: &[String]) -> Result<(), StatusCode> {
+    if categories
+        .iter()
+        .any(|c| !POI_CATEGORIES.contains(&c.as_str()))
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok(())
+}
+
+/// Reject values the table constraints would refuse
+fn check_poi(
+    category: Option<&str>,
+    source: Option<&str>,
+    confidence: Option<i32>,
+    attributes: Option<&Value>,
+) -> Result<(), StatusCode> {
+    check_attributes(source, confidence)?;
+    if category.is_some_and(|c| !POI_CATEGORIES.contains(&c))
+        || attributes.is_some_and(|a| !a.is_object())
+    {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+    Ok(())
+}
+
+fn fetch_error(what: &str, e: sqlx::Error) -> StatusCode {
+    tracing::error!("Failed to fetch {}: {}", what, e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+/// POIs within `within_km` of a route geometry, ordered by km along the route
+///
+/// The database finds candidates in the corridor; positions along the route
+/// come from the same linear referencing as km markers.
+pub(crate) async fn pois_along_route(
+    pool: &PgPool,
+    geometry: &Value,
+    within_km: f64,
+    categories: &[String],
+) -> Result<Vec<RoutePoi>, StatusCode> {
+    let route = LinearRoute::from_geometry(geometry).map_err(|e| {
+        tracing::warn!("Route has no usable geometry for POIs: {}", e);
+        StatusCode::UNPROCESSABLE_ENTITY
+    })?;
+
+    let within_m = within_km * 1000.0;
+    // Index prefilter in degrees, generous up to 60° latitude
+    let within_deg = within_m / 111_320.0 * 2.0;
+
+    let mut sql =
+        QueryBuilder::<Postgres>::new("WITH route AS (SELECT ST_SetSRID(ST_GeomFromGeoJSON(");
+    sql.push_bind(geometry.to_string())
+        .push(format!(
+            "), 4326) AS geometry)
+             SELECT {} FROM pois p, route
+             WHERE p.geometry && ST_Expand(route.geometry, ",
+            POI_COLUMNS_P
+        ))
+        .push_bind(within_deg)
+        .push(") AND ST_DWithin(p.geometry::geography, route.geometry::geography, ")
+        .push_bind(within_m)
+        .push(")");
+    if !categories.is_empty() {
+        sql.push(" AND p.category = ANY(")
+            .push_bind(categories.to_vec())
+            .push(")");
+    }
+
+    let pois = sql
+        .build_query_as::<Poi>()
+        .persistent(false)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| fetch_error("POIs along route", e))?;
+
+    let mut located: Vec<RoutePoi> = pois
+        .into_iter()
+        .map(|poi| {
+            let location = route.locate(poi.lng, poi.lat);
+            RoutePoi {
+                poi,
+                km: location.km,
+                offset_m: location.offset_m,
+                side: location.side,
+            }
+        })
+        .collect();
+    located.sort_by(|a, b| {
+        a.km.total_cmp(&b.km)
+            .then(a.offset_m.total_cmp(&b.offset_m))
+    });
+
+    Ok(located)
+}
+
+/// List POIs (public endpoint)
+pub async fn list_pois(
+    State(state): State<AppState>,
+    Query(query): Query<PoiQuery>,
+) -> Result<Json<Vec<Poi>>, StatusCode> {
+    check_categories(&query.category)?;
+    if query.min_confidence.is_some_and(|c| !(1..=5).contains(&c)) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut sql =
+        QueryBuilder::<Postgres>::new(format!("SELECT {} FROM pois WHERE true", POI_COLUMNS));
+    if !query.category.is_empty() {
+        sql.push(" AND category = ANY(")
+            .push_bind(query.category.clone())
+            .push(")");
+    }
+    if let Some(min_confidence) = query.min_confidence {
+        sql.push(" AND confidence >= ").push_bind(min_confidence);
+    }
+    if let Some(bbox) = query.bbox {
+        sql.push(" AND geometry && ST_MakeEnvelope(")
+            .push_bind(bbox.min_lng)
+            .push(", ")
+            .push_bind(bbox.min_lat)
+            .push(", ")
+            .push_bind(bbox.max_lng)
+            .push(", ")
+            .push_bind(bbox.max_lat)
+            .push(", 4326)");
+    }
+    sql.push(" ORDER BY id LIMIT ")
+        .push_bind(limit(query.limit)?);
+
+    let pois = sql
+        .build_query_as::<Poi>()
+        .persistent(false)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| fetch_error("POIs", e))?;
+
+    Ok(Json(pois))
+}
+
+/// One POI (public endpoint)
+pub async fn get_poi(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Poi>, StatusCode> {
+    let poi = sqlx::query_as::<_, Poi>(&format!("SELECT {} FROM pois WHERE id = $1", POI_COLUMNS))
+        .bind(id)
+        .persistent(false)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| fetch_error("POI", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(poi))
+}
+
+/// Create a POI (curators only)
+pub async fn create_poi(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+    Json(payload): Json<CreatePoi>,
+) -> Result<Json<Poi>, StatusCode> {
+    check_poi(
+        Some(&payload.category),
+        Some(&payload.source),
+        Some(payload.confidence),
+        payload.attributes.as_ref(),
+    )?;
+    let position = Position::new(payload.lng, payload.lat).map_err(|e| {
+        tracing::warn!("Rejected POI position: {}", e);
+        StatusCode::UNPROCESSABLE_ENTITY
+    })?;
+
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    // RLS policy "Curators create pois" checks is_curator() and the audit fields
+    let poi = sqlx::query_as::<_, Poi>(&format!(
+        "INSERT INTO pois
+             (category, name, geometry, attributes, source, confidence, last_verified,
+              created_by, updated_by)
+         VALUES ($1, $2, ST_SetSRID(ST_MakePoint($3, $4), 4326), COALESCE($5, '{{}}'::jsonb),
+                 $6, $7, $8, $9, $9)
+         RETURNING {}",
+        POI_COLUMNS
+    ))
+    .bind(&payload.category)
+    .bind(&payload.name)
+    .bind(position.lng)
+    .bind(position.lat)
+    .bind(&payload.attributes)
+    .bind(&payload.source)
+    .bind(payload.confidence)
+    .bind(payload.last_verified)
+    .bind(user_id)
+    .persistent(false)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| write_error("create POI", e))?;
+
+    commit(tx).await?;
+    tracing::info!(
+        "Curator {} created {} POI {}",
+        user_id,
+        poi.category,
+        poi.id
+    );
+
+    Ok(Json(poi))
+}
+
+/// Change a POI (curators only)
+pub async fn update_poi(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdatePoi>,
+) -> Result<Json<Poi>, StatusCode> {
+    check_poi(
+        payload.category.as_deref(),
+        payload.source.as_deref(),
+        payload.confidence,
+        payload.attributes.as_ref(),
+    )?;
+    let position = match (payload.lng, payload.lat) {
+        (Some(lng), Some(lat)) => Some(Position::new(lng, lat).map_err(|e| {
+            tracing::warn!("Rejected POI position: {}", e);
+            StatusCode::UNPROCESSABLE_ENTITY
+        })?),
+        (None, None) => None,
+        _ => return Err(StatusCode::UNPROCESSABLE_ENTITY),
+    };
+
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let poi = sqlx::query_as::<_, Poi>(&format!(
+        "UPDATE pois SET
+             category = COALESCE($2, category),
+             name = COALESCE($3, name),
+             geometry = COALESCE(ST_SetSRID(ST_MakePoint($4, $5), 4326), geometry),
+             attributes = COALESCE($6, attributes),
+             source = COALESCE($7, source),
+             confidence = COALESCE($8, confidence),
+             last_verified = COALESCE($9, last_verified),
+             updated_by = $10
+         WHERE id = $1
+         RETURNING {}",
+        POI_COLUMNS
+    ))
+    .bind(id)
+    .bind(&payload.category)
+    .bind(&payload.name)
+    .bind(position.map(|p| p.lng))
+    .bind(position.map(|p| p.lat))
+    .bind(&payload.attributes)
+    .bind(&payload.source)
+    .bind(payload.confidence)
+    .bind(payload.last_verified)
+    .bind(user_id)
+    .persistent(false)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| write_error("update POI", e))?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    commit(tx).await?;
+    tracing::info!("Curator {} updated POI {}", user_id, id);
+
+    Ok(Json(poi))
+}
+
+/// Delete a POI (curators only)
+pub async fn delete_poi(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, StatusCode> {
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let result = sqlx::query("DELETE FROM pois WHERE id = $1")
+        .bind(id)
+        .persistent(false)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| write_error("delete POI", e))?;
+    if result.rows_affected() == 0 {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    commit(tx).await?;
+    tracing::info!("Curator {} deleted POI {}", user_id, id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POIs near a route version in driving order (public endpoint)
+///
+/// Answers "where is the next well after km 230":
+/// `?category=water_well&after_km=230&limit=1`.
+pub async fn list_route_pois(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<RoutePoiQuery>,
+) -> Result<Json<Vec<RoutePoi>>, StatusCode> {
+    check_categories(&query.category)?;
+    let within_km = query.within_km.unwrap_or(DEFAULT_ROUTE_POI_KM);
+    if !(0.0..=MAX_ROUTE_POI_KM).contains(&within_km)
+        || query.after_km.is_some_and(|km| !km.is_finite())
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let limit = limit(query.limit)?;
+
+    let geometry = fetch_route_version_geometry(&state.pool, id, query.version_id).await?;
+    let mut pois = pois_along_route(&state.pool, &geometry, within_km, &query.category).await?;
+
+    if let Some(after_km) = query.after_km {
+        pois.retain(|p| p.km > after_km);
+    }
+    pois.truncate(limit as usize);
+
+    Ok(Json(pois))
+}
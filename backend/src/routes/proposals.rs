@@ -6,6 +6,7 @@ use axum::{
 use uuid::Uuid;
 
 use crate::db::RlsTransaction;
+use crate::geometry::types::MultiLineGeometry;
 use crate::middleware::AuthUser;
 use crate::models::{CreateProposal, RouteProposal, UpdateProposalStatus};
 use crate::AppState;
@@ -20,7 +21,13 @@ pub async fn list_proposals(
 ) -> Result<Json<Vec<RouteProposal>>, StatusCode> {
     let proposals = sqlx::query_as!(
         RouteProposal,
-        "SELECT * FROM route_proposals WHERE route_id = $1 ORDER BY created_at DESC",
+        r#"
+        SELECT
+            id, route_id, geometry as "geometry: sqlx::types::Json<MultiLineGeometry>", comment, status, created_by, created_at, updated_at
+        FROM route_proposals
+        WHERE route_id = $1
+        ORDER BY created_at DESC
+        "#,
         route_id
     )
     .fetch_all(&state.pool)
@@ -62,10 +69,10 @@ pub async fn create_proposal(
         r#"
         INSERT INTO route_proposals (route_id, geometry, comment, created_by)
         VALUES ($1, $2, $3, $4)
-        RETURNING *
+        RETURNING id, route_id, geometry as "geometry: sqlx::types::Json<MultiLineGeometry>", comment, status, created_by, created_at, updated_at
         "#,
         payload.route_id,
-        payload.geometry,
+        sqlx::types::Json(&payload.geometry) as _,
         payload.comment,
         created_by
     )
@@ -114,7 +121,10 @@ pub async fn update_proposal_status(
     // If user doesn't own the route, UPDATE will return no rows (RLS filters them out)
     let proposal = sqlx::query_as!(
         RouteProposal,
-        "UPDATE route_proposals SET status = $1 WHERE id = $2 RETURNING *",
+        r#"
+        UPDATE route_proposals SET status = $1 WHERE id = $2
+        RETURNING id, route_id, geometry as "geometry: sqlx::types::Json<MultiLineGeometry>", comment, status, created_by, created_at, updated_at
+        "#,
         payload.status,
         id
     )
@@ -0,0 +1,222 @@
+use std::collections::HashSet;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde_json::Value;
+use sqlx::{Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::geometry::validate::validate_area_geometry;
+use crate::middleware::Curator;
+use crate::models::{
+    RegionDetail, RegionFeatureCollection, RegionImportResult, RegionQuery, RegionSummary,
+    REGION_LEVELS,
+};
+use crate::routes::error::ApiError;
+use crate::routes::tracks::{begin, commit, user_id, write_error};
+use crate::AppState;
+
+/// Region outlines are detailed; allow large import files
+pub const MAX_REGION_IMPORT_BYTES: usize = 50 * 1024 * 1024;
+
+/// Region columns and track statistics, grouped by region
+///
+/// Length counts only the part of each track inside the region, clipped when
+/// the track or region was written (`track_regions.length_m`).
+const REGION_SUMMARY_SELECT: &str = "SELECT r.id, r.code, r.name, r.level, r.parent_id, \
+     r.created_at, r.updated_at, \
+     COUNT(t.id) AS track_count, \
+     COALESCE(SUM(tr.length_m), 0) / 1000 AS length_km, \
+     AVG(t.confidence)::float8 AS avg_confidence \
+     FROM regions r \
+     LEFT JOIN track_regions tr ON tr.region_id = r.id \
+     LEFT JOIN curated_tracks t ON t.id = tr.track_id \
+     WHERE true";
+
+fn query_error(what: &str, e: sqlx::Error) -> StatusCode {
+    tracing::error!("Failed to fetch {}: {}", what, e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+/// Regions with track counts, km and average confidence (public endpoint)
+///
+/// Countries come first, then areas, each by name.
+pub async fn list_regions(
+    State(state): State<AppState>,
+    Query(query): Query<RegionQuery>,
+) -> Result<Json<Vec<RegionSummary>>, StatusCode> {
+    if query
+        .level
+        .as_deref()
+        .is_some_and(|l| !REGION_LEVELS.contains(&l))
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut sql = QueryBuilder::<Postgres>::new(REGION_SUMMARY_SELECT);
+    if let Some(level) = &query.level {
+        sql.push(" AND r.level = ").push_bind(level.clone());
+    }
+    if let Some(parent) = &query.parent {
+        sql.push(" AND r.parent_id = (SELECT id FROM regions WHERE code = ")
+            .push_bind(parent.clone())
+            .push(")");
+    }
+    sql.push(" GROUP BY r.id ORDER BY r.parent_id NULLS FIRST, r.name");
+
+    let regions = sql
+        .build_query_as::<RegionSummary>()
+        .persistent(false)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| query_error("regions", e))?;
+
+    Ok(Json(regions))
+}
+
+/// One region by code, with its outline and areas (public endpoint)
+pub async fn get_region(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+) -> Result<Json<RegionDetail>, StatusCode> {
+    let summary = sqlx::query_as::<_, RegionSummary>(&format!(
+        "{} AND r.code = $1 GROUP BY r.id",
+        REGION_SUMMARY_SELECT
+    ))
+    .bind(&code)
+    .persistent(false)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| query_error("region", e))?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    let geometry: Value =
+        sqlx::query_scalar("SELECT ST_AsGeoJSON(geometry)::jsonb FROM regions WHERE id = $1")
+            .bind(summary.region.id)
+            .persistent(false)
+            .fetch_one(&state.pool)
+            .await
+            .map_err(|e| query_error("region outline", e))?;
+
+    let children = sqlx::query_as::<_, RegionSummary>(&format!(
+        "{} AND r.parent_id = $1 GROUP BY r.id ORDER BY r.name",
+        REGION_SUMMARY_SELECT
+    ))
+    .bind(summary.region.id)
+    .persistent(false)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| query_error("subregions", e))?;
+
+    Ok(Json(RegionDetail {
+        summary,
+        geometry,
+        children,
+    }))
+}
+
+/// Create or replace regions from a GeoJSON FeatureCollection (curators only)
+///
+/// Features are matched to existing regions by `code`. Countries are written
+/// before areas, so a file can hold both. Tracks are reassigned to changed
+/// regions by the database.
+pub async fn import_regions(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+    Json(payload): Json<RegionFeatureCollection>,
+) -> Result<Json<RegionImportResult>, ApiError> {
+    let mut codes = HashSet::new();
+    let mut features = Vec::with_capacity(payload.features.len());
+    for feature in &payload.features {
+        let properties = &feature.properties;
+        let level = properties
+            .level
+            .as_deref()
+            .unwrap_or(match properties.parent {
+                Some(_) => "area",
+                None => "country",
+            });
+        let valid = !properties.code.is_empty()
+            && codes.insert(properties.code.clone())
+            && REGION_LEVELS.contains(&level)
+            && (level == "country") == properties.parent.is_none();
+        if !valid {
+            tracing::warn!("Rejected region import: bad feature {:?}", properties.code);
+            return Err(StatusCode::UNPROCESSABLE_ENTITY.into());
+        }
+
+        let geometry = validate_area_geometry(&feature.geometry).map_err(|mut e| {
+            for issue in &mut e.issues {
+                issue.message = format!("{}: {}", properties.code, issue.message);
+            }
+            e
+        })?;
+        features.push((level.to_string(), geometry.to_string(), &feature.properties));
+    }
+    features.sort_by_key(|(_, _, properties)| properties.parent.is_some());
+
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let mut result = RegionImportResult {
+        created: Vec::new(),
+        updated: Vec::new(),
+    };
+    for (level, geometry, properties) in features {
+        let parent_id: Option<Uuid> = match &properties.parent {
+            Some(parent) => Some(
+                sqlx::query_scalar("SELECT id FROM regions WHERE code = $1 AND level = 'country'")
+                    .bind(parent)
+                    .persistent(false)
+                    .fetch_optional(&mut **tx)
+                    .await
+                    .map_err(|e| query_error("parent region", e))?
+                    .ok_or(StatusCode::UNPROCESSABLE_ENTITY)?,
+            ),
+            None => None,
+        };
+
+        // Self-intersecting outlines are common in hand-drawn files
+        let created: bool = sqlx::query_scalar(
+            "INSERT INTO regions (code, name, level, parent_id, geometry)
+             VALUES ($1, $2, $3, $4,
+                     ST_Multi(ST_CollectionExtract(
+                         ST_MakeValid(ST_SetSRID(ST_GeomFromGeoJSON($5), 4326)), 3)))
+             ON CONFLICT (code) DO UPDATE SET
+                 name = EXCLUDED.name,
+                 level = EXCLUDED.level,
+                 parent_id = EXCLUDED.parent_id,
+                 geometry = EXCLUDED.geometry,
+                 updated_at = NOW()
+             RETURNING xmax = 0",
+        )
+        .bind(&properties.code)
+        .bind(&properties.name)
+        .bind(&level)
+        .bind(parent_id)
+        .bind(geometry)
+        .persistent(false)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| write_error("import region", e))?;
+
+        if created {
+            result.created.push(properties.code.clone());
+        } else {
+            result.updated.push(properties.code.clone());
+        }
+    }
+
+    commit(tx).await?;
+    tracing::info!(
+        "Curator {} imported regions: {} created, {} updated",
+        user_id,
+        result.created.len(),
+        result.updated.len()
+    );
+
+    Ok(Json(result))
+}
@@ -1,26 +1,56 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
+use chrono::Utc;
 use serde_json::Value;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use uuid::Uuid;
 
 use crate::db::RlsTransaction;
-use crate::geometry::{route_geometry, simplify_geometry, RoutingConfig};
+use crate::geometry::lod::{GeometryResolution, VersionGeometry};
+use crate::geometry::simplification::SimplifyOptions;
+use crate::geometry::types::{ControlPoint, Position};
+use crate::geometry::validate::{
+    validate_line_geometry, GeometryValidationError, ValidationOptions,
+};
+use crate::geometry::{analyze_route, route_geometry, RouteAnalysis, RoutingConfig};
 use crate::middleware::AuthUser;
-use crate::models::{CreateRoute, Route, RouteWithGeometry, UpdateRoute, UpdateRouteControlPoints};
+use crate::models::{
+    CreateRoute, GeometryQuery, HeadingQuery, Route, RouteHazard, RouteWithGeometry, UpdateRoute,
+    UpdateRouteControlPoints,
+};
+use crate::routes::error::{is_permission_denied, ApiError};
+use crate::routes::hazards::{hazards_along_route, DEFAULT_HAZARD_CORRIDOR_M};
 use crate::AppState;
 
 /// Process geometry: route via hybrid approach (Mapbox + curated tracks) + simplify
 ///
 /// # Steps
 /// 1. Route geometry using hybrid approach (on-road vs off-road detection)
-/// 2. Simplify routed geometry using Douglas-Peucker algorithm
-/// 3. Return processed geometry with confidence score
-async fn process_geometry(pool: &PgPool, raw_geometry: &Value) -> Result<Value, StatusCode> {
-    let routing_config = RoutingConfig::default();
+/// 2. Simplify routed geometry (meter tolerance, control points always kept)
+/// 3. Precompute overview levels of detail, keeping the full-resolution line
+/// 4. Flag active hazards along the routed line (`None` if the lookup failed)
+///
+/// With `avoid_hazards`, curated tracks near active severe hazards are left
+/// out of routing; road segments from Mapbox are not affected.
+async fn process_geometry(
+    pool: &PgPool,
+    raw_geometry: &Value,
+    control_points: &[ControlPoint],
+    simplify: &SimplifyOptions,
+    avoid_hazards: bool,
+) -> Result<(VersionGeometry, Option<Vec<RouteHazard>>), StatusCode> {
+    simplify.validate().map_err(|e| {
+        tracing::warn!("Rejected simplification options: {}", e);
+        StatusCode::BAD_REQUEST
+    })?;
+
+    let routing_config = RoutingConfig {
+        avoid_severe_hazards: avoid_hazards,
+        ..Default::default()
+    };
 
     // Step 1: Hybrid routing (Mapbox Directions + curated tracks)
     let (routed_geometry, confidence) = route_geometry(pool, raw_geometry, &routing_config)
@@ -35,32 +65,138 @@ async fn process_geometry(pool: &PgPool, raw_geometry: &Value) -> Result<Value,
         confidence * 100.0
     );
 
-    // Step 2: Geometric simplification (0.0001° ≈ 11m tolerance)
-    let simplified_geometry = simplify_geometry(&routed_geometry, 0.0001).map_err(|e| {
+    // Steps 2-3: Geometric simplification, keeping every control point, and
+    // overview levels of detail
+    let keep: Vec<(f64, f64)> = control_points.iter().map(ControlPoint::as_tuple).collect();
+    let version = VersionGeometry::from_full(routed_geometry, simplify, &keep).map_err(|e| {
         tracing::error!("Geometric simplification failed: {}", e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
-    tracing::info!("Geometry simplification complete");
+    tracing::info!(
+        "Geometry simplification complete ({} overview levels)",
+        version.lods.len()
+    );
 
-    Ok(simplified_geometry)
+    // Step 4: Hazards within the corridor of the routed line; a failed lookup
+    // leaves them unchecked rather than failing the save
+    let hazards =
+        match hazards_along_route(pool, &version.geometry, DEFAULT_HAZARD_CORRIDOR_M).await {
+            Ok(hazards) => Some(hazards),
+            Err(status) => {
+                tracing::warn!("Hazard check of routed geometry failed: {}", status);
+                None
+            }
+        };
+    if let Some(hazards) = &hazards {
+        let severe = hazards
+            .iter()
+            .filter(|h| h.hazard.severity == "severe")
+            .count();
+        if severe > 0 {
+            tracing::warn!(
+                "Routed geometry passes {} severe hazard(s) (of {} active nearby)",
+                severe,
+                hazards.len()
+            );
+        }
+    }
+
+    Ok((version, hazards))
+}
+
+/// A route joined with its latest version
+///
+/// Read by `list_routes` and `fetch_route`, where `$1` selects the coarsest
+/// overview within that tolerance (meters) and `$2` asks for the
+/// full-resolution geometry; otherwise the standard geometry is returned.
+struct RouteRow {
+    id: Uuid,
+    name: String,
+    owner_id: Uuid,
+    control_points: sqlx::types::Json<Vec<ControlPoint>>,
+    created_at: chrono::DateTime<Utc>,
+    updated_at: chrono::DateTime<Utc>,
+    geometry: Option<Value>,
+    length_km: Option<f64>,
+    estimated_time_min: Option<i32>,
+    created_by: Option<Uuid>,
+}
+
+impl From<RouteRow> for RouteWithGeometry {
+    fn from(row: RouteRow) -> Self {
+        RouteWithGeometry {
+            route: Route {
+                id: row.id,
+                name: row.name,
+                owner_id: row.owner_id,
+                control_points: row.control_points,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+            },
+            geometry: row.geometry.unwrap_or(Value::Null),
+            length_km: row.length_km,
+            estimated_time_min: row.estimated_time_min,
+            created_by: row.created_by,
+            hazards: None,
+        }
+    }
+}
+
+/// Validate incoming line geometry, repairing fixable issues when asked
+fn validated_geometry(geometry: &Value, repair: bool) -> Result<Value, GeometryValidationError> {
+    let validated = validate_line_geometry(
+        geometry,
+        &ValidationOptions {
+            repair,
+            ..Default::default()
+        },
+    )?;
+
+    if !validated.repaired.is_empty() {
+        tracing::info!("Repaired {} geometry issues", validated.repaired.len());
+    }
+
+    Ok(validated.geometry)
+}
+
+fn resolution_of(query: &GeometryQuery) -> Result<GeometryResolution, StatusCode> {
+    GeometryResolution::from_params(query.zoom, query.tolerance_m).map_err(|e| {
+        tracing::warn!("Rejected geometry resolution: {}", e);
+        StatusCode::BAD_REQUEST
+    })
 }
 
 /// List all routes (public endpoint)
 ///
 /// Returns routes with their latest geometry version.
 /// Currently public - all users can see all routes.
+/// Pass `?zoom=` or `?tolerance_m=` to receive a lighter overview geometry.
 pub async fn list_routes(
     State(state): State<AppState>,
+    Query(query): Query<GeometryQuery>,
 ) -> Result<Json<Vec<RouteWithGeometry>>, StatusCode> {
-    let routes = sqlx::query!(
+    let (overview_tolerance, full) = resolution_of(&query)?.as_params();
+
+    let routes = sqlx::query_as!(
+        RouteRow,
         r#"
         SELECT
-            r.id, r.name, r.owner_id, r.control_points, r.created_at as "created_at!", r.updated_at as "updated_at!",
-            rv.geometry, rv.length_km, rv.estimated_time_min, rv.created_by
+            r.id, r.name, r.owner_id,
+            r.control_points as "control_points: sqlx::types::Json<Vec<ControlPoint>>",
+            r.created_at as "created_at!", r.updated_at as "updated_at!",
+            COALESCE(
+                (SELECT l.geometry FROM route_version_lods l
+                 WHERE l.route_version_id = rv.id AND l.tolerance_m <= $1
+                 ORDER BY l.tolerance_m DESC
+                 LIMIT 1),
+                CASE WHEN $2 THEN rv.full_geometry END,
+                rv.geometry
+            ) as "geometry?",
+            rv.length_km, rv.estimated_time_min, rv.created_by
         FROM routes r
         LEFT JOIN LATERAL (
-            SELECT geometry, length_km, estimated_time_min, created_by
+            SELECT id, geometry, full_geometry, length_km, estimated_time_min, created_by
             FROM route_versions
             WHERE route_id = r.id
             ORDER BY created_at DESC
@@ -68,7 +204,9 @@ pub async fn list_routes(
         ) rv ON true
         ORDER BY r.created_at DESC
         LIMIT 100
-        "#
+        "#,
+        overview_tolerance,
+        full
     )
     .fetch_all(&state.pool)
     .await
@@ -77,52 +215,79 @@ pub async fn list_routes(
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
-    let result = routes
-        .into_iter()
-        .map(|row| RouteWithGeometry {
-            route: Route {
-                id: row.id,
-                name: row.name,
-                owner_id: row.owner_id,
-                control_points: row.control_points,
-                created_at: row.created_at,
-                updated_at: row.updated_at,
-            },
-            geometry: row.geometry,
-            length_km: row.length_km,
-            estimated_time_min: row.estimated_time_min,
-            created_by: row.created_by,
-        })
-        .collect();
-
-    Ok(Json(result))
+    Ok(Json(routes.into_iter().map(Into::into).collect()))
 }
 
 /// Get a single route by ID (public endpoint)
 ///
-/// Returns the route with its latest geometry version.
+/// Returns the route with its latest geometry version, at the resolution
+/// picked by `?zoom=` / `?tolerance_m=` (standard geometry by default), and
+/// the active hazards within 1 km of it.
 pub async fn get_route(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    Query(query): Query<GeometryQuery>,
 ) -> Result<Json<RouteWithGeometry>, StatusCode> {
-    let route = sqlx::query!(
+    let mut route = fetch_route(&state.pool, id, resolution_of(&query)?).await?;
+
+    // Hazards are located on the full-resolution line, whatever is returned.
+    // The route is still returned, without hazards, when they can't be looked up.
+    if !route.geometry.is_null() {
+        let hazards = async {
+            let geometry = fetch_route_version_geometry(&state.pool, id, None).await?;
+            hazards_along_route(&state.pool, &geometry, DEFAULT_HAZARD_CORRIDOR_M).await
+        };
+        route.hazards = match hazards.await {
+            Ok(hazards) => Some(hazards),
+            Err(status) => {
+                tracing::warn!("Hazard check of route {} failed: {}", id, status);
+                None
+            }
+        };
+    }
+
+    Ok(Json(route))
+}
+
+/// Fetch a route with its latest geometry version at the given resolution
+pub(crate) async fn fetch_route(
+    pool: &PgPool,
+    id: Uuid,
+    resolution: GeometryResolution,
+) -> Result<RouteWithGeometry, StatusCode> {
+    let (overview_tolerance, full) = resolution.as_params();
+
+    let route = sqlx::query_as!(
+        RouteRow,
         r#"
         SELECT
-            r.id, r.name, r.owner_id, r.control_points, r.created_at as "created_at!", r.updated_at as "updated_at!",
-            rv.geometry, rv.length_km, rv.estimated_time_min, rv.created_by
+            r.id, r.name, r.owner_id,
+            r.control_points as "control_points: sqlx::types::Json<Vec<ControlPoint>>",
+            r.created_at as "created_at!", r.updated_at as "updated_at!",
+            COALESCE(
+                (SELECT l.geometry FROM route_version_lods l
+                 WHERE l.route_version_id = rv.id AND l.tolerance_m <= $1
+                 ORDER BY l.tolerance_m DESC
+                 LIMIT 1),
+                CASE WHEN $2 THEN rv.full_geometry END,
+                rv.geometry
+            ) as "geometry?",
+            rv.length_km, rv.estimated_time_min, rv.created_by
         FROM routes r
         LEFT JOIN LATERAL (
-            SELECT geometry, length_km, estimated_time_min, created_by
+            SELECT id, geometry, full_geometry, length_km, estimated_time_min, created_by
             FROM route_versions
             WHERE route_id = r.id
             ORDER BY created_at DESC
             LIMIT 1
         ) rv ON true
-        WHERE r.id = $1
+        WHERE r.id = $3
         "#,
+        overview_tolerance,
+        full,
         id
     )
-    .fetch_one(&state.pool)
+    .fetch_one(pool)
     .await
     .map_err(|e| match e {
         sqlx::Error::RowNotFound => StatusCode::NOT_FOUND,
@@ -132,19 +297,178 @@ pub async fn get_route(
         }
     })?;
 
-    Ok(Json(RouteWithGeometry {
-        route: Route {
-            id: route.id,
-            name: route.name,
-            owner_id: route.owner_id,
-            control_points: route.control_points,
-            created_at: route.created_at,
-            updated_at: route.updated_at,
-        },
-        geometry: route.geometry,
-        length_km: route.length_km,
-        estimated_time_min: route.estimated_time_min,
-        created_by: route.created_by,
+    Ok(route.into())
+}
+
+/// A stored route version, as traces are checked against it
+pub(crate) struct RouteVersion {
+    /// Full-resolution geometry
+    pub geometry: Value,
+    /// Control points the version was built from; unknown for versions
+    /// stored before they were recorded
+    pub control_points: Option<Vec<ControlPoint>>,
+}
+
+/// Fetch a route version
+///
+/// Uses the given version when provided, otherwise the route's latest version.
+pub(crate) async fn fetch_route_version(
+    pool: &PgPool,
+    route_id: Uuid,
+    version_id: Option<Uuid>,
+) -> Result<RouteVersion, StatusCode> {
+    let (geometry, control_points) =
+        sqlx::query_as::<_, (Value, Option<sqlx::types::Json<Vec<ControlPoint>>>)>(
+            "SELECT COALESCE(full_geometry, geometry), control_points FROM route_versions
+             WHERE route_id = $1 AND ($2::uuid IS NULL OR id = $2)
+             ORDER BY created_at DESC
+             LIMIT 1",
+        )
+        .bind(route_id)
+        .bind(version_id)
+        .persistent(false)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => StatusCode::NOT_FOUND,
+            _ => {
+                tracing::error!("Failed to fetch route version: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        })?;
+
+    Ok(RouteVersion {
+        geometry,
+        control_points: control_points.map(|points| points.0),
+    })
+}
+
+/// Fetch the full-resolution geometry of a route version
+///
+/// Uses the given version when provided, otherwise the route's latest version.
+pub(crate) async fn fetch_route_version_geometry(
+    pool: &PgPool,
+    route_id: Uuid,
+    version_id: Option<Uuid>,
+) -> Result<Value, StatusCode> {
+    Ok(fetch_route_version(pool, route_id, version_id)
+        .await?
+        .geometry)
+}
+
+/// Insert a route version, the control points it was built from and its
+/// overview levels of detail
+///
+/// Runs on the caller's connection so RLS policies "Owner create route
+/// versions" / "Owner create route version lods" apply inside transactions.
+pub(crate) async fn insert_route_version(
+    conn: &mut PgConnection,
+    route_id: Uuid,
+    control_points: &[ControlPoint],
+    version: &VersionGeometry,
+    created_by: Uuid,
+) -> Result<Uuid, sqlx::Error> {
+    let version_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO route_versions (route_id, geometry, full_geometry, control_points, created_by)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+        route_id,
+        &version.geometry,
+        version.full_geometry.as_ref(),
+        sqlx::types::Json(control_points) as _,
+        created_by
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    for lod in &version.lods {
+        sqlx::query!(
+            r#"
+            INSERT INTO route_version_lods (route_version_id, tolerance_m, point_count, geometry)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            version_id,
+            lod.tolerance_m,
+            lod.point_count as i32,
+            &lod.geometry
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(version_id)
+}
+
+/// Store a new version of an existing route inside an RLS transaction
+///
+/// Inserts the geometry into `route_versions` and replaces the route's
+/// control points. RLS policy "Owner create route versions" rejects the
+/// insert for non-owners, which is reported as `FORBIDDEN`.
+pub(crate) async fn store_route_version(
+    tx: &mut RlsTransaction<'_>,
+    auth_user: &AuthUser,
+    route_id: Uuid,
+    control_points: &[ControlPoint],
+    version: &VersionGeometry,
+) -> Result<(), StatusCode> {
+    let auth_user_uuid = Uuid::parse_str(&auth_user.id).map_err(|e| {
+        tracing::error!("Failed to parse user ID: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    insert_route_version(tx, route_id, control_points, version, auth_user_uuid)
+        .await
+        .map_err(|e| {
+            if is_permission_denied(&e) {
+                tracing::warn!(
+                    "User {} attempted to update route {} (permission denied by RLS)",
+                    auth_user.id,
+                    route_id
+                );
+                return StatusCode::FORBIDDEN;
+            }
+
+            tracing::error!("Failed to create route version: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    sqlx::query("UPDATE routes SET control_points = $1 WHERE id = $2")
+        .bind(sqlx::types::Json(control_points))
+        .bind(route_id)
+        .execute(&mut ***tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to update control points: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(())
+}
+
+/// Analyze a route's latest geometry (public endpoint)
+///
+/// Returns length plus true bearings and magnetic headings (CAP) at every
+/// route point and control point. Magnetic declination comes from the bundled
+/// World Magnetic Model for `?date=YYYY-MM-DD` (defaults to today).
+pub async fn get_route_analysis(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<HeadingQuery>,
+) -> Result<Json<RouteAnalysis>, StatusCode> {
+    let route = fetch_route(&state.pool, id, GeometryResolution::Full).await?;
+    let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+
+    let analysis =
+        analyze_route(&route.geometry, &route.route.control_points, date).map_err(|e| {
+            tracing::error!("Route analysis failed for {}: {}", id, e);
+            StatusCode::UNPROCESSABLE_ENTITY
+        })?;
+
+    Ok(Json(match query.coordinate_format {
+        Some(format) => analysis.with_coordinate_format(format),
+        None => analysis,
     }))
 }
 
@@ -156,7 +480,7 @@ pub async fn create_route(
     auth_user: AuthUser,
     State(state): State<AppState>,
     Json(payload): Json<CreateRoute>,
-) -> Result<Json<RouteWithGeometry>, StatusCode> {
+) -> Result<Json<RouteWithGeometry>, ApiError> {
     // Parse owner ID from authenticated user
     let owner_id = Uuid::parse_str(&auth_user.id).map_err(|e| {
         tracing::error!("Failed to parse user ID: {}", e);
@@ -164,8 +488,17 @@ pub async fn create_route(
     })?;
 
     // STEP 1: Process geometry (route + simplify)
+    let geometry = validated_geometry(&payload.geometry, payload.repair)?;
+
     tracing::info!("Processing geometry for new route '{}'", payload.name);
-    let processed_geometry = process_geometry(&state.pool, &payload.geometry).await?;
+    let (processed_geometry, hazards) = process_geometry(
+        &state.pool,
+        &geometry,
+        &payload.control_points,
+        &payload.simplify,
+        payload.avoid_hazards,
+    )
+    .await?;
 
     // Begin RLS transaction - sets auth.uid() to the authenticated user
     let mut tx = RlsTransaction::begin(&state.pool, &auth_user)
@@ -178,10 +511,17 @@ pub async fn create_route(
     // Create route - RLS policy "Users create routes" checks: owner_id = auth.uid()
     let route = sqlx::query_as!(
         Route,
-        "INSERT INTO routes (name, owner_id, control_points) VALUES ($1, $2, $3) RETURNING *",
+        r#"
+        INSERT INTO routes (name, owner_id, control_points)
+        VALUES ($1, $2, $3)
+        RETURNING
+            id, name, owner_id,
+            control_points as "control_points: sqlx::types::Json<Vec<ControlPoint>>",
+            created_at, updated_at
+        "#,
         payload.name,
         owner_id,
-        payload.control_points
+        sqlx::types::Json(&payload.control_points) as _
     )
     .fetch_one(&mut **tx)
     .await
@@ -191,13 +531,13 @@ pub async fn create_route(
     })?;
 
     // STEP 2: Store PROCESSED geometry (not raw payload)
-    sqlx::query!(
-        "INSERT INTO route_versions (route_id, geometry, created_by) VALUES ($1, $2, $3)",
+    insert_route_version(
+        &mut tx,
         route.id,
-        processed_geometry,
-        owner_id
+        &payload.control_points,
+        &processed_geometry,
+        owner_id,
     )
-    .execute(&mut **tx)
     .await
     .map_err(|e| {
         tracing::error!("Failed to create route version: {}", e);
@@ -212,10 +552,11 @@ pub async fn create_route(
 
     Ok(Json(RouteWithGeometry {
         route,
-        geometry: processed_geometry,
+        geometry: processed_geometry.geometry,
         length_km: None,
         estimated_time_min: None,
         created_by: Some(owner_id),
+        hazards,
     }))
 }
 
@@ -232,7 +573,7 @@ pub async fn update_route(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
     Json(payload): Json<UpdateRoute>,
-) -> Result<Json<RouteWithGeometry>, StatusCode> {
+) -> Result<Json<RouteWithGeometry>, ApiError> {
     // Parse user ID from authenticated user
     let auth_user_uuid = Uuid::parse_str(&auth_user.id).map_err(|e| {
         tracing::error!("Failed to parse user ID: {}", e);
@@ -240,8 +581,18 @@ pub async fn update_route(
     })?;
 
     // STEP 1: Process geometry (route + simplify)
+    let geometry = validated_geometry(&payload.geometry, payload.repair)?;
+
     tracing::info!("Processing geometry for route update (route_id: {})", id);
-    let processed_geometry = process_geometry(&state.pool, &payload.geometry).await?;
+    let existing = fetch_route(&state.pool, id, GeometryResolution::Standard).await?;
+    let (processed_geometry, hazards) = process_geometry(
+        &state.pool,
+        &geometry,
+        &existing.route.control_points,
+        &payload.simplify,
+        payload.avoid_hazards,
+    )
+    .await?;
 
     // Begin RLS transaction - sets auth.uid() to the authenticated user
     let mut tx = RlsTransaction::begin(&state.pool, &auth_user)
@@ -256,29 +607,22 @@ pub async fn update_route(
     // EXISTS (SELECT 1 FROM routes WHERE id = route_id AND owner_id = auth.uid())
     //
     // If user doesn't own the route, INSERT will fail with permission denied error
-    sqlx::query!(
-        "INSERT INTO route_versions (route_id, geometry, created_by) VALUES ($1, $2, $3)",
+    insert_route_version(
+        &mut tx,
         id,
-        processed_geometry, // <-- Routed + simplified geometry
-        auth_user_uuid
+        &existing.route.control_points,
+        &processed_geometry,
+        auth_user_uuid,
     )
-    .execute(&mut **tx)
     .await
     .map_err(|e| {
-        // Check if error is due to RLS policy violation
-        if let sqlx::Error::Database(ref db_err) = e {
-            if db_err.message().contains("permission denied")
-                || db_err
-                    .message()
-                    .contains("violates row-level security policy")
-            {
-                tracing::warn!(
-                    "User {} attempted to update route {} (permission denied by RLS)",
-                    auth_user.id,
-                    id
-                );
-                return StatusCode::FORBIDDEN;
-            }
+        if is_permission_denied(&e) {
+            tracing::warn!(
+                "User {} attempted to update route {} (permission denied by RLS)",
+                auth_user.id,
+                id
+            );
+            return StatusCode::FORBIDDEN;
         }
 
         tracing::error!("Failed to create route version: {}", e);
@@ -286,10 +630,13 @@ pub async fn update_route(
     })?;
 
     // Fetch the route with new geometry (RLS ensures we can only see owned routes)
-    let route = sqlx::query!(
+    let route = sqlx::query_as!(
+        Route,
         r#"
         SELECT
-            r.id, r.name, r.owner_id, r.control_points, r.created_at as "created_at!", r.updated_at as "updated_at!"
+            r.id, r.name, r.owner_id,
+            r.control_points as "control_points: sqlx::types::Json<Vec<ControlPoint>>",
+            r.created_at, r.updated_at
         FROM routes r
         WHERE r.id = $1
         "#,
@@ -299,7 +646,11 @@ pub async fn update_route(
     .await
     .map_err(|e| match e {
         sqlx::Error::RowNotFound => {
-            tracing::warn!("Route {} not found or not accessible by user {}", id, auth_user.id);
+            tracing::warn!(
+                "Route {} not found or not accessible by user {}",
+                id,
+                auth_user.id
+            );
             StatusCode::NOT_FOUND
         }
         _ => {
@@ -315,18 +666,12 @@ pub async fn update_route(
     })?;
 
     Ok(Json(RouteWithGeometry {
-        route: Route {
-            id: route.id,
-            name: route.name,
-            owner_id: route.owner_id,
-            control_points: route.control_points,
-            created_at: route.created_at,
-            updated_at: route.updated_at,
-        },
-        geometry: processed_geometry, // <-- Return processed geometry
+        route,
+        geometry: processed_geometry.geometry, // <-- Return processed geometry
         length_km: None,
         estimated_time_min: None,
         created_by: Some(auth_user_uuid),
+        hazards,
     }))
 }
 
@@ -335,32 +680,27 @@ pub async fn update_route_control_points(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
     Json(payload): Json<UpdateRouteControlPoints>,
-) -> Result<Json<RouteWithGeometry>, StatusCode> {
+) -> Result<Json<RouteWithGeometry>, ApiError> {
     // IMPORTANT: Process geometry BEFORE starting transaction to avoid connection pool deadlock
     // The transaction holds one connection, and process_geometry needs another for routing queries
-    let coordinates: Vec<serde_json::Value> = payload
-        .control_points
-        .as_array()
-        .ok_or(StatusCode::BAD_REQUEST)?
-        .iter()
-        .map(|p| {
-            // Handle both {lng, lat} and {coordinates: [lng, lat]} formats
-            if let Some(coords) = p.get("coordinates") {
-                Ok(coords.clone())
-            } else if let (Some(lng), Some(lat)) = (p.get("lng"), p.get("lat")) {
-                Ok(serde_json::json!([lng, lat]))
-            } else {
-                Err(StatusCode::BAD_REQUEST)
-            }
-        })
-        .collect::<Result<Vec<_>, _>>()?;
-
-    let geometry = serde_json::json!({
-        "type": "MultiLineString",
-        "coordinates": [coordinates]
-    });
-
-    let processed_geometry = process_geometry(&state.pool, &geometry).await?;
+    let coordinates: Vec<Position> = payload.control_points.iter().map(|p| p.position).collect();
+
+    let geometry = validated_geometry(
+        &serde_json::json!({
+            "type": "MultiLineString",
+            "coordinates": [coordinates]
+        }),
+        payload.repair,
+    )?;
+
+    let (processed_geometry, hazards) = process_geometry(
+        &state.pool,
+        &geometry,
+        &payload.control_points,
+        &payload.simplify,
+        payload.avoid_hazards,
+    )
+    .await?;
 
     // Now begin RLS transaction
     let mut tx = RlsTransaction::begin(&state.pool, &auth_user)
@@ -375,7 +715,9 @@ pub async fn update_route_control_points(
         Route,
         r#"
         SELECT
-            id, name, owner_id, control_points, created_at, updated_at
+            id, name, owner_id,
+            control_points as "control_points: sqlx::types::Json<Vec<ControlPoint>>",
+            created_at, updated_at
         FROM routes
         WHERE id = $1
         "#,
@@ -412,7 +754,7 @@ pub async fn update_route_control_points(
 
         // Update the control points on the route
         sqlx::query("UPDATE routes SET control_points = $1 WHERE id = $2")
-            .bind(&payload.control_points)
+            .bind(sqlx::types::Json(&payload.control_points))
             .bind(id)
             .execute(&mut **tx)
             .await
@@ -422,28 +764,22 @@ pub async fn update_route_control_points(
             })?;
 
         // STEP 2: Store PROCESSED geometry version
-        sqlx::query!(
-            "INSERT INTO route_versions (route_id, geometry, created_by) VALUES ($1, $2, $3)",
+        insert_route_version(
+            &mut tx,
             id,
-            processed_geometry,
-            auth_user_uuid
+            &payload.control_points,
+            &processed_geometry,
+            auth_user_uuid,
         )
-        .execute(&mut **tx)
         .await
         .map_err(|e| {
-            if let sqlx::Error::Database(ref db_err) = e {
-                if db_err.message().contains("permission denied")
-                    || db_err
-                        .message()
-                        .contains("violates row-level security policy")
-                {
-                    tracing::warn!(
-                        "User {} attempted to update route {} (permission denied by RLS)",
-                        auth_user.id,
-                        id
-                    );
-                    return StatusCode::FORBIDDEN;
-                }
+            if is_permission_denied(&e) {
+                tracing::warn!(
+                    "User {} attempted to update route {} (permission denied by RLS)",
+                    auth_user.id,
+                    id
+                );
+                return StatusCode::FORBIDDEN;
             }
 
             tracing::error!("Failed to create route version: {}", e);
@@ -461,14 +797,15 @@ pub async fn update_route_control_points(
                 id: existing_route.id,
                 name: existing_route.name,
                 owner_id: existing_route.owner_id,
-                control_points: payload.control_points,
+                control_points: sqlx::types::Json(payload.control_points),
                 created_at: existing_route.created_at,
                 updated_at: existing_route.updated_at,
             },
-            geometry: processed_geometry,
+            geometry: processed_geometry.geometry,
             length_km: None,
             estimated_time_min: None,
             created_by: Some(auth_user_uuid),
+            hazards,
         }))
     } else {
         // User does NOT own the route, create a proposal
@@ -480,38 +817,14 @@ pub async fn update_route_control_points(
             existing_route.owner_id
         );
 
-        // Handle both {lng, lat} and {coordinates: [lng, lat]} formats
-        let original_position = existing_route
-            .control_points
-            .as_array()
-            .and_then(|arr: &Vec<Value>| arr.get(payload.point_index as usize))
-            .and_then(|p: &Value| {
-                // Handle both formats
-                if let Some(coords) = p.get("coordinates") {
-                    Some(coords.clone())
-                } else if let (Some(lng), Some(lat)) = (p.get("lng"), p.get("lat")) {
-                    Some(serde_json::json!([lng, lat]))
-                } else {
-                    None
-                }
-            })
-            .ok_or(StatusCode::BAD_REQUEST)?;
-
-        let new_position = payload
-            .control_points
-            .as_array()
-            .and_then(|arr: &Vec<Value>| arr.get(payload.point_index as usize))
-            .and_then(|p: &Value| {
-                // Handle both formats
-                if let Some(coords) = p.get("coordinates") {
-                    Some(coords.clone())
-                } else if let (Some(lng), Some(lat)) = (p.get("lng"), p.get("lat")) {
-                    Some(serde_json::json!([lng, lat]))
-                } else {
-                    None
-                }
-            })
-            .ok_or(StatusCode::BAD_REQUEST)?;
+        let position_at = |points: &[ControlPoint]| {
+            points
+                .get(payload.point_index as usize)
+                .map(|p| p.position)
+                .ok_or(StatusCode::BAD_REQUEST)
+        };
+        let original_position = position_at(&existing_route.control_points)?;
+        let new_position = position_at(&payload.control_points)?;
 
         sqlx::query!(
             r#"
@@ -523,8 +836,8 @@ pub async fn update_route_control_points(
             auth_user.full_claims.email,
             payload.feature_index,
             payload.point_index,
-            original_position,
-            new_position,
+            sqlx::types::Json(original_position) as _,
+            sqlx::types::Json(new_position) as _
         )
         .execute(&mut **tx)
         .await
@@ -567,6 +880,7 @@ pub async fn update_route_control_points(
             length_km: None,
             estimated_time_min: None,
             created_by: None, // We don't have this for the original geometry easily here
+            hazards: None,
         }))
     }
 }
@@ -0,0 +1,230 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::db::RlsTransaction;
+use crate::geometry::lod::{GeometryResolution, VersionGeometry};
+use crate::geometry::operations::{self, EditedRoute};
+use crate::geometry::simplification::SimplifyOptions;
+use crate::geometry::types::ControlPoint;
+use crate::middleware::AuthUser;
+use crate::models::{JoinRoute, Route, RouteWithGeometry, SplitRoute, TrimRoute};
+use crate::routes::route_handlers::{fetch_route, store_route_version};
+use crate::AppState;
+
+/// Simplify an edited full-resolution geometry for storage
+fn version_geometry(edited: &EditedRoute) -> Result<VersionGeometry, StatusCode> {
+    let keep: Vec<(f64, f64)> = edited
+        .control_points
+        .iter()
+        .map(ControlPoint::as_tuple)
+        .collect();
+
+    VersionGeometry::from_full(edited.geometry.clone(), &SimplifyOptions::default(), &keep).map_err(
+        |e| {
+            tracing::error!("Geometric simplification failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        },
+    )
+}
+
+fn operation_failed(id: Uuid, e: anyhow::Error) -> StatusCode {
+    tracing::warn!("Route operation on {} rejected: {}", id, e);
+    StatusCode::UNPROCESSABLE_ENTITY
+}
+
+/// Store an edited route as a new version of `route` (owner only)
+async fn apply_edit(
+    state: &AppState,
+    auth_user: &AuthUser,
+    route: Route,
+    edited: EditedRoute,
+) -> Result<RouteWithGeometry, StatusCode> {
+    let auth_user_uuid = Uuid::parse_str(&auth_user.id).map_err(|e| {
+        tracing::error!("Failed to parse user ID: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let version = version_geometry(&edited)?;
+    let control_points = edited.control_points;
+
+    let mut tx = RlsTransaction::begin(&state.pool, auth_user)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to start RLS transaction: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    store_route_version(&mut tx, auth_user, route.id, &control_points, &version).await?;
+
+    tx.commit().await.map_err(|e| {
+        tracing::error!("Failed to commit transaction: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(RouteWithGeometry {
+        route: Route {
+            control_points: sqlx::types::Json(control_points),
+            ..route
+        },
+        geometry: version.geometry,
+        length_km: None,
+        estimated_time_min: None,
+        created_by: Some(auth_user_uuid),
+        hazards: None,
+    })
+}
+
+/// Reverse a route's direction (requires authentication and ownership)
+///
+/// Reverses control points and geometry and stores them as a new version.
+pub async fn reverse_route(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RouteWithGeometry>, StatusCode> {
+    let source = fetch_route(&state.pool, id, GeometryResolution::Full).await?;
+
+    let edited = operations::reverse(&source.route.control_points, &source.geometry)
+        .map_err(|e| operation_failed(id, e))?;
+
+    Ok(Json(
+        apply_edit(&state, &auth_user, source.route, edited).await?,
+    ))
+}
+
+/// Trim a route before or after a control point (requires authentication and ownership)
+pub async fn trim_route(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<TrimRoute>,
+) -> Result<Json<RouteWithGeometry>, StatusCode> {
+    let source = fetch_route(&state.pool, id, GeometryResolution::Full).await?;
+
+    let edited = operations::trim(
+        &source.route.control_points,
+        &source.geometry,
+        payload.point_index,
+        payload.side,
+    )
+    .map_err(|e| operation_failed(id, e))?;
+
+    Ok(Json(
+        apply_edit(&state, &auth_user, source.route, edited).await?,
+    ))
+}
+
+/// Join another route onto the end of this one (requires authentication and ownership)
+///
+/// Only the first route receives a new version; the other route is unchanged.
+pub async fn join_routes(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<JoinRoute>,
+) -> Result<Json<RouteWithGeometry>, StatusCode> {
+    if payload.other_route_id == id {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let first = fetch_route(&state.pool, id, GeometryResolution::Full).await?;
+    let second = fetch_route(
+        &state.pool,
+        payload.other_route_id,
+        GeometryResolution::Full,
+    )
+    .await?;
+
+    let edited = operations::join(
+        &first.route.control_points,
+        &first.geometry,
+        &second.route.control_points,
+        &second.geometry,
+    )
+    .map_err(|e| operation_failed(id, e))?;
+
+    Ok(Json(
+        apply_edit(&state, &auth_user, first.route, edited).await?,
+    ))
+}
+
+/// Split a route at a control point into two new routes (requires authentication)
+///
+/// The original route is left untouched. Both halves are created as routes
+/// owned by the authenticated user; RLS policy "Users create own routes"
+/// checks `owner_id = auth.uid()`.
+pub async fn split_route(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SplitRoute>,
+) -> Result<Json<Vec<RouteWithGeometry>>, StatusCode> {
+    let owner_id = Uuid::parse_str(&auth_user.id).map_err(|e| {
+        tracing::error!("Failed to parse user ID: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let source = fetch_route(&state.pool, id, GeometryResolution::Full).await?;
+
+    let (first, second) = operations::split_at(
+        &source.route.control_points,
+        &source.geometry,
+        payload.point_index,
+    )
+    .map_err(|e| operation_failed(id, e))?;
+
+    let mut tx = RlsTransaction::begin(&state.pool, &auth_user)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to start RLS transaction: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let mut created = Vec::with_capacity(2);
+    for (part, edited) in [(1, first), (2, second)] {
+        let name = format!("{} ({}/2)", source.route.name, part);
+        let version = version_geometry(&edited)?;
+
+        let route = sqlx::query_as::<_, Route>(
+            "INSERT INTO routes (name, owner_id, control_points) VALUES ($1, $2, $3) RETURNING *",
+        )
+        .bind(&name)
+        .bind(owner_id)
+        .bind(sqlx::types::Json(&edited.control_points))
+        .persistent(false)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to create split route: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+        store_route_version(
+            &mut tx,
+            &auth_user,
+            route.id,
+            &edited.control_points,
+            &version,
+        )
+        .await?;
+
+        created.push(RouteWithGeometry {
+            route,
+            geometry: version.geometry,
+            length_km: None,
+            estimated_time_min: None,
+            created_by: Some(owner_id),
+            hazards: None,
+        });
+    }
+
+    tx.commit().await.map_err(|e| {
+        tracing::error!("Failed to commit transaction: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(created))
+}
@@ -0,0 +1,386 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::{FromRow, PgConnection};
+use uuid::Uuid;
+
+use crate::db::{RevisionContext, RevisionKind};
+use crate::geometry::quality::assess_line;
+use crate::geometry::simplification::{simplify_line, SimplifyOptions};
+use crate::geometry::trace::parse_gpx_segments;
+use crate::middleware::{AuthUser, Curator};
+use crate::models::{
+    CreateSubmission, GpxSubmissionQuery, ReviewSubmission, SubmissionOverlap,
+    SubmissionQueueEntry, TrackSubmission,
+};
+use crate::routes::error::ApiError;
+use crate::routes::trace_validation::run_blocking;
+use crate::routes::tracks::{
+    begin, check_attributes, commit, set_revision_context, track_geometry, user_id, write_error,
+};
+use crate::AppState;
+
+/// Columns of a `TrackSubmission`
+const SUBMISSION_COLUMNS: &str = "id, submitted_by, ST_AsGeoJSON(geometry)::jsonb AS geometry, \
+     origin, surface, region, comment, recorded_on, status, reviewed_by, reviewed_at, \
+     review_comment, track_id, created_at";
+
+/// Pending submissions returned per queue request, oldest first
+const MAX_QUEUE: i64 = 100;
+
+/// How close a submission must run to a curated track to count as overlap
+const OVERLAP_DISTANCE_M: f64 = 50.0;
+
+#[derive(FromRow)]
+struct QueueRow {
+    #[sqlx(flatten)]
+    submission: TrackSubmission,
+    overlap_m: f64,
+    track_ids: Vec<Uuid>,
+}
+
+struct NewSubmission<'a> {
+    geometry: String,
+    origin: &'a str,
+    surface: Option<&'a str>,
+    region: Option<&'a str>,
+    comment: Option<&'a str>,
+    recorded_on: Option<chrono::NaiveDate>,
+}
+
+async fn insert_submission(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    submission: NewSubmission<'_>,
+) -> Result<TrackSubmission, StatusCode> {
+    // RLS policy "Users submit tracks" checks submitted_by = auth.uid()
+    sqlx::query_as::<_, TrackSubmission>(&format!(
+        "INSERT INTO track_submissions
+             (submitted_by, geometry, origin, surface, region, comment, recorded_on)
+         VALUES ($1, ST_SetSRID(ST_GeomFromGeoJSON($2), 4326), $3, $4, $5, $6, $7)
+         RETURNING {}",
+        SUBMISSION_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(submission.geometry)
+    .bind(submission.origin)
+    .bind(submission.surface)
+    .bind(submission.region)
+    .bind(submission.comment)
+    .bind(submission.recorded_on)
+    .persistent(false)
+    .fetch_one(conn)
+    .await
+    .map_err(|e| write_error("create submission", e))
+}
+
+/// Submit a drawn track for curator review (requires authentication)
+pub async fn create_submission(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Json(payload): Json<CreateSubmission>,
+) -> Result<Json<TrackSubmission>, ApiError> {
+    let geometry = track_geometry(&payload.geometry, payload.repair)?;
+
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let submission = insert_submission(
+        &mut tx,
+        user_id,
+        NewSubmission {
+            geometry,
+            origin: "drawn",
+            surface: payload.surface.as_deref(),
+            region: payload.region.as_deref(),
+            comment: payload.comment.as_deref(),
+            recorded_on: None,
+        },
+    )
+    .await?;
+
+    commit(tx).await?;
+    tracing::info!("User {} submitted track {}", user_id, submission.id);
+
+    Ok(Json(submission))
+}
+
+/// One segment of a GPX submission, simplified
+struct GpxLine {
+    line: serde_json::Value,
+    recorded_on: Option<chrono::NaiveDate>,
+    recorded_points: usize,
+}
+
+/// Split a GPX document into its segments and drop GPS jitter from each
+fn gpx_lines(body: &str) -> Result<Vec<GpxLine>, StatusCode> {
+    let segments = parse_gpx_segments(body).map_err(|e| {
+        tracing::warn!("Rejected GPX submission: {}", e);
+        StatusCode::UNPROCESSABLE_ENTITY
+    })?;
+
+    Ok(segments
+        .iter()
+        .map(|segment| {
+            let points: Vec<(f64, f64)> = segment.iter().map(|p| p.position()).collect();
+            let simplified = simplify_line(
+                &points,
+                vec![false; points.len()],
+                &SimplifyOptions::default(),
+            );
+            GpxLine {
+                line: serde_json::json!({
+                    "type": "LineString",
+                    "coordinates": simplified
+                        .iter()
+                        .map(|&(lng, lat)| vec![lng, lat])
+                        .collect::<Vec<_>>(),
+                }),
+                recorded_on: segment.iter().find_map(|p| p.time).map(|t| t.date_naive()),
+                recorded_points: points.len(),
+            }
+        })
+        .collect())
+}
+
+/// Submit a recorded GPX trace for curator review (requires authentication)
+///
+/// The body is the GPX document. Each track segment becomes its own
+/// submission, simplified to drop GPS jitter before it is stored: a gap
+/// between segments is where the recording stopped, and joining them would
+/// draw a track that isn't there.
+pub async fn create_gpx_submission(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Query(query): Query<GpxSubmissionQuery>,
+    body: String,
+) -> Result<Json<Vec<TrackSubmission>>, ApiError> {
+    let lines = run_blocking(move || gpx_lines(&body)).await?;
+    let geometries = lines
+        .iter()
+        .map(|gpx_line| track_geometry(&gpx_line.line, true))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let mut submissions = Vec::with_capacity(lines.len());
+    for (gpx_line, geometry) in lines.iter().zip(geometries) {
+        let submission = insert_submission(
+            &mut tx,
+            user_id,
+            NewSubmission {
+                geometry,
+                origin: "gpx",
+                surface: query.surface.as_deref(),
+                region: query.region.as_deref(),
+                comment: query.comment.as_deref(),
+                recorded_on: gpx_line.recorded_on,
+            },
+        )
+        .await?;
+        submissions.push(submission);
+    }
+
+    commit(tx).await?;
+    tracing::info!(
+        "User {} submitted {} GPX track(s) from {} recorded points",
+        user_id,
+        submissions.len(),
+        lines.iter().map(|l| l.recorded_points).sum::<usize>()
+    );
+
+    Ok(Json(submissions))
+}
+
+/// The authenticated user's submissions, newest first
+pub async fn list_my_submissions(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<TrackSubmission>>, StatusCode> {
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let submissions = sqlx::query_as::<_, TrackSubmission>(&format!(
+        "SELECT {} FROM track_submissions
+         WHERE submitted_by = $1
+         ORDER BY created_at DESC",
+        SUBMISSION_COLUMNS
+    ))
+    .bind(user_id)
+    .persistent(false)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch submissions: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    commit(tx).await?;
+
+    Ok(Json(submissions))
+}
+
+/// Pending submissions, oldest first, with overlap and quality checks (curators only)
+pub async fn submission_queue(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<SubmissionQueueEntry>>, StatusCode> {
+    let mut tx = begin(&state, &auth_user).await?;
+
+    // Overlap is the length of the submission inside a buffer around nearby tracks
+    let rows = sqlx::query_as::<_, QueueRow>(&format!(
+        "SELECT {}, ov.overlap_m, ov.track_ids
+         FROM (
+             SELECT * FROM track_submissions
+             WHERE status = 'pending'
+             ORDER BY created_at, id
+             LIMIT $1
+         ) s
+         CROSS JOIN LATERAL (
+             SELECT
+                 COALESCE(ST_Length(ST_Intersection(
+                     s.geometry,
+                     ST_Union(ST_Buffer(t.geometry::geography, $2)::geometry)
+                 )::geography), 0) AS overlap_m,
+                 COALESCE(array_agg(t.id), '{{}}') AS track_ids
+             FROM curated_tracks t
+             WHERE ST_DWithin(t.geometry::geography, s.geometry::geography, $2)
+         ) ov
+         ORDER BY created_at, id",
+        SUBMISSION_COLUMNS
+    ))
+    .bind(MAX_QUEUE)
+    .bind(OVERLAP_DISTANCE_M)
+    .persistent(false)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch submission queue: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    commit(tx).await?;
+
+    let queue = rows
+        .into_iter()
+        .map(|row| SubmissionQueueEntry {
+            quality: assess_line(&row.submission.geometry.points(), row.overlap_m),
+            overlap: SubmissionOverlap {
+                length_m: row.overlap_m,
+                track_ids: row.track_ids,
+            },
+            submission: row.submission,
+        })
+        .collect();
+
+    Ok(Json(queue))
+}
+
+/// Approve or reject a pending submission (curators only)
+///
+/// Approval inserts the line into `curated_tracks` with `source = 'curated'`
+/// and the chosen confidence, defaulting by how the line was captured.
+pub async fn review_submission(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ReviewSubmission>,
+) -> Result<Json<TrackSubmission>, StatusCode> {
+    let approve = match payload.status.as_str() {
+        "approved" => true,
+        "rejected" => false,
+        _ => return Err(StatusCode::UNPROCESSABLE_ENTITY),
+    };
+
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let submission = sqlx::query_as::<_, TrackSubmission>(&format!(
+        "SELECT {} FROM track_submissions WHERE id = $1 FOR UPDATE",
+        SUBMISSION_COLUMNS
+    ))
+    .bind(id)
+    .persistent(false)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch submission: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    if submission.status != "pending" {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let track_id = if approve {
+        let confidence = payload
+            .confidence
+            .unwrap_or_else(|| submission.default_confidence());
+        check_attributes(Some("curated"), Some(confidence))?;
+
+        let reason = format!("Approved submission {}", id);
+        let context = RevisionContext::new(RevisionKind::Edit).reason(Some(&reason));
+        set_revision_context(&mut tx, context).await?;
+
+        let track_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO curated_tracks
+                 (geometry, source, surface, confidence, last_verified, region, created_by, updated_by)
+             SELECT geometry, 'curated', COALESCE($2, surface), $3, recorded_on,
+                    COALESCE($4, region), $5, $5
+             FROM track_submissions
+             WHERE id = $1
+             RETURNING id",
+        )
+        .bind(id)
+        .bind(&payload.surface)
+        .bind(confidence)
+        .bind(&payload.region)
+        .bind(user_id)
+        .persistent(false)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| write_error("approve submission", e))?;
+
+        Some(track_id)
+    } else {
+        None
+    };
+
+    let reviewed = sqlx::query_as::<_, TrackSubmission>(&format!(
+        "UPDATE track_submissions SET
+             status = $2,
+             reviewed_by = $3,
+             reviewed_at = NOW(),
+             review_comment = $4,
+             track_id = $5
+         WHERE id = $1
+         RETURNING {}",
+        SUBMISSION_COLUMNS
+    ))
+    .bind(id)
+    .bind(&payload.status)
+    .bind(user_id)
+    .bind(&payload.comment)
+    .bind(track_id)
+    .persistent(false)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| write_error("review submission", e))?;
+
+    commit(tx).await?;
+    tracing::info!(
+        "Curator {} {} submission {}{}",
+        user_id,
+        payload.status,
+        id,
+        track_id
+            .map(|t| format!(" as track {}", t))
+            .unwrap_or_default()
+    );
+
+    Ok(Json(reviewed))
+}
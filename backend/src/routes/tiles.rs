@@ -0,0 +1,101 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use sqlx::{Postgres, QueryBuilder};
+
+use crate::models::TrackQuery;
+use crate::routes::tracks::push_track_filters;
+use crate::AppState;
+
+/// Deepest zoom tiles are generated for; clients overzoom beyond it
+const MAX_TILE_ZOOM: u32 = 22;
+
+/// Tile coordinate space and geometry buffer, in MVT units
+const TILE_EXTENT: i32 = 4096;
+const TILE_BUFFER: i32 = 64;
+
+const MVT_CONTENT_TYPE: &str = "application/vnd.mapbox-vector-tile";
+
+/// Tracks are re-imported and curated, not edited live; a few minutes of
+/// staleness is fine and revalidation is cheap thanks to the ETag
+const TILE_CACHE_CONTROL: &str = "public, max-age=300, stale-while-revalidate=3600";
+
+/// Curated tracks as a Mapbox Vector Tile (public endpoint)
+///
+/// One `tracks` layer with id, source, surface, confidence and last_verified
+/// attributes. Honors the `TrackQuery` filters. Responses carry an ETag of
+/// the tile bytes; a matching `If-None-Match` gets 304, an empty tile 204.
+pub async fn get_track_tile(
+    State(state): State<AppState>,
+    Path((z, x, y)): Path<(u32, u32, String)>,
+    Query(query): Query<TrackQuery>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let y: u32 = y
+        .strip_suffix(".mvt")
+        .ok_or(StatusCode::NOT_FOUND)?
+        .parse()
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+    if z > MAX_TILE_ZOOM || x >= 1 << z || y >= 1 << z {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let mut sql = QueryBuilder::<Postgres>::new("WITH bounds AS (SELECT ST_TileEnvelope(");
+    sql.push_bind(z as i32)
+        .push(", ")
+        .push_bind(x as i32)
+        .push(", ")
+        .push_bind(y as i32)
+        .push(") AS env), features AS (SELECT ST_AsMVTGeom(ST_Transform(geometry, 3857), bounds.env, ")
+        .push_bind(TILE_EXTENT)
+        .push(", ")
+        .push_bind(TILE_BUFFER)
+        .push(
+            ", true) AS geom, id::text AS id, source, surface, confidence, last_verified::text AS last_verified
+             FROM curated_tracks, bounds
+             WHERE geometry && ST_Transform(bounds.env, 4326)",
+        );
+    push_track_filters(&mut sql, &query)?;
+    sql.push("), tile AS (SELECT ST_AsMVT(features, 'tracks', ")
+        .push_bind(TILE_EXTENT)
+        .push(
+            ", 'geom') AS data FROM features WHERE geom IS NOT NULL)
+         SELECT COALESCE(data, ''::bytea), md5(COALESCE(data, ''::bytea)) FROM tile",
+        );
+
+    let (data, hash) = sql
+        .build_query_as::<(Vec<u8>, String)>()
+        .persistent(false)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to build track tile {}/{}/{}: {}", z, x, y, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let etag = format!("\"{}\"", hash);
+    let cache_headers = [
+        (header::ETAG, etag.clone()),
+        (header::CACHE_CONTROL, TILE_CACHE_CONTROL.to_string()),
+    ];
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|tags| tags.split(',').any(|t| t.trim() == etag || t.trim() == "*"));
+    if not_modified {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+    if data.is_empty() {
+        return Ok((StatusCode::NO_CONTENT, cache_headers).into_response());
+    }
+
+    Ok((
+        cache_headers,
+        [(header::CONTENT_TYPE, MVT_CONTENT_TYPE)],
+        data,
+    )
+        .into_response())
+}
@@ -0,0 +1,229 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::db::{rebuild_topology, DEFAULT_TOPOLOGY_TOLERANCE_M};
+use crate::geometry::topology::MAX_TOLERANCE_M;
+use crate::middleware::Curator;
+use crate::models::{
+    ComponentGap, DanglingEnd, DiagnosticsQuery, RebuildTopology, TopologyBuild, TopologyComponent,
+    TopologyStatus,
+};
+use crate::routes::tracks::{begin, commit, user_id};
+use crate::AppState;
+
+/// Default and maximum rows per diagnostics request
+const MAX_DIAGNOSTICS: i64 = 500;
+
+/// Default search distance for dangling ends (meters)
+const DEFAULT_DANGLING_DISTANCE_M: f64 = 200.0;
+
+/// Largest accepted dangling-end search distance (meters)
+const MAX_DANGLING_DISTANCE_M: f64 = 5_000.0;
+
+fn limit(query: &DiagnosticsQuery) -> Result<i64, StatusCode> {
+    match query.limit {
+        Some(n) if !(1..=MAX_DIAGNOSTICS).contains(&n) => Err(StatusCode::BAD_REQUEST),
+        n => Ok(n.unwrap_or(MAX_DIAGNOSTICS)),
+    }
+}
+
+fn query_error(what: &str, e: sqlx::Error) -> StatusCode {
+    tracing::error!("Failed to fetch {}: {}", what, e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+/// Latest topology build, and whether tracks changed since (public endpoint)
+pub async fn get_topology_status(
+    State(state): State<AppState>,
+) -> Result<Json<TopologyStatus>, StatusCode> {
+    let build = sqlx::query_as::<_, TopologyBuild>(
+        "SELECT * FROM track_topology_builds ORDER BY built_at DESC LIMIT 1",
+    )
+    .persistent(false)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| query_error("topology build", e))?;
+
+    // Every track change leaves a revision
+    let stale = match &build {
+        Some(build) => sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM curated_track_revisions WHERE created_at > $1)",
+        )
+        .bind(build.built_at)
+        .persistent(false)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(|e| query_error("track changes", e))?,
+        None => true,
+    };
+
+    Ok(Json(TopologyStatus { build, stale }))
+}
+
+/// Rebuild the track network topology from all curated tracks (curators only)
+pub async fn rebuild_track_topology(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+    Json(payload): Json<RebuildTopology>,
+) -> Result<Json<TopologyBuild>, StatusCode> {
+    let tolerance_m = payload.tolerance_m.unwrap_or(DEFAULT_TOPOLOGY_TOLERANCE_M);
+    if !(0.0..=MAX_TOLERANCE_M).contains(&tolerance_m) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let build = rebuild_topology(&mut tx, tolerance_m, Some(user_id))
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to rebuild track topology: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    commit(tx).await?;
+    tracing::info!(
+        "Curator {} rebuilt topology: {} nodes, {} edges, {} components",
+        user_id,
+        build.node_count,
+        build.edge_count,
+        build.component_count
+    );
+
+    Ok(Json(build))
+}
+
+/// Connected components of the network, largest first (public endpoint)
+pub async fn list_components(
+    State(state): State<AppState>,
+    Query(query): Query<DiagnosticsQuery>,
+) -> Result<Json<Vec<TopologyComponent>>, StatusCode> {
+    let components = sqlx::query_as::<_, TopologyComponent>(
+        "SELECT component,
+                COUNT(*) AS edge_count,
+                COUNT(DISTINCT track_id) AS track_count,
+                SUM(length_m) AS length_m,
+                ARRAY[ST_XMin(ST_Extent(geometry)), ST_YMin(ST_Extent(geometry)),
+                      ST_XMax(ST_Extent(geometry)), ST_YMax(ST_Extent(geometry))] AS bbox
+         FROM track_edges
+         GROUP BY component
+         ORDER BY length_m DESC, component
+         LIMIT $1",
+    )
+    .bind(limit(&query)?)
+    .persistent(false)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| query_error("topology components", e))?;
+
+    Ok(Json(components))
+}
+
+/// Dead ends within `max_distance_m` of another track, closest first (public endpoint)
+///
+/// These are the likeliest missing links; `bridges_components` marks the
+/// ones that would reconnect a separate piece of the network.
+pub async fn list_dangling_ends(
+    State(state): State<AppState>,
+    Query(query): Query<DiagnosticsQuery>,
+) -> Result<Json<Vec<DanglingEnd>>, StatusCode> {
+    let max_distance_m = query.max_distance_m.unwrap_or(DEFAULT_DANGLING_DISTANCE_M);
+    if !(0.0..=MAX_DANGLING_DISTANCE_M).contains(&max_distance_m) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    // Index prefilter in degrees, generous up to 60° latitude
+    let max_distance_deg = max_distance_m / 111_320.0 * 2.0;
+
+    let ends = sqlx::query_as::<_, DanglingEnd>(
+        "SELECT n.id AS node_id, ST_X(n.geometry) AS lng, ST_Y(n.geometry) AS lat,
+                own.track_id, near.track_id AS nearest_track_id, near.distance_m,
+                near.component <> n.component AS bridges_components
+         FROM track_nodes n
+         CROSS JOIN LATERAL (
+             SELECT track_id FROM track_edges
+             WHERE source_node = n.id OR target_node = n.id
+             LIMIT 1
+         ) own
+         CROSS JOIN LATERAL (
+             SELECT e.track_id, e.component,
+                    ST_Distance(e.geometry::geography, n.geometry::geography) AS distance_m
+             FROM track_edges e
+             WHERE e.geometry && ST_Expand(n.geometry, $2)
+               AND e.track_id <> own.track_id
+               AND ST_DWithin(e.geometry::geography, n.geometry::geography, $1)
+             ORDER BY distance_m
+             LIMIT 1
+         ) near
+         WHERE n.degree = 1
+         ORDER BY near.distance_m, n.id
+         LIMIT $3",
+    )
+    .bind(max_distance_m)
+    .bind(max_distance_deg)
+    .bind(limit(&query)?)
+    .persistent(false)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| query_error("dangling ends", e))?;
+
+    Ok(Json(ends))
+}
+
+/// How far each component lies from the rest of the network, largest gap
+/// first (curators only)
+///
+/// `max_distance_m` leaves out components farther than that, such as
+/// separate regions. Compares every pair of components, so it is meant for
+/// occasional review rather than interactive use.
+pub async fn list_component_gaps(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+    Query(query): Query<DiagnosticsQuery>,
+) -> Result<Json<Vec<ComponentGap>>, StatusCode> {
+    if query
+        .max_distance_m
+        .is_some_and(|d| !d.is_finite() || d < 0.0)
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let gaps = sqlx::query_as::<_, ComponentGap>(
+        "WITH components AS (
+             SELECT component, SUM(length_m) AS length_m, ST_Collect(geometry) AS geometry
+             FROM track_edges
+             GROUP BY component
+         )
+         SELECT c.component, c.length_m, near.component AS nearest_component, near.gap_m,
+                ARRAY[ST_X(near.from_point), ST_Y(near.from_point)] AS \"from\",
+                ARRAY[ST_X(near.to_point), ST_Y(near.to_point)] AS \"to\"
+         FROM components c
+         CROSS JOIN LATERAL (
+             SELECT o.component,
+                    ST_Distance(c.geometry::geography, o.geometry::geography) AS gap_m,
+                    ST_ClosestPoint(c.geometry, o.geometry) AS from_point,
+                    ST_ClosestPoint(o.geometry, c.geometry) AS to_point
+             FROM components o
+             WHERE o.component <> c.component
+             ORDER BY ST_Distance(c.geometry, o.geometry)
+             LIMIT 1
+         ) near
+         WHERE $1::float8 IS NULL OR near.gap_m <= $1
+         ORDER BY near.gap_m DESC, c.component
+         LIMIT $2",
+    )
+    .bind(query.max_distance_m)
+    .bind(limit(&query)?)
+    .persistent(false)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| query_error("component gaps", e))?;
+
+    commit(tx).await?;
+
+    Ok(Json(gaps))
+}
@@ -0,0 +1,210 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::geometry::deviation::{analyze_deviation, CandidateTrack, DeviationReport};
+use crate::geometry::linear_ref::LinearRoute;
+use crate::geometry::passage::{validate_passage, PassageReport};
+use crate::geometry::trace::{parse_gpx, TracePoint};
+use crate::geometry::types::MultiLineGeometry;
+use crate::middleware::AuthUser;
+use crate::models::{CreateProposal, CuratedTrack, DeviationQuery, ValidateTraceQuery};
+use crate::routes::route_handlers::fetch_route_version;
+use crate::AppState;
+
+/// Largest accepted GPX upload (bytes); a 12 hour stage logged at 1 Hz is
+/// about 4 MB
+pub const MAX_TRACE_BYTES: usize = 5 * 1024 * 1024;
+
+const DEFAULT_CORRIDOR_M: f64 = 200.0;
+
+const DEFAULT_DEVIATION_THRESHOLD_M: f64 = 100.0;
+
+/// Upper bound on curated tracks considered for one trace
+const MAX_CANDIDATE_TRACKS: i64 = 5000;
+
+#[derive(Debug, Serialize)]
+pub struct DeviationResponse {
+    #[serde(flatten)]
+    pub report: DeviationReport,
+    /// Ready-to-submit bodies for `POST /api/proposals`, one per stretch that
+    /// follows a curated track
+    pub proposals: Vec<CreateProposal>,
+}
+
+fn parse_trace(route_id: Uuid, body: &str) -> Result<Vec<TracePoint>, StatusCode> {
+    parse_gpx(body).map_err(|e| {
+        tracing::warn!("Rejected GPX trace for route {}: {}", route_id, e);
+        StatusCode::UNPROCESSABLE_ENTITY
+    })
+}
+
+fn planned_route(route_id: Uuid, geometry: &serde_json::Value) -> Result<LinearRoute, StatusCode> {
+    LinearRoute::from_geometry(geometry).map_err(|e| {
+        tracing::warn!("Route {} has no usable geometry: {}", route_id, e);
+        StatusCode::UNPROCESSABLE_ENTITY
+    })
+}
+
+/// Run trace parsing and analysis off the async runtime
+pub(crate) async fn run_blocking<T, F>(f: F) -> Result<T, StatusCode>
+where
+    F: FnOnce() -> Result<T, StatusCode> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await.map_err(|e| {
+        tracing::error!("Trace analysis task failed: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+}
+
+/// Curated tracks whose bounding box lies within `margin_m` of the trace
+async fn fetch_candidate_tracks(
+    state: &AppState,
+    trace: &[TracePoint],
+    margin_m: f64,
+) -> Result<Vec<CandidateTrack>, StatusCode> {
+    let (mut min_lng, mut min_lat) = (f64::INFINITY, f64::INFINITY);
+    let (mut max_lng, mut max_lat) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for p in trace {
+        min_lng = min_lng.min(p.lng);
+        min_lat = min_lat.min(p.lat);
+        max_lng = max_lng.max(p.lng);
+        max_lat = max_lat.max(p.lat);
+    }
+    // Degrees of latitude are the shortest, so this over-covers longitude
+    let margin_deg = margin_m
+        / 111_320.0
+        / min_lat
+            .abs()
+            .max(max_lat.abs())
+            .min(89.0)
+            .to_radians()
+            .cos();
+
+    let tracks = sqlx::query_as::<_, CuratedTrack>(
+        "SELECT id, ST_AsGeoJSON(geometry)::jsonb as geometry, source, surface, confidence, last_verified, region
+         FROM curated_tracks
+         WHERE geometry && ST_Expand(ST_MakeEnvelope($1, $2, $3, $4, 4326), $5)
+         LIMIT $6",
+    )
+    .bind(min_lng)
+    .bind(min_lat)
+    .bind(max_lng)
+    .bind(max_lat)
+    .bind(margin_deg)
+    .bind(MAX_CANDIDATE_TRACKS)
+    .persistent(false)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch candidate tracks: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(tracks
+        .into_iter()
+        .filter_map(|track| {
+            LinearRoute::new(track.geometry.points())
+                .ok()
+                .map(|route| CandidateTrack {
+                    id: track.id,
+                    route,
+                })
+        })
+        .collect())
+}
+
+/// Validate a recorded GPX trace against a route
+///
+/// The request body is the raw GPX document. Reports which typed waypoints
+/// (WPV, WPM, DZ/FZ, ...) of the route version were validated, speeds inside
+/// DZ/FZ zones and how far the trace strayed from the planned geometry.
+pub async fn validate_trace(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ValidateTraceQuery>,
+    body: String,
+) -> Result<Json<PassageReport>, StatusCode> {
+    let corridor_m = query.corridor_m.unwrap_or(DEFAULT_CORRIDOR_M);
+    if !corridor_m.is_finite() || corridor_m <= 0.0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let version = fetch_route_version(&state.pool, id, query.version_id).await?;
+    let Some(control_points) = version.control_points else {
+        tracing::warn!(
+            "User {} validated a trace against route {} version without control points",
+            auth_user.id,
+            id
+        );
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    };
+
+    let report = run_blocking(move || {
+        let trace = parse_trace(id, &body)?;
+        let planned = planned_route(id, &version.geometry)?;
+        Ok(validate_passage(
+            &control_points,
+            &planned,
+            &trace,
+            corridor_m,
+        ))
+    })
+    .await?;
+
+    Ok(Json(report))
+}
+
+/// Compare a recorded GPX trace with a route
+///
+/// The request body is the raw GPX document. Lists stretches that left the
+/// plan by more than `threshold_m`, curated tracks driven off the plan and an
+/// adherence score. Stretches that follow a curated track come with a
+/// proposal body the client can submit through the regular proposals flow.
+pub async fn analyze_trace_deviation(
+    _auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<DeviationQuery>,
+    body: String,
+) -> Result<Json<DeviationResponse>, StatusCode> {
+    let threshold_m = query.threshold_m.unwrap_or(DEFAULT_DEVIATION_THRESHOLD_M);
+    if !threshold_m.is_finite() || threshold_m <= 0.0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let trace = run_blocking(move || parse_trace(id, &body)).await?;
+    let version = fetch_route_version(&state.pool, id, query.version_id).await?;
+    let tracks = fetch_candidate_tracks(&state, &trace, threshold_m).await?;
+
+    let report = run_blocking(move || {
+        let planned = planned_route(id, &version.geometry)?;
+        Ok(analyze_deviation(&planned, &trace, &tracks, threshold_m))
+    })
+    .await?;
+
+    let proposals = report
+        .stretches
+        .iter()
+        .filter_map(|stretch| {
+            let geometry = MultiLineGeometry::try_from(stretch.proposed_geometry.clone()?).ok()?;
+            let track_id = stretch.curated_track_id?;
+            Some(CreateProposal {
+                route_id: id,
+                geometry,
+                comment: format!(
+                    "Driven detour along curated track {} between km {:.1} and {:.1} ({:.1} km)",
+                    track_id, stretch.from_km, stretch.to_km, stretch.driven_km
+                ),
+            })
+        })
+        .collect();
+
+    Ok(Json(DeviationResponse { report, proposals }))
+}
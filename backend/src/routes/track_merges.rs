@@ -0,0 +1,310 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use geo::{HaversineLength, LineString};
+use uuid::Uuid;
+
+use crate::db::{detect_duplicates, DuplicateOptions, RevisionContext, RevisionKind};
+use crate::geometry::similarity::{merged_confidence, MergeSide};
+use crate::middleware::Curator;
+use crate::models::{
+    ApplyMerge, CuratedTrack, DetectDuplicatesQuery, DuplicateDetection, MergeProposalDetail,
+    TrackMergeProposal,
+};
+use crate::routes::tracks::{
+    begin, commit, set_revision_context, user_id, write_error, TRACK_COLUMNS,
+};
+use crate::AppState;
+
+/// Columns of a `TrackMergeProposal`
+const PROPOSAL_COLUMNS: &str = "id, track_a, track_b, keep_track_id, overlap_a, overlap_b, \
+     frechet_m, merged_confidence, status, detected_at, resolved_by, resolved_at";
+
+/// A locked track as one side of a merge
+fn merge_side(track: &CuratedTrack, overlap: f64) -> MergeSide<'_> {
+    let points = track.geometry.points();
+    MergeSide {
+        source: &track.source,
+        confidence: track.confidence,
+        last_verified: track.last_verified,
+        length_m: LineString::from(points.clone()).haversine_length(),
+        points: points.len(),
+        overlap,
+    }
+}
+
+/// Pending proposals returned per request, oldest first
+const MAX_PROPOSALS: i64 = 100;
+
+/// Look for duplicate tracks and record merge proposals (curators only)
+///
+/// The same detection the duplicate tracks job runs over the whole network,
+/// limited to `bbox` when given.
+pub async fn detect_track_duplicates(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+    Query(query): Query<DetectDuplicatesQuery>,
+) -> Result<Json<DuplicateDetection>, StatusCode> {
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let detection = detect_duplicates(&mut tx, query.bbox, &DuplicateOptions::default())
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to detect duplicate tracks: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    commit(tx).await?;
+    tracing::info!(
+        "Duplicate detection: {} candidates, {} proposals, {} rejected",
+        detection.candidates,
+        detection.proposed,
+        detection.rejected
+    );
+
+    Ok(Json(detection))
+}
+
+/// Pending merge proposals with both tracks (curators only)
+///
+/// Proposals whose tracks have since been deleted or merged are skipped.
+pub async fn list_merge_proposals(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<MergeProposalDetail>>, StatusCode> {
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let proposals = sqlx::query_as::<_, TrackMergeProposal>(&format!(
+        "SELECT {} FROM track_merge_proposals p
+         WHERE status = 'pending'
+           AND EXISTS (SELECT 1 FROM curated_tracks WHERE id = p.track_a)
+           AND EXISTS (SELECT 1 FROM curated_tracks WHERE id = p.track_b)
+         ORDER BY detected_at, id
+         LIMIT $1",
+        PROPOSAL_COLUMNS
+    ))
+    .bind(MAX_PROPOSALS)
+    .persistent(false)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch merge proposals: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let ids: Vec<Uuid> = proposals
+        .iter()
+        .flat_map(|p| [p.track_a, p.track_b])
+        .collect();
+    let tracks = sqlx::query_as::<_, CuratedTrack>(&format!(
+        "SELECT {} FROM curated_tracks WHERE id = ANY($1)",
+        TRACK_COLUMNS
+    ))
+    .bind(&ids)
+    .persistent(false)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch proposed tracks: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    commit(tx).await?;
+
+    let track = |id: Uuid| tracks.iter().find(|t| t.id == id).cloned();
+    let details = proposals
+        .into_iter()
+        .filter_map(|proposal| {
+            Some(MergeProposalDetail {
+                a: track(proposal.track_a)?,
+                b: track(proposal.track_b)?,
+                proposal,
+            })
+        })
+        .collect();
+
+    Ok(Json(details))
+}
+
+async fn lock_proposal(
+    conn: &mut sqlx::PgConnection,
+    id: Uuid,
+) -> Result<TrackMergeProposal, StatusCode> {
+    let proposal = sqlx::query_as::<_, TrackMergeProposal>(&format!(
+        "SELECT {} FROM track_merge_proposals WHERE id = $1 FOR UPDATE",
+        PROPOSAL_COLUMNS
+    ))
+    .bind(id)
+    .persistent(false)
+    .fetch_optional(conn)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch merge proposal: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    if proposal.status != "pending" {
+        return Err(StatusCode::CONFLICT);
+    }
+    Ok(proposal)
+}
+
+/// Merge the two tracks of a proposal (curators only)
+///
+/// Atomically keeps one geometry, folds the other track's sources,
+/// verification date and missing attributes into it with the confidence of
+/// the pair as they stand, and deletes the other track. Both changes are
+/// recorded as `merge` revisions, so the merge can be reverted. Returns the
+/// kept track.
+pub async fn merge_tracks(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ApplyMerge>,
+) -> Result<Json<CuratedTrack>, StatusCode> {
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let proposal = lock_proposal(&mut tx, id).await?;
+    let keep_id = payload.keep_track_id.unwrap_or(proposal.keep_track_id);
+    let remove_id = if keep_id == proposal.track_a {
+        proposal.track_b
+    } else if keep_id == proposal.track_b {
+        proposal.track_a
+    } else {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    };
+
+    let tracks = sqlx::query_as::<_, CuratedTrack>(&format!(
+        "SELECT {} FROM curated_tracks WHERE id = ANY($1) FOR UPDATE",
+        TRACK_COLUMNS
+    ))
+    .bind([keep_id, remove_id])
+    .persistent(false)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch tracks to merge: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    // A track already merged or deleted leaves the proposal stale
+    let (Some(keep), Some(remove)) = (
+        tracks.iter().find(|t| t.id == keep_id),
+        tracks.iter().find(|t| t.id == remove_id),
+    ) else {
+        return Err(StatusCode::CONFLICT);
+    };
+
+    // The tracks may have been edited since detection: rate them as they are now
+    let overlap = |track: &CuratedTrack| {
+        if track.id == proposal.track_a {
+            proposal.overlap_a
+        } else {
+            proposal.overlap_b
+        }
+    };
+    let confidence = merged_confidence(
+        &merge_side(keep, overlap(keep)),
+        &merge_side(remove, overlap(remove)),
+    );
+
+    let mut provenance = keep.provenance.clone();
+    for source in &remove.provenance {
+        if !provenance.contains(source) {
+            provenance.push(source.clone());
+        }
+    }
+
+    let reason = payload
+        .reason
+        .unwrap_or_else(|| format!("Merged duplicate track {} into {}", remove_id, keep_id));
+    let context = RevisionContext::new(RevisionKind::Merge).reason(Some(&reason));
+    set_revision_context(&mut tx, context).await?;
+
+    let merged = sqlx::query_as::<_, CuratedTrack>(&format!(
+        "UPDATE curated_tracks SET
+             confidence = $2,
+             last_verified = $3,
+             surface = $4,
+             region = $5,
+             provenance = $6,
+             updated_by = $7
+         WHERE id = $1
+         RETURNING {}",
+        TRACK_COLUMNS
+    ))
+    .bind(keep_id)
+    .bind(confidence)
+    .bind(keep.last_verified.max(remove.last_verified))
+    .bind(keep.surface.as_ref().or(remove.surface.as_ref()))
+    .bind(keep.region.as_ref().or(remove.region.as_ref()))
+    .bind(&provenance)
+    .bind(user_id)
+    .persistent(false)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| write_error("merge track", e))?;
+
+    sqlx::query("DELETE FROM curated_tracks WHERE id = $1")
+        .bind(remove_id)
+        .persistent(false)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| write_error("delete merged track", e))?;
+
+    sqlx::query(
+        "UPDATE track_merge_proposals SET
+             status = 'merged', keep_track_id = $2, resolved_by = $3, resolved_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(keep_id)
+    .bind(user_id)
+    .persistent(false)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| write_error("resolve merge proposal", e))?;
+
+    commit(tx).await?;
+    tracing::info!(
+        "Curator {} merged track {} into {}",
+        user_id,
+        remove_id,
+        keep_id
+    );
+
+    Ok(Json(merged))
+}
+
+/// Mark a proposal as not a duplicate; detection will not raise it again
+/// (curators only)
+pub async fn dismiss_merge_proposal(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TrackMergeProposal>, StatusCode> {
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    lock_proposal(&mut tx, id).await?;
+    let proposal = sqlx::query_as::<_, TrackMergeProposal>(&format!(
+        "UPDATE track_merge_proposals SET
+             status = 'dismissed', resolved_by = $2, resolved_at = NOW()
+         WHERE id = $1
+         RETURNING {}",
+        PROPOSAL_COLUMNS
+    ))
+    .bind(id)
+    .bind(user_id)
+    .persistent(false)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| write_error("dismiss merge proposal", e))?;
+
+    commit(tx).await?;
+
+    Ok(Json(proposal))
+}
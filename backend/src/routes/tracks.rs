@@ -3,34 +3,226 @@ use axum::{
     http::StatusCode,
     Json,
 };
+use serde_json::Value;
+use sqlx::{PgConnection, Postgres, QueryBuilder};
 use uuid::Uuid;
 
-use crate::models::{CuratedTrack, TrackQuery};
+use crate::db::{RevisionContext, RevisionKind, RlsTransaction};
+use crate::geometry::lod::zoom_tolerance_m;
+use crate::geometry::validate::{validate_single_line, GeometryValidationError, ValidationOptions};
+use crate::middleware::{AuthUser, Curator};
+use crate::models::{
+    BulkTrackChanges, BulkTrackResult, ChangeReason, CreateTrack, CreateVerification, CuratedTrack,
+    HazardReport, NearPoint, RegionRef, TrackCursor, TrackDetail, TrackList, TrackQuery,
+    TrackRevision, TrackVerification, UpdateTrack, HAZARD_SEVERITIES, PASSABILITY, TRACK_SOURCES,
+};
+use crate::routes::error::{is_permission_denied, ApiError};
+use crate::routes::hazards::HAZARD_COLUMNS_H;
 use crate::AppState;
 
+/// Default and maximum number of tracks per request
+const MAX_TRACKS: i64 = 1000;
+
+/// Most changes accepted in one bulk request
+const MAX_BULK_CHANGES: usize = 1000;
+
+/// Columns of a `CuratedTrack`
+pub(crate) const TRACK_COLUMNS: &str =
+    "id, ST_AsGeoJSON(geometry)::jsonb AS geometry, source, surface, \
+     confidence, last_verified, region, created_by, updated_by, provenance, osm_way_id";
+
+/// Columns of a `TrackVerification`
+const VERIFICATION_COLUMNS: &str = "id, track_id, user_id, driven_on, surface, passability, \
+     ST_AsGeoJSON(trace)::jsonb AS trace, created_at";
+
+/// Field reports returned with a track
+const MAX_VERIFICATIONS: i64 = 50;
+
+/// How far any point of a verification trace may stray from the track it reports on
+const MAX_TRACE_OFFSET_M: f64 = 500.0;
+
+/// Columns of a `TrackRevision`
+const REVISION_COLUMNS: &str = "id, track_id, operation, kind, \
+     ST_AsGeoJSON(geometry)::jsonb AS geometry, source, surface, confidence, last_verified, \
+     region, provenance, actor_id, job, reason, import_run_id, created_at";
+
+/// Hazards this close to a track are returned with it (meters)
+const TRACK_HAZARD_DISTANCE_M: f64 = 100.0;
+
+/// Largest proximity search radius (meters)
+const MAX_RADIUS_M: f64 = 200_000.0;
+
+/// Length of one degree of latitude (meters)
+const METERS_PER_DEGREE: f64 = 111_320.0;
+
+#[derive(sqlx::FromRow)]
+struct TrackRow {
+    #[sqlx(flatten)]
+    track: CuratedTrack,
+    /// Sort key of proximity searches (meters)
+    distance_m: Option<f64>,
+}
+
+fn push_point(sql: &mut QueryBuilder<'_, Postgres>, near: NearPoint) {
+    sql.push("ST_SetSRID(ST_MakePoint(")
+        .push_bind(near.lng)
+        .push(", ")
+        .push_bind(near.lat)
+        .push("), 4326)");
+}
+
+/// Append the `TrackQuery` filters to a query over `curated_tracks`
+///
+/// Expects the builder to end inside a `WHERE` clause. Returns the validated
+/// proximity search, if any, so callers can order by distance.
+pub(crate) fn push_track_filters(
+    sql: &mut QueryBuilder<'_, Postgres>,
+    query: &TrackQuery,
+) -> Result<Option<(NearPoint, f64)>, StatusCode> {
+    let confidence_ok = |c: Option<i32>| c.is_none_or(|c| (1..=5).contains(&c));
+    if !confidence_ok(query.min_confidence)
+        || !confidence_ok(query.max_confidence)
+        || query.min_confidence > query.max_confidence.or(Some(5))
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    if let Some(source) = &query.source {
+        sql.push(" AND source = ").push_bind(source.clone());
+    }
+    if let Some(min_confidence) = query.min_confidence {
+        sql.push(" AND confidence >= ").push_bind(min_confidence);
+    }
+    if let Some(max_confidence) = query.max_confidence {
+        sql.push(" AND confidence <= ").push_bind(max_confidence);
+    }
+    if let Some(region) = &query.region {
+        sql.push(
+            " AND id IN (SELECT tr.track_id FROM track_regions tr \
+             JOIN regions r ON r.id = tr.region_id WHERE r.code = ",
+        )
+        .push_bind(region.clone())
+        .push(")");
+    }
+    if !query.surface.is_empty() {
+        sql.push(" AND surface = ANY(")
+            .push_bind(query.surface.clone())
+            .push(")");
+    }
+    if let Some(verified_since) = query.verified_since {
+        sql.push(" AND last_verified >= ").push_bind(verified_since);
+    }
+    if let Some(bbox) = query.bbox {
+        sql.push(" AND geometry && ST_MakeEnvelope(")
+            .push_bind(bbox.min_lng)
+            .push(", ")
+            .push_bind(bbox.min_lat)
+            .push(", ")
+            .push_bind(bbox.max_lng)
+            .push(", ")
+            .push_bind(bbox.max_lat)
+            .push(", 4326)");
+    }
+    let near = match (query.near, query.radius_m) {
+        (Some(near), Some(radius_m)) if radius_m > 0.0 && radius_m <= MAX_RADIUS_M => {
+            Some((near, radius_m))
+        }
+        (None, None) => None,
+        _ => return Err(StatusCode::BAD_REQUEST),
+    };
+    if let Some((point, radius_m)) = near {
+        // Index-friendly envelope first, then the exact geodesic distance
+        let dy = radius_m / METERS_PER_DEGREE;
+        let dx = (dy / point.lat.to_radians().cos().max(0.01)).min(360.0);
+        sql.push(" AND geometry && ST_Expand(");
+        push_point(sql, point);
+        sql.push(", ")
+            .push_bind(dx)
+            .push(", ")
+            .push_bind(dy)
+            .push(") AND ST_DWithin(geometry::geography, ");
+        push_point(sql, point);
+        sql.push("::geography, ").push_bind(radius_m).push(")");
+    }
+
+    Ok(near)
+}
+
+/// List curated tracks (public endpoint)
+///
+/// `bbox` and `near`/`radius_m` filter through the GIST index on the track
+/// geometry. Pages of `limit` tracks are ordered by id, or closest first for
+/// proximity searches; `next_cursor` continues after the last one. `zoom`
+/// simplifies the returned geometry for display.
 pub async fn list_tracks(
     State(state): State<AppState>,
     Query(query): Query<TrackQuery>,
-) -> Result<Json<Vec<CuratedTrack>>, StatusCode> {
-    let mut sql = String::from(
-        "SELECT id, ST_AsGeoJSON(geometry)::jsonb as geometry, source, surface, confidence, last_verified, region
-         FROM curated_tracks
-         WHERE 1=1",
-    );
+) -> Result<Json<TrackList>, StatusCode> {
+    let limit = query.limit.unwrap_or(MAX_TRACKS);
+    if !(1..=MAX_TRACKS).contains(&limit) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let tolerance_deg = query
+        .zoom
+        .map(zoom_tolerance_m)
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?
+        .map(|m| m / METERS_PER_DEGREE);
 
-    if query.source.is_some() {
-        sql.push_str(" AND source = $1");
+    let mut sql = QueryBuilder::<Postgres>::new("SELECT id, ST_AsGeoJSON(");
+    match tolerance_deg {
+        Some(tolerance) => {
+            sql.push("ST_SimplifyPreserveTopology(geometry, ")
+                .push_bind(tolerance)
+                .push(")");
+        }
+        None => {
+            sql.push("geometry");
+        }
     }
-    if query.min_confidence.is_some() {
-        sql.push_str(" AND confidence >= $2");
+    sql.push(
+        ")::jsonb as geometry, source, surface, confidence, last_verified, region, \
+         created_by, updated_by, provenance, osm_way_id, ",
+    );
+    match query.near {
+        Some(point) => {
+            sql.push("ST_Distance(geometry::geography, ");
+            push_point(&mut sql, point);
+            sql.push("::geography)");
+        }
+        None => {
+            sql.push("NULL::float8");
+        }
     }
-    if query.region.is_some() {
-        sql.push_str(" AND region = $3");
+    sql.push(" AS distance_m FROM curated_tracks WHERE true");
+
+    let near = push_track_filters(&mut sql, &query)?;
+    match (near, query.cursor) {
+        (None, Some(TrackCursor::Id(after))) => {
+            sql.push(" AND id > ").push_bind(after);
+        }
+        (Some((point, _)), Some(TrackCursor::Distance(distance_m, after))) => {
+            sql.push(" AND (ST_Distance(geometry::geography, ");
+            push_point(&mut sql, point);
+            sql.push("::geography), id) > (")
+                .push_bind(distance_m)
+                .push(", ")
+                .push_bind(after)
+                .push(")");
+        }
+        (_, None) => {}
+        _ => return Err(StatusCode::BAD_REQUEST),
     }
 
-    sql.push_str(" LIMIT 1000");
+    sql.push(match near {
+        Some(_) => " ORDER BY distance_m, id",
+        None => " ORDER BY id",
+    });
+    sql.push(" LIMIT ").push_bind(limit + 1);
 
-    let tracks = sqlx::query_as::<_, CuratedTrack>(&sql)
+    let mut rows = sql
+        .build_query_as::<TrackRow>()
         .persistent(false)
         .fetch_all(&state.pool)
         .await
@@ -39,18 +231,34 @@ pub async fn list_tracks(
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
-    Ok(Json(tracks))
+    let truncated = rows.len() as i64 > limit;
+    rows.truncate(limit as usize);
+
+    let next_cursor = rows
+        .last()
+        .filter(|_| truncated)
+        .map(|last| match last.distance_m {
+            Some(distance_m) => TrackCursor::Distance(distance_m, last.track.id),
+            None => TrackCursor::Id(last.track.id),
+        })
+        .map(|cursor| cursor.to_string());
+
+    Ok(Json(TrackList {
+        tracks: rows.into_iter().map(|row| row.track).collect(),
+        truncated,
+        next_cursor,
+    }))
 }
 
+/// A curated track with its regions, active hazards and latest field verification reports
 pub async fn get_track(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Result<Json<CuratedTrack>, StatusCode> {
-    let track = sqlx::query_as::<_, CuratedTrack>(
-        "SELECT id, ST_AsGeoJSON(geometry)::jsonb as geometry, source, surface, confidence, last_verified, region
-         FROM curated_tracks
-         WHERE id = $1",
-    )
+) -> Result<Json<TrackDetail>, StatusCode> {
+    let track = sqlx::query_as::<_, CuratedTrack>(&format!(
+        "SELECT {} FROM curated_tracks WHERE id = $1",
+        TRACK_COLUMNS
+    ))
     .bind(id)
     .persistent(false)
     .fetch_one(&state.pool)
@@ -63,5 +271,626 @@ pub async fn get_track(
         }
     })?;
 
+    let verifications = sqlx::query_as::<_, TrackVerification>(&format!(
+        "SELECT {} FROM track_verifications
+         WHERE track_id = $1
+         ORDER BY driven_on DESC, created_at DESC
+         LIMIT $2",
+        VERIFICATION_COLUMNS
+    ))
+    .bind(id)
+    .bind(MAX_VERIFICATIONS)
+    .persistent(false)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch track verifications: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let regions = sqlx::query_as::<_, RegionRef>(
+        "SELECT r.id, r.code, r.name, r.level
+         FROM track_regions tr
+         JOIN regions r ON r.id = tr.region_id
+         WHERE tr.track_id = $1
+         ORDER BY r.parent_id NULLS FIRST, r.name",
+    )
+    .bind(id)
+    .persistent(false)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch track regions: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let hazards = sqlx::query_as::<_, HazardReport>(&format!(
+        "SELECT {} FROM active_hazard_reports h
+         WHERE ST_DWithin(
+             h.geometry::geography,
+             (SELECT geometry FROM curated_tracks WHERE id = $1)::geography,
+             $2
+         )
+         ORDER BY array_position($3, h.severity) DESC, h.created_at DESC",
+        HAZARD_COLUMNS_H
+    ))
+    .bind(id)
+    .bind(TRACK_HAZARD_DISTANCE_M)
+    .bind(HAZARD_SEVERITIES)
+    .persistent(false)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch track hazards: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(TrackDetail {
+        track,
+        regions,
+        hazards,
+        verifications,
+    }))
+}
+
+pub(crate) fn user_id(auth_user: &AuthUser) -> Result<Uuid, StatusCode> {
+    Uuid::parse_str(&auth_user.id).map_err(|e| {
+        tracing::error!("Failed to parse user ID: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+pub(crate) async fn begin<'a>(
+    state: &'a AppState,
+    auth_user: &AuthUser,
+) -> Result<RlsTransaction<'a>, StatusCode> {
+    RlsTransaction::begin(&state.pool, auth_user)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to start RLS transaction: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+pub(crate) async fn commit(tx: RlsTransaction<'_>) -> Result<(), StatusCode> {
+    tx.commit().await.map_err(|e| {
+        tracing::error!("Failed to commit transaction: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+/// Map a write failure; RLS denials surface as 403
+pub(crate) fn write_error(action: &str, e: sqlx::Error) -> StatusCode {
+    if is_permission_denied(&e) {
+        tracing::warn!("RLS denied {}: {}", action, e);
+        StatusCode::FORBIDDEN
+    } else {
+        tracing::error!("Failed to {}: {}", action, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+/// Describe the following track changes in their revisions
+pub(crate) async fn set_revision_context(
+    conn: &mut PgConnection,
+    context: RevisionContext<'_>,
+) -> Result<(), StatusCode> {
+    context.apply(conn).await.map_err(|e| {
+        tracing::error!("Failed to set revision context: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+/// Reject attributes the table constraints would refuse
+pub(crate) fn check_attributes(
+    source: Option<&str>,
+    confidence: Option<i32>,
+) -> Result<(), StatusCode> {
+    if source.is_some_and(|s| !TRACK_SOURCES.contains(&s))
+        || confidence.is_some_and(|c| !(1..=5).contains(&c))
+    {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+    Ok(())
+}
+
+/// Validate track geometry, returning GeoJSON text for `ST_GeomFromGeoJSON`
+pub(crate) fn track_geometry(
+    geometry: &Value,
+    repair: bool,
+) -> Result<String, GeometryValidationError> {
+    let validated = validate_single_line(
+        geometry,
+        &ValidationOptions {
+            repair,
+            ..Default::default()
+        },
+    )?;
+
+    if !validated.repaired.is_empty() {
+        tracing::info!(
+            "Repaired {} track geometry issues",
+            validated.repaired.len()
+        );
+    }
+
+    Ok(validated.geometry.to_string())
+}
+
+/// Prefix geometry issues with the bulk item they belong to
+fn in_item(error: ApiError, item: &str) -> ApiError {
+    match error {
+        ApiError::InvalidGeometry(mut e) => {
+            for issue in &mut e.issues {
+                issue.message = format!("{}: {}", item, issue.message);
+            }
+            ApiError::InvalidGeometry(e)
+        }
+        other => other,
+    }
+}
+
+async fn insert_track(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    track: &CreateTrack,
+) -> Result<CuratedTrack, ApiError> {
+    check_attributes(Some(&track.source), Some(track.confidence))?;
+    let geometry = track_geometry(&track.geometry, track.repair)?;
+
+    // RLS policy "Curators create tracks" checks is_curator() and the audit fields
+    let created = sqlx::query_as::<_, CuratedTrack>(&format!(
+        "INSERT INTO curated_tracks
+             (geometry, source, surface, confidence, last_verified, region, created_by, updated_by)
+         VALUES (ST_SetSRID(ST_GeomFromGeoJSON($1), 4326), $2, $3, $4, $5, $6, $7, $7)
+         RETURNING {}",
+        TRACK_COLUMNS
+    ))
+    .bind(geometry)
+    .bind(&track.source)
+    .bind(&track.surface)
+    .bind(track.confidence)
+    .bind(track.last_verified)
+    .bind(&track.region)
+    .bind(user_id)
+    .persistent(false)
+    .fetch_one(conn)
+    .await
+    .map_err(|e| write_error("create track", e))?;
+
+    Ok(created)
+}
+
+async fn update_track_row(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    id: Uuid,
+    changes: &UpdateTrack,
+) -> Result<CuratedTrack, ApiError> {
+    check_attributes(changes.source.as_deref(), changes.confidence)?;
+    let geometry = changes
+        .geometry
+        .as_ref()
+        .map(|g| track_geometry(g, changes.repair))
+        .transpose()?;
+
+    let updated = sqlx::query_as::<_, CuratedTrack>(&format!(
+        "UPDATE curated_tracks SET
+             geometry = COALESCE(ST_SetSRID(ST_GeomFromGeoJSON($2), 4326), geometry),
+             source = COALESCE($3, source),
+             surface = CASE WHEN $9 THEN $4 ELSE surface END,
+             confidence = COALESCE($5, confidence),
+             last_verified = COALESCE($6, last_verified),
+             region = CASE WHEN $10 THEN $7 ELSE region END,
+             updated_by = $8
+         WHERE id = $1
+         RETURNING {}",
+        TRACK_COLUMNS
+    ))
+    .bind(id)
+    .bind(geometry)
+    .bind(&changes.source)
+    .bind(changes.surface.as_ref().and_then(Option::as_deref))
+    .bind(changes.confidence)
+    .bind(changes.last_verified)
+    .bind(changes.region.as_ref().and_then(Option::as_deref))
+    .bind(user_id)
+    .bind(changes.surface.is_some())
+    .bind(changes.region.is_some())
+    .persistent(false)
+    .fetch_optional(conn)
+    .await
+    .map_err(|e| write_error("update track", e))?;
+
+    Ok(updated.ok_or(StatusCode::NOT_FOUND)?)
+}
+
+async fn delete_track_row(conn: &mut PgConnection, id: Uuid) -> Result<(), StatusCode> {
+    let result = sqlx::query("DELETE FROM curated_tracks WHERE id = $1")
+        .bind(id)
+        .persistent(false)
+        .execute(conn)
+        .await
+        .map_err(|e| write_error("delete track", e))?;
+
+    if result.rows_affected() == 0 {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(())
+}
+
+/// Create a curated track (curators only)
+///
+/// The geometry must be a single valid line; `repair` fixes swapped lat/lng
+/// and duplicate points.
+pub async fn create_track(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+    Json(payload): Json<CreateTrack>,
+) -> Result<Json<CuratedTrack>, ApiError> {
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let context = RevisionContext::new(RevisionKind::Edit).reason(payload.reason.as_deref());
+    set_revision_context(&mut tx, context).await?;
+    let track = insert_track(&mut tx, user_id, &payload).await?;
+
+    commit(tx).await?;
+    tracing::info!("Curator {} created track {}", user_id, track.id);
+
+    Ok(Json(track))
+}
+
+/// Change a curated track's geometry or attributes (curators only)
+pub async fn update_track(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateTrack>,
+) -> Result<Json<CuratedTrack>, ApiError> {
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let context = RevisionContext::new(RevisionKind::Edit).reason(payload.reason.as_deref());
+    set_revision_context(&mut tx, context).await?;
+    let track = update_track_row(&mut tx, user_id, id, &payload).await?;
+
+    commit(tx).await?;
+    tracing::info!("Curator {} updated track {}", user_id, id);
+
+    Ok(Json(track))
+}
+
+/// Delete a curated track (curators only)
+///
+/// The track's history is kept, so it can be restored with a revert.
+pub async fn delete_track(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ChangeReason>,
+) -> Result<StatusCode, ApiError> {
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let context = RevisionContext::new(RevisionKind::Edit).reason(query.reason.as_deref());
+    set_revision_context(&mut tx, context).await?;
+    delete_track_row(&mut tx, id).await?;
+
+    commit(tx).await?;
+    tracing::info!("Curator {} deleted track {}", user_id, id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Apply many track creates, updates and deletes atomically (curators only)
+///
+/// Any failure rolls back the whole batch; geometry issues name the item
+/// (`create[3]`, `update[0]`) they were found in. Items without their own
+/// `reason` are recorded with the batch's.
+pub async fn bulk_tracks(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+    Json(payload): Json<BulkTrackChanges>,
+) -> Result<Json<BulkTrackResult>, ApiError> {
+    let total = payload.create.len() + payload.update.len() + payload.delete.len();
+    if total > MAX_BULK_CHANGES {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE.into());
+    }
+
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let batch_reason = payload.reason.as_deref();
+
+    let mut created = Vec::with_capacity(payload.create.len());
+    for (i, track) in payload.create.iter().enumerate() {
+        let reason = track.reason.as_deref().or(batch_reason);
+        set_revision_context(
+            &mut tx,
+            RevisionContext::new(RevisionKind::Edit).reason(reason),
+        )
+        .await?;
+        let track = insert_track(&mut tx, user_id, track)
+            .await
+            .map_err(|e| in_item(e, &format!("create[{}]", i)))?;
+        created.push(track.id);
+    }
+
+    let mut updated = Vec::with_capacity(payload.update.len());
+    for (i, update) in payload.update.iter().enumerate() {
+        let reason = update.changes.reason.as_deref().or(batch_reason);
+        set_revision_context(
+            &mut tx,
+            RevisionContext::new(RevisionKind::Edit).reason(reason),
+        )
+        .await?;
+        update_track_row(&mut tx, user_id, update.id, &update.changes)
+            .await
+            .map_err(|e| in_item(e, &format!("update[{}]", i)))?;
+        updated.push(update.id);
+    }
+
+    set_revision_context(
+        &mut tx,
+        RevisionContext::new(RevisionKind::Edit).reason(batch_reason),
+    )
+    .await?;
+    for &id in &payload.delete {
+        delete_track_row(&mut tx, id).await?;
+    }
+
+    commit(tx).await?;
+    tracing::info!(
+        "Curator {} bulk-changed tracks: {} created, {} updated, {} deleted",
+        user_id,
+        created.len(),
+        updated.len(),
+        payload.delete.len()
+    );
+
+    Ok(Json(BulkTrackResult {
+        created,
+        updated,
+        deleted: payload.delete,
+    }))
+}
+
+/// History of a curated track, newest first (public endpoint)
+///
+/// Each revision holds the track as it was before that change; the history
+/// of deleted tracks is kept.
+pub async fn list_track_revisions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<TrackRevision>>, StatusCode> {
+    let revisions = sqlx::query_as::<_, TrackRevision>(&format!(
+        "SELECT {} FROM curated_track_revisions
+         WHERE track_id = $1
+         ORDER BY created_at DESC, id",
+        REVISION_COLUMNS
+    ))
+    .bind(id)
+    .persistent(false)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch track revisions: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    if revisions.is_empty() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    Ok(Json(revisions))
+}
+
+/// Restore a curated track to its state before a revision (curators only)
+///
+/// A deleted track is recreated with the same id. Reverting the revision
+/// that created a track is a conflict, as there is no earlier state; delete
+/// the track instead.
+pub async fn revert_track(
+    Curator(auth_user): Curator,
+    State(state): State<AppState>,
+    Path((id, revision_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<ChangeReason>,
+) -> Result<Json<CuratedTrack>, StatusCode> {
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    let operation: Option<String> = sqlx::query_scalar(
+        "SELECT operation FROM curated_track_revisions WHERE id = $1 AND track_id = $2",
+    )
+    .bind(revision_id)
+    .bind(id)
+    .persistent(false)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to fetch track revision: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    match operation.as_deref() {
+        None => return Err(StatusCode::NOT_FOUND),
+        Some("insert") => return Err(StatusCode::CONFLICT),
+        Some(_) => {}
+    }
+
+    let reason = payload
+        .reason
+        .unwrap_or_else(|| format!("Revert to before revision {}", revision_id));
+    let context = RevisionContext::new(RevisionKind::Revert).reason(Some(&reason));
+    set_revision_context(&mut tx, context).await?;
+
+    // Copy the stored state in SQL so the geometry is restored exactly
+    let track = sqlx::query_as::<_, CuratedTrack>(&format!(
+        "INSERT INTO curated_tracks
+             (id, geometry, source, surface, confidence, last_verified, region, provenance,
+              created_by, updated_by)
+         SELECT track_id, geometry, source, surface, confidence, last_verified, region,
+                COALESCE(provenance, '{{}}'), $2, $2
+         FROM curated_track_revisions
+         WHERE id = $1
+         ON CONFLICT (id) DO UPDATE SET
+             geometry = EXCLUDED.geometry,
+             source = EXCLUDED.source,
+             surface = EXCLUDED.surface,
+             confidence = EXCLUDED.confidence,
+             last_verified = EXCLUDED.last_verified,
+             region = EXCLUDED.region,
+             provenance = EXCLUDED.provenance,
+             updated_by = EXCLUDED.updated_by
+         RETURNING {}",
+        TRACK_COLUMNS
+    ))
+    .bind(revision_id)
+    .bind(user_id)
+    .persistent(false)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| write_error("revert track", e))?;
+
+    commit(tx).await?;
+    tracing::info!(
+        "Curator {} reverted track {} to before revision {}",
+        user_id,
+        id,
+        revision_id
+    );
+
     Ok(Json(track))
 }
+
+/// Report driving a curated track (authenticated users)
+///
+/// A report newer than the track's `last_verified` moves it to the drive
+/// date and nudges confidence by passability, once per user; see the
+/// `apply_track_verification` trigger. The optional trace must stay within
+/// 500 m of the track.
+pub async fn create_verification(
+    auth_user: AuthUser,
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateVerification>,
+) -> Result<Json<TrackVerification>, ApiError> {
+    if !PASSABILITY.contains(&payload.passability.as_str())
+        || payload.driven_on > chrono::Utc::now().date_naive()
+    {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY.into());
+    }
+    let trace = payload
+        .trace
+        .as_ref()
+        .map(|t| track_geometry(t, false))
+        .transpose()?;
+
+    let user_id = user_id(&auth_user)?;
+    let mut tx = begin(&state, &auth_user).await?;
+
+    // Every point of the trace, sampled at least every $3 meters, must lie
+    // within $3 of the track: touching it once is not enough
+    let trace_on_track: Option<bool> = sqlx::query_scalar(
+        "SELECT $2::text IS NULL OR NOT EXISTS (
+             SELECT 1
+             FROM ST_DumpPoints(ST_Segmentize(
+                 ST_SetSRID(ST_GeomFromGeoJSON($2), 4326)::geography,
+                 $3
+             )::geometry) AS p
+             WHERE NOT ST_DWithin(geometry::geography, p.geom::geography, $3)
+         )
+         FROM curated_tracks WHERE id = $1",
+    )
+    .bind(id)
+    .bind(&trace)
+    .bind(MAX_TRACE_OFFSET_M)
+    .persistent(false)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to check verification trace: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    match trace_on_track {
+        None => return Err(StatusCode::NOT_FOUND.into()),
+        Some(false) => {
+            tracing::warn!("Verification trace for track {} is off the track", id);
+            return Err(StatusCode::UNPROCESSABLE_ENTITY.into());
+        }
+        Some(true) => {}
+    }
+
+    let verification = sqlx::query_as::<_, TrackVerification>(&format!(
+        "INSERT INTO track_verifications
+             (track_id, user_id, driven_on, surface, passability, trace)
+         VALUES ($1, $2, $3, $4, $5, ST_SetSRID(ST_GeomFromGeoJSON($6), 4326))
+         RETURNING {}",
+        VERIFICATION_COLUMNS
+    ))
+    .bind(id)
+    .bind(user_id)
+    .bind(payload.driven_on)
+    .bind(&payload.surface)
+    .bind(&payload.passability)
+    .bind(&trace)
+    .persistent(false)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| write_error("create verification", e))?;
+
+    commit(tx).await?;
+    tracing::info!(
+        "User {} verified track {} ({} on {})",
+        user_id,
+        id,
+        payload.passability,
+        payload.driven_on
+    );
+
+    Ok(Json(verification))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Uri;
+
+    fn filters(query: &str) -> Result<Option<(NearPoint, f64)>, StatusCode> {
+        let uri: Uri = format!("/tracks?{}", query).parse().unwrap();
+        let Query(query) = Query::<TrackQuery>::try_from_uri(&uri).unwrap();
+        let mut sql = QueryBuilder::<Postgres>::new("SELECT id FROM curated_tracks WHERE true");
+        push_track_filters(&mut sql, &query)
+    }
+
+    #[test]
+    fn test_near_and_radius_go_together() {
+        assert_eq!(filters("near=2.5,35.2"), Err(StatusCode::BAD_REQUEST));
+        assert_eq!(filters("radius_m=1000"), Err(StatusCode::BAD_REQUEST));
+
+        let (point, radius_m) = filters("near=2.5,35.2&radius_m=1000").unwrap().unwrap();
+        assert_eq!(
+            point,
+            NearPoint {
+                lng: 2.5,
+                lat: 35.2
+            }
+        );
+        assert_eq!(radius_m, 1000.0);
+    }
+
+    #[test]
+    fn test_radius_is_capped() {
+        let at_cap = format!("near=2.5,35.2&radius_m={}", MAX_RADIUS_M);
+        assert!(filters(&at_cap).unwrap().is_some());
+
+        let over_cap = format!("near=2.5,35.2&radius_m={}", MAX_RADIUS_M + 1.0);
+        assert_eq!(filters(&over_cap), Err(StatusCode::BAD_REQUEST));
+        assert_eq!(
+            filters("near=2.5,35.2&radius_m=0"),
+            Err(StatusCode::BAD_REQUEST)
+        );
+    }
+}
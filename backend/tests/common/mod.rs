@@ -75,7 +75,7 @@ impl TestUser {
     ///
     /// This creates an AuthUser instance that can be used with RlsTransaction::begin()
     pub fn to_auth_user(&self) -> dakar_planner_backend::middleware::auth::AuthUser {
-        use dakar_planner_backend::middleware::auth::{AuthUser, Claims};
+        use dakar_planner_backend::middleware::auth::{AppMetadata, AuthUser, Claims};
 
         let claims = Claims {
             sub: self.id.to_string(),
@@ -83,6 +83,7 @@ impl TestUser {
             exp: (chrono::Utc::now().timestamp() + 3600) as i64, // 1 hour from now
             role: "authenticated".to_string(),
             email: Some(self.email.clone()),
+            app_metadata: AppMetadata::default(),
         };
 
         AuthUser {
@@ -92,6 +93,16 @@ impl TestUser {
         }
     }
 
+    /// Convert TestUser to an AuthUser flagged as curator in `app_metadata`
+    ///
+    /// RLS policies check the flag through `is_curator()`, so the test users
+    /// don't need the role set in Supabase.
+    pub fn to_curator_auth_user(&self) -> dakar_planner_backend::middleware::auth::AuthUser {
+        let mut auth_user = self.to_auth_user();
+        auth_user.full_claims.app_metadata.role = Some("curator".to_string());
+        auth_user
+    }
+
     /// Create test user from email and password
     ///
     /// This attempts to sign in to Supabase with the provided credentials.
@@ -11,7 +11,7 @@ mod common;
 
 use common::{cleanup_test_data, create_test_pool, TestUser};
 use dakar_planner_backend::db::RlsTransaction;
-use sqlx::Row;
+use sqlx::{PgPool, Row};
 use uuid::Uuid;
 
 #[tokio::test]
@@ -39,8 +39,8 @@ async fn test_rls_user_can_insert_own_route() {
     .bind(&user.id)
     .bind("RLS Test Route")
     .bind(serde_json::json!([
-        {"lng": 2.5, "lat": 35.2},
-        {"lng": 2.6, "lat": 35.3}
+        {"type": "Point", "coordinates": [2.5, 35.2]},
+        {"type": "Point", "coordinates": [2.6, 35.3]}
     ]))
     .fetch_one(&mut **tx)
     .await;
@@ -78,7 +78,7 @@ async fn test_rls_user_cannot_insert_route_for_other_user() {
     .bind(&user2.id) // Trying to insert as different user!
     .bind("Malicious Route")
     .bind(serde_json::json!([
-        {"lng": 2.5, "lat": 35.2}
+        {"type": "Point", "coordinates": [2.5, 35.2]}
     ]))
     .execute(&mut **tx)
     .await;
@@ -108,7 +108,7 @@ async fn test_rls_user_can_update_own_route() {
     .bind(&route_id)
     .bind(&user.id)
     .bind("Original Name")
-    .bind(serde_json::json!([{"lng": 2.5, "lat": 35.2}]))
+    .bind(serde_json::json!([{"type": "Point", "coordinates": [2.5, 35.2]}]))
     .execute(&pool)
     .await
     .expect("Failed to insert test route");
@@ -163,7 +163,7 @@ async fn test_rls_user_cannot_update_other_users_route() {
     .bind(&route_id)
     .bind(&user1.id)
     .bind("User 1 Route")
-    .bind(serde_json::json!([{"lng": 2.5, "lat": 35.2}]))
+    .bind(serde_json::json!([{"type": "Point", "coordinates": [2.5, 35.2]}]))
     .execute(&pool)
     .await
     .expect("Failed to insert test route");
@@ -218,7 +218,7 @@ async fn test_rls_all_users_can_read_all_routes() {
     .bind(&route_id)
     .bind(&user1.id)
     .bind("Publicly Visible Route")
-    .bind(serde_json::json!([{"lng": 2.5, "lat": 35.2}]))
+    .bind(serde_json::json!([{"type": "Point", "coordinates": [2.5, 35.2]}]))
     .execute(&pool)
     .await
     .expect("Failed to insert test route");
@@ -276,7 +276,7 @@ async fn test_rls_proposal_update_owner_only() {
     .bind(&route_id)
     .bind(&user1.id)
     .bind("Route with Proposals")
-    .bind(serde_json::json!([{"lng": 2.5, "lat": 35.2}]))
+    .bind(serde_json::json!([{"type": "Point", "coordinates": [2.5, 35.2]}]))
     .execute(&pool)
     .await
     .expect("Failed to insert test route");
@@ -293,8 +293,8 @@ async fn test_rls_proposal_update_owner_only() {
     .bind(&route_id)
     .bind(&user2.id)
     .bind(&user2.email)
-    .bind(serde_json::json!({"lng": 2.5, "lat": 35.2}))
-    .bind(serde_json::json!({"lng": 2.6, "lat": 35.3}))
+    .bind(serde_json::json!([2.5, 35.2]))
+    .bind(serde_json::json!([2.6, 35.3]))
     .execute(&pool)
     .await
     .expect("Failed to insert proposal");
@@ -356,13 +356,962 @@ async fn test_rls_proposal_update_owner_only() {
     common::cleanup_proposals_by_user(&pool, user2.id).await;
 }
 
+/// Insert a curated track outside RLS (test setup)
+async fn insert_test_track(pool: &PgPool, wkt: &str) -> Uuid {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO curated_tracks (geometry, source, confidence)
+        VALUES (ST_GeomFromText($1, 4326), 'curated', 3)
+        RETURNING id
+        "#,
+    )
+    .bind(wkt)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to insert test track")
+}
+
+/// Delete test tracks and the revisions their triggers recorded
+async fn cleanup_test_tracks(pool: &PgPool, track_ids: &[Uuid]) {
+    sqlx::query("DELETE FROM curated_tracks WHERE id = ANY($1)")
+        .bind(track_ids)
+        .execute(pool)
+        .await
+        .expect("Failed to cleanup curated_tracks");
+    sqlx::query("DELETE FROM curated_track_revisions WHERE track_id = ANY($1)")
+        .bind(track_ids)
+        .execute(pool)
+        .await
+        .expect("Failed to cleanup curated_track_revisions");
+}
+
+#[tokio::test]
+async fn test_rls_is_curator_reads_app_metadata() {
+    dotenvy::from_filename(".env.test").ok();
+    let pool = create_test_pool().await;
+    let user = TestUser::test_user_1().await;
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let is_curator: bool = sqlx::query_scalar("SELECT is_curator()")
+        .fetch_one(&mut **tx)
+        .await
+        .expect("is_curator() failed");
+    assert!(!is_curator, "Users without the role are not curators");
+    tx.rollback().await.ok();
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let is_curator: bool = sqlx::query_scalar("SELECT is_curator()")
+        .fetch_one(&mut **tx)
+        .await
+        .expect("is_curator() failed");
+    assert!(is_curator, "app_metadata.role = curator makes a curator");
+    tx.rollback().await.ok();
+
+    let mut auth_user = user.to_auth_user();
+    auth_user.full_claims.app_metadata.roles = vec!["editor".to_string(), "curator".to_string()];
+    let mut tx = RlsTransaction::begin(&pool, &auth_user)
+        .await
+        .expect("Failed to begin RLS transaction");
+    let is_curator: bool = sqlx::query_scalar("SELECT is_curator()")
+        .fetch_one(&mut **tx)
+        .await
+        .expect("is_curator() failed");
+    assert!(
+        is_curator,
+        "app_metadata.roles containing curator makes a curator"
+    );
+    tx.rollback().await.ok();
+
+    // Outside an RLS transaction there are no claims
+    let is_curator: bool = sqlx::query_scalar("SELECT is_curator()")
+        .fetch_one(&pool)
+        .await
+        .expect("is_curator() failed");
+    assert!(!is_curator, "No claims means no curator");
+}
+
+#[tokio::test]
+async fn test_rls_curated_tracks_insert_curator_only() {
+    dotenvy::from_filename(".env.test").ok();
+    let pool = create_test_pool().await;
+    let user = TestUser::test_user_1().await;
+    let insert = r#"
+        INSERT INTO curated_tracks (geometry, source, confidence, created_by, updated_by)
+        VALUES (ST_GeomFromText('LINESTRING(-12.3 20.4, -12.4 20.5)', 4326), 'curated', 3, $1, $2)
+        RETURNING id
+    "#;
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let result = sqlx::query(insert)
+        .bind(&user.id)
+        .bind(&user.id)
+        .fetch_one(&mut **tx)
+        .await;
+    assert!(
+        result.is_err(),
+        "Non-curators should NOT be able to create curated tracks (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let result = sqlx::query(insert)
+        .bind(Uuid::new_v4())
+        .bind(&user.id)
+        .fetch_one(&mut **tx)
+        .await;
+    assert!(
+        result.is_err(),
+        "Curators should NOT be able to attribute a track to someone else (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let result = sqlx::query(insert)
+        .bind(&user.id)
+        .bind(&user.id)
+        .fetch_one(&mut **tx)
+        .await;
+    assert!(
+        result.is_ok(),
+        "Curators should be able to create curated tracks"
+    );
+    tx.rollback().await.ok();
+}
+
+#[tokio::test]
+async fn test_rls_curated_tracks_update_delete_curator_only() {
+    dotenvy::from_filename(".env.test").ok();
+    let pool = create_test_pool().await;
+    let user = TestUser::test_user_1().await;
+    let track_id = insert_test_track(&pool, "LINESTRING(-12.3 20.4, -12.4 20.5)").await;
+
+    // Non-curator: the USING clauses hide the track from UPDATE and DELETE
+    let mut tx = RlsTransaction::begin(&pool, &user.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let updated =
+        sqlx::query("UPDATE curated_tracks SET confidence = 5, updated_by = $1 WHERE id = $2")
+            .bind(&user.id)
+            .bind(&track_id)
+            .execute(&mut **tx)
+            .await
+            .expect("Query should execute");
+    assert_eq!(
+        updated.rows_affected(),
+        0,
+        "Non-curators should NOT be able to update curated tracks (RLS)"
+    );
+    let deleted = sqlx::query("DELETE FROM curated_tracks WHERE id = $1")
+        .bind(&track_id)
+        .execute(&mut **tx)
+        .await
+        .expect("Query should execute");
+    assert_eq!(
+        deleted.rows_affected(),
+        0,
+        "Non-curators should NOT be able to delete curated tracks (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    // Curator without updated_by = self fails the WITH CHECK
+    let mut tx = RlsTransaction::begin(&pool, &user.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let result =
+        sqlx::query("UPDATE curated_tracks SET confidence = 5, updated_by = $1 WHERE id = $2")
+            .bind(Uuid::new_v4())
+            .bind(&track_id)
+            .execute(&mut **tx)
+            .await;
+    assert!(
+        result.is_err(),
+        "Curator updates must record the curator as updated_by (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let updated =
+        sqlx::query("UPDATE curated_tracks SET confidence = 5, updated_by = $1 WHERE id = $2")
+            .bind(&user.id)
+            .bind(&track_id)
+            .execute(&mut **tx)
+            .await
+            .expect("Curators should be able to update curated tracks");
+    assert_eq!(
+        updated.rows_affected(),
+        1,
+        "Exactly one track should be updated"
+    );
+
+    let deleted = sqlx::query("DELETE FROM curated_tracks WHERE id = $1")
+        .bind(&track_id)
+        .execute(&mut **tx)
+        .await
+        .expect("Curators should be able to delete curated tracks");
+    assert_eq!(
+        deleted.rows_affected(),
+        1,
+        "Exactly one track should be deleted"
+    );
+    tx.rollback().await.ok();
+
+    cleanup_test_tracks(&pool, &[track_id]).await;
+}
+
+#[tokio::test]
+async fn test_rls_revisions_record_curator() {
+    dotenvy::from_filename(".env.test").ok();
+    let pool = create_test_pool().await;
+    let user = TestUser::test_user_1().await;
+    let track_id = insert_test_track(&pool, "LINESTRING(-12.3 20.4, -12.4 20.5)").await;
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    sqlx::query("UPDATE curated_tracks SET confidence = 5, updated_by = $1 WHERE id = $2")
+        .bind(&user.id)
+        .bind(&track_id)
+        .execute(&mut **tx)
+        .await
+        .expect("Curators should be able to update curated tracks");
+
+    // The SECURITY DEFINER trigger records the revision, although
+    // authenticated users may only read curated_track_revisions
+    let actor: Option<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT actor_id FROM curated_track_revisions
+        WHERE track_id = $1 AND operation = 'update'
+        "#,
+    )
+    .bind(&track_id)
+    .fetch_one(&mut **tx)
+    .await
+    .expect("The update should be recorded as a revision");
+    assert_eq!(actor, Some(user.id), "The revision should name the curator");
+    tx.rollback().await.ok();
+
+    cleanup_test_tracks(&pool, &[track_id]).await;
+}
+
+#[tokio::test]
+async fn test_rls_revisions_not_writable_directly() {
+    dotenvy::from_filename(".env.test").ok();
+    let pool = create_test_pool().await;
+    let user = TestUser::test_user_1().await;
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let result = sqlx::query(
+        r#"
+        INSERT INTO curated_track_revisions (track_id, operation, kind)
+        VALUES ($1, 'update', 'edit')
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .execute(&mut **tx)
+    .await;
+    assert!(
+        result.is_err(),
+        "Revisions are only written by the trigger, not by users (even curators)"
+    );
+    tx.rollback().await.ok();
+}
+
+#[tokio::test]
+async fn test_rls_verification_trigger_updates_track() {
+    dotenvy::from_filename(".env.test").ok();
+    let pool = create_test_pool().await;
+    let user = TestUser::test_user_1().await;
+    let track_id = insert_test_track(&pool, "LINESTRING(-12.3 20.4, -12.4 20.5)").await;
+
+    // A regular user reports a verification; the SECURITY DEFINER trigger
+    // updates the track they cannot update themselves
+    let mut tx = RlsTransaction::begin(&pool, &user.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    sqlx::query(
+        r#"
+        INSERT INTO track_verifications (track_id, user_id, driven_on, passability)
+        VALUES ($1, $2, '2026-01-10', 'good')
+        "#,
+    )
+    .bind(&track_id)
+    .bind(&user.id)
+    .execute(&mut **tx)
+    .await
+    .expect("Users should be able to report verifications");
+
+    let row = sqlx::query(
+        "SELECT confidence, last_verified::text AS last_verified FROM curated_tracks WHERE id = $1",
+    )
+    .bind(&track_id)
+    .fetch_one(&mut **tx)
+    .await
+    .expect("Failed to read track");
+    let confidence: i32 = row.get("confidence");
+    let last_verified: Option<String> = row.get("last_verified");
+    assert_eq!(confidence, 4, "A good passage raises confidence by one");
+    assert_eq!(last_verified.as_deref(), Some("2026-01-10"));
+
+    let kind: String = sqlx::query_scalar(
+        r#"
+        SELECT kind FROM curated_track_revisions
+        WHERE track_id = $1 AND operation = 'update'
+        "#,
+    )
+    .bind(&track_id)
+    .fetch_one(&mut **tx)
+    .await
+    .expect("The verification should be recorded as a revision");
+    assert_eq!(kind, "verification");
+
+    // A later report from the same user moves last_verified but not confidence
+    sqlx::query(
+        r#"
+        INSERT INTO track_verifications (track_id, user_id, driven_on, passability)
+        VALUES ($1, $2, '2026-01-12', 'good')
+        "#,
+    )
+    .bind(&track_id)
+    .bind(&user.id)
+    .execute(&mut **tx)
+    .await
+    .expect("Users should be able to report a track again");
+
+    let row = sqlx::query(
+        "SELECT confidence, last_verified::text AS last_verified FROM curated_tracks WHERE id = $1",
+    )
+    .bind(&track_id)
+    .fetch_one(&mut **tx)
+    .await
+    .expect("Failed to read track");
+    let confidence: i32 = row.get("confidence");
+    let last_verified: Option<String> = row.get("last_verified");
+    assert_eq!(confidence, 4, "Each user changes confidence only once");
+    assert_eq!(last_verified.as_deref(), Some("2026-01-12"));
+    tx.rollback().await.ok();
+
+    // Reporting on behalf of someone else is rejected
+    let mut tx = RlsTransaction::begin(&pool, &user.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let result = sqlx::query(
+        r#"
+        INSERT INTO track_verifications (track_id, user_id, driven_on, passability)
+        VALUES ($1, $2, '2026-01-10', 'good')
+        "#,
+    )
+    .bind(&track_id)
+    .bind(Uuid::new_v4())
+    .execute(&mut **tx)
+    .await;
+    assert!(
+        result.is_err(),
+        "Users should NOT be able to report verifications for others (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    cleanup_test_tracks(&pool, &[track_id]).await;
+}
+
+#[tokio::test]
+async fn test_rls_track_submissions() {
+    dotenvy::from_filename(".env.test").ok();
+    let pool = create_test_pool().await;
+    let user1 = TestUser::test_user_1().await; // Submitter
+    let user2 = TestUser::test_user_2().await; // Other user, then curator
+    let insert = r#"
+        INSERT INTO track_submissions (id, submitted_by, geometry, origin, status)
+        VALUES ($1, $2, ST_GeomFromText('LINESTRING(-12.3 20.4, -12.4 20.5)', 4326), 'drawn', $3)
+    "#;
+
+    // Submissions must be the user's own and pending
+    for (submitted_by, status, reason) in [
+        (user2.id, "pending", "for another user"),
+        (user1.id, "approved", "already approved"),
+    ] {
+        let mut tx = RlsTransaction::begin(&pool, &user1.to_auth_user())
+            .await
+            .expect("Failed to begin RLS transaction");
+        let result = sqlx::query(insert)
+            .bind(Uuid::new_v4())
+            .bind(submitted_by)
+            .bind(status)
+            .execute(&mut **tx)
+            .await;
+        assert!(
+            result.is_err(),
+            "Users should NOT be able to submit a track {} (RLS)",
+            reason
+        );
+        tx.rollback().await.ok();
+    }
+
+    let submission_id = Uuid::new_v4();
+    let mut tx = RlsTransaction::begin(&pool, &user1.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    sqlx::query(insert)
+        .bind(&submission_id)
+        .bind(&user1.id)
+        .bind("pending")
+        .execute(&mut **tx)
+        .await
+        .expect("Users should be able to submit tracks");
+    tx.commit().await.expect("Commit failed");
+
+    // Another user neither sees nor reviews it
+    let mut tx = RlsTransaction::begin(&pool, &user2.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let visible: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM track_submissions WHERE id = $1")
+        .bind(&submission_id)
+        .fetch_one(&mut **tx)
+        .await
+        .expect("Query should execute");
+    assert_eq!(visible, 0, "Users should NOT see others' submissions (RLS)");
+    let reviewed = sqlx::query(
+        r#"
+        UPDATE track_submissions SET status = 'approved', reviewed_by = $1, reviewed_at = NOW()
+        WHERE id = $2
+        "#,
+    )
+    .bind(&user2.id)
+    .bind(&submission_id)
+    .execute(&mut **tx)
+    .await
+    .expect("Query should execute");
+    assert_eq!(
+        reviewed.rows_affected(),
+        0,
+        "Non-curators should NOT be able to review submissions (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    // Nor can the submitter approve their own submission
+    let mut tx = RlsTransaction::begin(&pool, &user1.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let reviewed = sqlx::query(
+        r#"
+        UPDATE track_submissions SET status = 'approved', reviewed_by = $1, reviewed_at = NOW()
+        WHERE id = $2
+        "#,
+    )
+    .bind(&user1.id)
+    .bind(&submission_id)
+    .execute(&mut **tx)
+    .await
+    .expect("Query should execute");
+    assert_eq!(
+        reviewed.rows_affected(),
+        0,
+        "Submitters should NOT be able to approve their own submissions (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    // A curator sees and reviews it, as themselves only
+    let mut tx = RlsTransaction::begin(&pool, &user2.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let visible: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM track_submissions WHERE id = $1")
+        .bind(&submission_id)
+        .fetch_one(&mut **tx)
+        .await
+        .expect("Query should execute");
+    assert_eq!(visible, 1, "Curators should see every submission");
+    let reviewed = sqlx::query(
+        r#"
+        UPDATE track_submissions SET status = 'approved', reviewed_by = $1, reviewed_at = NOW()
+        WHERE id = $2
+        "#,
+    )
+    .bind(&user2.id)
+    .bind(&submission_id)
+    .execute(&mut **tx)
+    .await
+    .expect("Curators should be able to review submissions");
+    assert_eq!(
+        reviewed.rows_affected(),
+        1,
+        "Exactly one submission should be reviewed"
+    );
+    tx.rollback().await.ok();
+
+    let mut tx = RlsTransaction::begin(&pool, &user2.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let result = sqlx::query(
+        "UPDATE track_submissions SET status = 'approved', reviewed_by = $1 WHERE id = $2",
+    )
+    .bind(&user1.id)
+    .bind(&submission_id)
+    .execute(&mut **tx)
+    .await;
+    assert!(
+        result.is_err(),
+        "Curators should NOT be able to record someone else as reviewer (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    sqlx::query("DELETE FROM track_submissions WHERE id = $1")
+        .bind(&submission_id)
+        .execute(&pool)
+        .await
+        .expect("Failed to cleanup track_submissions");
+}
+
+#[tokio::test]
+async fn test_rls_track_merge_proposals_curator_only() {
+    dotenvy::from_filename(".env.test").ok();
+    let pool = create_test_pool().await;
+    let user = TestUser::test_user_1().await;
+    let (mut track_a, mut track_b) = (Uuid::new_v4(), Uuid::new_v4());
+    if track_b < track_a {
+        std::mem::swap(&mut track_a, &mut track_b);
+    }
+    let insert = r#"
+        INSERT INTO track_merge_proposals (
+            id, track_a, track_b, keep_track_id, overlap_a, overlap_b, frechet_m,
+            merged_confidence, status
+        )
+        VALUES ($1, $2, $3, $2, 0.95, 0.9, 12.0, 4, $4)
+    "#;
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let result = sqlx::query(insert)
+        .bind(Uuid::new_v4())
+        .bind(&track_a)
+        .bind(&track_b)
+        .bind("pending")
+        .execute(&mut **tx)
+        .await;
+    assert!(
+        result.is_err(),
+        "Non-curators should NOT be able to record merge proposals (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let result = sqlx::query(insert)
+        .bind(Uuid::new_v4())
+        .bind(&track_a)
+        .bind(&track_b)
+        .bind("merged")
+        .execute(&mut **tx)
+        .await;
+    assert!(
+        result.is_err(),
+        "Merge proposals may not be recorded as merged (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    // Detection records pairs rejected on shape as dismissed
+    let mut tx = RlsTransaction::begin(&pool, &user.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    sqlx::query(insert)
+        .bind(Uuid::new_v4())
+        .bind(&track_a)
+        .bind(&track_b)
+        .bind("dismissed")
+        .execute(&mut **tx)
+        .await
+        .expect("Curators should be able to record dismissed pairs");
+    tx.rollback().await.ok();
+
+    let proposal_id = Uuid::new_v4();
+    let mut tx = RlsTransaction::begin(&pool, &user.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    sqlx::query(insert)
+        .bind(&proposal_id)
+        .bind(&track_a)
+        .bind(&track_b)
+        .bind("pending")
+        .execute(&mut **tx)
+        .await
+        .expect("Curators should be able to record merge proposals");
+    tx.commit().await.expect("Commit failed");
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let visible: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM track_merge_proposals WHERE id = $1")
+            .bind(&proposal_id)
+            .fetch_one(&mut **tx)
+            .await
+            .expect("Query should execute");
+    assert_eq!(
+        visible, 0,
+        "Non-curators should NOT see merge proposals (RLS)"
+    );
+    let resolved = sqlx::query(
+        "UPDATE track_merge_proposals SET status = 'dismissed', resolved_by = $1 WHERE id = $2",
+    )
+    .bind(&user.id)
+    .bind(&proposal_id)
+    .execute(&mut **tx)
+    .await
+    .expect("Query should execute");
+    assert_eq!(
+        resolved.rows_affected(),
+        0,
+        "Non-curators should NOT be able to resolve merge proposals (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let resolved = sqlx::query(
+        "UPDATE track_merge_proposals SET status = 'dismissed', resolved_by = $1 WHERE id = $2",
+    )
+    .bind(&user.id)
+    .bind(&proposal_id)
+    .execute(&mut **tx)
+    .await
+    .expect("Curators should be able to resolve merge proposals");
+    assert_eq!(
+        resolved.rows_affected(),
+        1,
+        "Exactly one proposal should be resolved"
+    );
+    tx.rollback().await.ok();
+
+    sqlx::query("DELETE FROM track_merge_proposals WHERE id = $1")
+        .bind(&proposal_id)
+        .execute(&pool)
+        .await
+        .expect("Failed to cleanup track_merge_proposals");
+}
+
+#[tokio::test]
+async fn test_rls_regions_curator_only_and_assign_tracks() {
+    dotenvy::from_filename(".env.test").ok();
+    let pool = create_test_pool().await;
+    let user = TestUser::test_user_1().await;
+    let code = format!("rls-{}", Uuid::new_v4());
+    let insert = r#"
+        INSERT INTO regions (id, code, name, level, geometry)
+        VALUES ($1, $2, 'RLS Test Country', 'country',
+            ST_Multi(ST_MakeEnvelope(-13.0, 20.0, -12.0, 21.0, 4326)))
+    "#;
+    let inside = insert_test_track(&pool, "LINESTRING(-12.3 20.4, -12.4 20.5)").await;
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let result = sqlx::query(insert)
+        .bind(Uuid::new_v4())
+        .bind(&code)
+        .execute(&mut **tx)
+        .await;
+    assert!(
+        result.is_err(),
+        "Non-curators should NOT be able to create regions (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    // Curators may not write track_regions; the SECURITY DEFINER triggers do
+    let region_id = Uuid::new_v4();
+    let mut tx = RlsTransaction::begin(&pool, &user.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    sqlx::query(insert)
+        .bind(&region_id)
+        .bind(&code)
+        .execute(&mut **tx)
+        .await
+        .expect("Curators should be able to create regions");
+
+    let assigned: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM track_regions WHERE region_id = $1 AND track_id = $2",
+    )
+    .bind(&region_id)
+    .bind(&inside)
+    .fetch_one(&mut **tx)
+    .await
+    .expect("Query should execute");
+    assert_eq!(assigned, 1, "A new region is assigned the tracks inside it");
+
+    let track_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO curated_tracks (geometry, source, confidence, created_by, updated_by)
+        VALUES (ST_GeomFromText('LINESTRING(-12.6 20.6, -12.7 20.7)', 4326), 'curated', 3, $1, $1)
+        RETURNING id
+        "#,
+    )
+    .bind(&user.id)
+    .fetch_one(&mut **tx)
+    .await
+    .expect("Curators should be able to create curated tracks");
+    let assigned: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM track_regions WHERE region_id = $1 AND track_id = $2",
+    )
+    .bind(&region_id)
+    .bind(&track_id)
+    .fetch_one(&mut **tx)
+    .await
+    .expect("Query should execute");
+    assert_eq!(
+        assigned, 1,
+        "A new track is assigned the regions it crosses"
+    );
+
+    let result = sqlx::query("INSERT INTO track_regions (track_id, region_id) VALUES ($1, $2)")
+        .bind(&inside)
+        .bind(&region_id)
+        .execute(&mut **tx)
+        .await;
+    assert!(
+        result.is_err(),
+        "track_regions is derived data nobody writes directly"
+    );
+    tx.rollback().await.ok();
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let deleted = sqlx::query("DELETE FROM regions WHERE code = 'mr'")
+        .execute(&mut **tx)
+        .await
+        .expect("Query should execute");
+    assert_eq!(
+        deleted.rows_affected(),
+        0,
+        "Non-curators should NOT be able to delete regions (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    cleanup_test_tracks(&pool, &[inside]).await;
+}
+
+#[tokio::test]
+async fn test_rls_pois_curator_only() {
+    dotenvy::from_filename(".env.test").ok();
+    let pool = create_test_pool().await;
+    let user = TestUser::test_user_1().await;
+    let insert = r#"
+        INSERT INTO pois (id, category, name, geometry, source, confidence, created_by, updated_by)
+        VALUES ($1, 'water_well', 'Puits RLS', ST_SetSRID(ST_MakePoint(-12.3, 20.4), 4326), 'curated', 3, $2, $2)
+    "#;
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let result = sqlx::query(insert)
+        .bind(Uuid::new_v4())
+        .bind(&user.id)
+        .execute(&mut **tx)
+        .await;
+    assert!(
+        result.is_err(),
+        "Non-curators should NOT be able to create POIs (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    let poi_id = Uuid::new_v4();
+    let mut tx = RlsTransaction::begin(&pool, &user.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    sqlx::query(insert)
+        .bind(&poi_id)
+        .bind(&user.id)
+        .execute(&mut **tx)
+        .await
+        .expect("Curators should be able to create POIs");
+    tx.commit().await.expect("Commit failed");
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let updated = sqlx::query("UPDATE pois SET name = 'Renamed', updated_by = $1 WHERE id = $2")
+        .bind(&user.id)
+        .bind(&poi_id)
+        .execute(&mut **tx)
+        .await
+        .expect("Query should execute");
+    assert_eq!(
+        updated.rows_affected(),
+        0,
+        "Non-curators should NOT be able to update POIs (RLS)"
+    );
+    let deleted = sqlx::query("DELETE FROM pois WHERE id = $1")
+        .bind(&poi_id)
+        .execute(&mut **tx)
+        .await
+        .expect("Query should execute");
+    assert_eq!(
+        deleted.rows_affected(),
+        0,
+        "Non-curators should NOT be able to delete POIs (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    let mut tx = RlsTransaction::begin(&pool, &user.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let updated = sqlx::query("UPDATE pois SET name = 'Renamed', updated_by = $1 WHERE id = $2")
+        .bind(&user.id)
+        .bind(&poi_id)
+        .execute(&mut **tx)
+        .await
+        .expect("Curators should be able to update POIs");
+    assert_eq!(
+        updated.rows_affected(),
+        1,
+        "Exactly one POI should be updated"
+    );
+    let deleted = sqlx::query("DELETE FROM pois WHERE id = $1")
+        .bind(&poi_id)
+        .execute(&mut **tx)
+        .await
+        .expect("Curators should be able to delete POIs");
+    assert_eq!(
+        deleted.rows_affected(),
+        1,
+        "Exactly one POI should be deleted"
+    );
+    tx.commit().await.expect("Commit failed");
+}
+
+#[tokio::test]
+async fn test_rls_hazard_reports_resolution() {
+    dotenvy::from_filename(".env.test").ok();
+    let pool = create_test_pool().await;
+    let user1 = TestUser::test_user_1().await; // Reporter
+    let user2 = TestUser::test_user_2().await; // Other user, then curator
+    let insert = r#"
+        INSERT INTO hazard_reports (id, reported_by, kind, severity, geometry, expires_at)
+        VALUES ($1, $2, 'washout', 'severe', ST_SetSRID(ST_MakePoint(-12.3, 20.4), 4326),
+            NOW() + INTERVAL '7 days')
+    "#;
+    let resolve = "UPDATE hazard_reports SET resolved_by = $1, resolved_at = NOW() WHERE id = $2";
+
+    let mut tx = RlsTransaction::begin(&pool, &user1.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let result = sqlx::query(insert)
+        .bind(Uuid::new_v4())
+        .bind(&user2.id)
+        .execute(&mut **tx)
+        .await;
+    assert!(
+        result.is_err(),
+        "Users should NOT be able to report hazards as someone else (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    let hazard_id = Uuid::new_v4();
+    let mut tx = RlsTransaction::begin(&pool, &user1.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    sqlx::query(insert)
+        .bind(&hazard_id)
+        .bind(&user1.id)
+        .execute(&mut **tx)
+        .await
+        .expect("Users should be able to report hazards");
+    tx.commit().await.expect("Commit failed");
+
+    // Another user can't resolve it
+    let mut tx = RlsTransaction::begin(&pool, &user2.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let resolved = sqlx::query(resolve)
+        .bind(&user2.id)
+        .bind(&hazard_id)
+        .execute(&mut **tx)
+        .await
+        .expect("Query should execute");
+    assert_eq!(
+        resolved.rows_affected(),
+        0,
+        "Other users should NOT be able to resolve a hazard (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    // The reporter can, as themselves only
+    let mut tx = RlsTransaction::begin(&pool, &user1.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let result = sqlx::query(resolve)
+        .bind(&user2.id)
+        .bind(&hazard_id)
+        .execute(&mut **tx)
+        .await;
+    assert!(
+        result.is_err(),
+        "Resolutions must record the resolving user (RLS)"
+    );
+    tx.rollback().await.ok();
+
+    let mut tx = RlsTransaction::begin(&pool, &user1.to_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let resolved = sqlx::query(resolve)
+        .bind(&user1.id)
+        .bind(&hazard_id)
+        .execute(&mut **tx)
+        .await
+        .expect("Reporters should be able to resolve their hazards");
+    assert_eq!(
+        resolved.rows_affected(),
+        1,
+        "Exactly one hazard should be resolved"
+    );
+    tx.rollback().await.ok();
+
+    // So can a curator
+    let mut tx = RlsTransaction::begin(&pool, &user2.to_curator_auth_user())
+        .await
+        .expect("Failed to begin RLS transaction");
+    let resolved = sqlx::query(resolve)
+        .bind(&user2.id)
+        .bind(&hazard_id)
+        .execute(&mut **tx)
+        .await
+        .expect("Curators should be able to resolve any hazard");
+    assert_eq!(
+        resolved.rows_affected(),
+        1,
+        "Exactly one hazard should be resolved"
+    );
+    tx.rollback().await.ok();
+
+    sqlx::query("DELETE FROM hazard_reports WHERE id = $1")
+        .bind(&hazard_id)
+        .execute(&pool)
+        .await
+        .expect("Failed to cleanup hazard_reports");
+}
+
 #[tokio::test]
 async fn test_rls_transaction_validation_rejects_invalid_uuid() {
     dotenvy::from_filename(".env.test").ok();
     let pool = create_test_pool().await;
 
     // Create an AuthUser with invalid UUID
-    use dakar_planner_backend::middleware::auth::{AuthUser, Claims};
+    use dakar_planner_backend::middleware::auth::{AppMetadata, AuthUser, Claims};
 
     let claims = Claims {
         sub: "not-a-uuid".to_string(),
@@ -370,6 +1319,7 @@ async fn test_rls_transaction_validation_rejects_invalid_uuid() {
         exp: (chrono::Utc::now().timestamp() + 3600) as i64,
         role: "authenticated".to_string(),
         email: Some("test@example.com".to_string()),
+        app_metadata: AppMetadata::default(),
     };
 
     let auth_user = AuthUser {
@@ -389,7 +1339,7 @@ async fn test_rls_transaction_validation_rejects_invalid_role() {
     let pool = create_test_pool().await;
 
     // Create an AuthUser with invalid role
-    use dakar_planner_backend::middleware::auth::{AuthUser, Claims};
+    use dakar_planner_backend::middleware::auth::{AppMetadata, AuthUser, Claims};
 
     let user_id = Uuid::new_v4();
     let claims = Claims {
@@ -398,6 +1348,7 @@ async fn test_rls_transaction_validation_rejects_invalid_role() {
         exp: (chrono::Utc::now().timestamp() + 3600) as i64,
         role: "hacker".to_string(),
         email: Some("hacker@evil.com".to_string()),
+        app_metadata: AppMetadata::default(),
     };
 
     let auth_user = AuthUser {
@@ -0,0 +1,200 @@
+/// Integration tests for curated track listing and verification
+///
+/// These tests use the PRODUCTION database.
+/// Each test inserts tracks in an empty stretch of ocean and deletes them
+/// after execution.
+///
+/// Prerequisites:
+/// - Test user test-user-1@example.com must exist in production Supabase
+/// - .env.test must be configured with production credentials
+/// - Run tests with: cargo test --test tracks_test
+mod common;
+
+use axum::http::StatusCode;
+use axum::Router;
+use common::{
+    create_test_app_state, create_test_pool, send_authed_request, send_request, TestUser,
+};
+use dakar_planner_backend::routes::api_routes;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Helper to create test app with routes
+async fn create_test_app() -> Router {
+    let state = create_test_app_state().await;
+    api_routes().with_state(state)
+}
+
+/// Insert a track directly (bypasses RLS)
+async fn insert_test_track(pool: &PgPool, wkt: &str, surface: &str) -> Uuid {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO curated_tracks (geometry, source, surface, confidence)
+        VALUES (ST_GeomFromText($1, 4326), 'curated', $2, 3)
+        RETURNING id
+        "#,
+    )
+    .bind(wkt)
+    .bind(surface)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to insert test track")
+}
+
+/// Delete test tracks and the revisions their triggers recorded
+async fn cleanup_test_tracks(pool: &PgPool, track_ids: &[Uuid]) {
+    sqlx::query("DELETE FROM curated_tracks WHERE id = ANY($1)")
+        .bind(track_ids)
+        .execute(pool)
+        .await
+        .expect("Failed to cleanup curated_tracks");
+    sqlx::query("DELETE FROM curated_track_revisions WHERE track_id = ANY($1)")
+        .bind(track_ids)
+        .execute(pool)
+        .await
+        .expect("Failed to cleanup curated_track_revisions");
+}
+
+async fn list_tracks(path: &str) -> serde_json::Value {
+    let (status, body) = send_request(create_test_app().await, "GET", path, None).await;
+    assert_eq!(
+        status,
+        StatusCode::OK,
+        "Listing tracks failed. Body: {}",
+        body
+    );
+    serde_json::from_str(&body).expect("Response should be valid JSON")
+}
+
+#[tokio::test]
+async fn test_list_tracks_near_is_truncated_at_limit() {
+    dotenvy::from_filename(".env.test").ok();
+    let pool = create_test_pool().await;
+    let mut track_ids = Vec::new();
+    for offset in [0.001, 0.002, 0.003] {
+        let wkt = format!(
+            "LINESTRING(-30.5 {}, -30.49 {})",
+            10.5 + offset,
+            10.5 + offset
+        );
+        track_ids.push(insert_test_track(&pool, &wkt, "sand").await);
+    }
+
+    // Two of the three tracks fit in the page
+    let page = list_tracks("/tracks?near=-30.5,10.5&radius_m=5000&limit=2").await;
+    assert_eq!(page["tracks"].as_array().unwrap().len(), 2);
+    assert_eq!(page["truncated"], true, "A full page should be truncated");
+    let cursor = page["next_cursor"]
+        .as_str()
+        .expect("A truncated page should have a next cursor");
+
+    // The last page holds the remaining track and ends the listing
+    let page = list_tracks(&format!(
+        "/tracks?near=-30.5,10.5&radius_m=5000&limit=2&cursor={}",
+        cursor
+    ))
+    .await;
+    let tracks = page["tracks"].as_array().unwrap();
+    assert_eq!(tracks.len(), 1);
+    assert_eq!(tracks[0]["id"], track_ids[2].to_string());
+    assert_eq!(page["truncated"], false);
+    assert!(page["next_cursor"].is_null());
+
+    cleanup_test_tracks(&pool, &track_ids).await;
+}
+
+#[tokio::test]
+async fn test_list_tracks_combined_filters_page_with_cursor() {
+    dotenvy::from_filename(".env.test").ok();
+    let pool = create_test_pool().await;
+    let line = |lat: f64| format!("LINESTRING(-30.5 {}, -30.49 {})", lat, lat);
+    let sand_1 = insert_test_track(&pool, &line(11.501), "sand").await;
+    let gravel = insert_test_track(&pool, &line(11.502), "gravel").await;
+    let sand_2 = insert_test_track(&pool, &line(11.503), "sand").await;
+    // Within the radius but filtered out by surface and by the bbox
+    let asphalt = insert_test_track(&pool, &line(11.5015), "asphalt").await;
+    let south = insert_test_track(&pool, &line(11.498), "sand").await;
+    let track_ids = [sand_1, gravel, sand_2, asphalt, south];
+
+    let filters =
+        "bbox=-30.6,11.4995,-30.4,11.6&near=-30.5,11.5&radius_m=5000&surface=sand,gravel&limit=2";
+    let mut seen = Vec::new();
+    let mut path = format!("/tracks?{}", filters);
+    loop {
+        let page = list_tracks(&path).await;
+        for track in page["tracks"].as_array().unwrap() {
+            seen.push(track["id"].as_str().unwrap().parse::<Uuid>().unwrap());
+        }
+        match page["next_cursor"].as_str() {
+            Some(cursor) => {
+                assert_eq!(page["truncated"], true);
+                path = format!("/tracks?{}&cursor={}", filters, cursor);
+            }
+            None => {
+                assert_eq!(page["truncated"], false);
+                break;
+            }
+        }
+    }
+
+    // Closest first, each matching track exactly once
+    assert_eq!(seen, vec![sand_1, gravel, sand_2]);
+
+    cleanup_test_tracks(&pool, &track_ids).await;
+}
+
+#[tokio::test]
+async fn test_verification_trace_must_follow_the_track() {
+    dotenvy::from_filename(".env.test").ok();
+    let pool = create_test_pool().await;
+    let user = TestUser::test_user_1().await;
+    // ~1.1 km due east
+    let track_id = insert_test_track(&pool, "LINESTRING(-30.5 12.5, -30.49 12.5)", "sand").await;
+    let path = format!("/tracks/{}/verifications", track_id);
+    let report = |coordinates: serde_json::Value| {
+        serde_json::json!({
+            "driven_on": "2026-01-10",
+            "passability": "good",
+            "trace": {"type": "LineString", "coordinates": coordinates}
+        })
+        .to_string()
+    };
+
+    // Starts on the track, then heads ~2 km north of it
+    let (status, body) = send_authed_request(
+        create_test_app().await,
+        "POST",
+        &path,
+        &user.jwt,
+        Some(report(serde_json::json!([[-30.5, 12.5], [-30.49, 12.52]]))),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "A trace leaving the track should be rejected. Body: {}",
+        body
+    );
+
+    // Follows the track ~50 m off its line
+    let (status, body) = send_authed_request(
+        create_test_app().await,
+        "POST",
+        &path,
+        &user.jwt,
+        Some(report(serde_json::json!([
+            [-30.5, 12.5005],
+            [-30.49, 12.5005]
+        ]))),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::OK,
+        "A trace along the track should be accepted. Body: {}",
+        body
+    );
+
+    // Verifications are deleted with their track
+    cleanup_test_tracks(&pool, &[track_id]).await;
+}
@@ -1,42 +1,20 @@
 'use client'
 
-import { useEffect, useState } from 'react'
 import { Layer, Source } from 'react-map-gl'
 import { useMapStore } from '@/lib/store'
-import { fetchTracks } from '@/lib/api'
-import type { CuratedTrack } from '@/types'
+import { trackTilesUrl } from '@/lib/api'
 
 export default function TrackRenderer() {
   const { layers } = useMapStore()
-  const [tracks, setTracks] = useState<CuratedTrack[]>([])
-
-  useEffect(() => {
-    if (layers.osmTracks || layers.curatedTracks) {
-      fetchTracks().then(setTracks).catch(console.error)
-    }
-  }, [layers.osmTracks, layers.curatedTracks])
-
-  const geojson: GeoJSON.FeatureCollection = {
-    type: 'FeatureCollection',
-    features: tracks.map(track => ({
-      type: 'Feature',
-      properties: {
-        id: track.id,
-        confidence: track.confidence,
-        source: track.source,
-        surface: track.surface,
-      },
-      geometry: track.geometry,
-    })),
-  }
 
   if (!layers.osmTracks && !layers.curatedTracks) return null
 
   return (
-    <Source id="tracks" type="geojson" data={geojson}>
+    <Source id="tracks" type="vector" tiles={[trackTilesUrl()]} maxzoom={14}>
       <Layer
         id="tracks-layer"
         type="line"
+        source-layer="tracks"
         paint={{
           'line-color': [
             'match',
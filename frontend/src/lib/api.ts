@@ -1,11 +1,31 @@
 import axios from "axios";
 import axiosRetry from "axios-retry";
 import type {
+  ComponentGap,
   CuratedTrack,
+  DanglingEnd,
   Route,
+  RouteHazard,
+  RoutePoi,
   RouteProposal,
+  SubmissionQueueEntry,
   EditingSession,
+  HazardKind,
+  HazardReport,
+  HazardSeverity,
   PointChange,
+  Passability,
+  Poi,
+  PoiCategory,
+  RegionDetail,
+  RegionLevel,
+  RegionSummary,
+  TrackDetail,
+  TrackList,
+  TrackMergeProposal,
+  TrackRevision,
+  TrackSubmission,
+  TrackVerification,
 } from "@/types";
 import { logger } from "./logger";
 
@@ -107,15 +127,268 @@ export async function fetchTracks(
   bounds?: [number, number, number, number],
 ): Promise<CuratedTrack[]> {
   const params = bounds ? { bbox: bounds.join(",") } : {};
-  const response = await api.get("/api/tracks", { params });
-  return response.data;
+  const response = await api.get<TrackList>("/api/tracks", { params });
+  return response.data.tracks;
+}
+
+/** Vector tile URL template for curated tracks (layer `tracks`) */
+export function trackTilesUrl(): string {
+  return `${API_URL}/api/tiles/tracks/{z}/{x}/{y}.mvt`;
 }
 
-export async function fetchTrackById(id: string): Promise<CuratedTrack> {
+export async function fetchTrackById(id: string): Promise<TrackDetail> {
   const response = await api.get(`/api/tracks/${id}`);
   return response.data;
 }
 
+export async function createTrackVerification(
+  trackId: string,
+  data: {
+    drivenOn: string;
+    surface?: string;
+    passability: Passability;
+    trace?: GeoJSON.LineString;
+  },
+): Promise<TrackVerification> {
+  const response = await api.post(`/api/tracks/${trackId}/verifications`, {
+    driven_on: data.drivenOn,
+    surface: data.surface,
+    passability: data.passability,
+    trace: data.trace,
+  });
+  return response.data;
+}
+
+export async function fetchTrackRevisions(
+  id: string,
+): Promise<TrackRevision[]> {
+  const response = await api.get(`/api/tracks/${id}/revisions`);
+  return response.data;
+}
+
+// Duplicate track merging (curators)
+export async function fetchMergeProposals(): Promise<TrackMergeProposal[]> {
+  const response = await api.get("/api/tracks/merge-proposals");
+  return response.data;
+}
+
+export async function detectDuplicateTracks(
+  bounds?: [number, number, number, number],
+): Promise<{ candidates: number; proposed: number; rejected: number }> {
+  const params = bounds ? { bbox: bounds.join(",") } : {};
+  const response = await api.post("/api/tracks/merge-proposals/detect", null, {
+    params,
+  });
+  return response.data;
+}
+
+export async function mergeTracks(
+  proposalId: string,
+  data: { keepTrackId?: string; reason?: string } = {},
+): Promise<CuratedTrack> {
+  const response = await api.post(
+    `/api/tracks/merge-proposals/${proposalId}/merge`,
+    { keep_track_id: data.keepTrackId, reason: data.reason },
+  );
+  return response.data;
+}
+
+export async function dismissMergeProposal(
+  proposalId: string,
+): Promise<Omit<TrackMergeProposal, "a" | "b">> {
+  const response = await api.post(
+    `/api/tracks/merge-proposals/${proposalId}/dismiss`,
+  );
+  return response.data;
+}
+
+// Track network diagnostics
+export async function fetchDanglingEnds(
+  maxDistanceM?: number,
+): Promise<DanglingEnd[]> {
+  const response = await api.get("/api/topology/dangling-ends", {
+    params: { max_distance_m: maxDistanceM },
+  });
+  return response.data;
+}
+
+export async function fetchComponentGaps(
+  maxDistanceM?: number,
+): Promise<ComponentGap[]> {
+  const response = await api.get("/api/topology/gaps", {
+    params: { max_distance_m: maxDistanceM },
+  });
+  return response.data;
+}
+
+// Hazard reports
+export async function fetchHazards(
+  params: {
+    bbox?: [number, number, number, number];
+    kind?: HazardKind[];
+    min_severity?: HazardSeverity;
+  } = {},
+): Promise<HazardReport[]> {
+  const response = await api.get("/api/hazards", {
+    params: {
+      bbox: params.bbox?.join(","),
+      kind: params.kind?.join(","),
+      min_severity: params.min_severity,
+    },
+  });
+  return response.data;
+}
+
+export async function reportHazard(data: {
+  kind: HazardKind;
+  severity: HazardSeverity;
+  description?: string;
+  geometry: GeoJSON.Point | GeoJSON.LineString;
+  expires_at: string;
+}): Promise<HazardReport> {
+  const response = await api.post("/api/hazards", data);
+  return response.data;
+}
+
+export async function resolveHazard(id: string): Promise<HazardReport> {
+  const response = await api.post(`/api/hazards/${id}/resolve`);
+  return response.data;
+}
+
+export async function fetchRouteHazards(
+  routeId: string,
+  corridorM?: number,
+): Promise<RouteHazard[]> {
+  const response = await api.get(`/api/routes/${routeId}/hazards`, {
+    params: { corridor_m: corridorM },
+  });
+  return response.data;
+}
+
+// Points of interest
+export async function fetchPois(
+  params: {
+    bbox?: [number, number, number, number];
+    category?: PoiCategory[];
+    min_confidence?: number;
+  } = {},
+): Promise<Poi[]> {
+  const response = await api.get("/api/pois", {
+    params: {
+      bbox: params.bbox?.join(","),
+      category: params.category?.join(","),
+      min_confidence: params.min_confidence,
+    },
+  });
+  return response.data;
+}
+
+export async function createPoi(
+  data: Pick<Poi, "category" | "lng" | "lat" | "source" | "confidence"> &
+    Partial<Pick<Poi, "name" | "attributes" | "last_verified">>,
+): Promise<Poi> {
+  const response = await api.post("/api/pois", data);
+  return response.data;
+}
+
+export async function updatePoi(
+  id: string,
+  data: Partial<
+    Pick<
+      Poi,
+      | "category"
+      | "name"
+      | "lng"
+      | "lat"
+      | "attributes"
+      | "source"
+      | "confidence"
+      | "last_verified"
+    >
+  >,
+): Promise<Poi> {
+  const response = await api.patch(`/api/pois/${id}`, data);
+  return response.data;
+}
+
+export async function deletePoi(id: string): Promise<void> {
+  await api.delete(`/api/pois/${id}`);
+}
+
+/** POIs near a route in driving order, e.g. the next well after km 230 */
+export async function fetchRoutePois(
+  routeId: string,
+  params: {
+    within_km?: number;
+    category?: PoiCategory[];
+    after_km?: number;
+    limit?: number;
+  } = {},
+): Promise<RoutePoi[]> {
+  const response = await api.get(`/api/routes/${routeId}/pois`, {
+    params: { ...params, category: params.category?.join(",") },
+  });
+  return response.data;
+}
+
+// Regions
+export async function fetchRegions(
+  params: { level?: RegionLevel; parent?: string } = {},
+): Promise<RegionSummary[]> {
+  const response = await api.get("/api/regions", { params });
+  return response.data;
+}
+
+export async function fetchRegion(code: string): Promise<RegionDetail> {
+  const response = await api.get(`/api/regions/${encodeURIComponent(code)}`);
+  return response.data;
+}
+
+// Track submissions
+export async function submitTrack(data: {
+  geometry: GeoJSON.LineString;
+  surface?: string;
+  region?: string;
+  comment?: string;
+}): Promise<TrackSubmission> {
+  const response = await api.post("/api/submissions", data);
+  return response.data;
+}
+
+export async function submitGpxTrack(
+  gpx: string,
+  params: { surface?: string; region?: string; comment?: string } = {},
+): Promise<TrackSubmission[]> {
+  // One submission per track segment
+  const response = await api.post("/api/submissions/gpx", gpx, {
+    params,
+    headers: { "Content-Type": "application/gpx+xml" },
+  });
+  return response.data;
+}
+
+export async function fetchMySubmissions(): Promise<TrackSubmission[]> {
+  const response = await api.get("/api/submissions");
+  return response.data;
+}
+
+export async function fetchSubmissionQueue(): Promise<SubmissionQueueEntry[]> {
+  const response = await api.get("/api/submissions/queue");
+  return response.data;
+}
+
+export async function reviewSubmission(
+  id: string,
+  data: {
+    status: "approved" | "rejected";
+    comment?: string;
+    confidence?: CuratedTrack["confidence"];
+  },
+): Promise<TrackSubmission> {
+  const response = await api.patch(`/api/submissions/${id}`, data);
+  return response.data;
+}
+
 // Routes
 export async function fetchRoutes(): Promise<Route[]> {
   const response = await api.get("/api/routes");
@@ -131,20 +404,25 @@ export async function createRoute(data: {
   name: string;
   geometry: GeoJSON.MultiLineString;
   controlPoints: GeoJSON.Point[];
+  avoidHazards?: boolean;
 }): Promise<Route> {
   const response = await api.post("/api/routes", {
     name: data.name,
     geometry: data.geometry,
     control_points: data.controlPoints,
+    avoid_hazards: data.avoidHazards,
   });
   return response.data;
 }
 
 export async function updateRoute(
   id: string,
-  data: { geometry: GeoJSON.MultiLineString },
+  data: { geometry: GeoJSON.MultiLineString; avoidHazards?: boolean },
 ): Promise<Route> {
-  const response = await api.put(`/api/routes/${id}`, data);
+  const response = await api.put(`/api/routes/${id}`, {
+    geometry: data.geometry,
+    avoid_hazards: data.avoidHazards,
+  });
   return response.data;
 }
 
@@ -154,12 +432,14 @@ export async function updateRouteControlPoints(
     controlPoints: GeoJSON.Point[];
     featureIndex: number;
     pointIndex: number;
+    avoidHazards?: boolean;
   },
 ): Promise<Route> {
   const response = await api.put(`/api/routes/${id}/control-points`, {
     control_points: data.controlPoints,
     feature_index: data.featureIndex,
     point_index: data.pointIndex,
+    avoid_hazards: data.avoidHazards,
   });
   return response.data;
 }
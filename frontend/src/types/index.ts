@@ -6,6 +6,209 @@ export interface CuratedTrack {
   confidence: 1 | 2 | 3 | 4 | 5;
   last_verified: string | null;
   region: string;
+  created_by: string | null;
+  updated_by: string | null;
+  provenance: string[];
+  /** OpenStreetMap way the track was imported from */
+  osm_way_id: number | null;
+}
+
+export type Passability = "good" | "difficult" | "impassable";
+
+/** A user's report of driving a curated track */
+export interface TrackVerification {
+  id: string;
+  track_id: string;
+  user_id: string;
+  driven_on: string;
+  surface: string | null;
+  passability: Passability;
+  trace: GeoJSON.LineString | null;
+  created_at: string;
+}
+
+export interface TrackDetail extends CuratedTrack {
+  regions: RegionRef[];
+  hazards: HazardReport[];
+  verifications: TrackVerification[];
+}
+
+export type RegionLevel = "country" | "area";
+
+export interface RegionRef {
+  id: string;
+  code: string;
+  name: string;
+  level: RegionLevel;
+}
+
+export interface RegionSummary extends RegionRef {
+  parent_id: string | null;
+  track_count: number;
+  length_km: number;
+  avg_confidence: number | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface RegionDetail extends RegionSummary {
+  geometry: GeoJSON.MultiPolygon;
+  children: RegionSummary[];
+}
+
+/** A curated track as it was before one change (null attributes for the insert) */
+export interface TrackRevision {
+  id: string;
+  track_id: string;
+  operation: "insert" | "update" | "delete";
+  kind: "import" | "edit" | "confidence" | "merge" | "revert" | "verification" | "sql";
+  geometry: GeoJSON.LineString | null;
+  source: CuratedTrack["source"] | null;
+  surface: string | null;
+  confidence: CuratedTrack["confidence"] | null;
+  last_verified: string | null;
+  region: string | null;
+  provenance: string[] | null;
+  actor_id: string | null;
+  job: string | null;
+  reason: string | null;
+  import_run_id: string | null;
+  created_at: string;
+}
+
+export interface TrackList {
+  tracks: CuratedTrack[];
+  truncated: boolean;
+}
+
+export interface TrackSubmission {
+  id: string;
+  submitted_by: string;
+  geometry: GeoJSON.LineString;
+  origin: "drawn" | "gpx";
+  surface: string | null;
+  region: string | null;
+  comment: string | null;
+  recorded_on: string | null;
+  status: "pending" | "approved" | "rejected";
+  reviewed_by: string | null;
+  reviewed_at: string | null;
+  review_comment: string | null;
+  track_id: string | null;
+  created_at: string;
+}
+
+export interface SubmissionQueueEntry extends TrackSubmission {
+  overlap: { length_m: number; track_ids: string[] };
+  quality: {
+    length_m: number;
+    points: number;
+    max_gap_m: number;
+    overlap_ratio: number;
+    issues: {
+      kind: "too_short" | "sparse_points" | "spike" | "duplicate";
+      point: number | null;
+      message: string;
+    }[];
+  };
+}
+
+export interface TrackMergeProposal {
+  id: string;
+  track_a: string;
+  track_b: string;
+  keep_track_id: string;
+  overlap_a: number;
+  overlap_b: number;
+  frechet_m: number;
+  merged_confidence: CuratedTrack["confidence"];
+  status: "pending" | "merged" | "dismissed";
+  detected_at: string;
+  resolved_by: string | null;
+  resolved_at: string | null;
+  a: CuratedTrack;
+  b: CuratedTrack;
+}
+
+export interface DanglingEnd {
+  node_id: number;
+  lng: number;
+  lat: number;
+  track_id: string;
+  nearest_track_id: string;
+  distance_m: number;
+  bridges_components: boolean;
+}
+
+export interface ComponentGap {
+  component: number;
+  length_m: number;
+  nearest_component: number;
+  gap_m: number;
+  from: [number, number];
+  to: [number, number];
+}
+
+export type PoiCategory =
+  | "water_well"
+  | "fuel"
+  | "bivouac"
+  | "village"
+  | "checkpoint"
+  | "hazard"
+  | "mechanic"
+  | "medical";
+
+export interface Poi {
+  id: string;
+  category: PoiCategory;
+  name: string | null;
+  lng: number;
+  lat: number;
+  attributes: Record<string, unknown>;
+  source: CuratedTrack["source"];
+  confidence: number;
+  last_verified: string | null;
+  created_by: string | null;
+  updated_by: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+/** A POI located along a route version */
+export interface RoutePoi extends Poi {
+  km: number;
+  offset_m: number;
+  side: "left" | "right" | "on";
+}
+
+export type HazardKind =
+  | "washout"
+  | "soft_sand"
+  | "fesh_fesh"
+  | "border_closed"
+  | "security"
+  | "other";
+
+export type HazardSeverity = "low" | "moderate" | "severe";
+
+export interface HazardReport {
+  id: string;
+  reported_by: string;
+  kind: HazardKind;
+  severity: HazardSeverity;
+  description: string | null;
+  geometry: GeoJSON.Point | GeoJSON.LineString;
+  expires_at: string;
+  resolved_by: string | null;
+  resolved_at: string | null;
+  created_at: string;
+}
+
+/** An active hazard near a route version */
+export interface RouteHazard extends HazardReport {
+  km: number;
+  distance_m: number;
 }
 
 export interface Route {
@@ -18,6 +221,8 @@ export interface Route {
   estimated_time_min?: number;
   created_by?: string;
   created_at: string;
+  /** Active hazards near the route; only on single-route responses */
+  hazards?: RouteHazard[];
 }
 
 export interface RouteVersion {
@@ -0,0 +1,29 @@
+-- Multi-resolution route geometry
+--
+-- route_versions.geometry stays the standard (~11 m) display geometry.
+-- full_geometry keeps the unsimplified routed line when it differs from it.
+-- route_version_lods holds coarser overviews for low zoom levels.
+
+ALTER TABLE route_versions ADD COLUMN full_geometry JSONB;
+
+CREATE TABLE route_version_lods (
+    route_version_id UUID NOT NULL REFERENCES route_versions(id) ON DELETE CASCADE,
+    tolerance_m DOUBLE PRECISION NOT NULL CHECK (tolerance_m > 0),
+    point_count INTEGER NOT NULL,
+    geometry JSONB NOT NULL,
+    PRIMARY KEY (route_version_id, tolerance_m)
+);
+
+ALTER TABLE route_version_lods ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Authenticated users view all route version lods" ON route_version_lods FOR SELECT TO authenticated USING (true);
+CREATE POLICY "Owner create route version lods" ON route_version_lods FOR INSERT TO authenticated WITH CHECK (
+    EXISTS (
+        SELECT 1 FROM route_versions rv
+        JOIN routes r ON r.id = rv.route_id
+        WHERE rv.id = route_version_id AND r.owner_id = auth.uid()
+    )
+);
+
+GRANT ALL ON route_version_lods TO postgres, service_role;
+GRANT SELECT, INSERT ON route_version_lods TO authenticated;
@@ -0,0 +1,181 @@
+-- Typed geometry columns
+--
+-- The backend now reads these JSONB columns into typed models, so existing
+-- rows are normalized to the single shape each model accepts:
+-- * routes.control_points: GeoJSON Points; rally waypoint fields
+--   (waypoint_type, radius_m, speed_limit_kmh) become foreign members.
+--   Legacy {lng, lat} objects and nested properties are rewritten; points
+--   without a usable position and other members (name, id, ...) are dropped.
+-- * route_point_changes positions: [lng, lat] arrays.
+-- * route_proposals.geometry: MultiLineString (LineStrings are wrapped).
+-- Rows that cannot be interpreted are deleted. Nothing is lost: the
+-- original control points of every route they change, and every deleted
+-- row, are kept in the legacy_* tables below for review. CHECK constraints
+-- keep malformed values from being stored again.
+
+CREATE FUNCTION is_position(position JSONB) RETURNS BOOLEAN
+LANGUAGE sql IMMUTABLE AS $$
+    SELECT CASE
+        WHEN jsonb_typeof(position) = 'array'
+            AND jsonb_typeof(position->0) = 'number'
+            AND jsonb_typeof(position->1) = 'number'
+        THEN (position->>0)::float8 BETWEEN -180 AND 180
+            AND (position->>1)::float8 BETWEEN -90 AND 90
+        ELSE false
+    END
+$$;
+
+CREATE FUNCTION is_multilinestring(geometry JSONB) RETURNS BOOLEAN
+LANGUAGE sql IMMUTABLE AS $$
+    SELECT CASE
+        WHEN geometry->>'type' = 'MultiLineString'
+            AND jsonb_typeof(geometry->'coordinates') = 'array'
+            AND jsonb_array_length(geometry->'coordinates') > 0
+        THEN NOT EXISTS (
+            SELECT 1
+            FROM jsonb_array_elements(geometry->'coordinates') AS lines(line)
+            WHERE CASE
+                WHEN jsonb_typeof(line) = 'array' AND jsonb_array_length(line) >= 2
+                THEN EXISTS (
+                    SELECT 1 FROM jsonb_array_elements(line) AS positions(position)
+                    WHERE NOT is_position(position)
+                )
+                ELSE true
+            END
+        )
+        ELSE false
+    END
+$$;
+
+CREATE FUNCTION is_control_points(control_points JSONB) RETURNS BOOLEAN
+LANGUAGE sql IMMUTABLE AS $$
+    SELECT CASE
+        WHEN jsonb_typeof(control_points) = 'array'
+        THEN NOT EXISTS (
+            SELECT 1 FROM jsonb_array_elements(control_points) AS points(point)
+            WHERE point->>'type' IS DISTINCT FROM 'Point'
+                OR NOT is_position(point->'coordinates')
+        )
+        ELSE false
+    END
+$$;
+
+CREATE FUNCTION normalize_control_point(point JSONB) RETURNS JSONB
+LANGUAGE sql IMMUTABLE AS $$
+    SELECT jsonb_strip_nulls(jsonb_build_object(
+        'type', 'Point',
+        'coordinates', jsonb_build_array(
+            COALESCE(point->'coordinates'->0, point->'lng'),
+            COALESCE(point->'coordinates'->1, point->'lat')
+        ),
+        'waypoint_type', (
+            SELECT t FROM upper(COALESCE(
+                point->>'waypoint_type', point->'properties'->>'waypoint_type'
+            )) AS t
+            WHERE t IN ('DSS', 'ASS', 'WPV', 'WPM', 'WPE', 'WPS', 'WPC', 'DZ', 'FZ')
+        ),
+        'radius_m', (
+            SELECT r FROM COALESCE(point->'radius_m', point->'properties'->'radius_m') AS r
+            WHERE jsonb_typeof(r) = 'number'
+        ),
+        'speed_limit_kmh', (
+            SELECT s FROM COALESCE(
+                point->'speed_limit_kmh', point->'properties'->'speed_limit_kmh'
+            ) AS s
+            WHERE jsonb_typeof(s) = 'number'
+        )
+    ))
+$$;
+
+CREATE TABLE legacy_route_control_points (
+    route_id UUID PRIMARY KEY REFERENCES routes(id) ON DELETE CASCADE,
+    control_points JSONB NOT NULL,
+    archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Without constraints, so rows that fail the new checks fit
+CREATE TABLE legacy_route_point_changes (LIKE route_point_changes);
+CREATE TABLE legacy_route_proposals (LIKE route_proposals);
+
+ALTER TABLE legacy_route_control_points ENABLE ROW LEVEL SECURITY;
+ALTER TABLE legacy_route_point_changes ENABLE ROW LEVEL SECURITY;
+ALTER TABLE legacy_route_proposals ENABLE ROW LEVEL SECURITY;
+
+GRANT ALL ON legacy_route_control_points, legacy_route_point_changes, legacy_route_proposals
+    TO postgres, service_role;
+
+INSERT INTO legacy_route_control_points (route_id, control_points)
+SELECT id, control_points FROM routes;
+
+UPDATE routes SET control_points = COALESCE((
+    SELECT jsonb_agg(normalized ORDER BY ordinality)
+    FROM jsonb_array_elements(
+        CASE WHEN jsonb_typeof(control_points) = 'array' THEN control_points ELSE '[]'::jsonb END
+    ) WITH ORDINALITY AS points(point, ordinality)
+    CROSS JOIN LATERAL normalize_control_point(point) AS normalized
+    WHERE is_position(normalized->'coordinates')
+), '[]'::jsonb);
+
+-- Keep the originals only where normalizing changed something
+DELETE FROM legacy_route_control_points legacy
+USING routes r
+WHERE r.id = legacy.route_id AND r.control_points = legacy.control_points;
+
+DROP FUNCTION normalize_control_point(JSONB);
+
+ALTER TABLE routes
+    ADD CONSTRAINT routes_control_points_check CHECK (is_control_points(control_points));
+
+CREATE FUNCTION normalize_position(position JSONB) RETURNS JSONB
+LANGUAGE sql IMMUTABLE AS $$
+    SELECT jsonb_build_array(
+        COALESCE(position->0, position->'lng'),
+        COALESCE(position->1, position->'lat')
+    )
+$$;
+
+WITH removed AS (
+    DELETE FROM route_point_changes
+    WHERE NOT (
+        is_position(normalize_position(original_position))
+        AND is_position(normalize_position(new_position))
+    )
+    RETURNING *
+)
+INSERT INTO legacy_route_point_changes SELECT * FROM removed;
+
+UPDATE route_point_changes SET
+    original_position = normalize_position(original_position),
+    new_position = normalize_position(new_position);
+
+DROP FUNCTION normalize_position(JSONB);
+
+ALTER TABLE route_point_changes
+    ADD CONSTRAINT route_point_changes_positions_check
+    CHECK (is_position(original_position) AND is_position(new_position));
+
+CREATE FUNCTION normalize_line_geometry(geometry JSONB) RETURNS JSONB
+LANGUAGE sql IMMUTABLE AS $$
+    SELECT CASE
+        WHEN geometry->>'type' = 'LineString'
+        THEN jsonb_build_object(
+            'type', 'MultiLineString',
+            'coordinates', jsonb_build_array(geometry->'coordinates')
+        )
+        ELSE geometry
+    END
+$$;
+
+WITH removed AS (
+    DELETE FROM route_proposals
+    WHERE NOT is_multilinestring(normalize_line_geometry(geometry))
+    RETURNING *
+)
+INSERT INTO legacy_route_proposals SELECT * FROM removed;
+
+UPDATE route_proposals SET geometry = normalize_line_geometry(geometry);
+
+DROP FUNCTION normalize_line_geometry(JSONB);
+
+ALTER TABLE route_proposals
+    ADD CONSTRAINT route_proposals_geometry_check CHECK (is_multilinestring(geometry));
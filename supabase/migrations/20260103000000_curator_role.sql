@@ -0,0 +1,32 @@
+-- Curator role and write access to curated tracks
+--
+-- Curators are flagged in the Supabase JWT `app_metadata` (`role` or
+-- `roles` containing "curator"), which only admins can set. The backend
+-- publishes the verified claims in `request.jwt.claims` for every RLS
+-- transaction; `is_curator()` reads them.
+
+ALTER TABLE curated_tracks
+    ADD COLUMN created_by UUID,
+    ADD COLUMN updated_by UUID;
+
+CREATE FUNCTION is_curator() RETURNS BOOLEAN
+LANGUAGE sql STABLE AS $$
+    SELECT COALESCE(
+        claims->'app_metadata'->>'role' = 'curator'
+            OR claims->'app_metadata'->'roles' ? 'curator',
+        false
+    )
+    FROM (
+        SELECT NULLIF(current_setting('request.jwt.claims', true), '')::jsonb AS claims
+    ) AS current_claims
+$$;
+
+CREATE POLICY "Curators create tracks" ON curated_tracks FOR INSERT TO authenticated
+    WITH CHECK (is_curator() AND created_by = auth.uid() AND updated_by = auth.uid());
+CREATE POLICY "Curators update tracks" ON curated_tracks FOR UPDATE TO authenticated
+    USING (is_curator())
+    WITH CHECK (is_curator() AND updated_by = auth.uid());
+CREATE POLICY "Curators delete tracks" ON curated_tracks FOR DELETE TO authenticated
+    USING (is_curator());
+
+GRANT INSERT, UPDATE, DELETE ON curated_tracks TO authenticated;
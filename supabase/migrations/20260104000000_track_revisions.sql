@@ -0,0 +1,95 @@
+-- Curated track history
+--
+-- Every insert, update and delete on curated_tracks records a revision
+-- holding the row as it was before the change (NULL attributes for an
+-- insert). Writers describe the change with transaction-local settings:
+-- * app.revision_kind: import, edit, confidence, merge or revert
+--   (changes made without it, e.g. manual SQL, are recorded as 'sql')
+-- * app.revision_reason: free text
+-- * app.revision_job: name of the job making the change
+-- * app.import_run_id: the import run the change belongs to
+-- The acting user comes from request.jwt.claim.sub.
+
+CREATE TABLE track_import_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job TEXT NOT NULL,
+    source_name TEXT,
+    source_file TEXT,
+    status TEXT NOT NULL DEFAULT 'running' CHECK (status IN ('running', 'succeeded', 'failed')),
+    error TEXT,
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE TABLE curated_track_revisions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- No foreign key: the history of deleted tracks is kept
+    track_id UUID NOT NULL,
+    operation TEXT NOT NULL CHECK (operation IN ('insert', 'update', 'delete')),
+    kind TEXT NOT NULL CHECK (kind IN ('import', 'edit', 'confidence', 'merge', 'revert', 'sql')),
+    geometry GEOMETRY(LineString, 4326),
+    source TEXT,
+    surface TEXT,
+    confidence INTEGER,
+    last_verified DATE,
+    region TEXT,
+    actor_id UUID,
+    job TEXT,
+    reason TEXT,
+    import_run_id UUID REFERENCES track_import_runs(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT clock_timestamp()
+);
+
+CREATE INDEX idx_curated_track_revisions_track ON curated_track_revisions(track_id, created_at DESC);
+CREATE INDEX idx_curated_track_revisions_import_run ON curated_track_revisions(import_run_id);
+
+CREATE FUNCTION record_curated_track_revision() RETURNS TRIGGER
+LANGUAGE plpgsql SECURITY DEFINER SET search_path = public AS $$
+DECLARE
+    previous curated_tracks;
+BEGIN
+    -- Audit-only updates (updated_at, updated_by) are not revisions
+    IF TG_OP = 'UPDATE'
+        AND ST_AsBinary(NEW.geometry) = ST_AsBinary(OLD.geometry)
+        AND (NEW.source, NEW.surface, NEW.confidence, NEW.last_verified, NEW.region)
+            IS NOT DISTINCT FROM
+            (OLD.source, OLD.surface, OLD.confidence, OLD.last_verified, OLD.region)
+    THEN
+        RETURN NEW;
+    END IF;
+    IF TG_OP <> 'INSERT' THEN
+        previous := OLD;
+    END IF;
+
+    INSERT INTO curated_track_revisions (
+        track_id, operation, kind,
+        geometry, source, surface, confidence, last_verified, region,
+        actor_id, job, reason, import_run_id
+    ) VALUES (
+        COALESCE(NEW.id, OLD.id),
+        lower(TG_OP),
+        COALESCE(NULLIF(current_setting('app.revision_kind', true), ''), 'sql'),
+        previous.geometry, previous.source, previous.surface, previous.confidence,
+        previous.last_verified, previous.region,
+        NULLIF(current_setting('request.jwt.claim.sub', true), '')::uuid,
+        NULLIF(current_setting('app.revision_job', true), ''),
+        NULLIF(current_setting('app.revision_reason', true), ''),
+        NULLIF(current_setting('app.import_run_id', true), '')::uuid
+    );
+
+    RETURN COALESCE(NEW, OLD);
+END;
+$$;
+
+CREATE TRIGGER record_curated_track_revision
+    AFTER INSERT OR UPDATE OR DELETE ON curated_tracks
+    FOR EACH ROW EXECUTE FUNCTION record_curated_track_revision();
+
+ALTER TABLE track_import_runs ENABLE ROW LEVEL SECURITY;
+ALTER TABLE curated_track_revisions ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Public read track revisions" ON curated_track_revisions FOR SELECT USING (true);
+CREATE POLICY "Public read track import runs" ON track_import_runs FOR SELECT USING (true);
+
+GRANT ALL ON track_import_runs, curated_track_revisions TO postgres, service_role;
+GRANT SELECT ON track_import_runs, curated_track_revisions TO anon, authenticated;
@@ -0,0 +1,66 @@
+-- Field verification reports
+--
+-- Users who drove a curated track report when, what surface they found and
+-- whether it was passable. A report newer than the track's last_verified
+-- moves last_verified to the drive date and adjusts confidence:
+-- * good: +1 (up to 5)
+-- * difficult: unchanged
+-- * impassable: -1 (down to 1)
+-- Older reports are kept but change nothing.
+
+CREATE TABLE track_verifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    track_id UUID NOT NULL REFERENCES curated_tracks(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL,
+    driven_on DATE NOT NULL,
+    surface TEXT,
+    passability TEXT NOT NULL CHECK (passability IN ('good', 'difficult', 'impassable')),
+    -- Optional GPS trace snippet of the stretch driven
+    trace GEOMETRY(LineString, 4326),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_track_verifications_track ON track_verifications(track_id, driven_on DESC);
+
+-- Verification updates are recorded as their own kind of revision
+ALTER TABLE curated_track_revisions DROP CONSTRAINT curated_track_revisions_kind_check;
+ALTER TABLE curated_track_revisions ADD CONSTRAINT curated_track_revisions_kind_check
+    CHECK (kind IN ('import', 'edit', 'confidence', 'merge', 'revert', 'verification', 'sql'));
+
+-- Runs as owner: reporters may not update curated_tracks themselves
+CREATE FUNCTION apply_track_verification() RETURNS TRIGGER
+LANGUAGE plpgsql SECURITY DEFINER SET search_path = public AS $$
+BEGIN
+    PERFORM set_config('app.revision_kind', 'verification', true),
+            set_config('app.revision_reason',
+                format('Driven on %s: %s', NEW.driven_on, NEW.passability), true),
+            set_config('app.revision_job', '', true),
+            set_config('app.import_run_id', '', true);
+
+    UPDATE curated_tracks SET
+        last_verified = NEW.driven_on,
+        confidence = CASE NEW.passability
+            WHEN 'good' THEN LEAST(confidence + 1, 5)
+            WHEN 'impassable' THEN GREATEST(confidence - 1, 1)
+            ELSE confidence
+        END
+    WHERE id = NEW.track_id
+        AND (last_verified IS NULL OR last_verified < NEW.driven_on);
+
+    RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER apply_track_verification
+    AFTER INSERT ON track_verifications
+    FOR EACH ROW EXECUTE FUNCTION apply_track_verification();
+
+ALTER TABLE track_verifications ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Public read track verifications" ON track_verifications FOR SELECT USING (true);
+CREATE POLICY "Users report verifications" ON track_verifications FOR INSERT TO authenticated
+    WITH CHECK (user_id = auth.uid());
+
+GRANT ALL ON track_verifications TO postgres, service_role;
+GRANT SELECT ON track_verifications TO anon;
+GRANT SELECT, INSERT ON track_verifications TO authenticated;
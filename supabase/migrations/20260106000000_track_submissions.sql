@@ -0,0 +1,40 @@
+-- Crowd-sourced track submissions
+--
+-- Users submit drawn lines or GPX uploads; curators approve them into
+-- curated_tracks (source 'curated') or reject them.
+
+CREATE TABLE track_submissions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    submitted_by UUID NOT NULL,
+    geometry GEOMETRY(LineString, 4326) NOT NULL,
+    origin TEXT NOT NULL CHECK (origin IN ('drawn', 'gpx')),
+    surface TEXT,
+    region TEXT,
+    comment TEXT,
+    -- First timestamp of an uploaded GPX trace
+    recorded_on DATE,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'rejected')),
+    reviewed_by UUID,
+    reviewed_at TIMESTAMP WITH TIME ZONE,
+    review_comment TEXT,
+    -- The curated track an approved submission became
+    track_id UUID REFERENCES curated_tracks(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_track_submissions_status ON track_submissions(status, created_at);
+CREATE INDEX idx_track_submissions_submitted_by ON track_submissions(submitted_by, created_at DESC);
+CREATE INDEX idx_track_submissions_geometry ON track_submissions USING GIST(geometry);
+
+ALTER TABLE track_submissions ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Users read own submissions" ON track_submissions FOR SELECT TO authenticated
+    USING (submitted_by = auth.uid() OR is_curator());
+CREATE POLICY "Users submit tracks" ON track_submissions FOR INSERT TO authenticated
+    WITH CHECK (submitted_by = auth.uid() AND status = 'pending' AND reviewed_by IS NULL);
+CREATE POLICY "Curators review submissions" ON track_submissions FOR UPDATE TO authenticated
+    USING (is_curator())
+    WITH CHECK (is_curator() AND reviewed_by = auth.uid());
+
+GRANT ALL ON track_submissions TO postgres, service_role;
+GRANT SELECT, INSERT, UPDATE ON track_submissions TO authenticated;
@@ -0,0 +1,102 @@
+-- Duplicate track detection and merging
+--
+-- Overlapping curated_tracks pairs are proposed for merging. A merge keeps
+-- one geometry, folds the other track's sources into `provenance` and
+-- deletes it; both changes are recorded as 'merge' revisions.
+
+-- Every source a track's line has been seen in; starts as its own source
+ALTER TABLE curated_tracks ADD COLUMN provenance TEXT[] NOT NULL DEFAULT '{}';
+UPDATE curated_tracks SET provenance = ARRAY[source];
+
+CREATE FUNCTION include_track_source() RETURNS TRIGGER
+LANGUAGE plpgsql AS $$
+BEGIN
+    IF NOT NEW.source = ANY(NEW.provenance) THEN
+        NEW.provenance := NEW.provenance || NEW.source;
+    END IF;
+    RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER include_track_source
+    BEFORE INSERT OR UPDATE OF source, provenance ON curated_tracks
+    FOR EACH ROW EXECUTE FUNCTION include_track_source();
+
+-- Revisions keep provenance too, so reverting a merge restores it
+ALTER TABLE curated_track_revisions ADD COLUMN provenance TEXT[];
+
+CREATE OR REPLACE FUNCTION record_curated_track_revision() RETURNS TRIGGER
+LANGUAGE plpgsql SECURITY DEFINER SET search_path = public AS $$
+DECLARE
+    previous curated_tracks;
+BEGIN
+    -- Audit-only updates (updated_at, updated_by) are not revisions
+    IF TG_OP = 'UPDATE'
+        AND ST_AsBinary(NEW.geometry) = ST_AsBinary(OLD.geometry)
+        AND (NEW.source, NEW.surface, NEW.confidence, NEW.last_verified, NEW.region, NEW.provenance)
+            IS NOT DISTINCT FROM
+            (OLD.source, OLD.surface, OLD.confidence, OLD.last_verified, OLD.region, OLD.provenance)
+    THEN
+        RETURN NEW;
+    END IF;
+    IF TG_OP <> 'INSERT' THEN
+        previous := OLD;
+    END IF;
+
+    INSERT INTO curated_track_revisions (
+        track_id, operation, kind,
+        geometry, source, surface, confidence, last_verified, region, provenance,
+        actor_id, job, reason, import_run_id
+    ) VALUES (
+        COALESCE(NEW.id, OLD.id),
+        lower(TG_OP),
+        COALESCE(NULLIF(current_setting('app.revision_kind', true), ''), 'sql'),
+        previous.geometry, previous.source, previous.surface, previous.confidence,
+        previous.last_verified, previous.region, previous.provenance,
+        NULLIF(current_setting('request.jwt.claim.sub', true), '')::uuid,
+        NULLIF(current_setting('app.revision_job', true), ''),
+        NULLIF(current_setting('app.revision_reason', true), ''),
+        NULLIF(current_setting('app.import_run_id', true), '')::uuid
+    );
+
+    RETURN COALESCE(NEW, OLD);
+END;
+$$;
+
+CREATE TABLE track_merge_proposals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Ordered pair: track_a < track_b. No foreign keys: a merge deletes one
+    -- of the tracks, and the proposal is kept as its record.
+    track_a UUID NOT NULL,
+    track_b UUID NOT NULL,
+    keep_track_id UUID NOT NULL,
+    -- Share of each track within the overlap buffer of the other (0-1)
+    overlap_a DOUBLE PRECISION NOT NULL,
+    overlap_b DOUBLE PRECISION NOT NULL,
+    frechet_m DOUBLE PRECISION NOT NULL,
+    merged_confidence INTEGER NOT NULL CHECK (merged_confidence BETWEEN 1 AND 5),
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'merged', 'dismissed')),
+    detected_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    resolved_by UUID,
+    resolved_at TIMESTAMP WITH TIME ZONE,
+    CHECK (track_a < track_b),
+    CHECK (keep_track_id IN (track_a, track_b)),
+    UNIQUE (track_a, track_b)
+);
+
+CREATE INDEX idx_track_merge_proposals_pending ON track_merge_proposals(detected_at)
+    WHERE status = 'pending';
+CREATE INDEX idx_track_merge_proposals_track_b ON track_merge_proposals(track_b);
+
+ALTER TABLE track_merge_proposals ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Curators read merge proposals" ON track_merge_proposals FOR SELECT TO authenticated
+    USING (is_curator());
+CREATE POLICY "Curators record merge proposals" ON track_merge_proposals FOR INSERT TO authenticated
+    WITH CHECK (is_curator() AND status = 'pending');
+CREATE POLICY "Curators resolve merge proposals" ON track_merge_proposals FOR UPDATE TO authenticated
+    USING (is_curator())
+    WITH CHECK (is_curator());
+
+GRANT ALL ON track_merge_proposals TO postgres, service_role;
+GRANT SELECT, INSERT, UPDATE ON track_merge_proposals TO authenticated;
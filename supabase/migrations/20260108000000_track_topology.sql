@@ -0,0 +1,62 @@
+-- Track network topology
+--
+-- A materialized graph of curated_tracks: tracks are cut at crossings and
+-- where one ends within a tolerance of another, giving edges between
+-- nodes. The whole graph is rebuilt at once; track_topology_builds records
+-- each rebuild so readers can tell when it is stale.
+
+CREATE TABLE track_topology_builds (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tolerance_m DOUBLE PRECISION NOT NULL,
+    node_count INTEGER NOT NULL,
+    edge_count INTEGER NOT NULL,
+    component_count INTEGER NOT NULL,
+    built_by UUID,
+    built_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE track_nodes (
+    id BIGINT PRIMARY KEY,
+    geometry GEOMETRY(Point, 4326) NOT NULL,
+    degree INTEGER NOT NULL,
+    -- 0 is the component with the most track length
+    component INTEGER NOT NULL
+);
+
+CREATE TABLE track_edges (
+    id BIGINT PRIMARY KEY,
+    track_id UUID NOT NULL REFERENCES curated_tracks(id) ON DELETE CASCADE,
+    source_node BIGINT NOT NULL REFERENCES track_nodes(id) ON DELETE CASCADE,
+    target_node BIGINT NOT NULL REFERENCES track_nodes(id) ON DELETE CASCADE,
+    geometry GEOMETRY(LineString, 4326) NOT NULL,
+    length_m DOUBLE PRECISION NOT NULL,
+    component INTEGER NOT NULL
+);
+
+CREATE INDEX idx_track_nodes_geometry ON track_nodes USING GIST(geometry);
+CREATE INDEX idx_track_nodes_dangling ON track_nodes(id) WHERE degree = 1;
+CREATE INDEX idx_track_edges_geometry ON track_edges USING GIST(geometry);
+CREATE INDEX idx_track_edges_source ON track_edges(source_node);
+CREATE INDEX idx_track_edges_target ON track_edges(target_node);
+CREATE INDEX idx_track_edges_track ON track_edges(track_id);
+CREATE INDEX idx_track_edges_component ON track_edges(component);
+
+ALTER TABLE track_topology_builds ENABLE ROW LEVEL SECURITY;
+ALTER TABLE track_nodes ENABLE ROW LEVEL SECURITY;
+ALTER TABLE track_edges ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Public read topology builds" ON track_topology_builds FOR SELECT USING (true);
+CREATE POLICY "Public read track nodes" ON track_nodes FOR SELECT USING (true);
+CREATE POLICY "Public read track edges" ON track_edges FOR SELECT USING (true);
+
+CREATE POLICY "Curators rebuild topology" ON track_topology_builds FOR INSERT TO authenticated
+    WITH CHECK (is_curator());
+CREATE POLICY "Curators write track nodes" ON track_nodes FOR ALL TO authenticated
+    USING (is_curator()) WITH CHECK (is_curator());
+CREATE POLICY "Curators write track edges" ON track_edges FOR ALL TO authenticated
+    USING (is_curator()) WITH CHECK (is_curator());
+
+GRANT ALL ON track_topology_builds, track_nodes, track_edges TO postgres, service_role;
+GRANT SELECT ON track_topology_builds, track_nodes, track_edges TO anon;
+GRANT SELECT, INSERT ON track_topology_builds TO authenticated;
+GRANT SELECT, INSERT, DELETE ON track_nodes, track_edges TO authenticated;
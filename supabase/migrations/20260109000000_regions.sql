@@ -0,0 +1,74 @@
+-- Region polygons
+--
+-- Named areas in a two-level hierarchy (country, then area within it),
+-- imported from GeoJSON. Tracks are assigned to every region they
+-- intersect, both when a track is written and when a region's polygon
+-- changes. curated_tracks.region stays as the free-text label set by
+-- imports; filters and statistics go through track_regions.
+
+CREATE TABLE regions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Stable identifier used in URLs and track filters ("mr", "mr-adrar")
+    code TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    level TEXT NOT NULL CHECK (level IN ('country', 'area')),
+    parent_id UUID REFERENCES regions(id) ON DELETE CASCADE,
+    geometry GEOMETRY(MultiPolygon, 4326) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK ((level = 'country') = (parent_id IS NULL))
+);
+
+CREATE INDEX idx_regions_geometry ON regions USING GIST(geometry);
+CREATE INDEX idx_regions_parent ON regions(parent_id);
+
+CREATE TABLE track_regions (
+    track_id UUID NOT NULL REFERENCES curated_tracks(id) ON DELETE CASCADE,
+    region_id UUID NOT NULL REFERENCES regions(id) ON DELETE CASCADE,
+    PRIMARY KEY (track_id, region_id)
+);
+
+CREATE INDEX idx_track_regions_region ON track_regions(region_id);
+
+-- Runs as owner: track_regions is derived data nobody writes directly
+CREATE FUNCTION assign_track_regions() RETURNS TRIGGER
+LANGUAGE plpgsql SECURITY DEFINER SET search_path = public AS $$
+BEGIN
+    DELETE FROM track_regions WHERE track_id = NEW.id;
+    INSERT INTO track_regions (track_id, region_id)
+    SELECT NEW.id, r.id FROM regions r WHERE ST_Intersects(r.geometry, NEW.geometry);
+    RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER assign_track_regions
+    AFTER INSERT OR UPDATE OF geometry ON curated_tracks
+    FOR EACH ROW EXECUTE FUNCTION assign_track_regions();
+
+CREATE FUNCTION assign_region_tracks() RETURNS TRIGGER
+LANGUAGE plpgsql SECURITY DEFINER SET search_path = public AS $$
+BEGIN
+    DELETE FROM track_regions WHERE region_id = NEW.id;
+    INSERT INTO track_regions (track_id, region_id)
+    SELECT t.id, NEW.id FROM curated_tracks t WHERE ST_Intersects(t.geometry, NEW.geometry);
+    RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER assign_region_tracks
+    AFTER INSERT OR UPDATE OF geometry ON regions
+    FOR EACH ROW EXECUTE FUNCTION assign_region_tracks();
+
+ALTER TABLE regions ENABLE ROW LEVEL SECURITY;
+ALTER TABLE track_regions ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Public read regions" ON regions FOR SELECT USING (true);
+CREATE POLICY "Public read track regions" ON track_regions FOR SELECT USING (true);
+
+CREATE POLICY "Curators write regions" ON regions FOR ALL TO authenticated
+    USING (is_curator()) WITH CHECK (is_curator());
+
+GRANT ALL ON regions, track_regions TO postgres, service_role;
+GRANT SELECT ON regions, track_regions TO anon;
+GRANT SELECT, INSERT, UPDATE, DELETE ON regions TO authenticated;
+GRANT SELECT ON track_regions TO authenticated;
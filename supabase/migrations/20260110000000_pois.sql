@@ -0,0 +1,43 @@
+-- Points of interest
+--
+-- Wells, fuel, bivouacs and other places crews navigate to, curated like
+-- tracks: each carries a source, a confidence and when it was last seen.
+-- Category-specific details (water quality, fuel types, opening hours) go
+-- in the free-form attributes object.
+
+CREATE TABLE pois (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    category TEXT NOT NULL CHECK (category IN (
+        'water_well', 'fuel', 'bivouac', 'village', 'checkpoint', 'hazard', 'mechanic', 'medical'
+    )),
+    name TEXT,
+    geometry GEOMETRY(Point, 4326) NOT NULL,
+    attributes JSONB NOT NULL DEFAULT '{}'::jsonb CHECK (jsonb_typeof(attributes) = 'object'),
+    source TEXT NOT NULL CHECK (source IN ('osm', 'rally', 'curated')),
+    confidence INTEGER NOT NULL CHECK (confidence BETWEEN 1 AND 5),
+    last_verified DATE,
+    created_by UUID,
+    updated_by UUID,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_pois_geometry ON pois USING GIST(geometry);
+CREATE INDEX idx_pois_category ON pois(category);
+
+CREATE TRIGGER update_pois_updated_at BEFORE UPDATE ON pois FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+ALTER TABLE pois ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Public read pois" ON pois FOR SELECT USING (true);
+CREATE POLICY "Curators create pois" ON pois FOR INSERT TO authenticated
+    WITH CHECK (is_curator() AND created_by = auth.uid() AND updated_by = auth.uid());
+CREATE POLICY "Curators update pois" ON pois FOR UPDATE TO authenticated
+    USING (is_curator())
+    WITH CHECK (is_curator() AND updated_by = auth.uid());
+CREATE POLICY "Curators delete pois" ON pois FOR DELETE TO authenticated
+    USING (is_curator());
+
+GRANT ALL ON pois TO postgres, service_role;
+GRANT SELECT ON pois TO anon;
+GRANT SELECT, INSERT, UPDATE, DELETE ON pois TO authenticated;
@@ -0,0 +1,47 @@
+-- Hazard reports
+--
+-- Short-lived warnings from the field: washed-out pistes, soft sand,
+-- fesh-fesh, closed border posts, security incidents. Each report marks a
+-- point or a stretch and expires; the reporter or a curator can resolve it
+-- earlier. Only unexpired, unresolved reports are active.
+
+CREATE TABLE hazard_reports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    reported_by UUID NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN (
+        'washout', 'soft_sand', 'fesh_fesh', 'border_closed', 'security', 'other'
+    )),
+    severity TEXT NOT NULL CHECK (severity IN ('low', 'moderate', 'severe')),
+    description TEXT,
+    geometry GEOMETRY(Geometry, 4326) NOT NULL
+        CHECK (GeometryType(geometry) IN ('POINT', 'LINESTRING')),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    resolved_by UUID,
+    resolved_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (expires_at > created_at)
+);
+
+CREATE INDEX idx_hazard_reports_geometry ON hazard_reports USING GIST(geometry)
+    WHERE resolved_at IS NULL;
+CREATE INDEX idx_hazard_reports_expires ON hazard_reports(expires_at);
+
+CREATE VIEW active_hazard_reports WITH (security_invoker = true) AS
+    SELECT * FROM hazard_reports
+    WHERE resolved_at IS NULL AND expires_at > NOW();
+
+ALTER TABLE hazard_reports ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Public read hazard reports" ON hazard_reports FOR SELECT USING (true);
+CREATE POLICY "Users report hazards" ON hazard_reports FOR INSERT TO authenticated
+    WITH CHECK (reported_by = auth.uid() AND resolved_at IS NULL);
+CREATE POLICY "Reporters and curators resolve hazards" ON hazard_reports FOR UPDATE TO authenticated
+    USING (reported_by = auth.uid() OR is_curator())
+    WITH CHECK (resolved_by = auth.uid());
+
+GRANT ALL ON hazard_reports, active_hazard_reports TO postgres, service_role;
+GRANT SELECT ON hazard_reports, active_hazard_reports TO anon;
+GRANT SELECT, INSERT ON hazard_reports TO authenticated;
+-- Resolving is the only change; reports are never rewritten
+GRANT UPDATE (resolved_by, resolved_at) ON hazard_reports TO authenticated;
+GRANT SELECT ON active_hazard_reports TO authenticated;
@@ -0,0 +1,42 @@
+-- OSM way identity
+--
+-- Tracks imported from OpenStreetMap remember the way they came from, so a
+-- re-import updates them in place instead of adding copies. Deleting an OSM
+-- track (by a curator, or by merging it into a duplicate) tombstones its
+-- way so later imports do not bring it back.
+
+ALTER TABLE curated_tracks ADD COLUMN osm_way_id BIGINT;
+ALTER TABLE curated_tracks
+    ADD CONSTRAINT curated_tracks_osm_way_id_key UNIQUE (osm_way_id);
+
+-- Tracks loaded before way ids were kept cannot be matched; they stay as
+-- they are and a re-import adds the ways again as new tracks.
+
+CREATE TABLE deleted_osm_ways (
+    osm_way_id BIGINT PRIMARY KEY,
+    -- No foreign key: the track is gone
+    track_id UUID NOT NULL,
+    deleted_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE FUNCTION tombstone_osm_way() RETURNS TRIGGER
+LANGUAGE plpgsql SECURITY DEFINER SET search_path = public AS $$
+BEGIN
+    INSERT INTO deleted_osm_ways (osm_way_id, track_id)
+    VALUES (OLD.osm_way_id, OLD.id)
+    ON CONFLICT (osm_way_id) DO UPDATE SET track_id = EXCLUDED.track_id, deleted_at = NOW();
+    RETURN OLD;
+END;
+$$;
+
+CREATE TRIGGER tombstone_osm_way
+    AFTER DELETE ON curated_tracks
+    FOR EACH ROW WHEN (OLD.osm_way_id IS NOT NULL)
+    EXECUTE FUNCTION tombstone_osm_way();
+
+ALTER TABLE deleted_osm_ways ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Public read deleted OSM ways" ON deleted_osm_ways FOR SELECT USING (true);
+
+GRANT ALL ON deleted_osm_ways TO postgres, service_role;
+GRANT SELECT ON deleted_osm_ways TO anon, authenticated;
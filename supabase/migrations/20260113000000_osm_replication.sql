@@ -0,0 +1,52 @@
+-- OSM change-file replication
+--
+-- Incremental updates apply OSM change files (.osc) to imported tracks. A
+-- diff names the nodes and ways it touches but not the unchanged nodes of
+-- a modified way, so the last OSM state of every imported way and its
+-- nodes is mirrored here. The mirror also records what OSM last said about
+-- each way: imports only write the fields that changed on the OSM side, so
+-- a curator's confidence, region or surface adjustments survive updates.
+--
+-- Tracks imported before the mirror existed are compared against nothing;
+-- the first load after this migration treats their line and surface as
+-- changed.
+
+CREATE TABLE osm_ways (
+    id BIGINT PRIMARY KEY,
+    refs BIGINT[] NOT NULL,
+    -- Tags that decide relevance and surface (highway, surface, tracktype)
+    tags JSONB NOT NULL DEFAULT '{}'::jsonb,
+    geometry GEOMETRY(LineString, 4326) NOT NULL,
+    surface TEXT,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Which ways use a moved node
+CREATE INDEX idx_osm_ways_refs ON osm_ways USING GIN(refs);
+
+CREATE TABLE osm_nodes (
+    id BIGINT PRIMARY KEY,
+    lng DOUBLE PRECISION NOT NULL,
+    lat DOUBLE PRECISION NOT NULL
+);
+
+-- Last change file applied per region extract
+CREATE TABLE osm_replication_state (
+    region TEXT PRIMARY KEY,
+    sequence_number BIGINT NOT NULL,
+    applied_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_osm_ways_updated_at BEFORE UPDATE ON osm_ways
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Written only by import jobs
+ALTER TABLE osm_ways ENABLE ROW LEVEL SECURITY;
+ALTER TABLE osm_nodes ENABLE ROW LEVEL SECURITY;
+ALTER TABLE osm_replication_state ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Public read OSM replication state" ON osm_replication_state
+    FOR SELECT USING (true);
+
+GRANT ALL ON osm_ways, osm_nodes, osm_replication_state TO postgres, service_role;
+GRANT SELECT ON osm_replication_state TO anon, authenticated;
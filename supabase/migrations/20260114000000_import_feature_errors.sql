@@ -0,0 +1,11 @@
+-- Per-feature results of track file imports
+--
+-- A curated track file (GeoJSON, GPX, KML/KMZ) is imported feature by
+-- feature: a feature that cannot be read or whose line is invalid is
+-- skipped and recorded on the run instead of failing the whole import.
+-- Each error is {"index": <position in the file>, "name": <feature name or
+-- null>, "message": <reason>}.
+
+ALTER TABLE track_import_runs
+    ADD COLUMN tracks_imported INTEGER,
+    ADD COLUMN feature_errors JSONB NOT NULL DEFAULT '[]'::jsonb;
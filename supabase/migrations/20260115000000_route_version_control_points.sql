@@ -0,0 +1,22 @@
+-- Control points of each route version
+--
+-- Trace validation checks a GPX trace against the typed waypoints of the
+-- version it is compared with, which may be older than the route's current
+-- control points. Versions are now stored with the control points they
+-- were built from. Earlier versions cannot be reconstructed and stay NULL,
+-- except each route's latest version, which matches `routes.control_points`.
+
+ALTER TABLE route_versions
+    ADD COLUMN control_points JSONB
+    CHECK (control_points IS NULL OR is_control_points(control_points));
+
+UPDATE route_versions rv
+SET control_points = r.control_points
+FROM routes r
+WHERE r.id = rv.route_id
+    AND rv.id = (
+        SELECT latest.id FROM route_versions latest
+        WHERE latest.route_id = rv.route_id
+        ORDER BY latest.created_at DESC
+        LIMIT 1
+    );
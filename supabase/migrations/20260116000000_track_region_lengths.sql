@@ -0,0 +1,38 @@
+-- Length of each track within each of its regions
+--
+-- Region statistics summed the clipped length of every track on every
+-- request. The clipping is now done once, by the triggers that assign
+-- tracks to regions, and stored in track_regions.
+
+ALTER TABLE track_regions ADD COLUMN length_m DOUBLE PRECISION NOT NULL DEFAULT 0;
+
+UPDATE track_regions tr
+SET length_m = ST_Length(ST_Intersection(t.geometry, r.geometry)::geography)
+FROM curated_tracks t, regions r
+WHERE t.id = tr.track_id AND r.id = tr.region_id;
+
+ALTER TABLE track_regions ALTER COLUMN length_m DROP DEFAULT;
+
+CREATE OR REPLACE FUNCTION assign_track_regions() RETURNS TRIGGER
+LANGUAGE plpgsql SECURITY DEFINER SET search_path = public AS $$
+BEGIN
+    DELETE FROM track_regions WHERE track_id = NEW.id;
+    INSERT INTO track_regions (track_id, region_id, length_m)
+    SELECT NEW.id, r.id, ST_Length(ST_Intersection(NEW.geometry, r.geometry)::geography)
+    FROM regions r
+    WHERE ST_Intersects(r.geometry, NEW.geometry);
+    RETURN NEW;
+END;
+$$;
+
+CREATE OR REPLACE FUNCTION assign_region_tracks() RETURNS TRIGGER
+LANGUAGE plpgsql SECURITY DEFINER SET search_path = public AS $$
+BEGIN
+    DELETE FROM track_regions WHERE region_id = NEW.id;
+    INSERT INTO track_regions (track_id, region_id, length_m)
+    SELECT t.id, NEW.id, ST_Length(ST_Intersection(t.geometry, NEW.geometry)::geography)
+    FROM curated_tracks t
+    WHERE ST_Intersects(t.geometry, NEW.geometry);
+    RETURN NEW;
+END;
+$$;
@@ -0,0 +1,35 @@
+-- One confidence change per user and track
+--
+-- Each newer "good" report raised confidence by one, so a single user could
+-- take a track from 1 to 5 by reporting it repeatedly. A user's later
+-- reports on the same track still move last_verified, but only their first
+-- report changes confidence.
+
+CREATE OR REPLACE FUNCTION apply_track_verification() RETURNS TRIGGER
+LANGUAGE plpgsql SECURITY DEFINER SET search_path = public AS $$
+DECLARE
+    first_report BOOLEAN := NOT EXISTS (
+        SELECT 1 FROM track_verifications
+        WHERE track_id = NEW.track_id AND user_id = NEW.user_id AND id <> NEW.id
+    );
+BEGIN
+    PERFORM set_config('app.revision_kind', 'verification', true),
+            set_config('app.revision_reason',
+                format('Driven on %s: %s', NEW.driven_on, NEW.passability), true),
+            set_config('app.revision_job', '', true),
+            set_config('app.import_run_id', '', true);
+
+    UPDATE curated_tracks SET
+        last_verified = NEW.driven_on,
+        confidence = CASE
+            WHEN NOT first_report THEN confidence
+            WHEN NEW.passability = 'good' THEN LEAST(confidence + 1, 5)
+            WHEN NEW.passability = 'impassable' THEN GREATEST(confidence - 1, 1)
+            ELSE confidence
+        END
+    WHERE id = NEW.track_id
+        AND (last_verified IS NULL OR last_verified < NEW.driven_on);
+
+    RETURN NEW;
+END;
+$$;
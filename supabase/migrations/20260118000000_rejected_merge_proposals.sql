@@ -0,0 +1,10 @@
+-- Record pairs rejected by duplicate detection
+--
+-- Mutual overlaps whose shapes differ were dropped without a trace, so every
+-- run fetched them again and they kept using up the pair limit. Detection
+-- now records them as dismissed proposals with no resolved_by, which the
+-- candidate query already skips.
+
+DROP POLICY "Curators record merge proposals" ON track_merge_proposals;
+CREATE POLICY "Curators record merge proposals" ON track_merge_proposals FOR INSERT TO authenticated
+    WITH CHECK (is_curator() AND status IN ('pending', 'dismissed'));